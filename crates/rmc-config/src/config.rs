@@ -3,10 +3,13 @@
 //! Provides a centralized configuration system for the MCP server
 
 pub mod errors;
+#[cfg(feature = "indexer")]
 pub mod indexer;
 
 pub use errors::{Error, Result};
+#[cfg(feature = "indexer")]
 pub use indexer::TantivyConfig;
+#[cfg(feature = "indexer")]
 pub use indexer::{IndexerConfig, IndexerCoreConfig};
 
 use std::env;
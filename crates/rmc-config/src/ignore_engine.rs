@@ -0,0 +1,396 @@
+//! Shared ignore rules for every walk over a project tree
+//!
+//! The Merkle change detector, the indexer's file collector, watch mode and
+//! the hypergraph audits all ask [`IgnoreEngine`] whether a path belongs to
+//! the project, so an incremental Merkle refresh agrees with a full walk and
+//! an audit never reports findings in files search cannot see. Rules, from
+//! highest precedence down:
+//!
+//! 1. `include` / `exclude` globs in the `[index]` table of `.rmc.toml` at the
+//!    project root, with ripgrep `--glob` semantics: a non-empty `include`
+//!    list drops every file it does not match, `exclude` drops what it
+//!    matches, and both win over the ignore files below
+//! 2. `.rmcignore` files (gitignore syntax, nested per directory); a
+//!    `!pattern` re-includes what `.gitignore` or the defaults exclude
+//! 3. `.gitignore` files (nested per directory) and `.git/info/exclude`
+//! 4. [`DEFAULT_EXCLUDES`]: VCS metadata, build output and generated trees
+//!
+//! Within one kind of ignore file the deepest directory decides, as in git,
+//! and nothing beneath an ignored directory is visited. Which file types get
+//! indexed at all stays with the engine's `FileKind`.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use ignore::Match;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::overrides::{Override, OverrideBuilder};
+use serde::Deserialize;
+use thiserror::Error;
+use walkdir::WalkDir;
+
+/// Per-directory ignore file with gitignore syntax, read after `.gitignore`
+pub const RMCIGNORE_FILE: &str = ".rmcignore";
+/// Project config file at the workspace root; the engine reads its `[index]`
+/// table
+pub const PROJECT_CONFIG_FILE: &str = ".rmc.toml";
+
+const GITIGNORE_FILE: &str = ".gitignore";
+
+/// Directories excluded unless an ignore file or `include` glob re-includes
+/// them
+pub const DEFAULT_EXCLUDES: &[&str] = &[
+    ".git/",
+    ".jj/",
+    ".direnv/",
+    ".cargo/",
+    ".skeleton/",
+    "target/",
+    "vendor/",
+    "node_modules/",
+];
+
+/// Errors loading the ignore rules of a project
+#[derive(Error, Debug)]
+pub enum IgnoreError {
+    /// `.rmc.toml` exists but could not be read
+    #[error("failed to read {path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    /// `.rmc.toml` is not valid TOML or has an unknown `[index]` key
+    #[error("invalid {path}: {source}")]
+    Config {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+
+    /// An `include` / `exclude` entry is not a valid glob
+    #[error("invalid glob in [index] of .rmc.toml: {0}")]
+    Glob(#[from] ignore::Error),
+}
+
+/// The `[index]` table of `.rmc.toml`
+///
+/// ```toml
+/// [index]
+/// include = ["src/**", "crates/**"]
+/// exclude = ["**/generated/**", "tests/fixtures/**"]
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct IndexRules {
+    /// When non-empty, only files matching one of these globs are kept
+    pub include: Vec<String>,
+    /// Files and directories matching any of these globs are dropped
+    pub exclude: Vec<String>,
+}
+
+/// `.rmc.toml`; other tables are left to their own readers
+#[derive(Debug, Default, Deserialize)]
+struct ProjectConfig {
+    #[serde(default)]
+    index: IndexRules,
+}
+
+/// Ignore files found in one directory
+#[derive(Default)]
+struct DirRules {
+    rmcignore: Option<Gitignore>,
+    gitignore: Option<Gitignore>,
+}
+
+/// Ignore rules for one project root; see the module docs
+pub struct IgnoreEngine {
+    root: PathBuf,
+    overrides: Override,
+    git_exclude: Gitignore,
+    defaults: Gitignore,
+    /// Ignore files per directory, read on first use
+    dir_rules: Mutex<HashMap<PathBuf, Arc<DirRules>>>,
+}
+
+impl IgnoreEngine {
+    /// Engine for `root` with the `[index]` rules of its `.rmc.toml`, if any
+    pub fn load(root: &Path) -> Result<Self, IgnoreError> {
+        let config_path = root.join(PROJECT_CONFIG_FILE);
+        let rules = match std::fs::read_to_string(&config_path) {
+            Ok(source) => {
+                let config: ProjectConfig =
+                    toml::from_str(&source).map_err(|source| IgnoreError::Config {
+                        path: config_path.clone(),
+                        source,
+                    })?;
+                config.index
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => IndexRules::default(),
+            Err(source) => {
+                return Err(IgnoreError::Io {
+                    path: config_path,
+                    source,
+                });
+            }
+        };
+        Self::with_rules(root, &rules)
+    }
+
+    /// Engine for `root` with explicit include / exclude globs instead of
+    /// `.rmc.toml`
+    pub fn with_rules(root: &Path, rules: &IndexRules) -> Result<Self, IgnoreError> {
+        let mut overrides = OverrideBuilder::new(root);
+        for glob in &rules.include {
+            overrides.add(glob)?;
+        }
+        for glob in &rules.exclude {
+            overrides.add(&format!("!{glob}"))?;
+        }
+
+        let mut defaults = GitignoreBuilder::new(root);
+        for line in DEFAULT_EXCLUDES {
+            defaults.add_line(None, line)?;
+        }
+
+        Ok(Self {
+            root: root.to_path_buf(),
+            overrides: overrides.build()?,
+            git_exclude: read_ignore_file(root, &root.join(".git/info/exclude"))
+                .unwrap_or_else(Gitignore::empty),
+            defaults: defaults.build()?,
+            dir_rules: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Project root the rules are anchored at
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Whether `path`, or any directory between the root and it, is ignored
+    ///
+    /// Paths outside the root are never ignored. `path` does not have to
+    /// exist; a missing path is matched as a file.
+    pub fn is_ignored(&self, path: &Path) -> bool {
+        let Ok(relative) = path.strip_prefix(&self.root) else {
+            return false;
+        };
+        let depth = relative.components().count();
+        let mut current = self.root.clone();
+        for (idx, component) in relative.components().enumerate() {
+            current.push(component);
+            let is_dir = idx + 1 < depth || path.is_dir();
+            if self.is_entry_ignored(&current, is_dir) {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Walk `dir` (the root or a directory beneath it), skipping ignored
+    /// entries without descending into ignored directories
+    ///
+    /// Yields directories as well as files; walk errors are passed through
+    /// for the caller to count or log.
+    pub fn walk<'a>(
+        &'a self,
+        dir: &Path,
+    ) -> impl Iterator<Item = walkdir::Result<walkdir::DirEntry>> + use<'a> {
+        let dir_ignored = self.is_ignored(dir);
+        WalkDir::new(dir)
+            .follow_links(false)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(move |entry| {
+                if entry.depth() == 0 {
+                    !dir_ignored
+                } else {
+                    !self.is_entry_ignored(entry.path(), entry.file_type().is_dir())
+                }
+            })
+    }
+
+    /// Whether `path` holds ignore rules, so a change to it can change the
+    /// set of project files anywhere below its directory
+    pub fn is_rule_file(path: &Path) -> bool {
+        matches!(
+            path.file_name().and_then(|name| name.to_str()),
+            Some(GITIGNORE_FILE | RMCIGNORE_FILE | PROJECT_CONFIG_FILE)
+        ) || path.ends_with(".git/info/exclude")
+    }
+
+    /// Rules for one entry, given that no directory above it is ignored
+    fn is_entry_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        if let Some(ignored) = decided(self.overrides.matched(path, is_dir)) {
+            return ignored;
+        }
+
+        // Deepest directory first, stopping at the root
+        let dirs: Vec<Arc<DirRules>> = path
+            .ancestors()
+            .skip(1)
+            .take_while(|dir| dir.starts_with(&self.root))
+            .map(|dir| self.dir_rules(dir))
+            .collect();
+        let in_files = |pick: fn(&DirRules) -> Option<&Gitignore>| {
+            dirs.iter()
+                .filter_map(|rules| pick(rules))
+                .find_map(|gitignore| decided(gitignore.matched(path, is_dir)))
+        };
+
+        in_files(|rules| rules.rmcignore.as_ref())
+            .or_else(|| in_files(|rules| rules.gitignore.as_ref()))
+            .or_else(|| decided(self.git_exclude.matched(path, is_dir)))
+            .or_else(|| decided(self.defaults.matched(path, is_dir)))
+            .unwrap_or(false)
+    }
+
+    fn dir_rules(&self, dir: &Path) -> Arc<DirRules> {
+        let mut cache = self.dir_rules.lock().unwrap_or_else(|e| e.into_inner());
+        cache
+            .entry(dir.to_path_buf())
+            .or_insert_with(|| {
+                Arc::new(DirRules {
+                    rmcignore: read_ignore_file(dir, &dir.join(RMCIGNORE_FILE)),
+                    gitignore: read_ignore_file(dir, &dir.join(GITIGNORE_FILE)),
+                })
+            })
+            .clone()
+    }
+}
+
+/// Matcher for one ignore file anchored at `dir`, or `None` when the file
+/// does not exist. Malformed lines are logged and skipped, as git does.
+fn read_ignore_file(dir: &Path, file: &Path) -> Option<Gitignore> {
+    if !file.is_file() {
+        return None;
+    }
+    let mut builder = GitignoreBuilder::new(dir);
+    if let Some(err) = builder.add(file) {
+        tracing::warn!("Skipping invalid lines in {}: {}", file.display(), err);
+    }
+    match builder.build() {
+        Ok(gitignore) => Some(gitignore),
+        Err(err) => {
+            tracing::warn!("Ignoring {}: {}", file.display(), err);
+            None
+        }
+    }
+}
+
+/// `Some(true)` for an ignore match, `Some(false)` for a whitelist (`!`)
+/// match, `None` when the rules say nothing
+fn decided<T>(matched: Match<T>) -> Option<bool> {
+    match matched {
+        Match::None => None,
+        Match::Ignore(_) => Some(true),
+        Match::Whitelist(_) => Some(false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn write(root: &Path, relative: &str, contents: &str) {
+        let path = root.join(relative);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    fn walked_files(engine: &IgnoreEngine) -> Vec<String> {
+        engine
+            .walk(engine.root())
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+            .map(|e| {
+                let relative = e.path().strip_prefix(engine.root()).unwrap();
+                relative.to_string_lossy().replace('\\', "/")
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_default_excludes() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        write(root, "src/lib.rs", "");
+        write(root, "target/debug/build/out.rs", "");
+        write(root, "node_modules/pkg/index.js", "");
+        write(root, ".git/config", "");
+        write(root, ".cargo/registry/cache/lib.rs", "");
+        write(root, "crates/a/vendor/dep/lib.rs", "");
+
+        let engine = IgnoreEngine::load(root).unwrap();
+        assert_eq!(walked_files(&engine), vec!["src/lib.rs"]);
+        assert!(engine.is_ignored(&root.join("target/debug/build/out.rs")));
+        assert!(!engine.is_ignored(&root.join("src/lib.rs")));
+    }
+
+    #[test]
+    fn test_nested_gitignore_and_rmcignore_override() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        write(root, ".gitignore", "*.gen.rs\n/fixtures/\n");
+        write(root, "src/lib.rs", "");
+        write(root, "src/schema.gen.rs", "");
+        write(root, "fixtures/big/case.rs", "");
+        write(root, "src/proto/.gitignore", "out/\n");
+        write(root, "src/proto/out/messages.rs", "");
+        // Re-include one generated file and the vendored sources
+        write(root, ".rmcignore", "!src/api.gen.rs\n!vendor/\n");
+        write(root, "src/api.gen.rs", "");
+        write(root, "vendor/dep/lib.rs", "");
+
+        let engine = IgnoreEngine::load(root).unwrap();
+        assert_eq!(
+            walked_files(&engine),
+            vec![
+                ".gitignore",
+                ".rmcignore",
+                "src/api.gen.rs",
+                "src/lib.rs",
+                "src/proto/.gitignore",
+                "vendor/dep/lib.rs",
+            ]
+        );
+        assert!(engine.is_ignored(&root.join("src/proto/out/messages.rs")));
+        assert!(engine.is_ignored(&root.join("fixtures/big/case.rs")));
+    }
+
+    #[test]
+    fn test_project_config_include_and_exclude() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        write(
+            root,
+            PROJECT_CONFIG_FILE,
+            "[index]\ninclude = [\"src/**\"]\nexclude = [\"**/generated/**\"]\n",
+        );
+        write(root, "src/lib.rs", "");
+        write(root, "src/generated/bindings.rs", "");
+        write(root, "benches/bench.rs", "");
+
+        let engine = IgnoreEngine::load(root).unwrap();
+        assert_eq!(walked_files(&engine), vec!["src/lib.rs"]);
+        assert!(engine.is_ignored(&root.join("benches/bench.rs")));
+
+        write(root, PROJECT_CONFIG_FILE, "[index]\nexcludes = [\"src/**\"]\n");
+        assert!(matches!(IgnoreEngine::load(root), Err(IgnoreError::Config { .. })));
+    }
+
+    #[test]
+    fn test_walk_below_ignored_directory_is_empty() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        write(root, "target/gen/lib.rs", "");
+
+        let engine = IgnoreEngine::load(root).unwrap();
+        assert_eq!(engine.walk(&root.join("target/gen")).count(), 0);
+        assert!(IgnoreEngine::is_rule_file(Path::new("/ws/crates/a/.rmcignore")));
+        assert!(!IgnoreEngine::is_rule_file(Path::new("/ws/src/lib.rs")));
+    }
+}
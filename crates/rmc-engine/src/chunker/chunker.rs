@@ -4,7 +4,7 @@ use std::path::Path;
 
 use crate::parser::{ParseResult, Symbol};
 
-use super::types::{ChunkContext, ChunkId, CodeChunk};
+use super::types::{ChunkContext, ChunkId, CodeChunk, assign_content_ids};
 
 /// Code chunker that splits by symbols and adds context
 pub struct Chunker {
@@ -77,7 +77,7 @@ impl Chunker {
 
             // Create the chunk
             let chunk = CodeChunk {
-                id: ChunkId::new(),
+                id: ChunkId::pending(),
                 content: code,
                 context,
                 overlap_prev: None, // Will be filled in later
@@ -89,6 +89,7 @@ impl Chunker {
 
         // Add overlap between adjacent chunks
         self.add_overlap(&mut chunks);
+        assign_content_ids(&mut chunks);
 
         Ok(chunks)
     }
@@ -294,4 +295,28 @@ fn second() {
             assert!(chunks[1].overlap_prev.is_some(), "Second chunk should have overlap_prev");
         }
     }
+
+    #[test]
+    fn test_chunk_ids_survive_edits_elsewhere_in_the_file() {
+        let chunk_ids = |source: &str| {
+            let mut parser = RustParser::new().unwrap();
+            let parse_result = parser.parse_source_complete(source).unwrap();
+            Chunker::new()
+                .chunk_file(Path::new("src/lib.rs"), source, &parse_result)
+                .unwrap()
+                .into_iter()
+                .map(|c| (c.context.symbol_name, c.id))
+                .collect::<std::collections::HashMap<_, _>>()
+        };
+
+        let original = "fn first() {\n    one();\n}\n\nfn second() {\n    two();\n}\n";
+        // `first` grows by a line, shifting `second` down.
+        let edited = "fn first() {\n    one();\n    again();\n}\n\nfn second() {\n    two();\n}\n";
+        let before = chunk_ids(original);
+        let after = chunk_ids(edited);
+
+        assert_eq!(before, chunk_ids(original));
+        assert_ne!(before["first"], after["first"]);
+        assert_eq!(before["second"], after["second"]);
+    }
 }
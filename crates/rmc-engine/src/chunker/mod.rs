@@ -5,7 +5,9 @@
 
 mod chunker;
 mod split;
+mod text;
 mod types;
 
 pub use chunker::Chunker;
+pub use text::{FileKind, MARKDOWN_SECTION_KIND, TEXT_WINDOW_KIND, TOML_SECTION_KIND};
 pub use types::{ChunkContext, ChunkId, ChunkSplitConfig, CodeChunk};
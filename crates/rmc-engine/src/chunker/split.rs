@@ -5,7 +5,7 @@
 //! leaf chunks by line.
 
 use super::chunker::Chunker;
-use super::types::{ChunkId, ChunkSplitConfig, CodeChunk};
+use super::types::{ChunkSplitConfig, CodeChunk, assign_content_ids};
 
 impl Chunker {
     /// Split or elide chunks whose formatted embedding text exceeds the target.
@@ -72,6 +72,8 @@ impl Chunker {
             chunk.overlap_next = None;
         }
         self.add_overlap(&mut output);
+        // Parent names and split parts are part of the ID.
+        assign_content_ids(&mut output);
 
         output
     }
@@ -141,7 +143,6 @@ fn make_part_chunk(
     content: &str,
 ) -> CodeChunk {
     let mut part = source.clone();
-    part.id = ChunkId::new();
     part.content = content.to_string();
     part.context.line_start = source.context.line_start + start_line_offset;
     part.context.line_end = source.context.line_start + end_line_offset.saturating_sub(1);
@@ -263,13 +264,15 @@ mod tests {
         assert_eq!(chunks[0].context.split_total, Some(3));
         assert_eq!(chunks[2].context.line_start, 16);
         assert_eq!(chunks[2].context.line_end, 18);
+        assert_ne!(chunks[0].id, chunks[1].id);
+        assert_ne!(chunks[1].id, chunks[2].id);
     }
 
     #[test]
     fn test_splitter_keeps_small_chunks_unchanged() {
         let chunker = Chunker::with_overlap(0.0);
         let leaf = synthetic_chunk("small_fn", "function", 1, 2);
-        let id = leaf.id;
+        let id = ChunkId::for_chunk(&leaf, 0);
 
         let chunks = chunker.split_oversized_chunks(
             vec![leaf],
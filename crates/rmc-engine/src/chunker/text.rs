@@ -0,0 +1,341 @@
+//! Chunking for non-Rust project files: TOML sections, markdown headings and
+//! generic line windows.
+//!
+//! [`FileKind::for_path`] decides which files the indexer picks up at all;
+//! everything that is not [`FileKind::Rust`] goes through
+//! [`Chunker::chunk_text_file`] instead of the tree-sitter path. Text chunks
+//! carry a `symbol_kind` of their own (`toml_section`, `markdown_section`,
+//! `text_window`) so search can filter them, and an empty `module_path`
+//! since they belong to no Rust module.
+
+use std::path::Path;
+
+use super::chunker::Chunker;
+use super::types::{ChunkContext, ChunkId, CodeChunk, assign_content_ids};
+
+/// `symbol_kind` of a TOML table (`[package]`, `[[bin]]`, root keys)
+pub const TOML_SECTION_KIND: &str = "toml_section";
+/// `symbol_kind` of a markdown heading and the text under it
+pub const MARKDOWN_SECTION_KIND: &str = "markdown_section";
+/// `symbol_kind` of a fixed-size line window of any other text file
+pub const TEXT_WINDOW_KIND: &str = "text_window";
+
+/// Lines per [`TEXT_WINDOW_KIND`] chunk
+const TEXT_WINDOW_LINES: usize = 60;
+
+/// Extensions chunked as plain line windows: files Rust code commonly pulls
+/// in with `include_str!` or build scripts
+const TEXT_WINDOW_EXTENSIONS: &[&str] = &["sql", "proto", "graphql", "gql", "yaml", "yml"];
+
+/// How a file is chunked, by name / extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileKind {
+    /// `*.rs`, parsed with tree-sitter
+    Rust,
+    /// `*.toml` (`Cargo.toml`, `rustfmt.toml`, ...), chunked per table
+    Toml,
+    /// `*.md` / `*.markdown`, chunked per heading
+    Markdown,
+    /// Other known text formats, chunked in line windows
+    Text,
+}
+
+impl FileKind {
+    /// File kind for `path`, or `None` for files that are not indexed
+    pub fn for_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "rs" => Some(Self::Rust),
+            "toml" => Some(Self::Toml),
+            "md" | "markdown" => Some(Self::Markdown),
+            ext if TEXT_WINDOW_EXTENSIONS.contains(&ext) => Some(Self::Text),
+            _ => None,
+        }
+    }
+
+    /// Whether the indexer picks up `path` at all
+    pub fn is_indexed(path: &Path) -> bool {
+        Self::for_path(path).is_some()
+    }
+}
+
+/// A run of lines that becomes one chunk (1-based, inclusive)
+struct Section {
+    name: String,
+    start_line: usize,
+    end_line: usize,
+}
+
+impl Chunker {
+    /// Chunk a non-Rust file according to `kind`
+    ///
+    /// `FileKind::Rust` yields no chunks; Rust files go through
+    /// [`Chunker::chunk_file`] with a parse result. Blank sections are
+    /// dropped; oversized ones are left to
+    /// [`Chunker::split_oversized_chunks`] like any other leaf chunk.
+    pub fn chunk_text_file(
+        &self,
+        file_path: &Path,
+        source: &str,
+        kind: FileKind,
+    ) -> Vec<CodeChunk> {
+        let lines: Vec<&str> = source.lines().collect();
+        let file_name = file_path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        let (sections, symbol_kind) = match kind {
+            FileKind::Rust => return Vec::new(),
+            FileKind::Toml => (toml_sections(&lines, &file_name), TOML_SECTION_KIND),
+            FileKind::Markdown => {
+                (markdown_sections(&lines, &file_name), MARKDOWN_SECTION_KIND)
+            }
+            FileKind::Text => (line_windows(&lines, &file_name), TEXT_WINDOW_KIND),
+        };
+
+        let mut chunks: Vec<CodeChunk> = sections
+            .into_iter()
+            .filter_map(|section| {
+                let content = lines[section.start_line - 1..section.end_line].join("\n");
+                if content.trim().is_empty() {
+                    return None;
+                }
+                Some(CodeChunk {
+                    id: ChunkId::pending(),
+                    content,
+                    context: ChunkContext {
+                        file_path: file_path.to_path_buf(),
+                        module_path: Vec::new(),
+                        symbol_name: section.name,
+                        symbol_kind: symbol_kind.to_string(),
+                        docstring: None,
+                        imports: Vec::new(),
+                        outgoing_calls: Vec::new(),
+                        parent_symbol_name: None,
+                        split_part: None,
+                        split_total: None,
+                        line_start: section.start_line,
+                        line_end: section.end_line,
+                    },
+                    overlap_prev: None,
+                    overlap_next: None,
+                })
+            })
+            .collect();
+
+        self.add_overlap(&mut chunks);
+        assign_content_ids(&mut chunks);
+        chunks
+    }
+}
+
+/// Cut `lines` before every line for which `header` returns a name. Lines
+/// before the first header form a section named `preamble`.
+fn sections_at(
+    lines: &[&str],
+    preamble: &str,
+    mut header: impl FnMut(&str) -> Option<String>,
+) -> Vec<Section> {
+    let mut sections = Vec::new();
+    let mut current = Section {
+        name: preamble.to_string(),
+        start_line: 1,
+        end_line: 0,
+    };
+    for (idx, &line) in lines.iter().enumerate() {
+        if let Some(name) = header(line) {
+            if current.end_line >= current.start_line {
+                sections.push(current);
+            }
+            current = Section {
+                name,
+                start_line: idx + 1,
+                end_line: idx + 1,
+            };
+        } else {
+            current.end_line = idx + 1;
+        }
+    }
+    if current.end_line >= current.start_line {
+        sections.push(current);
+    }
+    sections
+}
+
+/// One section per `[table]` / `[[array.of.tables]]` header; root keys are
+/// named after the file
+fn toml_sections(lines: &[&str], file_name: &str) -> Vec<Section> {
+    sections_at(lines, file_name, toml_header)
+}
+
+fn toml_header(line: &str) -> Option<String> {
+    let line = line.trim();
+    let line = match line.find('#') {
+        // A `#` inside a quoted key is rare enough to ignore
+        Some(comment) => line[..comment].trim_end(),
+        None => line,
+    };
+    let inner = line
+        .strip_prefix("[[")
+        .and_then(|rest| rest.strip_suffix("]]"))
+        .or_else(|| line.strip_prefix('[').and_then(|rest| rest.strip_suffix(']')))?
+        .trim();
+    // Keys only: rules out array values such as `[1, 2]` continuing a
+    // multi-line array
+    let is_key =
+        |c: char| c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | '"' | '\'' | ' ');
+    (!inner.is_empty() && inner.chars().all(is_key)).then(|| inner.to_string())
+}
+
+/// One section per ATX heading (`#` .. `######`) outside fenced code blocks;
+/// text before the first heading is named after the file
+fn markdown_sections(lines: &[&str], file_name: &str) -> Vec<Section> {
+    let mut fence: Option<&str> = None;
+    sections_at(lines, file_name, |line| {
+        let trimmed = line.trim_start();
+        for marker in ["```", "~~~"] {
+            if trimmed.starts_with(marker) {
+                match fence {
+                    Some(open) if open == marker => fence = None,
+                    Some(_) => {}
+                    None => fence = Some(marker),
+                }
+                return None;
+            }
+        }
+        if fence.is_some() {
+            return None;
+        }
+        markdown_heading(trimmed)
+    })
+}
+
+fn markdown_heading(line: &str) -> Option<String> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    let rest = &line[level..];
+    if !(rest.is_empty() || rest.starts_with(' ') || rest.starts_with('\t')) {
+        return None;
+    }
+    // Closing sequence: `## Title ##`
+    let title = rest.trim().trim_end_matches('#').trim_end();
+    (!title.is_empty()).then(|| title.to_string())
+}
+
+/// Consecutive windows of [`TEXT_WINDOW_LINES`] lines, all named after the
+/// file
+fn line_windows(lines: &[&str], file_name: &str) -> Vec<Section> {
+    (0..lines.len())
+        .step_by(TEXT_WINDOW_LINES)
+        .map(|start| Section {
+            name: file_name.to_string(),
+            start_line: start + 1,
+            end_line: (start + TEXT_WINDOW_LINES).min(lines.len()),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names_and_lines(chunks: &[CodeChunk]) -> Vec<(&str, usize, usize)> {
+        chunks
+            .iter()
+            .map(|c| (c.context.symbol_name.as_str(), c.context.line_start, c.context.line_end))
+            .collect()
+    }
+
+    #[test]
+    fn test_file_kind_for_path() {
+        assert_eq!(FileKind::for_path(Path::new("src/lib.rs")), Some(FileKind::Rust));
+        assert_eq!(FileKind::for_path(Path::new("Cargo.toml")), Some(FileKind::Toml));
+        assert_eq!(FileKind::for_path(Path::new("docs/DESIGN.MD")), Some(FileKind::Markdown));
+        assert_eq!(FileKind::for_path(Path::new("migrations/001.sql")), Some(FileKind::Text));
+        assert_eq!(FileKind::for_path(Path::new("Cargo.lock")), None);
+        assert_eq!(FileKind::for_path(Path::new("LICENSE")), None);
+    }
+
+    #[test]
+    fn test_chunk_toml_by_table() {
+        let source = "\
+# workspace member
+[package]
+name = \"demo\"
+
+[features]
+default = [\"std\"]
+gpu = [
+    [\"cuda\"],
+]
+
+[[bin]] # the CLI
+name = \"demo-cli\"
+";
+        let chunks = Chunker::with_overlap(0.0).chunk_text_file(
+            Path::new("/ws/Cargo.toml"),
+            source,
+            FileKind::Toml,
+        );
+
+        assert_eq!(
+            names_and_lines(&chunks),
+            vec![
+                ("Cargo.toml", 1, 1),
+                ("package", 2, 4),
+                ("features", 5, 10),
+                ("bin", 11, 12),
+            ]
+        );
+        assert!(chunks.iter().all(|c| c.context.symbol_kind == TOML_SECTION_KIND));
+        assert!(chunks[2].content.contains("gpu = ["));
+        assert!(chunks[0].context.module_path.is_empty());
+    }
+
+    #[test]
+    fn test_chunk_markdown_by_heading_skips_code_fences() {
+        let source = "\
+Intro text.
+
+# Design
+Overview.
+
+```sh
+# not a heading
+```
+
+## Storage ##
+Uses LMDB.
+";
+        let chunks = Chunker::with_overlap(0.0).chunk_text_file(
+            Path::new("docs/design.md"),
+            source,
+            FileKind::Markdown,
+        );
+
+        assert_eq!(
+            names_and_lines(&chunks),
+            vec![("design.md", 1, 2), ("Design", 3, 9), ("Storage", 10, 11)]
+        );
+        assert!(chunks.iter().all(|c| c.context.symbol_kind == MARKDOWN_SECTION_KIND));
+    }
+
+    #[test]
+    fn test_chunk_text_in_line_windows() {
+        let source = (1..=130).map(|n| format!("-- line {n}")).collect::<Vec<_>>().join("\n");
+        let chunks = Chunker::with_overlap(0.0).chunk_text_file(
+            Path::new("schema.sql"),
+            &source,
+            FileKind::Text,
+        );
+
+        assert_eq!(
+            names_and_lines(&chunks),
+            vec![("schema.sql", 1, 60), ("schema.sql", 61, 120), ("schema.sql", 121, 130)]
+        );
+        assert!(chunks.iter().all(|c| c.context.symbol_kind == TEXT_WINDOW_KIND));
+    }
+}
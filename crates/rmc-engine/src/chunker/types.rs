@@ -1,10 +1,25 @@
 //! Data types for code chunks: identifiers, context, and split configuration.
 
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
 use std::path::PathBuf;
 use uuid::Uuid;
 
+/// Version of the chunk ID derivation. Bump it whenever the fields hashed
+/// by [`ChunkId::for_chunk`] change, so stored IDs stop matching and every
+/// chunk is re-embedded once.
+const CHUNK_ID_SCHEME: &str = "chunk-id:v2";
+
+/// Imports listed in a chunk's embedding header
+const EMBEDDED_IMPORTS: usize = 5;
+
 /// A unique identifier for a code chunk
+///
+/// Chunks produced by [`Chunker`](super::Chunker) carry content-addressed
+/// IDs: the same chunk of the same file gets the same ID on every reindex,
+/// which lets the indexer reuse embeddings already stored in the vector
+/// store and keeps BM25 and vector rows reconcilable by ID.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct ChunkId(Uuid);
 
@@ -14,6 +29,56 @@ impl ChunkId {
         Self(Uuid::new_v4())
     }
 
+    /// Placeholder for chunks whose ID is assigned by
+    /// [`assign_content_ids`] once chunking is done.
+    pub(super) fn pending() -> Self {
+        Self(Uuid::nil())
+    }
+
+    /// Deterministic ID for `chunk`.
+    ///
+    /// Hashes every input of [`CodeChunk::format_for_embedding`]: the file
+    /// path, symbol path (module, symbol, kind, parent, split part), the
+    /// docstring, the embedded imports, outgoing calls and content, so an
+    /// equal ID always means an equal embedding text. The line range is
+    /// neither hashed nor embedded: an edit higher up in the file shifts
+    /// every chunk below it, and re-embedding all of them is the cost these
+    /// IDs exist to avoid. `occurrence` tells apart chunks that are
+    /// otherwise identical within one file.
+    pub fn for_chunk(chunk: &CodeChunk, occurrence: usize) -> Self {
+        let context = &chunk.context;
+        let mut hasher = Sha256::new();
+        let mut field = |bytes: &[u8]| {
+            // Length-prefix every field so adjacent fields cannot run
+            // into each other.
+            hasher.update((bytes.len() as u64).to_le_bytes());
+            hasher.update(bytes);
+        };
+        field(CHUNK_ID_SCHEME.as_bytes());
+        field(context.file_path.to_string_lossy().as_bytes());
+        field(context.module_path.join("::").as_bytes());
+        field(context.symbol_name.as_bytes());
+        field(context.symbol_kind.as_bytes());
+        field(context.parent_symbol_name.as_deref().unwrap_or("").as_bytes());
+        field(&(context.split_part.unwrap_or(0) as u64).to_le_bytes());
+        field(&(context.split_total.unwrap_or(0) as u64).to_le_bytes());
+        field(context.docstring.as_deref().unwrap_or("").as_bytes());
+        let imports = &context.imports[..context.imports.len().min(EMBEDDED_IMPORTS)];
+        field(imports.join("\n").as_bytes());
+        field(context.outgoing_calls.join("\n").as_bytes());
+        field(chunk.content.as_bytes());
+        field(&(occurrence as u64).to_le_bytes());
+
+        let digest = hasher.finalize();
+        let mut bytes = [0u8; 16];
+        bytes.copy_from_slice(&digest[..16]);
+        // Mark it as an RFC 9562 version 8 (custom) UUID so it still
+        // parses and prints like the v4 IDs written by older indexes.
+        bytes[6] = (bytes[6] & 0x0f) | 0x80;
+        bytes[8] = (bytes[8] & 0x3f) | 0x80;
+        Self(Uuid::from_bytes(bytes))
+    }
+
     /// Convert to string representation
     pub fn to_string(&self) -> String {
         self.0.to_string()
@@ -31,6 +96,26 @@ impl Default for ChunkId {
     }
 }
 
+/// Give every chunk of one file its content-addressed ID.
+///
+/// Runs after anything that changes the hashed fields (splitting sets the
+/// parent and split part). Identical chunks get increasing occurrence
+/// numbers in file order, so their IDs stay distinct and stable as long as
+/// the duplicates keep their relative order.
+pub(super) fn assign_content_ids(chunks: &mut [CodeChunk]) {
+    let mut occurrences: HashMap<ChunkId, usize> = HashMap::new();
+    for chunk in chunks {
+        let base = ChunkId::for_chunk(chunk, 0);
+        let seen = occurrences.entry(base).or_insert(0);
+        chunk.id = if *seen == 0 {
+            base
+        } else {
+            ChunkId::for_chunk(chunk, *seen)
+        };
+        *seen += 1;
+    }
+}
+
 /// Context information for a code chunk
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChunkContext {
@@ -85,12 +170,9 @@ impl CodeChunk {
     pub fn format_for_embedding(&self) -> String {
         let mut parts = Vec::new();
 
-        // File and location context
+        // File context. The line range stays out: it is not part of the
+        // chunk ID, so embeddings are reused across line shifts.
         parts.push(format!("// File: {}", self.context.file_path.display()));
-        parts.push(format!(
-            "// Location: lines {}-{}",
-            self.context.line_start, self.context.line_end
-        ));
 
         // Module context
         if !self.context.module_path.is_empty() {
@@ -127,7 +209,7 @@ impl CodeChunk {
                 .context
                 .imports
                 .iter()
-                .take(5)
+                .take(EMBEDDED_IMPORTS)
                 .cloned()
                 .collect::<Vec<_>>()
                 .join(", ");
@@ -239,7 +321,7 @@ mod tests {
         let formatted = chunk.format_for_embedding();
 
         assert!(formatted.contains("File: src/main.rs"));
-        assert!(formatted.contains("lines 10-12"));
+        assert!(!formatted.contains("lines 10-12"));
         assert!(formatted.contains("Module: crate::main"));
         assert!(formatted.contains("Symbol: test (function)"));
         assert!(formatted.contains("Purpose: A test function"));
@@ -247,4 +329,53 @@ mod tests {
         assert!(formatted.contains("Calls: println"));
         assert!(formatted.contains("fn test()"));
     }
+
+    #[test]
+    fn test_content_ids_ignore_lines_and_separate_duplicates() {
+        let chunk = |line_start: usize| CodeChunk {
+            id: ChunkId::pending(),
+            content: "fn test() {}".to_string(),
+            context: ChunkContext {
+                file_path: PathBuf::from("src/lib.rs"),
+                module_path: vec!["crate".to_string()],
+                symbol_name: "test".to_string(),
+                symbol_kind: "function".to_string(),
+                docstring: None,
+                imports: vec![],
+                outgoing_calls: vec![],
+                parent_symbol_name: None,
+                split_part: None,
+                split_total: None,
+                line_start,
+                line_end: line_start,
+            },
+            overlap_prev: None,
+            overlap_next: None,
+        };
+
+        assert_eq!(ChunkId::for_chunk(&chunk(1), 0), ChunkId::for_chunk(&chunk(40), 0));
+
+        let mut renamed = chunk(1);
+        renamed.context.file_path = PathBuf::from("src/other.rs");
+        assert_ne!(ChunkId::for_chunk(&chunk(1), 0), ChunkId::for_chunk(&renamed, 0));
+
+        // Embedded imports change the ID; imports past the embedded ones do not
+        let mut imported = chunk(1);
+        imported.context.imports = (0..EMBEDDED_IMPORTS).map(|i| format!("m{i}")).collect();
+        assert_ne!(ChunkId::for_chunk(&chunk(1), 0), ChunkId::for_chunk(&imported, 0));
+        let mut more_imports = imported.clone();
+        more_imports.context.imports.push("extra".to_string());
+        assert_eq!(
+            ChunkId::for_chunk(&imported, 0),
+            ChunkId::for_chunk(&more_imports, 0)
+        );
+
+        let mut chunks = vec![chunk(1), chunk(5)];
+        assign_content_ids(&mut chunks);
+        assert_eq!(chunks[0].id, ChunkId::for_chunk(&chunk(1), 0));
+        assert_ne!(chunks[0].id, chunks[1].id);
+
+        let id = chunks[0].id;
+        assert_eq!(ChunkId::from_string(&id.to_string()).unwrap(), id);
+    }
 }
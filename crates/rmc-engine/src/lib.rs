@@ -6,5 +6,6 @@ pub mod embeddings;
 pub mod parser;
 pub mod schema;
 pub mod search;
+pub mod tokenizer;
 #[cfg(feature = "vector-store")]
 pub mod vector_store;
@@ -3,10 +3,23 @@
 //! Based on bloop's indexes/schema.rs design, adapted for single-repository
 //! use case with focus on persistent indexing and incremental updates.
 
+use std::path::Path;
+
 use tantivy::schema::{
-    Field, IndexRecordOption, Schema, SchemaBuilder, TextFieldIndexing, TextOptions, STORED,
-    STRING,
+    Field, IndexRecordOption, Schema, SchemaBuilder, TextFieldIndexing, TextOptions, INDEXED,
+    STORED, STRING,
 };
+use tantivy::Index;
+
+use crate::tokenizer::{register_code_tokenizer, CODE_TOKENIZER};
+
+/// Version of [`ChunkSchema`]; part of the indexing identity, so a bump
+/// forces a full reindex
+///
+/// - v1: `default` tokenizer on content / symbol_name / docstring
+/// - v2: code tokenizer (identifier sub-words, see [`crate::tokenizer`])
+/// - v3: `is_test` flag, so test scope filters run inside the query
+pub const CHUNK_SCHEMA_VERSION: u32 = 3;
 
 /// Schema for indexing files with metadata for change detection
 #[derive(Clone)]
@@ -108,6 +121,10 @@ pub struct ChunkSchema {
 
     /// Full CodeChunk as JSON (stored only, for retrieval)
     pub chunk_json: Field,
+
+    /// Whether the chunk is test code (indexed, used for filtering; see
+    /// [`crate::search::is_test_chunk`])
+    pub is_test: Field,
 }
 
 impl ChunkSchema {
@@ -115,17 +132,18 @@ impl ChunkSchema {
     pub fn new() -> Self {
         let mut builder = SchemaBuilder::new();
 
-        // Text field options for code content
+        // Text field options for code content (identifiers split into sub-words)
         let code_options = TextOptions::default().set_stored().set_indexing_options(
             TextFieldIndexing::default()
-                .set_tokenizer("default")
+                .set_tokenizer(CODE_TOKENIZER)
                 .set_index_option(IndexRecordOption::WithFreqsAndPositions),
         );
 
-        // Text field options for documentation (same as code for now)
+        // Text field options for documentation (same as code for now, so
+        // identifiers quoted in docs split the same way)
         let doc_options = TextOptions::default().set_stored().set_indexing_options(
             TextFieldIndexing::default()
-                .set_tokenizer("default")
+                .set_tokenizer(CODE_TOKENIZER)
                 .set_index_option(IndexRecordOption::WithFreqsAndPositions),
         );
 
@@ -153,6 +171,9 @@ impl ChunkSchema {
         // Full chunk as JSON (stored only)
         let chunk_json = builder.add_text_field("chunk_json", STRING | STORED);
 
+        // Test-code flag (indexed for filtering)
+        let is_test = builder.add_bool_field("is_test", INDEXED | STORED);
+
         Self {
             schema: builder.build(),
             chunk_id,
@@ -163,6 +184,7 @@ impl ChunkSchema {
             module_path,
             docstring,
             chunk_json,
+            is_test,
         }
     }
 
@@ -170,6 +192,34 @@ impl ChunkSchema {
     pub fn schema(&self) -> Schema {
         self.schema.clone()
     }
+
+    /// Open the chunk index at `index_path`, creating it if missing
+    ///
+    /// An index written with a different schema (e.g. before
+    /// [`CHUNK_SCHEMA_VERSION`] 2) is deleted and recreated empty; the
+    /// indexing identity bump makes the next run repopulate it. The code
+    /// tokenizer is registered on the returned index.
+    pub fn open_or_create_index(&self, index_path: &Path) -> tantivy::Result<Index> {
+        if index_path.join("meta.json").exists() {
+            let index = Index::open_in_dir(index_path)?;
+            if index.schema() == self.schema {
+                register_code_tokenizer(&index);
+                return Ok(index);
+            }
+            tracing::info!(
+                "Chunk index at {} has an outdated schema, rebuilding (chunk schema v{})",
+                index_path.display(),
+                CHUNK_SCHEMA_VERSION
+            );
+            drop(index);
+            std::fs::remove_dir_all(index_path)?;
+        }
+
+        std::fs::create_dir_all(index_path)?;
+        let index = Index::create_in_dir(index_path, self.schema())?;
+        register_code_tokenizer(&index);
+        Ok(index)
+    }
 }
 
 impl Default for ChunkSchema {
@@ -220,6 +270,7 @@ mod tests {
         assert!(schema.schema.get_field("module_path").is_ok());
         assert!(schema.schema.get_field("docstring").is_ok());
         assert!(schema.schema.get_field("chunk_json").is_ok());
+        assert!(schema.schema.get_field("is_test").is_ok());
     }
 
     #[test]
@@ -236,5 +287,28 @@ mod tests {
         assert_eq!(schema1.module_path, schema2.module_path);
         assert_eq!(schema1.docstring, schema2.docstring);
         assert_eq!(schema1.chunk_json, schema2.chunk_json);
+        assert_eq!(schema1.is_test, schema2.is_test);
+    }
+
+    #[test]
+    fn test_chunk_index_rebuilt_on_schema_change() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let index_path = temp_dir.path().join("chunks");
+
+        // An index left over from an older schema
+        let mut builder = SchemaBuilder::new();
+        builder.add_text_field("content", STRING | STORED);
+        std::fs::create_dir_all(&index_path).unwrap();
+        Index::create_in_dir(&index_path, builder.build()).unwrap();
+
+        let schema = ChunkSchema::new();
+        let index = schema.open_or_create_index(&index_path).unwrap();
+        assert!(index.schema() == schema.schema());
+        assert!(index.tokenizers().get(CODE_TOKENIZER).is_some());
+
+        // A current index is reopened as is
+        drop(index);
+        let reopened = schema.open_or_create_index(&index_path).unwrap();
+        assert!(reopened.schema() == schema.schema());
     }
 }
@@ -2,13 +2,16 @@
 //!
 //! Provides chunk-level BM25 search to complement vector search in hybrid scenarios.
 
+use super::filter::SearchFilter;
 use crate::chunker::{ChunkId, CodeChunk};
 use crate::schema::ChunkSchema;
+use crate::tokenizer::{query_tokenizers, register_code_tokenizer};
+use std::collections::HashMap;
 use std::path::Path;
 use tantivy::collector::TopDocs;
-use tantivy::query::QueryParser;
-use tantivy::schema::Value;
-use tantivy::{Index, IndexReader, TantivyDocument};
+use tantivy::query::{BooleanQuery, Explanation, Occur, Query, QueryParser, TermQuery};
+use tantivy::schema::{IndexRecordOption, Value};
+use tantivy::{Index, IndexReader, TantivyDocument, Term};
 
 /// BM25 search for code chunks
 ///
@@ -28,14 +31,10 @@ impl Bm25Search {
     pub fn new(index_path: &Path) -> Result<Self, Box<dyn std::error::Error + Send>> {
         let schema = ChunkSchema::new();
 
-        // Open existing index
-        let index = if index_path.join("meta.json").exists() {
-            Index::open_in_dir(index_path).map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send>)?
-        } else {
-            // Create new index if doesn't exist
-            std::fs::create_dir_all(index_path).map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send>)?;
-            Index::create_in_dir(index_path, schema.schema()).map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send>)?
-        };
+        // Open existing index (or create it if it doesn't exist / is outdated)
+        let index = schema
+            .open_or_create_index(index_path)
+            .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send>)?;
 
         let reader = index.reader().map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send>)?;
 
@@ -48,9 +47,11 @@ impl Bm25Search {
 
     /// Create a BM25 search instance from an existing Tantivy Index
     ///
-    /// This is useful when you already have an Index instance (e.g., from UnifiedIndexer)
+    /// This is useful when you already have an Index instance (e.g., from UnifiedIndexer).
+    /// Registers the code tokenizer on it.
     pub fn from_index(index: Index) -> Result<Self, Box<dyn std::error::Error + Send>> {
         let schema = ChunkSchema::new();
+        register_code_tokenizer(&index);
         let reader = index.reader().map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send>)?;
 
         Ok(Self {
@@ -69,55 +70,134 @@ impl Bm25Search {
         query: &str,
         limit: usize,
     ) -> Result<Vec<(ChunkId, f32, CodeChunk)>, Box<dyn std::error::Error + Send>> {
+        self.search_filtered(query, &SearchFilter::default(), limit)
+    }
+
+    /// Search restricted by a [`SearchFilter`]
+    ///
+    /// Filter fields are ANDed onto the parsed text query as Tantivy clauses;
+    /// hits are then re-checked with [`SearchFilter::matches`], over-fetching
+    /// until `limit` hits survive or the index runs out.
+    pub fn search_filtered(
+        &self,
+        query: &str,
+        filter: &SearchFilter,
+        limit: usize,
+    ) -> Result<Vec<(ChunkId, f32, CodeChunk)>, Box<dyn std::error::Error + Send>> {
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
         let searcher = self.reader.searcher();
+        let query = self.build_query(query, filter)?;
+
+        let mut fetch = limit;
+        loop {
+            let top_docs = searcher.search(&query, &TopDocs::with_limit(fetch)).map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send>)?;
+            let fetched = top_docs.len();
+
+            // Convert Tantivy results to our format
+            let mut results = Vec::new();
+            for (score, doc_address) in top_docs {
+                let doc: TantivyDocument = searcher.doc(doc_address).map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send>)?;
+
+                // Extract chunk_id
+                let chunk_id_str = doc
+                    .get_first(self.schema.chunk_id)
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| {
+                        Box::new(std::io::Error::new(std::io::ErrorKind::NotFound, "Missing chunk_id field"))
+                            as Box<dyn std::error::Error + Send>
+                    })?;
+                let chunk_id = ChunkId::from_string(chunk_id_str).map_err(|e| {
+                    Box::new(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("{:?}", e)))
+                        as Box<dyn std::error::Error + Send>
+                })?;
+
+                // Deserialize CodeChunk from JSON
+                let chunk_json = doc
+                    .get_first(self.schema.chunk_json)
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| {
+                        Box::new(std::io::Error::new(std::io::ErrorKind::NotFound, "Missing chunk_json field"))
+                            as Box<dyn std::error::Error + Send>
+                    })?;
+                let chunk: CodeChunk = serde_json::from_str(chunk_json).map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send>)?;
+                if !filter.matches(&chunk) {
+                    continue;
+                }
+
+                results.push((chunk_id, score, chunk));
+            }
+
+            if results.len() >= limit || fetched < fetch {
+                results.truncate(limit);
+                return Ok(results);
+            }
+            fetch = fetch.saturating_mul(2);
+        }
+    }
 
-        // Parse query across multiple fields
-        let query_parser = QueryParser::for_index(
-            &self.index,
+    /// Tantivy's score breakdown for each of `chunk_ids` under the query
+    /// [`Self::search_filtered`] runs
+    ///
+    /// Chunks missing from the index or not matching the query are left out.
+    pub fn explain(
+        &self,
+        query: &str,
+        filter: &SearchFilter,
+        chunk_ids: &[ChunkId],
+    ) -> Result<HashMap<ChunkId, Explanation>, Box<dyn std::error::Error + Send>> {
+        let searcher = self.reader.searcher();
+        let query = self.build_query(query, filter)?;
+
+        let mut explanations = HashMap::new();
+        for &chunk_id in chunk_ids {
+            let term = Term::from_field_text(self.schema.chunk_id, &chunk_id.to_string());
+            let lookup = TermQuery::new(term, IndexRecordOption::Basic);
+            let found = searcher
+                .search(&lookup, &TopDocs::with_limit(1))
+                .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send>)?;
+            let Some(&(_, doc_address)) = found.first() else {
+                continue;
+            };
+            // Tantivy reports a non-matching document as an error.
+            if let Ok(explanation) = query.explain(&searcher, doc_address) {
+                explanations.insert(chunk_id, explanation);
+            }
+        }
+        Ok(explanations)
+    }
+
+    /// Parse `query` across content, symbol_name and docstring, ANDed with
+    /// the filter's clauses
+    fn build_query(
+        &self,
+        query: &str,
+        filter: &SearchFilter,
+    ) -> Result<Box<dyn Query>, Box<dyn std::error::Error + Send>> {
+        // Query-side code tokenizer: identifiers become sub-word phrases
+        // (see `crate::tokenizer`)
+        let query_parser = QueryParser::new(
+            self.index.schema(),
             vec![
                 self.schema.content,      // Main code content
                 self.schema.symbol_name,  // Symbol names
                 self.schema.docstring,    // Documentation
             ],
+            query_tokenizers(),
         );
 
         let query = query_parser.parse_query(query).map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send>)?;
-
-        // Search with limit
-        let top_docs = searcher.search(&query, &TopDocs::with_limit(limit)).map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send>)?;
-
-        // Convert Tantivy results to our format
-        let mut results = Vec::new();
-        for (score, doc_address) in top_docs {
-            let doc: TantivyDocument = searcher.doc(doc_address).map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send>)?;
-
-            // Extract chunk_id
-            let chunk_id_str = doc
-                .get_first(self.schema.chunk_id)
-                .and_then(|v| v.as_str())
-                .ok_or_else(|| {
-                    Box::new(std::io::Error::new(std::io::ErrorKind::NotFound, "Missing chunk_id field"))
-                        as Box<dyn std::error::Error + Send>
-                })?;
-            let chunk_id = ChunkId::from_string(chunk_id_str).map_err(|e| {
-                Box::new(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("{:?}", e)))
-                    as Box<dyn std::error::Error + Send>
-            })?;
-
-            // Deserialize CodeChunk from JSON
-            let chunk_json = doc
-                .get_first(self.schema.chunk_json)
-                .and_then(|v| v.as_str())
-                .ok_or_else(|| {
-                    Box::new(std::io::Error::new(std::io::ErrorKind::NotFound, "Missing chunk_json field"))
-                        as Box<dyn std::error::Error + Send>
-                })?;
-            let chunk: CodeChunk = serde_json::from_str(chunk_json).map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send>)?;
-
-            results.push((chunk_id, score, chunk));
-        }
-
-        Ok(results)
+        let filter_clauses = filter
+            .tantivy_clauses(&self.schema)
+            .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send>)?;
+        Ok(if filter_clauses.is_empty() {
+            query
+        } else {
+            let mut clauses = vec![(Occur::Must, query)];
+            clauses.extend(filter_clauses.into_iter().map(|clause| (Occur::Must, clause)));
+            Box::new(BooleanQuery::new(clauses))
+        })
     }
 
     /// Get the index for writing operations
@@ -157,6 +237,7 @@ impl Clone for Bm25Search {
 mod tests {
     use super::*;
     use crate::chunker::{ChunkContext, CodeChunk};
+    use crate::search::{TestScope, is_test_chunk};
     use std::path::PathBuf;
     use tantivy::doc;
     use tempfile::TempDir;
@@ -230,6 +311,7 @@ mod tests {
             schema.module_path => chunk1.context.module_path.join("::"),
             schema.docstring => chunk1.context.docstring.clone().unwrap_or_default(),
             schema.chunk_json => chunk1_json,
+            schema.is_test => is_test_chunk(&chunk1),
         )).unwrap();
 
         // Add chunk2
@@ -243,6 +325,7 @@ mod tests {
             schema.module_path => chunk2.context.module_path.join("::"),
             schema.docstring => chunk2.context.docstring.clone().unwrap_or_default(),
             schema.chunk_json => chunk2_json,
+            schema.is_test => is_test_chunk(&chunk2),
         )).unwrap();
 
         index_writer.commit().unwrap();
@@ -289,6 +372,7 @@ mod tests {
             schema.module_path => chunk.context.module_path.join("::"),
             schema.docstring => chunk.context.docstring.clone().unwrap(),
             schema.chunk_json => chunk_json,
+            schema.is_test => is_test_chunk(&chunk),
         )).unwrap();
 
         index_writer.commit().unwrap();
@@ -303,4 +387,218 @@ mod tests {
         assert!(!results.is_empty());
         assert_eq!(results[0].0, chunk_id);
     }
+
+    #[test]
+    fn test_bm25_search_matches_identifier_sub_words() {
+        let temp_dir = TempDir::new().unwrap();
+        let index_path = temp_dir.path().join("bm25_index");
+
+        let bm25_search = Bm25Search::new(&index_path).unwrap();
+
+        let mut batcher = create_test_chunk(
+            ChunkId::new(),
+            "EmbeddingBatcher",
+            "pub struct EmbeddingBatcher { queue: Vec<String> }",
+        );
+        batcher.context.docstring = None;
+        let mut parser = create_test_chunk(
+            ChunkId::new(),
+            "parse_file_complete",
+            "fn parse_file_complete(path: &Path) -> Result<ParsedFile> { }",
+        );
+        parser.context.docstring = None;
+
+        let mut index_writer = bm25_search.index().writer(50_000_000).unwrap();
+        let schema = bm25_search.schema();
+        for chunk in [&batcher, &parser] {
+            index_writer.add_document(doc!(
+                schema.chunk_id => chunk.id.to_string(),
+                schema.content => chunk.content.clone(),
+                schema.symbol_name => chunk.context.symbol_name.clone(),
+                schema.symbol_kind => chunk.context.symbol_kind.clone(),
+                schema.file_path => chunk.context.file_path.display().to_string(),
+                schema.module_path => chunk.context.module_path.join("::"),
+                schema.docstring => chunk.context.docstring.clone().unwrap_or_default(),
+                schema.chunk_json => serde_json::to_string(chunk).unwrap(),
+                schema.is_test => is_test_chunk(chunk),
+            )).unwrap();
+        }
+        index_writer.commit().unwrap();
+
+        let mut bm25_search_mut = bm25_search.clone();
+        bm25_search_mut.reload().unwrap();
+
+        for query in ["embedding batcher", "EmbeddingBatcher", "embeddingbatcher"] {
+            let results = bm25_search_mut.search(query, 10).unwrap();
+            assert_eq!(results.len(), 1, "query {query:?}");
+            assert_eq!(results[0].0, batcher.id, "query {query:?}");
+        }
+        for query in ["parse", "parse_file", "ParseFile"] {
+            let results = bm25_search_mut.search(query, 10).unwrap();
+            assert!(!results.is_empty(), "query {query:?}");
+            assert_eq!(results[0].0, parser.id, "query {query:?}");
+        }
+    }
+
+    #[test]
+    fn test_bm25_search_filtered() {
+        let temp_dir = TempDir::new().unwrap();
+        let index_path = temp_dir.path().join("bm25_index");
+
+        let bm25_search = Bm25Search::new(&index_path).unwrap();
+
+        let mut parser_fn = create_test_chunk(ChunkId::new(), "parse_config", "fn parse_config() { }");
+        parser_fn.context.file_path = PathBuf::from("/ws/engine/src/parser.rs");
+        parser_fn.context.module_path = vec!["crate".to_string(), "parser".to_string()];
+        let mut parser_struct = create_test_chunk(ChunkId::new(), "ParseConfig", "struct ParseConfig { }");
+        parser_struct.context.file_path = PathBuf::from("/ws/engine/src/parser.rs");
+        parser_struct.context.module_path = vec!["crate".to_string(), "parser".to_string()];
+        parser_struct.context.symbol_kind = "struct".to_string();
+        let mut other_crate = create_test_chunk(ChunkId::new(), "parse_args", "fn parse_args() { }");
+        other_crate.context.file_path = PathBuf::from("/ws/cli/src/main.rs");
+        other_crate.context.module_path = vec!["crate".to_string(), "main".to_string()];
+
+        let mut index_writer = bm25_search.index().writer(50_000_000).unwrap();
+        let schema = bm25_search.schema();
+        for chunk in [&parser_fn, &parser_struct, &other_crate] {
+            index_writer.add_document(doc!(
+                schema.chunk_id => chunk.id.to_string(),
+                schema.content => chunk.content.clone(),
+                schema.symbol_name => chunk.context.symbol_name.clone(),
+                schema.symbol_kind => chunk.context.symbol_kind.clone(),
+                schema.file_path => chunk.context.file_path.display().to_string(),
+                schema.module_path => chunk.context.module_path.join("::"),
+                schema.docstring => chunk.context.docstring.clone().unwrap_or_default(),
+                schema.chunk_json => serde_json::to_string(chunk).unwrap(),
+                schema.is_test => is_test_chunk(chunk),
+            )).unwrap();
+        }
+        index_writer.commit().unwrap();
+
+        let mut bm25_search_mut = bm25_search.clone();
+        bm25_search_mut.reload().unwrap();
+
+        // "Test function ..." docstrings make every chunk a text hit.
+        assert_eq!(bm25_search_mut.search("test", 10).unwrap().len(), 3);
+
+        let filter = SearchFilter {
+            path_prefix: Some(PathBuf::from("/ws/engine")),
+            symbol_kinds: vec!["function".to_string()],
+            ..Default::default()
+        };
+        let results = bm25_search_mut.search_filtered("test", &filter, 10).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].0, parser_fn.id);
+
+        let filter = SearchFilter {
+            module_prefix: Some("crate::parser".to_string()),
+            file_glob: Some(glob::Pattern::new("/ws/*/src/*.rs").unwrap()),
+            ..Default::default()
+        };
+        let results = bm25_search_mut.search_filtered("test", &filter, 10).unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|(_, _, chunk)| chunk.context.file_path.ends_with("parser.rs")));
+    }
+
+    #[test]
+    fn test_bm25_search_filtered_test_scope_fills_page() {
+        let temp_dir = TempDir::new().unwrap();
+        let index_path = temp_dir.path().join("bm25_index");
+
+        let bm25_search = Bm25Search::new(&index_path).unwrap();
+
+        // Tests outrank the one production chunk for "parse".
+        let mut chunks: Vec<CodeChunk> = (0..5)
+            .map(|i| {
+                let mut chunk = create_test_chunk(
+                    ChunkId::new(),
+                    &format!("parse_case_{i}"),
+                    "#[test]\nfn parse_case() { parse(); parse(); parse(); }",
+                );
+                chunk.context.file_path = PathBuf::from("/ws/core/src/parser.rs");
+                chunk
+            })
+            .collect();
+        let mut production = create_test_chunk(ChunkId::new(), "driver", "fn driver() { parse(); }");
+        production.context.file_path = PathBuf::from("/ws/core/src/parser.rs");
+        chunks.push(production.clone());
+
+        let mut index_writer = bm25_search.index().writer(50_000_000).unwrap();
+        let schema = bm25_search.schema();
+        for chunk in &chunks {
+            index_writer.add_document(doc!(
+                schema.chunk_id => chunk.id.to_string(),
+                schema.content => chunk.content.clone(),
+                schema.symbol_name => chunk.context.symbol_name.clone(),
+                schema.symbol_kind => chunk.context.symbol_kind.clone(),
+                schema.file_path => chunk.context.file_path.display().to_string(),
+                schema.module_path => chunk.context.module_path.join("::"),
+                schema.docstring => chunk.context.docstring.clone().unwrap_or_default(),
+                schema.chunk_json => serde_json::to_string(chunk).unwrap(),
+                schema.is_test => is_test_chunk(chunk),
+            )).unwrap();
+        }
+        index_writer.commit().unwrap();
+
+        let mut bm25_search_mut = bm25_search.clone();
+        bm25_search_mut.reload().unwrap();
+
+        let exclude = SearchFilter {
+            tests: TestScope::Exclude,
+            ..Default::default()
+        };
+        let results = bm25_search_mut.search_filtered("parse", &exclude, 1).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].0, production.id);
+
+        let only = SearchFilter {
+            tests: TestScope::Only,
+            ..Default::default()
+        };
+        let results = bm25_search_mut.search_filtered("parse", &only, 3).unwrap();
+        assert_eq!(results.len(), 3);
+        assert!(results.iter().all(|(_, _, chunk)| is_test_chunk(chunk)));
+    }
+
+    #[test]
+    fn test_bm25_explain_matches_search_scores() {
+        let temp_dir = TempDir::new().unwrap();
+        let index_path = temp_dir.path().join("bm25_index");
+
+        let bm25_search = Bm25Search::new(&index_path).unwrap();
+        let parser = create_test_chunk(ChunkId::new(), "parse_file", "fn parse_file() { }");
+        let reader = create_test_chunk(ChunkId::new(), "read_content", "fn read_content() { }");
+
+        let mut index_writer = bm25_search.index().writer(50_000_000).unwrap();
+        let schema = bm25_search.schema();
+        for chunk in [&parser, &reader] {
+            index_writer.add_document(doc!(
+                schema.chunk_id => chunk.id.to_string(),
+                schema.content => chunk.content.clone(),
+                schema.symbol_name => chunk.context.symbol_name.clone(),
+                schema.symbol_kind => chunk.context.symbol_kind.clone(),
+                schema.file_path => chunk.context.file_path.display().to_string(),
+                schema.module_path => chunk.context.module_path.join("::"),
+                schema.docstring => chunk.context.docstring.clone().unwrap_or_default(),
+                schema.chunk_json => serde_json::to_string(chunk).unwrap(),
+                schema.is_test => is_test_chunk(chunk),
+            )).unwrap();
+        }
+        index_writer.commit().unwrap();
+
+        let mut bm25_search_mut = bm25_search.clone();
+        bm25_search_mut.reload().unwrap();
+
+        let filter = SearchFilter::default();
+        let results = bm25_search_mut.search_filtered("parse", &filter, 10).unwrap();
+        let missing = ChunkId::new();
+        let explanations = bm25_search_mut
+            .explain("parse", &filter, &[parser.id, reader.id, missing])
+            .unwrap();
+
+        // `read_content` does not match and `missing` is not indexed.
+        assert_eq!(explanations.len(), 1);
+        let explained = explanations[&parser.id].value();
+        assert!((explained - results[0].1).abs() < 1e-4, "{explained} != {}", results[0].1);
+    }
 }
@@ -24,6 +24,10 @@ pub enum SearchError {
     #[error("BM25 search error: {0}")]
     Bm25(Box<dyn std::error::Error + Send>),
 
+    /// Reranking failed
+    #[error("Rerank error: {0}")]
+    Rerank(String),
+
     /// No results found
     #[error("No results found")]
     NoResults,
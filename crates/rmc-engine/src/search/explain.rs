@@ -0,0 +1,248 @@
+//! Per-hit explanations of a hybrid search
+//!
+//! [`SearchExplanation`] records what went into a ranking: the fusion
+//! settings and filters, then for each hit its BM25 side (rank, score,
+//! matched query terms per field, Tantivy's score tree), its vector side
+//! (rank, cosine similarity) and the RRF contribution of each side. Used
+//! to debug why a chunk does or does not surface and to tune
+//! [`HybridSearchConfig`](super::HybridSearchConfig) weights.
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use super::SearchResult;
+use crate::chunker::{ChunkId, CodeChunk};
+use crate::tokenizer::CodeTokenizer;
+
+/// Which engines produced the candidates
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchMode {
+    /// BM25 and vector candidates fused with RRF
+    Hybrid,
+    /// No BM25 index was available; vector candidates only
+    VectorOnly,
+}
+
+/// Fusion settings, filters and per-hit breakdown of one search
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchExplanation {
+    pub query: String,
+    pub mode: SearchMode,
+    /// True when no BM25 index was loaded, so only vector candidates were
+    /// ranked. `HybridSearch` has no vector-side fallback: a failed vector
+    /// search fails the whole search instead.
+    pub bm25_missing: bool,
+    pub bm25_weight: f32,
+    pub vector_weight: f32,
+    pub rrf_k: f32,
+    pub candidate_count: usize,
+    /// Name of the reranker applied after fusion, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reranker: Option<&'static str>,
+    /// Filters applied to both engines, as `name=value`
+    pub filters: Vec<String>,
+    pub hits: Vec<HitExplanation>,
+}
+
+/// Breakdown of one returned hit
+#[derive(Debug, Clone, Serialize)]
+pub struct HitExplanation {
+    /// 1-based position in the full ranking (page offset included)
+    pub rank: usize,
+    pub chunk_id: ChunkId,
+    pub file_path: PathBuf,
+    pub symbol_name: String,
+    /// Final fused score
+    pub score: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rerank_score: Option<f32>,
+    /// `None` when BM25 did not return this chunk
+    pub bm25: Option<Bm25HitExplanation>,
+    /// `None` when vector search did not return this chunk
+    pub vector: Option<VectorHitExplanation>,
+}
+
+/// BM25 side of a hit
+#[derive(Debug, Clone, Serialize)]
+pub struct Bm25HitExplanation {
+    pub rank: usize,
+    pub score: f32,
+    /// `bm25_weight / (rrf_k + rank)`
+    pub rrf_contribution: f32,
+    /// Query terms found in each searched field
+    pub matched_terms: Vec<FieldMatch>,
+    /// Tantivy's score tree, when the document could be explained
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tantivy: Option<serde_json::Value>,
+}
+
+/// Vector side of a hit
+#[derive(Debug, Clone, Serialize)]
+pub struct VectorHitExplanation {
+    pub rank: usize,
+    /// Store similarity, `1 - cosine_distance / 2`
+    pub score: f32,
+    pub cosine: f32,
+    /// `vector_weight / (rrf_k + rank)`
+    pub rrf_contribution: f32,
+}
+
+/// Query terms found in one chunk field
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FieldMatch {
+    pub field: &'static str,
+    pub terms: Vec<String>,
+}
+
+/// RRF contribution of a 1-based `rank` under `weight`
+pub fn rrf_contribution(weight: f32, rrf_k: f32, rank: usize) -> f32 {
+    weight / (rrf_k + rank as f32)
+}
+
+/// Query terms (as the BM25 query tokenizer splits them) that occur in each
+/// of the fields BM25 searches, in query order
+///
+/// Computed from the chunk rather than from Tantivy's explanation, which
+/// does not name the terms of the sub-word phrases identifiers become.
+pub fn matched_terms(query: &str, chunk: &CodeChunk) -> Vec<FieldMatch> {
+    let mut seen = HashSet::new();
+    let query_terms: Vec<String> = CodeTokenizer::for_queries()
+        .terms(query)
+        .into_iter()
+        .filter(|term| seen.insert(term.clone()))
+        .collect();
+    let mut index_side = CodeTokenizer::for_indexing();
+    let fields = [
+        ("symbol_name", chunk.context.symbol_name.as_str()),
+        ("content", chunk.content.as_str()),
+        ("docstring", chunk.context.docstring.as_deref().unwrap_or_default()),
+    ];
+
+    fields
+        .into_iter()
+        .filter_map(|(field, text)| {
+            let indexed: HashSet<String> = index_side.terms(text).into_iter().collect();
+            let terms: Vec<String> = query_terms
+                .iter()
+                .filter(|term| indexed.contains(*term))
+                .cloned()
+                .collect();
+            (!terms.is_empty()).then_some(FieldMatch { field, terms })
+        })
+        .collect()
+}
+
+/// Explanation of one fused `result` at 1-based `rank`, without Tantivy's
+/// score tree
+pub fn explain_hit(
+    query: &str,
+    rank: usize,
+    result: &SearchResult,
+    bm25_weight: f32,
+    vector_weight: f32,
+    rrf_k: f32,
+) -> HitExplanation {
+    let bm25 = result
+        .bm25_rank
+        .zip(result.bm25_score)
+        .map(|(rank, score)| Bm25HitExplanation {
+            rank,
+            score,
+            rrf_contribution: rrf_contribution(bm25_weight, rrf_k, rank),
+            matched_terms: matched_terms(query, &result.chunk),
+            tantivy: None,
+        });
+    let vector = result
+        .vector_rank
+        .zip(result.vector_score)
+        .map(|(rank, score)| VectorHitExplanation {
+            rank,
+            score,
+            cosine: 2.0 * score - 1.0,
+            rrf_contribution: rrf_contribution(vector_weight, rrf_k, rank),
+        });
+    HitExplanation {
+        rank,
+        chunk_id: result.chunk_id,
+        file_path: result.chunk.context.file_path.clone(),
+        symbol_name: result.chunk.context.symbol_name.clone(),
+        score: result.score,
+        rerank_score: result.rerank_score,
+        bm25,
+        vector,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chunker::ChunkContext;
+
+    fn result(bm25: Option<(usize, f32)>, vector: Option<(usize, f32)>) -> SearchResult {
+        SearchResult {
+            chunk_id: ChunkId::new(),
+            score: 0.016,
+            bm25_score: bm25.map(|(_, score)| score),
+            vector_score: vector.map(|(_, score)| score),
+            bm25_rank: bm25.map(|(rank, _)| rank),
+            vector_rank: vector.map(|(rank, _)| rank),
+            rerank_score: None,
+            chunk: CodeChunk {
+                id: ChunkId::new(),
+                content: "fn parse_config(path: &Path) -> Config { }".to_string(),
+                context: ChunkContext {
+                    file_path: PathBuf::from("src/config.rs"),
+                    module_path: vec!["crate".to_string()],
+                    symbol_name: "parse_config".to_string(),
+                    symbol_kind: "function".to_string(),
+                    docstring: Some("Reads the configuration file".to_string()),
+                    imports: vec![],
+                    outgoing_calls: vec![],
+                    parent_symbol_name: None,
+                    split_part: None,
+                    split_total: None,
+                    line_start: 1,
+                    line_end: 1,
+                },
+                overlap_prev: None,
+                overlap_next: None,
+            },
+        }
+    }
+
+    #[test]
+    fn matched_terms_are_listed_per_field_in_query_order() {
+        let hit = result(None, None);
+        assert_eq!(
+            matched_terms("ParseConfig file path", &hit.chunk),
+            vec![
+                FieldMatch { field: "symbol_name", terms: vec!["parse".into(), "config".into()] },
+                FieldMatch {
+                    field: "content",
+                    terms: vec!["parse".into(), "config".into(), "path".into()],
+                },
+                FieldMatch { field: "docstring", terms: vec!["file".into()] },
+            ]
+        );
+        assert!(matched_terms("tokenizer", &hit.chunk).is_empty());
+    }
+
+    #[test]
+    fn explain_hit_splits_rrf_contributions_by_engine() {
+        let both = explain_hit("parse", 1, &result(Some((2, 7.5)), Some((1, 0.9))), 0.5, 0.5, 60.0);
+        let bm25 = both.bm25.expect("bm25 side");
+        let vector = both.vector.expect("vector side");
+        assert_eq!(bm25.rank, 2);
+        assert!((bm25.rrf_contribution - 0.5 / 62.0).abs() < 1e-6);
+        assert!((vector.rrf_contribution - 0.5 / 61.0).abs() < 1e-6);
+        assert!((vector.cosine - 0.8).abs() < 1e-6);
+        assert_eq!(bm25.matched_terms[0].field, "symbol_name");
+
+        let vector_only = explain_hit("parse", 3, &result(None, Some((3, 0.7))), 0.5, 0.5, 60.0);
+        assert!(vector_only.bm25.is_none());
+        assert_eq!(vector_only.rank, 3);
+    }
+}
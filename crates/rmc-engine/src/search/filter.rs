@@ -0,0 +1,522 @@
+//! Structured search filters
+//!
+//! A [`SearchFilter`] restricts both engines before RRF fusion:
+//! - BM25: extra `Must` clauses on the Tantivy `ChunkSchema` STRING fields
+//! - Vector: a LanceDB `where` predicate over the stored metadata columns
+//!
+//! The test scope runs against the `is_test` flag both stores record at
+//! index time (see [`is_test_chunk`]). The native translations are allowed
+//! to over-match (SQL `LIKE` cannot express every glob), so every candidate
+//! is also checked with [`SearchFilter::matches`] and the engines over-fetch
+//! until a full page survives.
+
+use std::path::{Path, PathBuf};
+
+use glob::{MatchOptions, Pattern};
+use tantivy::Term;
+use tantivy::query::{BooleanQuery, Occur, Query, RegexQuery, TermQuery};
+use tantivy::schema::IndexRecordOption;
+
+use crate::chunker::CodeChunk;
+use crate::schema::ChunkSchema;
+
+/// Whether test code is kept, dropped, or the only thing returned
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TestScope {
+    /// No test filtering (default)
+    #[default]
+    Include,
+    /// Drop chunks classified as test code
+    Exclude,
+    /// Keep only chunks classified as test code
+    Only,
+}
+
+impl TestScope {
+    /// Parse `"include"`, `"exclude"` or `"only"` (case-insensitive)
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "include" => Some(Self::Include),
+            "exclude" => Some(Self::Exclude),
+            "only" => Some(Self::Only),
+            _ => None,
+        }
+    }
+
+    /// Value the `is_test` index field must have, or `None` for no
+    /// restriction
+    pub(crate) fn flag(self) -> Option<bool> {
+        match self {
+            Self::Include => None,
+            Self::Exclude => Some(false),
+            Self::Only => Some(true),
+        }
+    }
+}
+
+/// Filters applied to both search engines before fusion
+///
+/// Empty fields mean "no restriction"; all set fields must match.
+#[derive(Debug, Clone, Default)]
+pub struct SearchFilter {
+    /// Keep chunks whose file lives under this directory (a crate root)
+    pub path_prefix: Option<PathBuf>,
+    /// Keep chunks whose module path equals or is nested under this one
+    /// (e.g. `crate::parser`)
+    pub module_prefix: Option<String>,
+    /// Keep chunks with one of these symbol kinds (`function`, `struct`, ...)
+    pub symbol_kinds: Vec<String>,
+    /// Keep chunks whose file path matches this glob. `*` stops at `/`,
+    /// `**` crosses directories.
+    pub file_glob: Option<Pattern>,
+    /// Test / non-test restriction
+    pub tests: TestScope,
+}
+
+impl SearchFilter {
+    /// True when no restriction is set
+    pub fn is_empty(&self) -> bool {
+        self.path_prefix.is_none()
+            && self.module_prefix.is_none()
+            && self.symbol_kinds.is_empty()
+            && self.file_glob.is_none()
+            && self.tests == TestScope::Include
+    }
+
+    /// Exact check of a chunk against every set field
+    pub fn matches(&self, chunk: &CodeChunk) -> bool {
+        let context = &chunk.context;
+        if let Some(prefix) = &self.path_prefix
+            && !context.file_path.starts_with(prefix)
+        {
+            return false;
+        }
+        if let Some(module) = &self.module_prefix {
+            let module_path = context.module_path.join("::");
+            let nested = module_path
+                .strip_prefix(module.as_str())
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"));
+            if !nested {
+                return false;
+            }
+        }
+        if !self.symbol_kinds.is_empty()
+            && !self.symbol_kinds.iter().any(|kind| *kind == context.symbol_kind)
+        {
+            return false;
+        }
+        if let Some(glob) = &self.file_glob
+            && !glob.matches_path_with(&context.file_path, glob_options())
+        {
+            return false;
+        }
+        match self.tests {
+            TestScope::Include => true,
+            TestScope::Exclude => !is_test_chunk(chunk),
+            TestScope::Only => is_test_chunk(chunk),
+        }
+    }
+
+    /// One `name=value` line per set field, for search explanations
+    pub fn describe(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        if let Some(prefix) = &self.path_prefix {
+            lines.push(format!("path_prefix={}", prefix.display()));
+        }
+        if let Some(module) = &self.module_prefix {
+            lines.push(format!("module_prefix={module}"));
+        }
+        if !self.symbol_kinds.is_empty() {
+            lines.push(format!("symbol_kind={}", self.symbol_kinds.join("|")));
+        }
+        if let Some(glob) = &self.file_glob {
+            lines.push(format!("file_glob={}", glob.as_str()));
+        }
+        match self.tests {
+            TestScope::Include => {}
+            TestScope::Exclude => lines.push("tests=exclude".to_string()),
+            TestScope::Only => lines.push("tests=only".to_string()),
+        }
+        lines
+    }
+
+    /// Tantivy clauses to AND with the text query
+    pub(crate) fn tantivy_clauses(
+        &self,
+        schema: &ChunkSchema,
+    ) -> tantivy::Result<Vec<Box<dyn Query>>> {
+        let mut clauses: Vec<Box<dyn Query>> = Vec::new();
+        if let Some(prefix) = &self.path_prefix {
+            let pattern = format!("{}/.*", escape_regex(&path_str(prefix)));
+            clauses.push(Box::new(RegexQuery::from_pattern(&pattern, schema.file_path)?));
+        }
+        if let Some(module) = &self.module_prefix {
+            let pattern = format!("{}(::.*)?", escape_regex(module));
+            clauses.push(Box::new(RegexQuery::from_pattern(&pattern, schema.module_path)?));
+        }
+        if !self.symbol_kinds.is_empty() {
+            let kinds: Vec<(Occur, Box<dyn Query>)> = self
+                .symbol_kinds
+                .iter()
+                .map(|kind| {
+                    let term = Term::from_field_text(schema.symbol_kind, kind);
+                    (
+                        Occur::Should,
+                        Box::new(TermQuery::new(term, IndexRecordOption::Basic)) as Box<dyn Query>,
+                    )
+                })
+                .collect();
+            clauses.push(Box::new(BooleanQuery::new(kinds)));
+        }
+        if let Some(glob) = &self.file_glob {
+            let pattern = glob_to_regex(glob.as_str());
+            clauses.push(Box::new(RegexQuery::from_pattern(&pattern, schema.file_path)?));
+        }
+        if let Some(is_test) = self.tests.flag() {
+            let term = Term::from_field_bool(schema.is_test, is_test);
+            clauses.push(Box::new(TermQuery::new(term, IndexRecordOption::Basic)));
+        }
+        Ok(clauses)
+    }
+
+    /// LanceDB `where` predicate, or `None` when nothing can be pushed down.
+    /// `LIKE` patterns are widened where SQL cannot express the glob.
+    #[cfg(feature = "vector-store")]
+    pub(crate) fn lance_predicate(&self) -> Option<String> {
+        let mut predicates = Vec::new();
+        if let Some(prefix) = &self.path_prefix {
+            predicates.push(format!(
+                "file_path LIKE '{}/%'",
+                like_literal(&path_str(prefix))
+            ));
+        }
+        if let Some(module) = &self.module_prefix {
+            predicates.push(format!(
+                "(module_path = '{}' OR module_path LIKE '{}::%')",
+                module.replace('\'', "''"),
+                like_literal(module)
+            ));
+        }
+        if !self.symbol_kinds.is_empty() {
+            let kinds: Vec<String> = self
+                .symbol_kinds
+                .iter()
+                .map(|kind| format!("'{}'", kind.replace('\'', "''")))
+                .collect();
+            predicates.push(format!("symbol_kind IN ({})", kinds.join(", ")));
+        }
+        if let Some(glob) = &self.file_glob {
+            predicates.push(format!("file_path LIKE '{}'", glob_to_like(glob.as_str())));
+        }
+        if let Some(is_test) = self.tests.flag() {
+            predicates.push(format!("is_test = {is_test}"));
+        }
+        if predicates.is_empty() {
+            None
+        } else {
+            Some(predicates.join(" AND "))
+        }
+    }
+}
+
+/// Heuristic test-code classification used by [`TestScope`]
+///
+/// A chunk counts as test code when its file sits under a `tests/`
+/// directory or is named `tests.rs` / `*_test.rs` / `*_tests.rs`, when it is
+/// a `tests` module, or when its leading attributes mention `test`
+/// (`#[test]`, `#[tokio::test]`, `#[cfg(test)]`).
+pub fn is_test_chunk(chunk: &CodeChunk) -> bool {
+    let context = &chunk.context;
+    let path = &context.file_path;
+    if path.components().any(|c| c.as_os_str() == "tests") {
+        return true;
+    }
+    if let Some(stem) = path.file_stem().and_then(|s| s.to_str())
+        && (stem == "tests" || stem.ends_with("_test") || stem.ends_with("_tests"))
+    {
+        return true;
+    }
+    if context.symbol_kind == "module" && context.symbol_name == "tests" {
+        return true;
+    }
+    chunk
+        .content
+        .lines()
+        .map(str::trim_start)
+        .take_while(|line| line.starts_with("#[") || line.starts_with("///") || line.is_empty())
+        .any(|line| line.starts_with("#[") && line.contains("test"))
+}
+
+fn glob_options() -> MatchOptions {
+    MatchOptions {
+        require_literal_separator: true,
+        ..MatchOptions::new()
+    }
+}
+
+fn path_str(path: &Path) -> String {
+    path.display().to_string().trim_end_matches('/').to_string()
+}
+
+/// Escape a literal for the Tantivy (regex-syntax) dialect
+fn escape_regex(literal: &str) -> String {
+    let mut out = String::with_capacity(literal.len());
+    for c in literal.chars() {
+        if "\\.+*?()|[]{}^$".contains(c) {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Translate a glob to a full-match regex with the same semantics as
+/// [`glob_options`]: `*`/`?` stay within one path segment, `**` crosses them.
+fn glob_to_regex(glob: &str) -> String {
+    let mut out = String::new();
+    let mut chars = glob.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    out.push_str("(.*/)?");
+                } else {
+                    out.push_str(".*");
+                }
+            }
+            '*' => out.push_str("[^/]*"),
+            '?' => out.push_str("[^/]"),
+            '[' => {
+                out.push('[');
+                if matches!(chars.peek(), Some('!') | Some('^')) {
+                    chars.next();
+                    out.push('^');
+                }
+                for c in chars.by_ref() {
+                    if c == ']' {
+                        break;
+                    }
+                    if c == '\\' {
+                        out.push('\\');
+                    }
+                    out.push(c);
+                }
+                out.push(']');
+            }
+            other => out.push_str(&escape_regex(&other.to_string())),
+        }
+    }
+    out
+}
+
+/// Escape a literal for a SQL `LIKE` pattern. `%` / `_` become the
+/// single-character wildcard instead of relying on an ESCAPE clause, which
+/// only widens the match.
+#[cfg(feature = "vector-store")]
+fn like_literal(literal: &str) -> String {
+    literal
+        .chars()
+        .map(|c| match c {
+            '%' | '_' => "_".to_string(),
+            '\'' => "''".to_string(),
+            other => other.to_string(),
+        })
+        .collect()
+}
+
+/// Widen a glob into a SQL `LIKE` pattern that matches a superset of it
+#[cfg(feature = "vector-store")]
+fn glob_to_like(glob: &str) -> String {
+    let mut out = String::new();
+    let mut chars = glob.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' => {
+                while chars.peek() == Some(&'*') {
+                    chars.next();
+                }
+                out.push('%');
+            }
+            '?' => out.push('_'),
+            '[' => {
+                for c in chars.by_ref() {
+                    if c == ']' {
+                        break;
+                    }
+                }
+                out.push('_');
+            }
+            other => out.push_str(&like_literal(&other.to_string())),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chunker::{ChunkContext, ChunkId};
+
+    fn chunk(
+        file_path: &str,
+        module_path: &[&str],
+        name: &str,
+        kind: &str,
+        content: &str,
+    ) -> CodeChunk {
+        CodeChunk {
+            id: ChunkId::new(),
+            content: content.to_string(),
+            context: ChunkContext {
+                file_path: PathBuf::from(file_path),
+                module_path: module_path.iter().map(|s| s.to_string()).collect(),
+                symbol_name: name.to_string(),
+                symbol_kind: kind.to_string(),
+                docstring: None,
+                imports: vec![],
+                outgoing_calls: vec![],
+                parent_symbol_name: None,
+                split_part: None,
+                split_total: None,
+                line_start: 1,
+                line_end: 1,
+            },
+            overlap_prev: None,
+            overlap_next: None,
+        }
+    }
+
+    #[test]
+    fn matches_combines_every_set_field() {
+        let parser = chunk(
+            "/ws/crates/engine/src/parser/mod.rs",
+            &["crate", "parser"],
+            "parse",
+            "function",
+            "fn parse() {}",
+        );
+        let filter = SearchFilter {
+            path_prefix: Some(PathBuf::from("/ws/crates/engine")),
+            module_prefix: Some("crate::parser".to_string()),
+            symbol_kinds: vec!["function".to_string(), "struct".to_string()],
+            file_glob: Some(Pattern::new("/ws/crates/*/src/**/*.rs").unwrap()),
+            tests: TestScope::Exclude,
+        };
+        assert!(filter.matches(&parser));
+
+        let mut other_kind = parser.clone();
+        other_kind.context.symbol_kind = "impl".to_string();
+        assert!(!filter.matches(&other_kind));
+
+        let mut sibling_module = parser.clone();
+        sibling_module.context.module_path = vec!["crate".to_string(), "parser_utils".to_string()];
+        assert!(!filter.matches(&sibling_module));
+
+        let mut other_crate = parser.clone();
+        other_crate.context.file_path = PathBuf::from("/ws/crates/engine-macros/src/parser/mod.rs");
+        assert!(!filter.matches(&other_crate));
+
+        // `*` does not cross directories.
+        let shallow = SearchFilter {
+            file_glob: Some(Pattern::new("/ws/crates/*.rs").unwrap()),
+            ..Default::default()
+        };
+        assert!(!shallow.matches(&parser));
+        assert!(SearchFilter::default().matches(&parser));
+    }
+
+    #[test]
+    fn describe_lists_set_fields_only() {
+        assert!(SearchFilter::default().describe().is_empty());
+        let filter = SearchFilter {
+            module_prefix: Some("crate::parser".to_string()),
+            symbol_kinds: vec!["function".to_string(), "struct".to_string()],
+            tests: TestScope::Only,
+            ..Default::default()
+        };
+        assert_eq!(
+            filter.describe(),
+            vec!["module_prefix=crate::parser", "symbol_kind=function|struct", "tests=only"]
+        );
+    }
+
+    #[test]
+    fn test_scope_uses_paths_modules_and_attributes() {
+        let integration = chunk(
+            "/ws/tests/api.rs",
+            &["api"],
+            "roundtrip",
+            "function",
+            "fn roundtrip() {}",
+        );
+        let unit = chunk(
+            "/ws/src/lib.rs",
+            &["crate", "lib"],
+            "parses",
+            "function",
+            "#[tokio::test]\nasync fn parses() {}",
+        );
+        let module = chunk("/ws/src/lib.rs", &["crate", "lib"], "tests", "module", "mod tests {}");
+        let production = chunk(
+            "/ws/src/lib.rs",
+            &["crate", "lib"],
+            "test_connection",
+            "function",
+            "/// Checks the link.\n#[inline]\npub fn test_connection() {}",
+        );
+        for test_chunk in [&integration, &unit, &module] {
+            assert!(is_test_chunk(test_chunk), "{}", test_chunk.context.symbol_name);
+        }
+        assert!(!is_test_chunk(&production));
+
+        let exclude = SearchFilter {
+            tests: TestScope::Exclude,
+            ..Default::default()
+        };
+        assert!(!exclude.matches(&unit));
+        assert!(exclude.matches(&production));
+        let only = SearchFilter {
+            tests: TestScope::Only,
+            ..Default::default()
+        };
+        assert!(only.matches(&integration));
+        assert!(!only.matches(&production));
+        assert_eq!(TestScope::parse("EXCLUDE"), Some(TestScope::Exclude));
+        assert_eq!(TestScope::parse("skip"), None);
+    }
+
+    #[test]
+    fn glob_translations_keep_segment_semantics() {
+        assert_eq!(glob_to_regex("/ws/src/*.rs"), "/ws/src/[^/]*\\.rs");
+        assert_eq!(glob_to_regex("/ws/**/mod.rs"), "/ws/(.*/)?mod\\.rs");
+        assert_eq!(glob_to_regex("/ws/[!t]?.rs"), "/ws/[^t][^/]\\.rs");
+    }
+
+    #[cfg(feature = "vector-store")]
+    #[test]
+    fn lance_predicate_widens_like_wildcards() {
+        let filter = SearchFilter {
+            path_prefix: Some(PathBuf::from("/ws/my_crate")),
+            module_prefix: Some("crate::o'neil".to_string()),
+            symbol_kinds: vec!["function".to_string()],
+            file_glob: Some(Pattern::new("/ws/**/a?.rs").unwrap()),
+            tests: TestScope::Exclude,
+        };
+        assert_eq!(
+            filter.lance_predicate().unwrap(),
+            "file_path LIKE '/ws/my_crate/%' \
+             AND (module_path = 'crate::o''neil' OR module_path LIKE 'crate::o''neil::%') \
+             AND symbol_kind IN ('function') \
+             AND file_path LIKE '/ws/%/a_.rs' \
+             AND is_test = false"
+        );
+        let tests_only = SearchFilter {
+            tests: TestScope::Only,
+            ..Default::default()
+        };
+        assert_eq!(tests_only.lance_predicate().as_deref(), Some("is_test = true"));
+        assert_eq!(SearchFilter::default().lance_predicate(), None);
+    }
+}
@@ -4,6 +4,9 @@
 
 mod bm25;
 mod error;
+mod explain;
+mod filter;
+mod rerank;
 #[cfg(feature = "hybrid-search")]
 mod resilient;
 #[cfg(feature = "hybrid-search")]
@@ -11,6 +14,14 @@ mod rrf_tuner;
 
 pub use bm25::Bm25Search;
 pub use error::SearchError;
+pub use explain::{
+    Bm25HitExplanation, FieldMatch, HitExplanation, SearchExplanation, SearchMode,
+    VectorHitExplanation, explain_hit, matched_terms, rrf_contribution,
+};
+pub use filter::{SearchFilter, TestScope, is_test_chunk};
+#[cfg(feature = "embeddings")]
+pub use rerank::{CrossEncoderModel, CrossEncoderReranker};
+pub use rerank::{DEFAULT_RERANK_DEPTH, FeatureReranker, Reranker, rerank};
 #[cfg(feature = "hybrid-search")]
 pub use resilient::ResilientHybridSearch;
 
@@ -18,10 +29,12 @@ use crate::chunker::{ChunkId, CodeChunk};
 #[cfg(feature = "hybrid-search")]
 use crate::embeddings::EmbeddingGenerator;
 #[cfg(feature = "hybrid-search")]
-use crate::vector_store::{VectorStore, VectorSearchResult};
+use crate::vector_store::{AnnSearchParams, VectorStore, VectorSearchResult};
 use serde::{Deserialize, Serialize};
 #[cfg(feature = "hybrid-search")]
 use std::collections::HashMap;
+#[cfg(feature = "hybrid-search")]
+use std::sync::Arc;
 
 /// Configuration for hybrid search
 #[derive(Debug, Clone)]
@@ -34,6 +47,14 @@ pub struct HybridSearchConfig {
     pub rrf_k: f32,
     /// Number of candidates to fetch from each engine
     pub candidate_count: usize,
+    /// Number of fused results the reranker rescores, when one is set
+    pub rerank_depth: usize,
+    /// IVF partitions probed per vector query once the store has an ANN
+    /// index (`None`: LanceDB's default)
+    pub nprobes: Option<usize>,
+    /// Re-rank `refine_factor` times the vector candidates with exact
+    /// distances once the store has an ANN index (`None`: no refinement)
+    pub refine_factor: Option<u32>,
 }
 
 impl Default for HybridSearchConfig {
@@ -43,6 +64,9 @@ impl Default for HybridSearchConfig {
             vector_weight: 0.5,
             rrf_k: 60.0,
             candidate_count: 100,
+            rerank_depth: DEFAULT_RERANK_DEPTH,
+            nprobes: None,
+            refine_factor: None,
         }
     }
 }
@@ -62,6 +86,9 @@ pub struct SearchResult {
     pub bm25_rank: Option<usize>,
     /// Rank in vector results (if found)
     pub vector_rank: Option<usize>,
+    /// Reranker score (if the result was reranked)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rerank_score: Option<f32>,
     /// The actual chunk
     pub chunk: CodeChunk,
 }
@@ -71,6 +98,7 @@ pub struct SearchResult {
 pub(crate) struct VectorSearch {
     embedding_generator: EmbeddingGenerator,
     vector_store: VectorStore,
+    params: AnnSearchParams,
 }
 
 #[cfg(feature = "hybrid-search")]
@@ -83,14 +111,31 @@ impl VectorSearch {
         Self {
             embedding_generator,
             vector_store,
+            params: AnnSearchParams::default(),
         }
     }
 
+    /// Tune queries against the store's ANN index with `params`
+    pub fn with_params(mut self, params: AnnSearchParams) -> Self {
+        self.params = params;
+        self
+    }
+
     /// Search for similar chunks using a text query
     pub async fn search(
         &self,
         query: &str,
         limit: usize,
+    ) -> Result<Vec<VectorSearchResult>, SearchError> {
+        self.search_filtered(query, &SearchFilter::default(), limit).await
+    }
+
+    /// Search for similar chunks restricted by a [`SearchFilter`]
+    pub async fn search_filtered(
+        &self,
+        query: &str,
+        filter: &SearchFilter,
+        limit: usize,
     ) -> Result<Vec<VectorSearchResult>, SearchError> {
         // Generate embedding for the query (instruction-prefixed).
         let mut query_embeddings = self
@@ -104,7 +149,7 @@ impl VectorSearch {
             ))?;
 
         // Search in vector store
-        self.vector_store.search(query_embedding, limit).await
+        self.vector_store.search_filtered(query_embedding, filter, limit, &self.params).await
             .map_err(SearchError::VectorStore)
     }
 }
@@ -115,6 +160,7 @@ pub struct HybridSearch {
     vector_search: VectorSearch,
     bm25_search: Option<Bm25Search>,
     config: HybridSearchConfig,
+    reranker: Option<Arc<dyn Reranker>>,
 }
 
 #[cfg(feature = "hybrid-search")]
@@ -127,9 +173,15 @@ impl HybridSearch {
         config: HybridSearchConfig,
     ) -> Self {
         Self {
-            vector_search: VectorSearch::new(embedding_generator, vector_store),
+            vector_search: VectorSearch::new(embedding_generator, vector_store).with_params(
+                AnnSearchParams {
+                    nprobes: config.nprobes,
+                    refine_factor: config.refine_factor,
+                },
+            ),
             bm25_search,
             config,
+            reranker: None,
         }
     }
 
@@ -142,6 +194,13 @@ impl HybridSearch {
         Self::new(embedding_generator, vector_store, bm25_search, HybridSearchConfig::default())
     }
 
+    /// Rescore the top `rerank_depth` fused results with `reranker`
+    /// before paging
+    pub fn with_reranker(mut self, reranker: Arc<dyn Reranker>) -> Self {
+        self.reranker = Some(reranker);
+        self
+    }
+
     /// Perform hybrid search combining BM25 and vector search
     ///
     /// If BM25 search is available, runs both searches in parallel and merges
@@ -163,17 +222,115 @@ impl HybridSearch {
         limit: usize,
         rrf_k: f32,
     ) -> Result<Vec<SearchResult>, SearchError> {
+        self.fused_search(query, &SearchFilter::default(), limit, 0, rrf_k)
+            .await
+    }
+
+    /// Perform hybrid search restricted by a [`SearchFilter`], skipping the
+    /// first `offset` fused results
+    ///
+    /// The filter is applied to the BM25 and vector candidates before RRF
+    /// fusion, so ranks reflect positions among matching chunks only.
+    pub async fn search_filtered(
+        &self,
+        query: &str,
+        filter: &SearchFilter,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<SearchResult>, SearchError> {
+        self.fused_search(query, filter, limit, offset, self.config.rrf_k)
+            .await
+    }
+
+    /// [`Self::search_filtered`] plus a [`SearchExplanation`] of the
+    /// returned page
+    ///
+    /// Each hit that BM25 returned is looked up again to attach Tantivy's
+    /// score tree, so this costs one index lookup per hit on top of the
+    /// search itself.
+    pub async fn search_explained(
+        &self,
+        query: &str,
+        filter: &SearchFilter,
+        limit: usize,
+        offset: usize,
+    ) -> Result<(Vec<SearchResult>, SearchExplanation), SearchError> {
+        let results = self.search_filtered(query, filter, limit, offset).await?;
+        let config = &self.config;
+        let mut hits: Vec<HitExplanation> = results
+            .iter()
+            .enumerate()
+            .map(|(idx, result)| {
+                explain_hit(
+                    query,
+                    offset + idx + 1,
+                    result,
+                    config.bm25_weight,
+                    config.vector_weight,
+                    config.rrf_k,
+                )
+            })
+            .collect();
+
+        let bm25_hits: Vec<ChunkId> = results
+            .iter()
+            .filter(|result| result.bm25_rank.is_some())
+            .map(|result| result.chunk_id)
+            .collect();
+        if let Some(bm25) = self.bm25_search.clone().filter(|_| !bm25_hits.is_empty()) {
+            let query_clone = query.to_string();
+            let filter_clone = filter.clone();
+            let mut trees = tokio::task::spawn_blocking(move || {
+                bm25.explain(&query_clone, &filter_clone, &bm25_hits)
+            })
+            .await
+            .map_err(|e| SearchError::Bm25(Box::new(e)))?
+            .map_err(SearchError::Bm25)?;
+            for hit in &mut hits {
+                if let (Some(side), Some(tree)) = (hit.bm25.as_mut(), trees.remove(&hit.chunk_id)) {
+                    side.tantivy = serde_json::to_value(&tree).ok();
+                }
+            }
+        }
+
+        let bm25_missing = self.bm25_search.is_none();
+        let explanation = SearchExplanation {
+            query: query.to_string(),
+            mode: if bm25_missing { SearchMode::VectorOnly } else { SearchMode::Hybrid },
+            bm25_missing,
+            bm25_weight: config.bm25_weight,
+            vector_weight: config.vector_weight,
+            rrf_k: config.rrf_k,
+            candidate_count: config.candidate_count.max(offset + limit),
+            reranker: self.reranker.as_ref().map(|reranker| reranker.name()),
+            filters: filter.describe(),
+            hits,
+        };
+        Ok((results, explanation))
+    }
+
+    async fn fused_search(
+        &self,
+        query: &str,
+        filter: &SearchFilter,
+        limit: usize,
+        offset: usize,
+        rrf_k: f32,
+    ) -> Result<Vec<SearchResult>, SearchError> {
+        // Deep pages need more candidates than the default pool.
+        let candidate_count = self.config.candidate_count.max(offset + limit);
+
         // Run vector search and BM25 search in parallel (if BM25 is available)
         let (vector_results, bm25_results) = if let Some(bm25) = &self.bm25_search {
             // BM25 search is sync, run in blocking task
             let bm25_clone = bm25.clone();
             let query_clone = query.to_string();
-            let candidate_count = self.config.candidate_count;
+            let filter_clone = filter.clone();
 
             let (vector_future, bm25_future) = tokio::join!(
-                self.vector_search.search(query, candidate_count),
+                self.vector_search.search_filtered(query, filter, candidate_count),
                 tokio::task::spawn_blocking(move || {
-                    bm25_clone.search(&query_clone, candidate_count)
+                    bm25_clone.search_filtered(&query_clone, &filter_clone, candidate_count)
                 })
             );
 
@@ -187,7 +344,7 @@ impl HybridSearch {
             // No BM25 search available, vector only
             let vector_results = self
                 .vector_search
-                .search(query, self.config.candidate_count)
+                .search_filtered(query, filter, candidate_count)
                 .await?;
 
             (vector_results, vec![])
@@ -196,8 +353,23 @@ impl HybridSearch {
         // Apply Reciprocal Rank Fusion
         let merged = self.reciprocal_rank_fusion_with_k(&vector_results, &bm25_results, rrf_k);
 
-        // Return top N results
-        Ok(merged.into_iter().take(limit).collect())
+        // Rerank at least the requested page, so paging stays consistent
+        let merged = match &self.reranker {
+            Some(reranker) => {
+                let reranker = Arc::clone(reranker);
+                let query = query.to_string();
+                let depth = self.config.rerank_depth.max(offset + limit);
+                tokio::task::spawn_blocking(move || {
+                    rerank(reranker.as_ref(), &query, merged, depth)
+                })
+                .await
+                .map_err(|e| SearchError::Rerank(e.to_string()))??
+            }
+            None => merged,
+        };
+
+        // Return the requested page
+        Ok(merged.into_iter().skip(offset).take(limit).collect())
     }
 
     /// Reciprocal Rank Fusion with custom k parameter
@@ -231,6 +403,7 @@ impl HybridSearch {
                 vector_score: Some(result.score),
                 bm25_rank: None,
                 vector_rank: Some(rank + 1),
+                rerank_score: None,
                 chunk: result.chunk,
             })
             .collect())
@@ -308,6 +481,7 @@ fn reciprocal_rank_fusion_core(
             vector_score: s.vector_score,
             bm25_rank: s.bm25_rank,
             vector_rank: s.vector_rank,
+            rerank_score: None,
             chunk: s.chunk,
         })
         .collect();
@@ -364,6 +538,7 @@ mod tests {
         assert_eq!(config.vector_weight, 0.5);
         assert_eq!(config.rrf_k, 60.0);
         assert_eq!(config.candidate_count, 100);
+        assert_eq!(config.rerank_depth, DEFAULT_RERANK_DEPTH);
     }
 
     #[test]
@@ -394,6 +569,7 @@ mod tests {
             schema.module_path => chunk1.context.module_path.join("::"),
             schema.docstring => chunk1.context.docstring.clone().unwrap_or_default(),
             schema.chunk_json => chunk1_json,
+            schema.is_test => is_test_chunk(&chunk1),
         )).unwrap();
 
         index_writer.commit().unwrap();
@@ -419,6 +595,7 @@ mod tests {
             vector_score: Some(0.92),
             bm25_rank: Some(2),
             vector_rank: Some(3),
+            rerank_score: None,
             chunk,
         };
 
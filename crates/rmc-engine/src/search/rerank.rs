@@ -0,0 +1,436 @@
+//! Second-stage reranking of fused search results
+//!
+//! RRF fusion only knows ranks, so a test that mentions a symbol often
+//! outranks the symbol's definition. A [`Reranker`] rescores the top of the
+//! fused list with the query in hand; [`rerank`] applies it and leaves the
+//! tail below the rerank depth in fused order.
+//!
+//! Two implementations:
+//! - [`FeatureReranker`]: deterministic, model-free scoring from exact symbol
+//!   matches, doc and path term overlap, test-code demotion and an optional
+//!   caller-supplied fan-in (e.g. hypergraph usage counts)
+//! - [`CrossEncoderReranker`] (`embeddings` feature): a local ONNX
+//!   cross-encoder run through fastembed
+
+use std::collections::HashSet;
+
+use super::filter::is_test_chunk;
+use super::{SearchError, SearchResult};
+use crate::chunker::CodeChunk;
+use crate::tokenizer::CodeTokenizer;
+
+/// Fused results rescored by default; the rest keep their fused order
+pub const DEFAULT_RERANK_DEPTH: usize = 50;
+
+/// Second-stage scorer for fused search results
+pub trait Reranker: Send + Sync {
+    /// Short name for logs and responses
+    fn name(&self) -> &'static str;
+
+    /// Relevance of each result to `query`, higher is better
+    ///
+    /// Returns one score per result, in the order of `results`.
+    fn score(&self, query: &str, results: &[SearchResult]) -> Result<Vec<f32>, SearchError>;
+}
+
+/// Rescore the first `depth` results with `reranker` and sort them by the
+/// new score
+///
+/// Each rescored result gets `rerank_score` set; ties keep their fused
+/// order. Results past `depth` are appended unchanged.
+pub fn rerank(
+    reranker: &dyn Reranker,
+    query: &str,
+    mut results: Vec<SearchResult>,
+    depth: usize,
+) -> Result<Vec<SearchResult>, SearchError> {
+    let tail = results.split_off(depth.min(results.len()));
+    let scores = reranker.score(query, &results)?;
+    if scores.len() != results.len() {
+        return Err(SearchError::Rerank(format!(
+            "{} returned {} scores for {} results",
+            reranker.name(),
+            scores.len(),
+            results.len()
+        )));
+    }
+
+    for (result, score) in results.iter_mut().zip(scores) {
+        result.rerank_score = Some(score);
+    }
+    results.sort_by(|a, b| {
+        b.rerank_score
+            .partial_cmp(&a.rerank_score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    results.extend(tail);
+    Ok(results)
+}
+
+/// Weight of an exact match between the query and the symbol name
+const EXACT_SYMBOL_WEIGHT: f32 = 3.0;
+/// Weight of the share of query words found in the symbol name
+const SYMBOL_WORDS_WEIGHT: f32 = 1.5;
+/// Weight of the share of query words found in the docstring
+const DOC_WORDS_WEIGHT: f32 = 1.0;
+/// Weight of the share of query words found in the file or module path
+const PATH_WORDS_WEIGHT: f32 = 0.5;
+/// Per natural-log unit of fan-in
+const FAN_IN_WEIGHT: f32 = 0.3;
+/// Subtracted from test code unless the query asks for tests
+const TEST_PENALTY: f32 = 1.5;
+/// Weight of the fused rank, so ties fall back to RRF order
+const FUSED_RANK_WEIGHT: f32 = 0.5;
+
+type FanIn = Box<dyn Fn(&CodeChunk) -> Option<u32> + Send + Sync>;
+
+/// Deterministic, model-free reranker
+///
+/// Scores each result from what the query says about the chunk's symbol:
+/// an exact symbol-name match dominates, then the share of query words in
+/// the symbol name, docstring and path. Test code is demoted unless the
+/// query mentions tests, and a fan-in source (see
+/// [`FeatureReranker::with_fan_in`]) lifts heavily used definitions. The
+/// fused rank is a small prior, so results the features cannot tell apart
+/// keep their RRF order.
+#[derive(Default)]
+pub struct FeatureReranker {
+    fan_in: Option<FanIn>,
+}
+
+impl FeatureReranker {
+    /// Reranker without a fan-in source
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use `fan_in` to look up how many places use a chunk's symbol
+    ///
+    /// `None` means unknown and adds nothing.
+    pub fn with_fan_in(
+        mut self,
+        fan_in: impl Fn(&CodeChunk) -> Option<u32> + Send + Sync + 'static,
+    ) -> Self {
+        self.fan_in = Some(Box::new(fan_in));
+        self
+    }
+
+    fn score_one(&self, query: &QueryTerms, chunk: &CodeChunk, rank: usize) -> f32 {
+        let context = &chunk.context;
+        let mut score = 0.0;
+
+        let symbol = context.symbol_name.to_lowercase();
+        if !query.compact.is_empty()
+            && (symbol == query.text || squash(&symbol) == query.compact)
+        {
+            score += EXACT_SYMBOL_WEIGHT;
+        }
+        score += SYMBOL_WORDS_WEIGHT * query.coverage(&context.symbol_name);
+        if let Some(doc) = &context.docstring {
+            score += DOC_WORDS_WEIGHT * query.coverage(doc);
+        }
+        let path = format!(
+            "{} {}",
+            context.file_path.display(),
+            context.module_path.join("::")
+        );
+        score += PATH_WORDS_WEIGHT * query.coverage(&path);
+
+        if let Some(fan_in) = self.fan_in.as_ref().and_then(|fan_in| fan_in(chunk)) {
+            score += FAN_IN_WEIGHT * (1.0 + fan_in as f32).ln();
+        }
+        if !query.mentions_tests && is_test_chunk(chunk) {
+            score -= TEST_PENALTY;
+        }
+
+        score + FUSED_RANK_WEIGHT / (1 + rank) as f32
+    }
+}
+
+impl Reranker for FeatureReranker {
+    fn name(&self) -> &'static str {
+        "features"
+    }
+
+    fn score(&self, query: &str, results: &[SearchResult]) -> Result<Vec<f32>, SearchError> {
+        let query = QueryTerms::new(query);
+        Ok(results
+            .iter()
+            .enumerate()
+            .map(|(rank, result)| self.score_one(&query, &result.chunk, rank))
+            .collect())
+    }
+}
+
+/// Query words as the code tokenizer splits them
+struct QueryTerms {
+    /// Trimmed, lowercased query
+    text: String,
+    /// Query with everything but letters and digits removed
+    compact: String,
+    words: HashSet<String>,
+    mentions_tests: bool,
+}
+
+impl QueryTerms {
+    fn new(query: &str) -> Self {
+        let text = query.trim().to_lowercase();
+        // `Parser::parse` asks for `parse`, not for `parserparse`.
+        let last_segment = text.rsplit("::").next().unwrap_or(&text);
+        let words: HashSet<String> = sub_words(&text).into_iter().collect();
+        let mentions_tests = words.iter().any(|word| word.starts_with("test"));
+        Self {
+            compact: squash(last_segment),
+            text: last_segment.to_string(),
+            words,
+            mentions_tests,
+        }
+    }
+
+    /// Share of query words that occur in `text`
+    fn coverage(&self, text: &str) -> f32 {
+        if self.words.is_empty() {
+            return 0.0;
+        }
+        let found: HashSet<String> = sub_words(text).into_iter().collect();
+        let hits = self.words.iter().filter(|word| found.contains(*word)).count();
+        hits as f32 / self.words.len() as f32
+    }
+}
+
+/// Lowercased sub-words of `text` (`parse_file` → `parse`, `file`)
+fn sub_words(text: &str) -> Vec<String> {
+    CodeTokenizer::for_queries().terms(text)
+}
+
+fn squash(text: &str) -> String {
+    text.chars()
+        .filter(char::is_ascii_alphanumeric)
+        .collect::<String>()
+        .to_ascii_lowercase()
+}
+
+#[cfg(feature = "embeddings")]
+pub use cross_encoder::{CrossEncoderModel, CrossEncoderReranker};
+
+#[cfg(feature = "embeddings")]
+mod cross_encoder {
+    use std::sync::Mutex;
+
+    use fastembed::{RerankInitOptions, RerankerModel, TextRerank};
+
+    use super::{Reranker, SearchError, SearchResult};
+    use crate::chunker::CodeChunk;
+
+    /// Characters of chunk content passed to the cross-encoder; the model
+    /// truncates at its token limit anyway
+    const MAX_DOCUMENT_CHARS: usize = 2_000;
+
+    /// Cross-encoder models available through fastembed
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    pub enum CrossEncoderModel {
+        /// `jinaai/jina-reranker-v1-turbo-en`: small English model, fast on CPU
+        #[default]
+        JinaTurboEn,
+        /// `BAAI/bge-reranker-base`: larger, slower, usually more precise
+        BgeBase,
+    }
+
+    impl CrossEncoderModel {
+        /// Parse `"jina-turbo-en"` or `"bge-base"`
+        pub fn parse(value: &str) -> Option<Self> {
+            match value.trim().to_ascii_lowercase().as_str() {
+                "jina-turbo-en" => Some(Self::JinaTurboEn),
+                "bge-base" => Some(Self::BgeBase),
+                _ => None,
+            }
+        }
+
+        fn to_fastembed(self) -> RerankerModel {
+            match self {
+                Self::JinaTurboEn => RerankerModel::JINARerankerV1TurboEn,
+                Self::BgeBase => RerankerModel::BGERerankerBase,
+            }
+        }
+    }
+
+    /// Local ONNX cross-encoder reranker
+    ///
+    /// Scores (query, chunk) pairs jointly, which catches relevance that
+    /// neither BM25 nor the bi-encoder embedding sees. Loading downloads the
+    /// model on first use; scoring is CPU-bound, so async callers should run
+    /// it on a blocking thread.
+    pub struct CrossEncoderReranker {
+        model: Mutex<TextRerank>,
+    }
+
+    impl CrossEncoderReranker {
+        /// Load `model`
+        pub fn new(model: CrossEncoderModel) -> Result<Self, SearchError> {
+            tracing::info!(model = ?model, "loading cross-encoder reranker");
+            let options = RerankInitOptions::new(model.to_fastembed())
+                .with_show_download_progress(false);
+            let model = TextRerank::try_new(options)
+                .map_err(|e| SearchError::Rerank(format!("failed to load reranker: {e}")))?;
+            Ok(Self {
+                model: Mutex::new(model),
+            })
+        }
+    }
+
+    impl Reranker for CrossEncoderReranker {
+        fn name(&self) -> &'static str {
+            "cross_encoder"
+        }
+
+        fn score(&self, query: &str, results: &[SearchResult]) -> Result<Vec<f32>, SearchError> {
+            if results.is_empty() {
+                return Ok(Vec::new());
+            }
+            let documents: Vec<String> = results
+                .iter()
+                .map(|result| rerank_document(&result.chunk))
+                .collect();
+            let documents: Vec<&str> = documents.iter().map(String::as_str).collect();
+
+            let ranked = self
+                .model
+                .lock()
+                .map_err(|_| SearchError::Rerank("reranker lock poisoned".to_string()))?
+                .rerank(query, documents, false, None)
+                .map_err(|e| SearchError::Rerank(format!("reranking failed: {e}")))?;
+
+            let mut scores = vec![f32::NEG_INFINITY; results.len()];
+            for hit in ranked {
+                if let Some(score) = scores.get_mut(hit.index) {
+                    *score = hit.score;
+                }
+            }
+            Ok(scores)
+        }
+    }
+
+    /// Compact text the cross-encoder judges: symbol, location, doc, code
+    fn rerank_document(chunk: &CodeChunk) -> String {
+        let context = &chunk.context;
+        let mut document = format!(
+            "{} ({}) in {}\n",
+            context.symbol_name,
+            context.symbol_kind,
+            context.file_path.display()
+        );
+        if let Some(doc) = &context.docstring {
+            document.push_str(doc);
+            document.push('\n');
+        }
+        let mut end = chunk.content.len().min(MAX_DOCUMENT_CHARS);
+        while !chunk.content.is_char_boundary(end) {
+            end -= 1;
+        }
+        document.push_str(&chunk.content[..end]);
+        document
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chunker::{ChunkContext, ChunkId};
+    use std::path::PathBuf;
+
+    fn result(symbol_name: &str, file_path: &str, content: &str) -> SearchResult {
+        SearchResult {
+            chunk_id: ChunkId::new(),
+            score: 0.0,
+            bm25_score: None,
+            vector_score: None,
+            bm25_rank: None,
+            vector_rank: None,
+            rerank_score: None,
+            chunk: CodeChunk {
+                id: ChunkId::new(),
+                content: content.to_string(),
+                context: ChunkContext {
+                    file_path: PathBuf::from(file_path),
+                    module_path: vec!["crate".to_string()],
+                    symbol_name: symbol_name.to_string(),
+                    symbol_kind: "function".to_string(),
+                    docstring: None,
+                    imports: vec![],
+                    outgoing_calls: vec![],
+                    parent_symbol_name: None,
+                    split_part: None,
+                    split_total: None,
+                    line_start: 1,
+                    line_end: 1,
+                },
+                overlap_prev: None,
+                overlap_next: None,
+            },
+        }
+    }
+
+    fn names(results: &[SearchResult]) -> Vec<&str> {
+        results
+            .iter()
+            .map(|r| r.chunk.context.symbol_name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_feature_reranker_puts_definition_above_tests() {
+        let fused = vec![
+            result("parses_config", "tests/config.rs", "#[test]\nfn parses_config() {}"),
+            result("load", "src/loader.rs", "fn load() {}"),
+            result("parse_config", "src/config.rs", "fn parse_config() {}"),
+        ];
+
+        let reranked = rerank(&FeatureReranker::new(), "parse_config", fused, 10).unwrap();
+
+        assert_eq!(names(&reranked), vec!["parse_config", "load", "parses_config"]);
+        assert!(reranked.iter().all(|r| r.rerank_score.is_some()));
+    }
+
+    #[test]
+    fn test_feature_reranker_keeps_tests_when_asked_for() {
+        let fused = vec![
+            result("parse_config", "src/config.rs", "fn parse_config() {}"),
+            result("parse_config_test", "tests/config.rs", "#[test]\nfn parse_config_test() {}"),
+        ];
+
+        let reranked = rerank(&FeatureReranker::new(), "parse config test", fused, 10).unwrap();
+
+        assert_eq!(reranked[0].chunk.context.symbol_name, "parse_config_test");
+    }
+
+    #[test]
+    fn test_feature_reranker_uses_fan_in() {
+        let fused = vec![
+            result("open_store", "src/a.rs", "fn open_store() {}"),
+            result("open_store", "src/b.rs", "fn open_store() {}"),
+        ];
+        let reranker = FeatureReranker::new().with_fan_in(|chunk| {
+            (chunk.context.file_path == PathBuf::from("src/b.rs")).then_some(40)
+        });
+
+        let reranked = rerank(&reranker, "open store", fused, 10).unwrap();
+
+        assert_eq!(reranked[0].chunk.context.file_path, PathBuf::from("src/b.rs"));
+    }
+
+    #[test]
+    fn test_rerank_leaves_tail_in_fused_order() {
+        let fused = vec![
+            result("helper", "src/a.rs", "fn helper() {}"),
+            result("target", "src/b.rs", "fn target() {}"),
+            result("other", "src/c.rs", "fn other() {}"),
+            result("target", "src/d.rs", "fn target() {}"),
+        ];
+
+        let reranked = rerank(&FeatureReranker::new(), "target", fused, 2).unwrap();
+
+        assert_eq!(names(&reranked), vec!["target", "helper", "other", "target"]);
+        assert!(reranked[2].rerank_score.is_none());
+        assert!(reranked[3].rerank_score.is_none());
+    }
+}
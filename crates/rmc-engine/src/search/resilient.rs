@@ -151,6 +151,7 @@ impl ResilientHybridSearch {
                 vector_score: None,
                 bm25_rank: None,
                 vector_rank: None,
+                rerank_score: None,
                 chunk,
             })
             .collect())
@@ -193,6 +194,7 @@ impl ResilientHybridSearch {
                 vector_score: Some(result.score),
                 bm25_rank: None,
                 vector_rank: Some(rank + 1),
+                rerank_score: None,
                 chunk: result.chunk,
             })
             .collect())
@@ -352,6 +352,7 @@ mod tests {
             vector_score: Some(score),
             bm25_rank: None,
             vector_rank: Some(1),
+            rerank_score: None,
             chunk: CodeChunk {
                 id: ChunkId::new(),
                 content: "test content".to_string(),
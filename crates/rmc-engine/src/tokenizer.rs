@@ -0,0 +1,288 @@
+//! Code-aware Tantivy tokenizer for chunk-level BM25 search
+//!
+//! Tantivy's `default` tokenizer only splits on non-alphanumeric characters,
+//! so `EmbeddingBatcher` is a single term and a search for `embedding batch`
+//! never reaches it. The code tokenizer splits identifiers into lowercased
+//! sub-words on `snake_case`, `CamelCase` and letter/digit boundaries, and
+//! treats `::` like any other separator so path segments become consecutive
+//! positions.
+//!
+//! The index-side tokenizer also emits the whole lowercased identifier at
+//! the position of its first sub-word, so `embeddingbatcher` still matches.
+//! The query-side tokenizer emits sub-words only: a multi-word identifier in
+//! a query (`parse_file`, `EmbeddingBatcher`) becomes a phrase query over the
+//! indexed sub-word positions, which matches `parse_file_complete` too.
+
+use tantivy::Index;
+use tantivy::tokenizer::{
+    RemoveLongFilter, TextAnalyzer, Token, TokenStream, Tokenizer, TokenizerManager,
+};
+
+/// Name the chunk schema's text fields are indexed with
+pub const CODE_TOKENIZER: &str = "code";
+
+/// Tokens at least this many bytes long (blobs, minified literals) are dropped
+const MAX_TOKEN_BYTES: usize = 100;
+
+/// Identifier-splitting tokenizer; see the module docs
+#[derive(Clone, Debug)]
+pub struct CodeTokenizer {
+    /// Emit the whole identifier alongside its sub-words (index side only)
+    keep_identifiers: bool,
+    tokens: Vec<Token>,
+}
+
+impl CodeTokenizer {
+    /// Tokenizer for documents: whole identifiers plus their sub-words
+    pub fn for_indexing() -> Self {
+        Self {
+            keep_identifiers: true,
+            tokens: Vec::new(),
+        }
+    }
+
+    /// Tokenizer for queries: sub-words only
+    pub fn for_queries() -> Self {
+        Self {
+            keep_identifiers: false,
+            tokens: Vec::new(),
+        }
+    }
+
+    /// Lowercased token texts of `text`, in order
+    pub fn terms(&mut self, text: &str) -> Vec<String> {
+        self.tokens.clear();
+        tokenize(text, self.keep_identifiers, &mut self.tokens);
+        self.tokens.drain(..).map(|token| token.text).collect()
+    }
+}
+
+impl Default for CodeTokenizer {
+    fn default() -> Self {
+        Self::for_indexing()
+    }
+}
+
+impl Tokenizer for CodeTokenizer {
+    type TokenStream<'a> = CodeTokenStream<'a>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> CodeTokenStream<'a> {
+        self.tokens.clear();
+        tokenize(text, self.keep_identifiers, &mut self.tokens);
+        CodeTokenStream {
+            tokens: self.tokens.as_mut_slice(),
+            index: 0,
+        }
+    }
+}
+
+/// Token stream over the tokens [`CodeTokenizer`] computed up front
+pub struct CodeTokenStream<'a> {
+    tokens: &'a mut [Token],
+    /// One past the current token; 0 before the first `advance`
+    index: usize,
+}
+
+impl TokenStream for CodeTokenStream<'_> {
+    fn advance(&mut self) -> bool {
+        if self.index < self.tokens.len() {
+            self.index += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn token(&self) -> &Token {
+        &self.tokens[self.index - 1]
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        &mut self.tokens[self.index - 1]
+    }
+}
+
+/// Register the index-side code tokenizer on `index`
+///
+/// Tantivy does not persist tokenizers, so this must run on every `Index`
+/// handle for a chunk index before writing to it or parsing queries with
+/// [`QueryParser::for_index`](tantivy::query::QueryParser::for_index).
+pub fn register_code_tokenizer(index: &Index) {
+    index
+        .tokenizers()
+        .register(CODE_TOKENIZER, analyzer(CodeTokenizer::for_indexing()));
+}
+
+/// Tokenizer manager for parsing BM25 queries
+///
+/// Tantivy's built-in tokenizers plus the query-side code tokenizer, so
+/// indexes written before the code tokenizer keep working.
+pub fn query_tokenizers() -> TokenizerManager {
+    let manager = TokenizerManager::default();
+    manager.register(CODE_TOKENIZER, analyzer(CodeTokenizer::for_queries()));
+    manager
+}
+
+fn analyzer(tokenizer: CodeTokenizer) -> TextAnalyzer {
+    TextAnalyzer::builder(tokenizer)
+        .filter(RemoveLongFilter::limit(MAX_TOKEN_BYTES))
+        .build()
+}
+
+fn tokenize(text: &str, keep_identifiers: bool, out: &mut Vec<Token>) {
+    let mut position = 0;
+    let mut parts: Vec<(usize, usize)> = Vec::new();
+
+    for (start, word) in words(text) {
+        parts.clear();
+        sub_words(word, &mut parts);
+        if parts.is_empty() {
+            continue;
+        }
+
+        if keep_identifiers && parts.len() > 1 {
+            let (from, to) = (parts[0].0, parts[parts.len() - 1].1);
+            out.push(Token {
+                offset_from: start + from,
+                offset_to: start + to,
+                position,
+                text: word[from..to].to_lowercase(),
+                position_length: parts.len(),
+            });
+        }
+        for (index, &(from, to)) in parts.iter().enumerate() {
+            out.push(Token {
+                offset_from: start + from,
+                offset_to: start + to,
+                position: position + index,
+                text: word[from..to].to_lowercase(),
+                position_length: 1,
+            });
+        }
+        position += parts.len();
+    }
+}
+
+/// Maximal runs of alphanumerics and `_`, with their byte offsets
+fn words(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    let mut rest = text.char_indices().peekable();
+    std::iter::from_fn(move || {
+        let (start, _) = rest.find(|&(_, c)| is_word(c))?;
+        let mut end = text.len();
+        while let Some(&(offset, c)) = rest.peek() {
+            if !is_word(c) {
+                end = offset;
+                break;
+            }
+            rest.next();
+        }
+        Some((start, &text[start..end]))
+    })
+}
+
+/// Byte ranges of the sub-words in `word`: `_`-separated segments, each
+/// split further on case and letter/digit changes
+fn sub_words(word: &str, out: &mut Vec<(usize, usize)>) {
+    let mut offset = 0;
+    for segment in word.split('_') {
+        if !segment.is_empty() {
+            split_segment(segment, offset, out);
+        }
+        offset += segment.len() + 1;
+    }
+}
+
+fn split_segment(segment: &str, base: usize, out: &mut Vec<(usize, usize)>) {
+    let chars: Vec<(usize, char)> = segment.char_indices().collect();
+    let mut start = 0;
+    for i in 1..chars.len() {
+        let (prev, (offset, cur)) = (chars[i - 1].1, chars[i]);
+        let next = chars.get(i + 1).map(|&(_, c)| c);
+        // `parseFile` | `HTTPServer` (last capital of a run starts a word) |
+        // `sha256` / `u32x4`
+        let boundary = (prev.is_lowercase() && cur.is_uppercase())
+            || (prev.is_uppercase() && cur.is_uppercase() && next.is_some_and(char::is_lowercase))
+            || prev.is_alphabetic() != cur.is_alphabetic();
+        if boundary {
+            out.push((base + start, base + offset));
+            start = offset;
+        }
+    }
+    out.push((base + start, base + segment.len()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(tokenizer: &mut CodeTokenizer, text: &str) -> Vec<(String, usize)> {
+        let mut stream = tokenizer.token_stream(text);
+        let mut out = Vec::new();
+        while stream.advance() {
+            let token = stream.token();
+            out.push((token.text.clone(), token.position));
+        }
+        out
+    }
+
+    fn owned(tokens: &[(&str, usize)]) -> Vec<(String, usize)> {
+        tokens.iter().map(|&(text, pos)| (text.to_string(), pos)).collect()
+    }
+
+    #[test]
+    fn test_splits_camel_case_and_keeps_identifier() {
+        let mut tokenizer = CodeTokenizer::for_indexing();
+        assert_eq!(
+            texts(&mut tokenizer, "EmbeddingBatcher::new"),
+            owned(&[
+                ("embeddingbatcher", 0),
+                ("embedding", 0),
+                ("batcher", 1),
+                ("new", 2),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_splits_snake_case_acronyms_and_digits() {
+        let mut tokenizer = CodeTokenizer::for_indexing();
+        assert_eq!(
+            texts(&mut tokenizer, "parse_file_complete(HTTPServer, sha256)"),
+            owned(&[
+                ("parse_file_complete", 0),
+                ("parse", 0),
+                ("file", 1),
+                ("complete", 2),
+                ("httpserver", 3),
+                ("http", 3),
+                ("server", 4),
+                ("sha256", 5),
+                ("sha", 5),
+                ("256", 6),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_query_side_emits_sub_words_only() {
+        let mut tokenizer = CodeTokenizer::for_queries();
+        assert_eq!(
+            texts(&mut tokenizer, "crate::parser::parse_file"),
+            owned(&[("crate", 0), ("parser", 1), ("parse", 2), ("file", 3)])
+        );
+    }
+
+    #[test]
+    fn test_offsets_point_into_source_text() {
+        let text = "let __ = __private_helperFn;";
+        let mut tokenizer = CodeTokenizer::for_indexing();
+        let mut stream = tokenizer.token_stream(text);
+        let mut spans = Vec::new();
+        while stream.advance() {
+            let token = stream.token();
+            spans.push(&text[token.offset_from..token.offset_to]);
+        }
+        assert_eq!(spans, vec!["let", "private_helperFn", "private", "helper", "Fn"]);
+    }
+}
@@ -0,0 +1,310 @@
+//! Approximate-nearest-neighbour index policy
+//!
+//! Below a few tens of thousands of rows a flat scan over the vector column
+//! is fast enough and exact. Past [`AnnIndexConfig::min_rows`] the backend
+//! builds an ANN index on the vector column, then keeps it current after
+//! upserts: new rows are folded into the existing index cheaply, and once
+//! they make up a large share of the table the index is retrained so its
+//! partitions still fit the data.
+//!
+//! [`AnnIndexConfig::plan`] decides what to do from row counts alone, so
+//! the policy is testable without a table; backends execute the returned
+//! [`AnnIndexAction`]. Builds and retrains can be held back (see
+//! [`AnnIndexConfig::rebuild`]) so watcher passes never block on one.
+
+use serde::Serialize;
+
+/// Rows below which ANN indexing is not worth it by default
+pub const DEFAULT_ANN_MIN_ROWS: usize = 50_000;
+
+/// Share of unindexed rows (relative to indexed ones) that triggers a
+/// retrain instead of an incremental refresh
+pub const DEFAULT_RETRAIN_RATIO: f32 = 0.25;
+
+/// `refine_factor` queries use against a PQ index unless configured: PQ
+/// distances are coarse enough to reorder the top hits
+pub const DEFAULT_PQ_REFINE_FACTOR: u32 = 10;
+
+/// Fewest rows PQ codebook training accepts (one per centroid)
+const MIN_TRAINING_ROWS: usize = 256;
+
+/// ANN index type built on the vector column
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnnIndexKind {
+    /// IVF partitions with product-quantized vectors: smallest on disk,
+    /// needs `refine_factor` for exact top results
+    #[default]
+    IvfPq,
+    /// IVF partitions with an HNSW graph over scalar-quantized vectors:
+    /// better recall at the same `nprobes`, larger and slower to build
+    IvfHnswSq,
+}
+
+impl AnnIndexKind {
+    /// Parse `"ivf_pq"` or `"ivf_hnsw_sq"`
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "ivf_pq" => Some(Self::IvfPq),
+            "ivf_hnsw_sq" => Some(Self::IvfHnswSq),
+            _ => None,
+        }
+    }
+
+    /// `refine_factor` to query this kind of index with when none is
+    /// configured
+    pub fn default_refine_factor(self) -> Option<u32> {
+        match self {
+            Self::IvfPq => Some(DEFAULT_PQ_REFINE_FACTOR),
+            Self::IvfHnswSq => None,
+        }
+    }
+}
+
+/// When and how the vector column gets an ANN index
+#[derive(Debug, Clone)]
+pub struct AnnIndexConfig {
+    /// Build and maintain an index at all
+    pub enabled: bool,
+    pub kind: AnnIndexKind,
+    /// Rows the table must hold before the first index is built
+    pub min_rows: usize,
+    /// Retrain once unindexed rows exceed this share of indexed ones
+    pub retrain_ratio: f32,
+    /// IVF partitions; `None` derives them from the row count
+    pub num_partitions: Option<u32>,
+    /// PQ sub-vectors; `None` derives them from the dimension
+    pub num_sub_vectors: Option<u32>,
+    /// Allow building or retraining the index in this pass. When `false`
+    /// (watcher passes) a due build is [`AnnIndexAction::Deferred`] to the
+    /// next full index or sync run.
+    pub rebuild: bool,
+}
+
+impl Default for AnnIndexConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            kind: AnnIndexKind::default(),
+            min_rows: DEFAULT_ANN_MIN_ROWS,
+            retrain_ratio: DEFAULT_RETRAIN_RATIO,
+            num_partitions: None,
+            num_sub_vectors: None,
+            rebuild: true,
+        }
+    }
+}
+
+/// Indexed and unindexed row counts of an existing vector index
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AnnIndexCoverage {
+    pub indexed_rows: usize,
+    pub unindexed_rows: usize,
+}
+
+/// What [`AnnIndexConfig::plan`] decided for the vector column
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum AnnIndexAction {
+    /// Indexing is disabled; searches scan
+    Disabled,
+    /// Too few rows for an index; searches scan
+    BelowThreshold { rows: usize, min_rows: usize },
+    /// Build the first index
+    Create { kind: AnnIndexKind, rows: usize },
+    /// Fold unindexed rows into the existing index
+    Refresh { unindexed_rows: usize },
+    /// Rebuild the index over all rows
+    Retrain { kind: AnnIndexKind, rows: usize },
+    /// A build or retrain is due but [`AnnIndexConfig::rebuild`] is off;
+    /// new rows stay unindexed and are scanned until the next full run
+    Deferred { rows: usize },
+    /// Every row is already indexed
+    UpToDate,
+}
+
+impl AnnIndexConfig {
+    /// Config that never builds an index
+    pub fn disabled() -> Self {
+        Self {
+            enabled: false,
+            ..Self::default()
+        }
+    }
+
+    /// Decide what to do for a table of `rows` rows whose vector index, if
+    /// any, has `coverage`
+    ///
+    /// An existing index is kept even if the table shrinks below
+    /// `min_rows`; dropping it would only make searches slower.
+    pub fn plan(&self, rows: usize, coverage: Option<AnnIndexCoverage>) -> AnnIndexAction {
+        if !self.enabled {
+            return AnnIndexAction::Disabled;
+        }
+        let Some(coverage) = coverage else {
+            let min_rows = self.min_rows.max(MIN_TRAINING_ROWS);
+            return if rows < min_rows {
+                AnnIndexAction::BelowThreshold { rows, min_rows }
+            } else if !self.rebuild {
+                AnnIndexAction::Deferred { rows }
+            } else {
+                AnnIndexAction::Create {
+                    kind: self.kind,
+                    rows,
+                }
+            };
+        };
+        if coverage.unindexed_rows == 0 {
+            return AnnIndexAction::UpToDate;
+        }
+        let grown = coverage.unindexed_rows as f32 > coverage.indexed_rows as f32 * self.retrain_ratio;
+        // Refreshing instead would fold the rows in and reset the ratio,
+        // so a held-back retrain leaves them unindexed.
+        if grown && !self.rebuild {
+            AnnIndexAction::Deferred { rows }
+        } else if grown {
+            AnnIndexAction::Retrain {
+                kind: self.kind,
+                rows,
+            }
+        } else {
+            AnnIndexAction::Refresh {
+                unindexed_rows: coverage.unindexed_rows,
+            }
+        }
+    }
+
+    /// IVF partitions for `rows` rows: the configured count, else about
+    /// `sqrt(rows)`
+    pub fn partitions_for(&self, rows: usize) -> u32 {
+        self.num_partitions
+            .unwrap_or_else(|| ((rows as f64).sqrt().round() as u32).max(1))
+    }
+
+    /// PQ sub-vectors for `dim`-dimensional vectors: the configured count,
+    /// else the largest divisor of `dim` not above `dim / 16`
+    pub fn sub_vectors_for(&self, dim: usize) -> u32 {
+        if let Some(count) = self.num_sub_vectors {
+            return count;
+        }
+        let target = (dim / 16).max(1);
+        (1..=target).rev().find(|n| dim % n == 0).unwrap_or(1) as u32
+    }
+}
+
+/// Query-time ANN knobs; ignored while the table has no vector index
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AnnSearchParams {
+    /// IVF partitions probed per query; `None` uses LanceDB's default.
+    /// Higher is slower and closer to exact.
+    pub nprobes: Option<usize>,
+    /// Re-rank `limit * refine_factor` candidates with exact distances;
+    /// `None` returns the quantized distances as-is
+    pub refine_factor: Option<u32>,
+}
+
+impl AnnSearchParams {
+    /// Params for an index of `kind`: `refine_factor` falls back to
+    /// [`AnnIndexKind::default_refine_factor`]
+    pub fn for_kind(kind: AnnIndexKind, nprobes: Option<usize>, refine_factor: Option<u32>) -> Self {
+        Self {
+            nprobes,
+            refine_factor: refine_factor.or(kind.default_refine_factor()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plan_waits_for_threshold_then_creates() {
+        let config = AnnIndexConfig {
+            min_rows: 1_000,
+            ..AnnIndexConfig::default()
+        };
+        assert_eq!(
+            config.plan(999, None),
+            AnnIndexAction::BelowThreshold { rows: 999, min_rows: 1_000 }
+        );
+        assert_eq!(
+            config.plan(1_000, None),
+            AnnIndexAction::Create { kind: AnnIndexKind::IvfPq, rows: 1_000 }
+        );
+        // PQ training needs at least one row per centroid.
+        let tiny = AnnIndexConfig { min_rows: 10, ..AnnIndexConfig::default() };
+        assert!(matches!(tiny.plan(100, None), AnnIndexAction::BelowThreshold { .. }));
+        assert_eq!(AnnIndexConfig::disabled().plan(1_000_000, None), AnnIndexAction::Disabled);
+    }
+
+    #[test]
+    fn plan_refreshes_small_deltas_and_retrains_large_ones() {
+        let config = AnnIndexConfig::default();
+        let coverage = |indexed_rows, unindexed_rows| {
+            Some(AnnIndexCoverage { indexed_rows, unindexed_rows })
+        };
+        assert_eq!(config.plan(100_000, coverage(100_000, 0)), AnnIndexAction::UpToDate);
+        assert_eq!(
+            config.plan(110_000, coverage(100_000, 10_000)),
+            AnnIndexAction::Refresh { unindexed_rows: 10_000 }
+        );
+        assert_eq!(
+            config.plan(140_000, coverage(100_000, 40_000)),
+            AnnIndexAction::Retrain { kind: AnnIndexKind::IvfPq, rows: 140_000 }
+        );
+        // A shrunken table keeps its index.
+        assert_eq!(config.plan(10, coverage(10, 0)), AnnIndexAction::UpToDate);
+    }
+
+    #[test]
+    fn plan_defers_builds_when_rebuild_is_off() {
+        let config = AnnIndexConfig {
+            rebuild: false,
+            ..AnnIndexConfig::default()
+        };
+        let coverage = |indexed_rows, unindexed_rows| {
+            Some(AnnIndexCoverage { indexed_rows, unindexed_rows })
+        };
+        assert_eq!(config.plan(60_000, None), AnnIndexAction::Deferred { rows: 60_000 });
+        assert_eq!(
+            config.plan(140_000, coverage(100_000, 40_000)),
+            AnnIndexAction::Deferred { rows: 140_000 }
+        );
+        assert_eq!(
+            config.plan(110_000, coverage(100_000, 10_000)),
+            AnnIndexAction::Refresh { unindexed_rows: 10_000 }
+        );
+        assert!(matches!(config.plan(10, None), AnnIndexAction::BelowThreshold { .. }));
+    }
+
+    #[test]
+    fn search_params_refine_pq_by_default() {
+        let pq = AnnSearchParams::for_kind(AnnIndexKind::IvfPq, Some(32), None);
+        assert_eq!(pq.nprobes, Some(32));
+        assert_eq!(pq.refine_factor, Some(DEFAULT_PQ_REFINE_FACTOR));
+        let explicit = AnnSearchParams::for_kind(AnnIndexKind::IvfPq, None, Some(3));
+        assert_eq!(explicit.refine_factor, Some(3));
+        let hnsw = AnnSearchParams::for_kind(AnnIndexKind::IvfHnswSq, None, None);
+        assert_eq!(hnsw.refine_factor, None);
+        assert_eq!(AnnIndexKind::parse(" IVF_HNSW_SQ "), Some(AnnIndexKind::IvfHnswSq));
+        assert_eq!(AnnIndexKind::parse("flat"), None);
+    }
+
+    #[test]
+    fn derived_partitions_and_sub_vectors() {
+        let config = AnnIndexConfig::default();
+        assert_eq!(config.partitions_for(250_000), 500);
+        assert_eq!(config.sub_vectors_for(1024), 64);
+        assert_eq!(config.sub_vectors_for(4096), 256);
+        assert_eq!(config.sub_vectors_for(384), 24);
+        assert_eq!(config.sub_vectors_for(8), 1);
+        let fixed = AnnIndexConfig {
+            num_partitions: Some(64),
+            num_sub_vectors: Some(32),
+            ..AnnIndexConfig::default()
+        };
+        assert_eq!(fixed.partitions_for(250_000), 64);
+        assert_eq!(fixed.sub_vectors_for(1024), 32);
+    }
+}
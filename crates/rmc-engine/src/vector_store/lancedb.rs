@@ -5,28 +5,40 @@
 
 use async_trait::async_trait;
 use arrow_array::{
-    Array, FixedSizeListArray, Float32Array, RecordBatch, RecordBatchIterator, RecordBatchReader,
-    StringArray,
+    Array, BooleanArray, FixedSizeListArray, Float32Array, RecordBatch, RecordBatchIterator,
+    RecordBatchReader, StringArray,
 };
 use arrow_schema::{DataType, Field, Schema};
 use futures::TryStreamExt;
 use lancedb::connect;
 use lancedb::index::scalar::BTreeIndexBuilder;
+use lancedb::index::vector::{IvfHnswSqIndexBuilder, IvfPqIndexBuilder};
 use lancedb::index::Index;
-use lancedb::query::{ExecutableQuery, QueryBase};
+use lancedb::query::{ExecutableQuery, QueryBase, Select};
+use lancedb::table::{OptimizeAction, OptimizeOptions};
 use lancedb::DistanceType;
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Instant;
 
 use crate::chunker::{ChunkId, CodeChunk};
 use crate::embeddings::Embedding;
+use crate::search::{SearchFilter, is_test_chunk};
+use super::ann::{
+    AnnIndexAction, AnnIndexConfig, AnnIndexCoverage, AnnIndexKind, AnnSearchParams,
+};
 use super::error::VectorStoreError;
 use super::traits::VectorStoreBackend;
 use super::VectorSearchResult;
 
 const TABLE_NAME: &str = "vectors";
 const METADATA_FILE: &str = "metadata.json";
+const VECTOR_COLUMN: &str = "vector";
+/// IDs (or paths) per `IN (...)` predicate when looking up or deleting
+/// rows, to keep the filter expression a reasonable size on large files.
+const ID_LOOKUP_BATCH: usize = 512;
 
 /// On-disk fingerprint stored next to the LanceDB table. Records which
 /// embedder produced the vectors so that a switch is detected on reopen
@@ -232,7 +244,7 @@ impl LanceDbBackend {
         Schema::new(vec![
             Field::new("id", DataType::Utf8, false),
             Field::new(
-                "vector",
+                VECTOR_COLUMN,
                 DataType::FixedSizeList(
                     Arc::new(Field::new("item", DataType::Float32, true)),
                     vector_dim as i32,
@@ -243,6 +255,7 @@ impl LanceDbBackend {
             Field::new("file_path", DataType::Utf8, false),
             Field::new("symbol_kind", DataType::Utf8, false),
             Field::new("module_path", DataType::Utf8, false),
+            Field::new("is_test", DataType::Boolean, false),
         ])
     }
 
@@ -271,6 +284,7 @@ impl LanceDbBackend {
             let file_path_array = StringArray::from(Vec::<String>::new());
             let symbol_kind_array = StringArray::from(Vec::<String>::new());
             let module_path_array = StringArray::from(Vec::<String>::new());
+            let is_test_array = BooleanArray::from(Vec::<bool>::new());
 
             let batch = RecordBatch::try_new(
                 schema.clone(),
@@ -281,6 +295,7 @@ impl LanceDbBackend {
                     Arc::new(file_path_array),
                     Arc::new(symbol_kind_array),
                     Arc::new(module_path_array),
+                    Arc::new(is_test_array),
                 ],
             )
             .map_err(|e| VectorStoreError::backend(format!("Failed to create batch: {}", e)))?;
@@ -352,6 +367,7 @@ impl LanceDbBackend {
         let mut file_paths = Vec::with_capacity(n);
         let mut symbol_kinds = Vec::with_capacity(n);
         let mut module_paths = Vec::with_capacity(n);
+        let mut is_tests = Vec::with_capacity(n);
 
         // Single pass over all chunks
         for (id, embedding, chunk) in chunks {
@@ -365,6 +381,7 @@ impl LanceDbBackend {
             file_paths.push(chunk.context.file_path.display().to_string());
             symbol_kinds.push(chunk.context.symbol_kind.clone());
             module_paths.push(chunk.context.module_path.join("::"));
+            is_tests.push(is_test_chunk(chunk));
         }
 
         // Build Arrow arrays from pre-allocated vectors
@@ -380,6 +397,7 @@ impl LanceDbBackend {
         let file_path_array = StringArray::from(file_paths);
         let symbol_kind_array = StringArray::from(symbol_kinds);
         let module_path_array = StringArray::from(module_paths);
+        let is_test_array = BooleanArray::from(is_tests);
 
         RecordBatch::try_new(
             schema,
@@ -390,11 +408,156 @@ impl LanceDbBackend {
                 Arc::new(file_path_array),
                 Arc::new(symbol_kind_array),
                 Arc::new(module_path_array),
+                Arc::new(is_test_array),
             ],
         )
         .map_err(|e| VectorStoreError::backend(format!("Failed to create batch: {}", e)))
     }
 
+    /// Search result rows that pass `filter`, with cosine distance turned
+    /// into a similarity score
+    fn batches_to_results(
+        batches: Vec<RecordBatch>,
+        filter: &SearchFilter,
+    ) -> Result<Vec<VectorSearchResult>, VectorStoreError> {
+        let mut search_results = Vec::new();
+
+        for batch in batches {
+            let id_col = batch
+                .column_by_name("id")
+                .ok_or_else(|| VectorStoreError::query("Missing id column"))?;
+            let id_array = id_col
+                .as_any()
+                .downcast_ref::<StringArray>()
+                .ok_or_else(|| VectorStoreError::query("Invalid id column type"))?;
+
+            let chunk_json_col = batch
+                .column_by_name("chunk_json")
+                .ok_or_else(|| VectorStoreError::query("Missing chunk_json column"))?;
+            let chunk_json_array = chunk_json_col
+                .as_any()
+                .downcast_ref::<StringArray>()
+                .ok_or_else(|| VectorStoreError::query("Invalid chunk_json column type"))?;
+
+            // LanceDB adds _distance column for search results
+            let distance_col = batch
+                .column_by_name("_distance")
+                .ok_or_else(|| VectorStoreError::query("Missing _distance column"))?;
+            let distance_array = distance_col
+                .as_any()
+                .downcast_ref::<Float32Array>()
+                .ok_or_else(|| VectorStoreError::query("Invalid _distance column type"))?;
+
+            for i in 0..batch.num_rows() {
+                let id_str = id_array.value(i);
+                let chunk_json = chunk_json_array.value(i);
+                let distance = distance_array.value(i);
+
+                let chunk_id = ChunkId::from_string(id_str).map_err(|e| {
+                    VectorStoreError::serialization(format!("Invalid chunk ID: {:?}", e))
+                })?;
+
+                let chunk: CodeChunk = serde_json::from_str(chunk_json).map_err(|e| {
+                    VectorStoreError::serialization(format!("Failed to deserialize chunk: {}", e))
+                })?;
+                if !filter.matches(&chunk) {
+                    continue;
+                }
+
+                // Convert cosine distance to similarity score
+                // Cosine distance range is [0, 2], where 0 = identical, 2 = opposite
+                // Convert to similarity [0, 1] (standard cosine similarity range)
+                let score = 1.0 - (distance / 2.0);
+
+                search_results.push(VectorSearchResult {
+                    chunk_id,
+                    score,
+                    chunk,
+                });
+            }
+        }
+
+        Ok(search_results)
+    }
+
+    /// Delete the rows with `chunk_ids`, [`ID_LOOKUP_BATCH`] IDs per predicate
+    async fn delete_ids(
+        table: &lancedb::Table,
+        chunk_ids: &[ChunkId],
+    ) -> Result<(), VectorStoreError> {
+        for ids in chunk_ids.chunks(ID_LOOKUP_BATCH) {
+            table
+                .delete(&format!("id IN ({})", Self::id_list(ids)))
+                .await
+                .map_err(|e| VectorStoreError::backend(format!("Failed to delete chunks: {}", e)))?;
+        }
+        Ok(())
+    }
+
+    /// `'id', 'id', ...` for an `id IN (...)` predicate
+    fn id_list(chunk_ids: &[ChunkId]) -> String {
+        chunk_ids
+            .iter()
+            .map(|id| format!("'{}'", id.to_string()))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Indexed and unindexed rows of the index on the vector column, or
+    /// `None` while the column has no index
+    async fn vector_index_coverage(
+        &self,
+        table: &lancedb::Table,
+    ) -> Result<Option<AnnIndexCoverage>, VectorStoreError> {
+        let indices = table
+            .list_indices()
+            .await
+            .map_err(|e| VectorStoreError::query(format!("Failed to list indices: {}", e)))?;
+        let Some(index) = indices
+            .iter()
+            .find(|index| index.columns.iter().any(|column| column == VECTOR_COLUMN))
+        else {
+            return Ok(None);
+        };
+        let stats = table.index_stats(&index.name).await.map_err(|e| {
+            VectorStoreError::query(format!("Failed to read vector index stats: {}", e))
+        })?;
+        Ok(stats.map(|stats| AnnIndexCoverage {
+            indexed_rows: stats.num_indexed_rows,
+            unindexed_rows: stats.num_unindexed_rows,
+        }))
+    }
+
+    /// Build (or replace) the ANN index on the vector column over all
+    /// `rows` rows, with the cosine distance searches use
+    async fn build_vector_index(
+        &self,
+        table: &lancedb::Table,
+        config: &AnnIndexConfig,
+        rows: usize,
+    ) -> Result<(), VectorStoreError> {
+        let num_partitions = config.partitions_for(rows);
+        let index = match config.kind {
+            AnnIndexKind::IvfPq => Index::IvfPq(
+                IvfPqIndexBuilder::default()
+                    .distance_type(DistanceType::Cosine)
+                    .num_partitions(num_partitions)
+                    .num_sub_vectors(config.sub_vectors_for(self.vector_dim)),
+            ),
+            AnnIndexKind::IvfHnswSq => Index::IvfHnswSq(
+                IvfHnswSqIndexBuilder::default()
+                    .distance_type(DistanceType::Cosine)
+                    .num_partitions(num_partitions),
+            ),
+        };
+        table
+            .create_index(&[VECTOR_COLUMN], index)
+            .replace(true)
+            .execute()
+            .await
+            .map_err(|e| VectorStoreError::backend(format!("Failed to create vector index: {}", e)))
+    }
+
     /// Get table, returns error if not exists
     async fn get_table(&self) -> Result<lancedb::Table, VectorStoreError> {
         self.db
@@ -443,78 +606,164 @@ impl VectorStoreBackend for LanceDbBackend {
         &self,
         query_vector: Embedding,
         limit: usize,
+    ) -> Result<Vec<VectorSearchResult>, VectorStoreError> {
+        self.search_filtered(
+            query_vector,
+            &SearchFilter::default(),
+            limit,
+            &AnnSearchParams::default(),
+        )
+        .await
+    }
+
+    async fn search_filtered(
+        &self,
+        query_vector: Embedding,
+        filter: &SearchFilter,
+        limit: usize,
+        params: &AnnSearchParams,
     ) -> Result<Vec<VectorSearchResult>, VectorStoreError> {
         let table = self.get_table().await?;
+        // Pre-filter on the metadata columns; `matches` drops what the
+        // widened predicate lets through.
+        let predicate = filter.lance_predicate();
+
+        // Over-fetch until `limit` rows survive `matches` or the table runs
+        // out, so a widened `LIKE` cannot leave the page short.
+        let mut fetch = limit;
+        loop {
+            let mut query = table
+                .vector_search(query_vector.clone())
+                .map_err(|e| VectorStoreError::query(format!("Failed to create search: {}", e)))?
+                .distance_type(DistanceType::Cosine)
+                .limit(fetch);
+            // Both knobs are no-ops until the vector column has an index.
+            if let Some(nprobes) = params.nprobes {
+                query = query.nprobes(nprobes);
+            }
+            if let Some(refine_factor) = params.refine_factor {
+                query = query.refine_factor(refine_factor);
+            }
+            if let Some(predicate) = &predicate {
+                query = query.only_if(predicate.clone());
+            }
 
-        let results = table
-            .vector_search(query_vector)
-            .map_err(|e| VectorStoreError::query(format!("Failed to create search: {}", e)))?
-            .distance_type(DistanceType::Cosine)
-            .limit(limit)
-            .execute()
-            .await
-            .map_err(|e| VectorStoreError::query(format!("Search failed: {}", e)))?;
+            let results = query
+                .execute()
+                .await
+                .map_err(|e| VectorStoreError::query(format!("Search failed: {}", e)))?;
 
-        let batches: Vec<RecordBatch> = results
-            .try_collect()
+            let batches: Vec<RecordBatch> = results
+                .try_collect()
+                .await
+                .map_err(|e| VectorStoreError::query(format!("Failed to collect results: {}", e)))?;
+            let fetched: usize = batches.iter().map(RecordBatch::num_rows).sum();
+
+            let mut search_results = Self::batches_to_results(batches, filter)?;
+            if search_results.len() >= limit || fetched < fetch {
+                search_results.truncate(limit);
+                return Ok(search_results);
+            }
+            fetch = fetch.saturating_mul(2);
+        }
+    }
+
+    async fn get_embeddings(
+        &self,
+        chunk_ids: &[ChunkId],
+    ) -> Result<HashMap<ChunkId, Embedding>, VectorStoreError> {
+        let mut embeddings = HashMap::new();
+        if chunk_ids.is_empty() {
+            return Ok(embeddings);
+        }
+
+        let table = self.get_table().await?;
+        for ids in chunk_ids.chunks(ID_LOOKUP_BATCH) {
+            let results = table
+                .query()
+                .only_if(format!("id IN ({})", Self::id_list(ids)))
+                .select(Select::columns(&["id", "vector"]))
+                .limit(ids.len())
+                .execute()
+                .await
+                .map_err(|e| VectorStoreError::query(format!("Embedding lookup failed: {}", e)))?;
+            let batches: Vec<RecordBatch> = results.try_collect().await.map_err(|e| {
+                VectorStoreError::query(format!("Failed to collect results: {}", e))
+            })?;
+
+            for batch in batches {
+                let id_array = batch
+                    .column_by_name("id")
+                    .and_then(|col| col.as_any().downcast_ref::<StringArray>())
+                    .ok_or_else(|| VectorStoreError::query("Missing or invalid id column"))?;
+                let vector_array = batch
+                    .column_by_name("vector")
+                    .and_then(|col| col.as_any().downcast_ref::<FixedSizeListArray>())
+                    .ok_or_else(|| VectorStoreError::query("Missing or invalid vector column"))?;
+
+                for i in 0..batch.num_rows() {
+                    let chunk_id = ChunkId::from_string(id_array.value(i)).map_err(|e| {
+                        VectorStoreError::serialization(format!("Invalid chunk ID: {:?}", e))
+                    })?;
+                    let values = vector_array.value(i);
+                    let values = values
+                        .as_any()
+                        .downcast_ref::<Float32Array>()
+                        .ok_or_else(|| VectorStoreError::query("Invalid vector item type"))?;
+                    embeddings.insert(chunk_id, values.values().to_vec());
+                }
+            }
+        }
+
+        tracing::debug!(
+            "Found {} of {} chunk embeddings in LanceDB",
+            embeddings.len(),
+            chunk_ids.len()
+        );
+        Ok(embeddings)
+    }
+
+    async fn chunk_file_paths(&self) -> Result<HashMap<ChunkId, String>, VectorStoreError> {
+        let table = self.get_table().await?;
+        let rows = table
+            .count_rows(None)
             .await
-            .map_err(|e| VectorStoreError::query(format!("Failed to collect results: {}", e)))?;
+            .map_err(|e| VectorStoreError::query(format!("Failed to count rows: {}", e)))?;
+        let mut paths = HashMap::with_capacity(rows);
+        if rows == 0 {
+            return Ok(paths);
+        }
 
-        let mut search_results = Vec::new();
+        let results = table
+            .query()
+            .select(Select::columns(&["id", "file_path"]))
+            .limit(rows)
+            .execute()
+            .await
+            .map_err(|e| VectorStoreError::query(format!("Chunk listing failed: {}", e)))?;
+        let batches: Vec<RecordBatch> = results.try_collect().await.map_err(|e| {
+            VectorStoreError::query(format!("Failed to collect results: {}", e))
+        })?;
 
         for batch in batches {
-            let id_col = batch
+            let id_array = batch
                 .column_by_name("id")
-                .ok_or_else(|| VectorStoreError::query("Missing id column"))?;
-            let id_array = id_col
-                .as_any()
-                .downcast_ref::<StringArray>()
-                .ok_or_else(|| VectorStoreError::query("Invalid id column type"))?;
-
-            let chunk_json_col = batch
-                .column_by_name("chunk_json")
-                .ok_or_else(|| VectorStoreError::query("Missing chunk_json column"))?;
-            let chunk_json_array = chunk_json_col
-                .as_any()
-                .downcast_ref::<StringArray>()
-                .ok_or_else(|| VectorStoreError::query("Invalid chunk_json column type"))?;
-
-            // LanceDB adds _distance column for search results
-            let distance_col = batch
-                .column_by_name("_distance")
-                .ok_or_else(|| VectorStoreError::query("Missing _distance column"))?;
-            let distance_array = distance_col
-                .as_any()
-                .downcast_ref::<Float32Array>()
-                .ok_or_else(|| VectorStoreError::query("Invalid _distance column type"))?;
+                .and_then(|col| col.as_any().downcast_ref::<StringArray>())
+                .ok_or_else(|| VectorStoreError::query("Missing or invalid id column"))?;
+            let path_array = batch
+                .column_by_name("file_path")
+                .and_then(|col| col.as_any().downcast_ref::<StringArray>())
+                .ok_or_else(|| VectorStoreError::query("Missing or invalid file_path column"))?;
 
             for i in 0..batch.num_rows() {
-                let id_str = id_array.value(i);
-                let chunk_json = chunk_json_array.value(i);
-                let distance = distance_array.value(i);
-
-                let chunk_id = ChunkId::from_string(id_str).map_err(|e| {
+                let chunk_id = ChunkId::from_string(id_array.value(i)).map_err(|e| {
                     VectorStoreError::serialization(format!("Invalid chunk ID: {:?}", e))
                 })?;
-
-                let chunk: CodeChunk = serde_json::from_str(chunk_json).map_err(|e| {
-                    VectorStoreError::serialization(format!("Failed to deserialize chunk: {}", e))
-                })?;
-
-                // Convert cosine distance to similarity score
-                // Cosine distance range is [0, 2], where 0 = identical, 2 = opposite
-                // Convert to similarity [0, 1] (standard cosine similarity range)
-                let score = 1.0 - (distance / 2.0);
-
-                search_results.push(VectorSearchResult {
-                    chunk_id,
-                    score,
-                    chunk,
-                });
+                paths.insert(chunk_id, path_array.value(i).to_string());
             }
         }
 
-        Ok(search_results)
+        Ok(paths)
     }
 
     async fn delete_chunks(&self, chunk_ids: Vec<ChunkId>) -> Result<(), VectorStoreError> {
@@ -523,15 +772,7 @@ impl VectorStoreBackend for LanceDbBackend {
         }
 
         let table = self.get_table().await?;
-
-        // Build filter for deletion
-        let ids: Vec<String> = chunk_ids.iter().map(|id| format!("'{}'", id.to_string())).collect();
-        let filter = format!("id IN ({})", ids.join(", "));
-
-        table
-            .delete(&filter)
-            .await
-            .map_err(|e| VectorStoreError::backend(format!("Failed to delete chunks: {}", e)))?;
+        Self::delete_ids(&table, &chunk_ids).await?;
 
         tracing::debug!("Deleted {} chunks from LanceDB", chunk_ids.len());
         Ok(())
@@ -553,6 +794,119 @@ impl VectorStoreBackend for LanceDbBackend {
         Ok(())
     }
 
+    async fn delete_stale_chunks(
+        &self,
+        file_paths: &[String],
+        keep: &[ChunkId],
+    ) -> Result<(), VectorStoreError> {
+        if file_paths.is_empty() {
+            return Ok(());
+        }
+
+        // List the files' IDs and diff them here: an `id NOT IN (...)` over
+        // every kept ID would grow with the size of the batch.
+        let table = self.get_table().await?;
+        let keep: HashSet<ChunkId> = keep.iter().copied().collect();
+        let mut stale = Vec::new();
+        for paths in file_paths.chunks(ID_LOOKUP_BATCH) {
+            let paths: Vec<String> = paths
+                .iter()
+                .map(|path| format!("'{}'", path.replace('\'', "''")))
+                .collect();
+            let filter = format!("file_path IN ({})", paths.join(", "));
+            let rows = table
+                .count_rows(Some(filter.clone()))
+                .await
+                .map_err(|e| VectorStoreError::query(format!("Failed to count rows: {}", e)))?;
+            if rows == 0 {
+                continue;
+            }
+            let results = table
+                .query()
+                .only_if(filter)
+                .select(Select::columns(&["id"]))
+                .limit(rows)
+                .execute()
+                .await
+                .map_err(|e| VectorStoreError::query(format!("Chunk listing failed: {}", e)))?;
+            let batches: Vec<RecordBatch> = results.try_collect().await.map_err(|e| {
+                VectorStoreError::query(format!("Failed to collect results: {}", e))
+            })?;
+
+            for batch in batches {
+                let id_array = batch
+                    .column_by_name("id")
+                    .and_then(|col| col.as_any().downcast_ref::<StringArray>())
+                    .ok_or_else(|| VectorStoreError::query("Missing or invalid id column"))?;
+                for i in 0..batch.num_rows() {
+                    let chunk_id = ChunkId::from_string(id_array.value(i)).map_err(|e| {
+                        VectorStoreError::serialization(format!("Invalid chunk ID: {:?}", e))
+                    })?;
+                    if !keep.contains(&chunk_id) {
+                        stale.push(chunk_id);
+                    }
+                }
+            }
+        }
+        Self::delete_ids(&table, &stale).await?;
+
+        tracing::debug!(
+            "Deleted {} stale chunks for {} files",
+            stale.len(),
+            file_paths.len()
+        );
+        Ok(())
+    }
+
+    async fn ensure_vector_index(
+        &self,
+        config: &AnnIndexConfig,
+    ) -> Result<AnnIndexAction, VectorStoreError> {
+        let table = self.get_table().await?;
+        let rows = table
+            .count_rows(None)
+            .await
+            .map_err(|e| VectorStoreError::query(format!("Failed to count rows: {}", e)))?;
+        let coverage = if config.enabled {
+            self.vector_index_coverage(&table).await?
+        } else {
+            None
+        };
+
+        let action = config.plan(rows, coverage);
+        let start = Instant::now();
+        match action {
+            AnnIndexAction::Create { .. } | AnnIndexAction::Retrain { .. } => {
+                self.build_vector_index(&table, config, rows).await?;
+                tracing::info!(
+                    ?action,
+                    elapsed_ms = start.elapsed().as_millis() as u64,
+                    "Built ANN index on the vector column"
+                );
+            }
+            AnnIndexAction::Refresh { .. } => {
+                table
+                    .optimize(OptimizeAction::Index(OptimizeOptions::default()))
+                    .await
+                    .map_err(|e| {
+                        VectorStoreError::backend(format!("Failed to refresh vector index: {}", e))
+                    })?;
+                tracing::debug!(
+                    ?action,
+                    elapsed_ms = start.elapsed().as_millis() as u64,
+                    "Refreshed ANN index"
+                );
+            }
+            AnnIndexAction::Deferred { .. } => {
+                tracing::debug!(?action, "Deferred ANN index build to the next full run");
+            }
+            AnnIndexAction::Disabled
+            | AnnIndexAction::BelowThreshold { .. }
+            | AnnIndexAction::UpToDate => {}
+        }
+        Ok(action)
+    }
+
     async fn count(&self) -> Result<usize, VectorStoreError> {
         let table = self.get_table().await?;
 
@@ -762,10 +1116,165 @@ mod tests {
             .unwrap();
 
         assert_eq!(backend.count().await.unwrap(), 2);
+        let paths = backend.chunk_file_paths().await.unwrap();
+        assert_eq!(paths.get(&chunk1_id).map(String::as_str), Some("file1.rs"));
+        assert_eq!(paths.get(&chunk2_id).map(String::as_str), Some("file2.rs"));
 
         backend.delete_by_file_path("file1.rs").await.unwrap();
 
         assert_eq!(backend.count().await.unwrap(), 1);
+        let paths = backend.chunk_file_paths().await.unwrap();
+        assert_eq!(paths.keys().collect::<Vec<_>>(), vec![&chunk2_id]);
+    }
+
+    #[tokio::test]
+    async fn test_lancedb_get_embeddings_and_delete_stale_chunks() {
+        let temp_dir = TempDir::new().unwrap();
+        let backend = LanceDbBackend::new(temp_dir.path().to_path_buf(), 4, "test-embedder:v1")
+            .await
+            .unwrap();
+
+        let kept_id = ChunkId::new();
+        let kept = create_test_chunk(kept_id, "fn kept() {}", "file1.rs");
+        let stale_id = ChunkId::new();
+        let stale = create_test_chunk(stale_id, "fn stale() {}", "file1.rs");
+        let other_id = ChunkId::new();
+        let other = create_test_chunk(other_id, "fn other() {}", "file2.rs");
+
+        backend
+            .upsert_chunks(vec![
+                (kept_id, vec![0.1, 0.2, 0.3, 0.4], kept),
+                (stale_id, vec![0.5, 0.6, 0.7, 0.8], stale),
+                (other_id, vec![0.9, 1.0, 1.1, 1.2], other),
+            ])
+            .await
+            .unwrap();
+
+        let missing_id = ChunkId::new();
+        let found = backend.get_embeddings(&[kept_id, missing_id]).await.unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[&kept_id], vec![0.1, 0.2, 0.3, 0.4]);
+
+        // More kept IDs than fit one predicate
+        let mut keep: Vec<ChunkId> = (0..ID_LOOKUP_BATCH).map(|_| ChunkId::new()).collect();
+        keep.push(kept_id);
+        backend
+            .delete_stale_chunks(&["file1.rs".to_string()], &keep)
+            .await
+            .unwrap();
+
+        assert_eq!(backend.count().await.unwrap(), 2);
+        let found = backend
+            .get_embeddings(&[kept_id, stale_id, other_id])
+            .await
+            .unwrap();
+        assert!(found.contains_key(&kept_id));
+        assert!(!found.contains_key(&stale_id));
+        assert!(found.contains_key(&other_id));
+    }
+
+    #[tokio::test]
+    async fn test_lancedb_search_filtered() {
+        let temp_dir = TempDir::new().unwrap();
+        let backend = LanceDbBackend::new(temp_dir.path().to_path_buf(), 4, "test-embedder:v1")
+            .await
+            .unwrap();
+
+        let lib_id = ChunkId::new();
+        let lib = create_test_chunk(lib_id, "fn parse() {}", "/ws/core/src/lib.rs");
+        let struct_id = ChunkId::new();
+        let mut config = create_test_chunk(struct_id, "struct Config {}", "/ws/core/src/lib.rs");
+        config.context.symbol_kind = "struct".to_string();
+        let test_id = ChunkId::new();
+        let integration = create_test_chunk(test_id, "fn parses() {}", "/ws/core/tests/parse.rs");
+
+        backend
+            .upsert_chunks(vec![
+                (lib_id, vec![0.1, 0.2, 0.3, 0.4], lib),
+                (struct_id, vec![0.1, 0.2, 0.3, 0.5], config),
+                (test_id, vec![0.1, 0.2, 0.3, 0.6], integration),
+            ])
+            .await
+            .unwrap();
+
+        let filter = SearchFilter {
+            symbol_kinds: vec!["function".to_string()],
+            tests: crate::search::TestScope::Exclude,
+            ..Default::default()
+        };
+        let results = backend
+            .search_filtered(vec![0.1, 0.2, 0.3, 0.4], &filter, 10, &AnnSearchParams::default())
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].chunk_id, lib_id);
+
+        let filter = SearchFilter {
+            file_glob: Some(glob::Pattern::new("/ws/*/tests/*.rs").unwrap()),
+            ..Default::default()
+        };
+        let results = backend
+            .search_filtered(vec![0.1, 0.2, 0.3, 0.4], &filter, 10, &AnnSearchParams::default())
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].chunk_id, test_id);
+    }
+
+    #[tokio::test]
+    async fn test_lancedb_ann_index_lifecycle() {
+        let temp_dir = TempDir::new().unwrap();
+        let backend = LanceDbBackend::new(temp_dir.path().to_path_buf(), 4, "test-embedder:v1")
+            .await
+            .unwrap();
+        let rows = |range: std::ops::Range<usize>| {
+            range
+                .map(|i| {
+                    let id = ChunkId::new();
+                    let x = i as f32;
+                    let embedding = vec![x.sin(), x.cos(), (x * 0.5).sin(), 1.0];
+                    (id, embedding, create_test_chunk(id, "fn f() {}", "lib.rs"))
+                })
+                .collect::<Vec<_>>()
+        };
+        let config = AnnIndexConfig {
+            min_rows: 300,
+            ..AnnIndexConfig::default()
+        };
+
+        backend.upsert_chunks(rows(0..299)).await.unwrap();
+        assert_eq!(
+            backend.ensure_vector_index(&config).await.unwrap(),
+            AnnIndexAction::BelowThreshold { rows: 299, min_rows: 300 }
+        );
+
+        let first = rows(299..300);
+        let (wanted, embedding, _) = first[0].clone();
+        backend.upsert_chunks(first).await.unwrap();
+        assert_eq!(
+            backend.ensure_vector_index(&config).await.unwrap(),
+            AnnIndexAction::Create { kind: AnnIndexKind::IvfPq, rows: 300 }
+        );
+        assert_eq!(
+            backend.ensure_vector_index(&config).await.unwrap(),
+            AnnIndexAction::UpToDate
+        );
+
+        backend.upsert_chunks(rows(300..310)).await.unwrap();
+        assert_eq!(
+            backend.ensure_vector_index(&config).await.unwrap(),
+            AnnIndexAction::Refresh { unindexed_rows: 10 }
+        );
+
+        let params = AnnSearchParams {
+            nprobes: Some(20),
+            refine_factor: Some(10),
+        };
+        let results = backend
+            .search_filtered(embedding, &SearchFilter::default(), 5, &params)
+            .await
+            .unwrap();
+        assert_eq!(results[0].chunk_id, wanted);
     }
 
     #[tokio::test]
@@ -3,18 +3,25 @@
 //! Provides vector search capabilities for code chunks using embeddings.
 //! Uses LanceDB as the embedded, zero-config backend.
 
+mod ann;
 mod error;
 mod lancedb;
 mod traits;
 
 // Re-exports
+pub use ann::{
+    AnnIndexAction, AnnIndexConfig, AnnIndexCoverage, AnnIndexKind, AnnSearchParams,
+    DEFAULT_ANN_MIN_ROWS, DEFAULT_PQ_REFINE_FACTOR, DEFAULT_RETRAIN_RATIO,
+};
 pub use error::VectorStoreError;
 pub use lancedb::LanceDbBackend;
 pub use traits::VectorStoreBackend;
 
 use crate::chunker::{ChunkId, CodeChunk};
 use crate::embeddings::{Embedding, EmbeddingBackend};
+use crate::search::SearchFilter;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
 
@@ -137,6 +144,42 @@ impl VectorStore {
         self.backend.search(query_vector, limit).await
     }
 
+    /// Search for similar chunks restricted by a [`SearchFilter`], tuning
+    /// the ANN index (if any) with `params`
+    pub async fn search_filtered(
+        &self,
+        query_vector: Embedding,
+        filter: &SearchFilter,
+        limit: usize,
+        params: &AnnSearchParams,
+    ) -> Result<Vec<VectorSearchResult>, VectorStoreError> {
+        self.backend
+            .search_filtered(query_vector, filter, limit, params)
+            .await
+    }
+
+    /// Create, refresh or retrain the ANN index on the vector column as
+    /// `config` plans it; see [`AnnIndexConfig::plan`]
+    pub async fn ensure_vector_index(
+        &self,
+        config: &AnnIndexConfig,
+    ) -> Result<AnnIndexAction, VectorStoreError> {
+        self.backend.ensure_vector_index(config).await
+    }
+
+    /// Fetch the stored embeddings of whichever `chunk_ids` exist
+    pub async fn get_embeddings(
+        &self,
+        chunk_ids: &[ChunkId],
+    ) -> Result<HashMap<ChunkId, Embedding>, VectorStoreError> {
+        self.backend.get_embeddings(chunk_ids).await
+    }
+
+    /// Every stored chunk ID with the file path it was indexed from
+    pub async fn chunk_file_paths(&self) -> Result<HashMap<ChunkId, String>, VectorStoreError> {
+        self.backend.chunk_file_paths().await
+    }
+
     /// Delete chunks by their IDs
     pub async fn delete_chunks(&self, chunk_ids: Vec<ChunkId>) -> Result<(), VectorStoreError> {
         self.backend.delete_chunks(chunk_ids).await
@@ -147,6 +190,19 @@ impl VectorStore {
         self.backend.delete_by_file_path(file_path).await
     }
 
+    /// Delete the chunks of `file_paths` whose IDs are not in `keep`
+    ///
+    /// Used after re-indexing files in place: `keep` is every chunk ID the
+    /// files produce now, so rows left over from their previous content are
+    /// dropped while unchanged chunks keep theirs.
+    pub async fn delete_stale_chunks(
+        &self,
+        file_paths: &[String],
+        keep: &[ChunkId],
+    ) -> Result<(), VectorStoreError> {
+        self.backend.delete_stale_chunks(file_paths, keep).await
+    }
+
     /// Get the total number of vectors in the store
     pub async fn count(&self) -> Result<usize, VectorStoreError> {
         self.backend.count().await
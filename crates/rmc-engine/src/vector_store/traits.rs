@@ -3,9 +3,12 @@
 //! Defines the interface that all vector storage backends must implement.
 
 use async_trait::async_trait;
+use std::collections::HashMap;
 
 use crate::chunker::{ChunkId, CodeChunk};
 use crate::embeddings::Embedding;
+use crate::search::SearchFilter;
+use super::ann::{AnnIndexAction, AnnIndexConfig, AnnSearchParams};
 use super::error::VectorStoreError;
 use super::VectorSearchResult;
 
@@ -28,6 +31,30 @@ pub trait VectorStoreBackend: Send + Sync {
         limit: usize,
     ) -> Result<Vec<VectorSearchResult>, VectorStoreError>;
 
+    /// Search for similar chunks restricted by a [`SearchFilter`], tuning
+    /// the ANN index (if any) with `params`
+    async fn search_filtered(
+        &self,
+        query_vector: Embedding,
+        filter: &SearchFilter,
+        limit: usize,
+        params: &AnnSearchParams,
+    ) -> Result<Vec<VectorSearchResult>, VectorStoreError>;
+
+    /// Fetch the stored embeddings of whichever `chunk_ids` exist
+    ///
+    /// IDs missing from the store are absent from the returned map.
+    async fn get_embeddings(
+        &self,
+        chunk_ids: &[ChunkId],
+    ) -> Result<HashMap<ChunkId, Embedding>, VectorStoreError>;
+
+    /// Every stored chunk ID with the file path it was indexed from
+    ///
+    /// Reads the whole ID and path columns; meant for consistency checks,
+    /// not for the query path.
+    async fn chunk_file_paths(&self) -> Result<HashMap<ChunkId, String>, VectorStoreError>;
+
     /// Delete chunks by their IDs
     async fn delete_chunks(
         &self,
@@ -40,6 +67,22 @@ pub trait VectorStoreBackend: Send + Sync {
         file_path: &str,
     ) -> Result<(), VectorStoreError>;
 
+    /// Delete the chunks of `file_paths` whose IDs are not in `keep`
+    async fn delete_stale_chunks(
+        &self,
+        file_paths: &[String],
+        keep: &[ChunkId],
+    ) -> Result<(), VectorStoreError>;
+
+    /// Create, refresh or retrain the ANN index on the vector column as
+    /// `config` plans it for the current row counts
+    ///
+    /// Call after a batch of upserts or deletes. Returns the action taken.
+    async fn ensure_vector_index(
+        &self,
+        config: &AnnIndexConfig,
+    ) -> Result<AnnIndexAction, VectorStoreError>;
+
     /// Get the total number of vectors in the store
     async fn count(&self) -> Result<usize, VectorStoreError>;
 
@@ -111,9 +111,10 @@ fn visit_module(
                     set_attrs_for(model, def_to_node, ModuleDefId::StaticId(id), &src.value);
                 }
             }
-            // Macros, builtin types, variants-as-decls, and trait aliases
-            // aren't modeled as Item Nodes (filtered upstream in
-            // bindings.rs::process_entry).
+            // Builtin types, variants-as-decls, and trait aliases aren't
+            // modeled as Item Nodes (filtered upstream in
+            // bindings.rs::process_entry); macro Items get their attributes
+            // from macros.rs.
             _ => {}
         }
     }
@@ -234,6 +235,15 @@ fn set_attrs_for<N>(
     if !item_node.attributes.is_empty() {
         return;
     }
+    item_node.attributes = outer_attributes(node);
+}
+
+/// Outer attributes and doc-comment lines of `node`, in the `Node.attributes`
+/// format. Shared with the field pass, which builds its nodes directly.
+pub(super) fn outer_attributes<N>(node: &N) -> Vec<String>
+where
+    N: HasAttrs + HasDocComments,
+{
     let mut out: Vec<String> = Vec::new();
     for attr in node.attrs() {
         // Outer attributes only; inner attrs (`#![...]`) on items aren't
@@ -265,7 +275,7 @@ fn set_attrs_for<N>(
             out.push(format!("/// {body}"));
         }
     }
-    item_node.attributes = out;
+    out
 }
 
 #[cfg(test)]
@@ -5,6 +5,7 @@ use ra_ap_ide_db::RootDatabase;
 use ra_ap_syntax::ast::{self, AstNode};
 use ra_ap_syntax::{SyntaxNode, TokenAtOffset};
 use ra_ap_vfs::{FileId, Vfs};
+use rmc_config::ignore_engine::IgnoreEngine;
 
 use super::ids::NodeId;
 use super::snapshot::OpenedSnapshot;
@@ -123,3 +124,16 @@ pub(in crate::graph) fn resolve_workspace_relative(
         .ok()
         .map(|p| p.to_string_lossy().into_owned())
 }
+
+/// [`resolve_workspace_relative`] for the file-iterating audits: also `None`
+/// for files the project's ignore rules exclude (generated code, fixture
+/// trees), so audits report on the same files search indexes.
+pub(in crate::graph) fn resolve_audited_file(
+    vfs: &Vfs,
+    file_id: FileId,
+    workspace_root: &Path,
+    ignores: &IgnoreEngine,
+) -> Option<String> {
+    let rel_path = resolve_workspace_relative(vfs, file_id, workspace_root)?;
+    (!ignores.is_ignored(&workspace_root.join(&rel_path))).then_some(rel_path)
+}
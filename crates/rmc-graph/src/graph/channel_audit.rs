@@ -17,12 +17,13 @@ use ra_ap_hir_def::nameres::crate_def_map;
 use ra_ap_syntax::AstToken;
 use ra_ap_syntax::ast::{self, AstNode, HasArgList};
 use ra_ap_vfs::FileId;
+use rmc_config::ignore_engine::IgnoreEngine;
 use serde::{Deserialize, Serialize};
 
 use super::ast_resolve::resolve_call_to_function;
 use super::audit_util::{
-    canonical_function_path, enclosed_by_cfg_test, resolve_enclosing_function,
-    resolve_workspace_relative,
+    canonical_function_path, enclosed_by_cfg_test, resolve_audited_file,
+    resolve_enclosing_function,
 };
 use super::ids::NodeId;
 use super::loader::LoadedWorkspace;
@@ -79,6 +80,7 @@ pub fn channel_capacity_audit(
     let workspace_root = loaded.workspace_root.clone();
     let db = &loaded.db;
     let vfs = &loaded.vfs;
+    let ignores = IgnoreEngine::load(&workspace_root)?;
 
     let mut findings: Vec<ChannelFinding> = Vec::new();
 
@@ -126,7 +128,7 @@ pub fn channel_capacity_audit(
         }
 
         for (file_id, crate_name) in file_to_crate {
-            let rel_path = match resolve_workspace_relative(vfs, file_id, &workspace_root) {
+            let rel_path = match resolve_audited_file(vfs, file_id, &workspace_root, &ignores) {
                 Some(p) => p,
                 None => continue,
             };
@@ -0,0 +1,511 @@
+//! Git co-change history (THEORY P11).
+//!
+//! Reads the local repository's commit log with `git log --unified=0` (no
+//! network, no libgit2) and maps every commit's changed line ranges onto
+//! snapshot Items. Item spans are only known at the snapshot's worktree
+//! state, so the walk runs newest-first and carries each Item's line range
+//! backwards through every hunk: lines above a hunk keep their number, lines
+//! below it shift by the hunk's size delta, and renames move the tracked
+//! ranges to the old path. An Item whose range collapses (it was added in
+//! the commit) or whose file was added stops being tracked.
+//!
+//! Limitations:
+//! - Uncommitted edits shift the worktree spans relative to `HEAD`; hunks
+//!   near those edits may be attributed to a neighbouring Item.
+//! - Merge commits are skipped; their changes are attributed through the
+//!   merged branch's own commits.
+//! - Commits touching more than `max_items_per_commit` Items (formatting
+//!   sweeps, mass renames) are counted as skipped rather than coupled.
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::Result;
+
+use super::ids::NodeId;
+use super::query::audits::GraphAuditError;
+use super::snapshot::OpenedSnapshot;
+
+pub(crate) const DEFAULT_MAX_COMMITS: usize = 500;
+pub(crate) const HARD_CAP_MAX_COMMITS: usize = 10_000;
+pub(crate) const DEFAULT_MAX_ITEMS_PER_COMMIT: usize = 50;
+
+/// Separates commits in the `git log` output (ASCII record separator).
+const COMMIT_MARKER: char = '\u{1e}';
+
+#[derive(Debug, Clone)]
+pub struct GitHistoryOpts {
+    pub max_commits: usize,
+    /// Passed through as `git log --since=<value>`.
+    pub since: Option<String>,
+    pub max_items_per_commit: usize,
+}
+
+/// One `@@ -old_start,old_len +new_start,new_len @@` header. A zero length
+/// means a pure insertion (`old_len`) or deletion (`new_len`) after the
+/// given line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Hunk {
+    pub old_start: u32,
+    pub old_len: u32,
+    pub new_start: u32,
+    pub new_len: u32,
+}
+
+/// One file section of a commit diff. `None` paths are `/dev/null`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct FileDiff {
+    pub old_path: Option<String>,
+    pub new_path: Option<String>,
+    pub hunks: Vec<Hunk>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CommitDiff {
+    pub sha: String,
+    pub time: i64,
+    pub files: Vec<FileDiff>,
+}
+
+/// A commit that touched at least one tracked Item.
+#[derive(Debug, Clone)]
+pub(crate) struct TouchedCommit {
+    pub sha: String,
+    pub time: i64,
+    pub items: Vec<NodeId>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ItemHistory {
+    /// Newest first; commits above `max_items_per_commit` are excluded.
+    pub commits: Vec<TouchedCommit>,
+    pub commits_scanned: usize,
+    pub commits_skipped_large: usize,
+    pub newest_commit: Option<String>,
+    pub oldest_commit: Option<String>,
+}
+
+/// 1-indexed inclusive line range of one Item in some revision of a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct TrackedSpan {
+    pub item: NodeId,
+    pub start: u32,
+    pub end: u32,
+}
+
+/// Walk the workspace's git history and attribute each commit to the
+/// snapshot Items it changed.
+pub(crate) fn item_history(snap: &OpenedSnapshot, opts: &GitHistoryOpts) -> Result<ItemHistory> {
+    let workspace_root = Path::new(&snap.manifest.workspace_root);
+    let commits = read_git_log(workspace_root, opts)?;
+    Ok(attribute_commits(
+        worktree_spans(snap),
+        commits,
+        opts.max_items_per_commit,
+    ))
+}
+
+/// Item spans of the snapshot, converted from bytes to lines against the
+/// files currently on disk. Files that can no longer be read are dropped.
+fn worktree_spans(snap: &OpenedSnapshot) -> HashMap<String, Vec<TrackedSpan>> {
+    let mut tracked: HashMap<String, Vec<TrackedSpan>> = HashMap::new();
+    for (file, spans) in snap.span_index() {
+        let Ok(table) = snap.line_to_byte(file) else {
+            continue;
+        };
+        let line_of = |byte: u32| table.partition_point(|&offset| offset <= byte) as u32;
+        let file_spans = spans
+            .iter()
+            .map(|&(start, end, item)| TrackedSpan {
+                item,
+                start: line_of(start),
+                end: line_of(end.saturating_sub(1).max(start)),
+            })
+            .collect();
+        tracked.insert(file.clone(), file_spans);
+    }
+    tracked
+}
+
+fn read_git_log(workspace_root: &Path, opts: &GitHistoryOpts) -> Result<Vec<CommitDiff>> {
+    let unavailable = |reason: String| GraphAuditError::GitHistoryUnavailable {
+        directory: workspace_root.to_path_buf(),
+        reason,
+    };
+    let mut command = Command::new("git");
+    command
+        .arg("-C")
+        .arg(workspace_root)
+        .args(["-c", "core.quotepath=false", "log"])
+        .args(["--no-merges", "--no-color", "--no-ext-diff", "--no-textconv"])
+        .args(["-M", "--unified=0", "--relative"])
+        .arg(format!("--format={COMMIT_MARKER}%H %ct"))
+        .arg(format!("--max-count={}", opts.max_commits));
+    if let Some(since) = opts.since.as_deref() {
+        command.arg(format!("--since={since}"));
+    }
+    command.args(["--", "*.rs"]);
+
+    let output = command
+        .output()
+        .map_err(|error| unavailable(format!("failed to run git: {error}")))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(unavailable(stderr.trim().to_string()).into());
+    }
+    Ok(parse_git_log(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Parse `git log --unified=0` output produced with the `COMMIT_MARKER`
+/// format. Hunk bodies are skipped by counting lines, so added or removed
+/// source lines that look like diff headers are never misread.
+pub(crate) fn parse_git_log(output: &str) -> Vec<CommitDiff> {
+    let mut commits = Vec::new();
+    for record in output.split(COMMIT_MARKER).filter(|record| !record.trim().is_empty()) {
+        let mut lines = record.lines();
+        let Some((sha, time)) = lines.next().and_then(|header| header.split_once(' ')) else {
+            continue;
+        };
+        let mut commit = CommitDiff {
+            sha: sha.to_string(),
+            time: time.trim().parse().unwrap_or(0),
+            files: Vec::new(),
+        };
+        let mut file: Option<FileDiff> = None;
+        let (mut removed_left, mut added_left) = (0u32, 0u32);
+        for line in lines {
+            if removed_left > 0 || added_left > 0 {
+                if line.starts_with('-') {
+                    removed_left = removed_left.saturating_sub(1);
+                } else if line.starts_with('+') {
+                    added_left = added_left.saturating_sub(1);
+                }
+                continue;
+            }
+            if line.starts_with("diff --git ") {
+                commit.files.extend(file.take());
+                file = Some(FileDiff::default());
+                continue;
+            }
+            let Some(current) = file.as_mut() else {
+                continue;
+            };
+            if let Some(path) = line.strip_prefix("rename from ") {
+                current.old_path = Some(path.to_string());
+            } else if let Some(path) = line.strip_prefix("rename to ") {
+                current.new_path = Some(path.to_string());
+            } else if let Some(path) = line.strip_prefix("--- ") {
+                current.old_path = diff_path(path, "a/");
+            } else if let Some(path) = line.strip_prefix("+++ ") {
+                current.new_path = diff_path(path, "b/");
+            } else if let Some(hunk) = parse_hunk_header(line) {
+                removed_left = hunk.old_len;
+                added_left = hunk.new_len;
+                current.hunks.push(hunk);
+            }
+        }
+        commit.files.extend(file);
+        commits.push(commit);
+    }
+    commits
+}
+
+fn diff_path(path: &str, prefix: &str) -> Option<String> {
+    let path = path.trim_end_matches('\t');
+    if path == "/dev/null" {
+        return None;
+    }
+    Some(path.strip_prefix(prefix).unwrap_or(path).to_string())
+}
+
+/// `@@ -12,3 +12,0 @@ fn context` → `Hunk`. A missing length means 1.
+fn parse_hunk_header(line: &str) -> Option<Hunk> {
+    let rest = line.strip_prefix("@@ -")?;
+    let (ranges, _) = rest.split_once(" @@")?;
+    let (old, new) = ranges.split_once(" +")?;
+    let range = |text: &str| -> Option<(u32, u32)> {
+        match text.split_once(',') {
+            Some((start, len)) => Some((start.parse().ok()?, len.parse().ok()?)),
+            None => Some((text.parse().ok()?, 1)),
+        }
+    };
+    let (old_start, old_len) = range(old)?;
+    let (new_start, new_len) = range(new)?;
+    Some(Hunk {
+        old_start,
+        old_len,
+        new_start,
+        new_len,
+    })
+}
+
+/// Attribute `commits` (newest first) to the Items in `tracked`, whose
+/// spans are in the coordinates of the newest revision.
+pub(crate) fn attribute_commits(
+    mut tracked: HashMap<String, Vec<TrackedSpan>>,
+    commits: Vec<CommitDiff>,
+    max_items_per_commit: usize,
+) -> ItemHistory {
+    let mut history = ItemHistory {
+        commits_scanned: commits.len(),
+        newest_commit: commits.first().map(|commit| commit.sha.clone()),
+        oldest_commit: commits.last().map(|commit| commit.sha.clone()),
+        ..ItemHistory::default()
+    };
+    for commit in commits {
+        let mut touched: Vec<NodeId> = Vec::new();
+        let mut seen: HashSet<NodeId> = HashSet::new();
+        let mut moved: Vec<(String, Vec<TrackedSpan>)> = Vec::new();
+        for file in &commit.files {
+            let Some(new_path) = file.new_path.as_deref() else {
+                continue;
+            };
+            let Some(mut spans) = tracked.remove(new_path) else {
+                continue;
+            };
+            for hunk in &file.hunks {
+                for item in innermost_touched(&spans, hunk) {
+                    if seen.insert(item) {
+                        touched.push(item);
+                    }
+                }
+            }
+            let Some(old_path) = file.old_path.clone() else {
+                // File added in this commit: nothing older to attribute.
+                continue;
+            };
+            spans.retain_mut(|span| {
+                let start = pre_image_line(span.start, &file.hunks, true);
+                let end = pre_image_line(span.end, &file.hunks, false);
+                span.start = start;
+                span.end = end;
+                start <= end && start > 0
+            });
+            moved.push((old_path, spans));
+        }
+        // Re-insert after the whole commit so a swap of two paths within
+        // one commit can't clobber a not-yet-processed file.
+        for (path, spans) in moved {
+            tracked.entry(path).or_default().extend(spans);
+        }
+
+        if touched.is_empty() {
+            continue;
+        }
+        if touched.len() > max_items_per_commit {
+            history.commits_skipped_large += 1;
+            continue;
+        }
+        history.commits.push(TouchedCommit {
+            sha: commit.sha,
+            time: commit.time,
+            items: touched,
+        });
+    }
+    history
+}
+
+/// Items whose span the hunk changes, keeping only the innermost ones so a
+/// method edit is not also charged to its enclosing trait or impl block.
+fn innermost_touched(spans: &[TrackedSpan], hunk: &Hunk) -> Vec<NodeId> {
+    let hit: Vec<&TrackedSpan> = spans
+        .iter()
+        .filter(|span| {
+            if hunk.new_len == 0 {
+                // Pure deletion between `new_start` and `new_start + 1`.
+                span.start <= hunk.new_start && span.end > hunk.new_start
+            } else {
+                let last = hunk.new_start + hunk.new_len - 1;
+                span.start <= last && span.end >= hunk.new_start
+            }
+        })
+        .collect();
+    hit.iter()
+        .filter(|outer| {
+            !hit.iter().any(|inner| {
+                inner.item != outer.item
+                    && outer.start <= inner.start
+                    && inner.end <= outer.end
+                    && (outer.start, outer.end) != (inner.start, inner.end)
+            })
+        })
+        .map(|span| span.item)
+        .collect()
+}
+
+/// Map a post-image line of one file diff to the pre-image. A line inside a
+/// changed region maps to the region's first (`is_start`) or last old line;
+/// for pure insertions that yields an empty range, which callers use to
+/// drop Items introduced by the commit.
+fn pre_image_line(line: u32, hunks: &[Hunk], is_start: bool) -> u32 {
+    let mut delta: i64 = 0;
+    for hunk in hunks {
+        let new_end = if hunk.new_len == 0 {
+            hunk.new_start + 1
+        } else {
+            hunk.new_start + hunk.new_len
+        };
+        if line < new_end {
+            if hunk.new_len == 0 || line < hunk.new_start {
+                break;
+            }
+            // Inside the changed region.
+            return match (is_start, hunk.old_len) {
+                (true, 0) => hunk.old_start + 1,
+                (true, _) => hunk.old_start,
+                (false, 0) => hunk.old_start,
+                (false, len) => hunk.old_start + len - 1,
+            };
+        }
+        delta += i64::from(hunk.old_len) - i64::from(hunk.new_len);
+    }
+    (i64::from(line) + delta).max(0) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn id(n: u8) -> NodeId {
+        NodeId([n; 32])
+    }
+
+    fn span(n: u8, start: u32, end: u32) -> TrackedSpan {
+        TrackedSpan {
+            item: id(n),
+            start,
+            end,
+        }
+    }
+
+    fn hunk(old_start: u32, old_len: u32, new_start: u32, new_len: u32) -> Hunk {
+        Hunk {
+            old_start,
+            old_len,
+            new_start,
+            new_len,
+        }
+    }
+
+    #[test]
+    fn parses_renames_additions_and_hunk_bodies() {
+        let log = "\u{1e}aaaa 200\n\n\
+            diff --git a/src/old.rs b/src/new.rs\n\
+            similarity index 90%\n\
+            rename from src/old.rs\n\
+            rename to src/new.rs\n\
+            --- a/src/old.rs\n\
+            +++ b/src/new.rs\n\
+            @@ -3 +3,2 @@ fn f() {\n\
+            -    one();\n\
+            ++++ b/looks_like_a_header.rs\n\
+            +    two();\n\
+            \u{1e}bbbb 100\n\n\
+            diff --git a/src/lib.rs b/src/lib.rs\n\
+            new file mode 100644\n\
+            --- /dev/null\n\
+            +++ b/src/lib.rs\n\
+            @@ -0,0 +1,2 @@\n\
+            +mod new;\n\
+            +pub fn f() {}\n";
+        let commits = parse_git_log(log);
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[0].sha, "aaaa");
+        assert_eq!(commits[0].time, 200);
+        assert_eq!(
+            commits[0].files,
+            vec![FileDiff {
+                old_path: Some("src/old.rs".to_string()),
+                new_path: Some("src/new.rs".to_string()),
+                hunks: vec![hunk(3, 1, 3, 2)],
+            }]
+        );
+        assert_eq!(commits[1].files[0].old_path, None);
+        assert_eq!(commits[1].files[0].hunks, vec![hunk(0, 0, 1, 2)]);
+    }
+
+    #[test]
+    fn maps_post_image_lines_through_hunks() {
+        // Two lines inserted after old line 4, old lines 10-12 replaced by one.
+        let hunks = [hunk(4, 0, 5, 2), hunk(10, 3, 12, 1)];
+        assert_eq!(pre_image_line(3, &hunks, true), 3);
+        assert_eq!(pre_image_line(8, &hunks, true), 6);
+        assert_eq!(pre_image_line(12, &hunks, true), 10);
+        assert_eq!(pre_image_line(12, &hunks, false), 12);
+        assert_eq!(pre_image_line(20, &hunks, true), 20);
+        // Inserted lines collapse to an empty range.
+        assert!(pre_image_line(5, &hunks, true) > pre_image_line(6, &hunks, false));
+    }
+
+    #[test]
+    fn attributes_commits_to_innermost_items_and_follows_renames() {
+        let mut tracked = HashMap::new();
+        // `outer` (1-20) contains `inner` (5-8); `other` (22-30) follows.
+        tracked.insert(
+            "src/new.rs".to_string(),
+            vec![span(1, 1, 20), span(2, 5, 8), span(3, 22, 30)],
+        );
+        let commits = vec![
+            CommitDiff {
+                sha: "head".to_string(),
+                time: 3,
+                files: vec![FileDiff {
+                    old_path: Some("src/new.rs".to_string()),
+                    new_path: Some("src/new.rs".to_string()),
+                    // Two lines inside `inner` and one line inside `other`.
+                    hunks: vec![hunk(5, 0, 6, 2), hunk(22, 1, 24, 1)],
+                }],
+            },
+            CommitDiff {
+                sha: "rename".to_string(),
+                time: 2,
+                files: vec![FileDiff {
+                    old_path: Some("src/old.rs".to_string()),
+                    new_path: Some("src/new.rs".to_string()),
+                    hunks: Vec::new(),
+                }],
+            },
+            CommitDiff {
+                sha: "root".to_string(),
+                time: 1,
+                files: vec![FileDiff {
+                    old_path: Some("src/old.rs".to_string()),
+                    new_path: Some("src/old.rs".to_string()),
+                    // `other` sits at lines 20-28 in this revision.
+                    hunks: vec![hunk(22, 1, 22, 1)],
+                }],
+            },
+        ];
+        let history = attribute_commits(tracked, commits, 10);
+        assert_eq!(history.commits_scanned, 3);
+        assert_eq!(history.newest_commit.as_deref(), Some("head"));
+        let touched: Vec<(&str, Vec<NodeId>)> = history
+            .commits
+            .iter()
+            .map(|commit| (commit.sha.as_str(), commit.items.clone()))
+            .collect();
+        assert_eq!(
+            touched,
+            vec![("head", vec![id(2), id(3)]), ("root", vec![id(3)])]
+        );
+    }
+
+    #[test]
+    fn oversized_commits_are_skipped() {
+        let mut tracked = HashMap::new();
+        tracked.insert("src/lib.rs".to_string(), vec![span(1, 1, 2), span(2, 3, 4)]);
+        let commits = vec![CommitDiff {
+            sha: "fmt".to_string(),
+            time: 1,
+            files: vec![FileDiff {
+                old_path: Some("src/lib.rs".to_string()),
+                new_path: Some("src/lib.rs".to_string()),
+                hunks: vec![hunk(1, 4, 1, 4)],
+            }],
+        }];
+        let history = attribute_commits(tracked, commits, 1);
+        assert!(history.commits.is_empty());
+        assert_eq!(history.commits_skipped_large, 1);
+    }
+}
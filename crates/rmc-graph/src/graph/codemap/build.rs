@@ -615,7 +615,7 @@ fn extract_snippet(
 }
 
 /// Walk `workspace_root` for every `.rs` file (excluding generated/tooling
-/// directories — mirroring [`crate::graph::storage::compute_fingerprint`]'s
+/// directories — mirroring [`crate::graph::storage::compute_file_digests`]'s
 /// filter) and return the maximum file `mtime` in seconds since the UNIX
 /// epoch.
 ///
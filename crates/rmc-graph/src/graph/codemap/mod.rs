@@ -2,7 +2,8 @@
 //!
 //! Split across five sibling files in PRs 12-13:
 //!   - `model`: serializable response types (Codemap, CodemapNode, ...).
-//!   - `seeds`: SeedHit DTO + seed resolution (override + search-hit) + path/span helpers.
+//!   - `seeds`: SeedHit DTO + seed resolution (override + search-hit) + path/span helpers,
+//!     plus per-hit graph signals for graph-boosted search.
 //!   - `build`: build_codemap algorithm + pre-build freshness check.
 //!   - `hierarchy`: filtered module-tree projection used by build_codemap.
 //!   - `render`: mermaid + outline output formatting.
@@ -17,6 +18,6 @@ pub(super) mod render;
 mod test_support;
 
 pub use model::*;
-pub use seeds::SeedHit;
+pub use seeds::{ANCHOR_MAX_HOPS, HitGraphSignals, SeedHit, search_hit_signals};
 pub use build::{build_codemap, newest_source_mtime};
 pub use render::{render_mermaid, render_outline};
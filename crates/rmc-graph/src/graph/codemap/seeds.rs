@@ -8,9 +8,14 @@
 //! PR 13 absorbed the path/span helpers (`canonicalize_and_strip`,
 //! `enclosing_item_for_line_range`) here because the seeds layer is their
 //! only non-test consumer.
+//!
+//! `search_hit_signals` reuses the same snapping for graph-boosted search:
+//! it reports fan-in, pub-ness and anchor distance of each hit's Item
+//! without building a codemap.
 
 use std::collections::HashSet;
 use std::collections::HashMap;
+use std::collections::hash_map::Entry;
 use std::path::{Path, PathBuf};
 
 use crate::graph::codemap::model::CodemapOptions;
@@ -35,6 +40,115 @@ pub struct SeedHit {
     pub score: f32,
 }
 
+/// Hypergraph facts about the Item a search hit snaps to, used to boost
+/// search ranking by centrality and proximity.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HitGraphSignals {
+    pub node: NodeId,
+    pub qualified_name: String,
+    /// Non-import references to the Item (`usages_by_target` entries).
+    pub fan_in: u32,
+    /// Declared plain `pub` (not `pub(crate)` etc.).
+    pub is_pub: bool,
+    /// Call/use hops from the anchor, `Some(0)` for the anchor itself.
+    /// `None` without an anchor or beyond [`ANCHOR_MAX_HOPS`].
+    pub anchor_distance: Option<u32>,
+}
+
+/// How far `search_hit_signals` walks from the anchor.
+pub const ANCHOR_MAX_HOPS: u32 = 3;
+
+/// Stop the anchor walk once this many nodes are reached; hub anchors
+/// (e.g. a shared error type) would otherwise pull in the whole workspace.
+const ANCHOR_MAX_NODES: usize = 10_000;
+
+/// Snap each hit to its enclosing Item (as `resolve_search_seeds` does) and
+/// collect the Item's [`HitGraphSignals`]. The output is parallel to `hits`;
+/// hits outside the snapshot yield `None`.
+///
+/// Distance is measured over call/use edges in both directions, so callers
+/// and callees of the anchor are both one hop away. An Item nested in the
+/// anchor (a method of an anchor type) counts as one hop as well.
+pub fn search_hit_signals(
+    snap: &OpenedSnapshot,
+    hits: &[SeedHit],
+    ws_root: &Path,
+    anchor: Option<NodeId>,
+) -> anyhow::Result<Vec<Option<HitGraphSignals>>> {
+    let distances = match anchor {
+        Some(anchor) => anchor_distances(snap, anchor)?,
+        None => HashMap::new(),
+    };
+    let rtxn = snap.read_txn()?;
+    let mut by_node: HashMap<NodeId, Option<HitGraphSignals>> = HashMap::new();
+    let mut out = Vec::with_capacity(hits.len());
+    for hit in hits {
+        let Some(nid) = canonicalize_and_strip(&hit.file_path, ws_root).and_then(|rel| {
+            enclosing_item_for_line_range(snap, &rel, hit.line_start, hit.line_end)
+        }) else {
+            out.push(None);
+            continue;
+        };
+        if let Some(signals) = by_node.get(&nid) {
+            out.push(signals.clone());
+            continue;
+        }
+        let signals = match snap.node(&rtxn, nid)? {
+            Some(node) => {
+                let fan_in = snap
+                    .dbs
+                    .usages_by_target
+                    .get_duplicates(&rtxn, nid.as_bytes())?
+                    .map_or(0, |entries| entries.count());
+                let nested_in_anchor = anchor.is_some() && node.parent_id == anchor;
+                let anchor_distance = match distances.get(&nid) {
+                    Some(&hops) => Some(if nested_in_anchor { hops.min(1) } else { hops }),
+                    None => nested_in_anchor.then_some(1),
+                };
+                Some(HitGraphSignals {
+                    node: nid,
+                    qualified_name: node.qualified_name,
+                    fan_in: fan_in as u32,
+                    is_pub: node.visibility.as_deref() == Some("pub"),
+                    anchor_distance,
+                })
+            }
+            None => None,
+        };
+        by_node.insert(nid, signals.clone());
+        out.push(signals);
+    }
+    Ok(out)
+}
+
+/// Breadth-first hop counts from `anchor` over `callees_of` and
+/// `referrers_of`, up to [`ANCHOR_MAX_HOPS`].
+fn anchor_distances(snap: &OpenedSnapshot, anchor: NodeId) -> anyhow::Result<HashMap<NodeId, u32>> {
+    let mut distances = HashMap::from([(anchor, 0)]);
+    let mut frontier = vec![anchor];
+    for hop in 1..=ANCHOR_MAX_HOPS {
+        let mut next = Vec::new();
+        for node in frontier {
+            let mut neighbours = snap.callees_of(node)?;
+            neighbours.extend(snap.referrers_of(node)?);
+            for neighbour in neighbours {
+                if let Entry::Vacant(slot) = distances.entry(neighbour) {
+                    slot.insert(hop);
+                    next.push(neighbour);
+                }
+            }
+            if distances.len() >= ANCHOR_MAX_NODES {
+                return Ok(distances);
+            }
+        }
+        if next.is_empty() {
+            break;
+        }
+        frontier = next;
+    }
+    Ok(distances)
+}
+
 /// Convert a 1-indexed inclusive line range into a byte range for `file`,
 /// then find the smallest enclosing Item NodeId from the span index.
 ///
@@ -345,6 +459,54 @@ mod tests {
         assert!(got2.is_none(), "end before start is invalid");
     }
 
+    #[test]
+    fn search_hit_signals_report_fan_in_pub_and_anchor_distance() {
+        let fixture = shared_fixture();
+        let ws_root = PathBuf::from(&fixture.snap.manifest.workspace_root);
+        let lib_rs = ws_root.join("src").join("lib.rs");
+        let (caller_id, _) = fixture
+            .snap
+            .lookup_by_qualified_name("synthetic_codemap_crate::caller")
+            .expect("lookup_by_qualified_name caller")
+            .expect("caller resolves");
+        let hit = |file_path: PathBuf, line_start, line_end| SeedHit {
+            file_path,
+            line_start,
+            line_end,
+            score: 1.0,
+        };
+
+        // Fixture lines: `other` is 8-10, `callee` 12, `caller` 14-16.
+        let signals = search_hit_signals(
+            &fixture.snap,
+            &[
+                hit(lib_rs.clone(), 12, 12),
+                hit(lib_rs.clone(), 8, 10),
+                hit(lib_rs, 14, 16),
+                hit(ws_root.join("src").join("missing.rs"), 1, 1),
+            ],
+            &ws_root,
+            Some(caller_id),
+        )
+        .expect("search_hit_signals succeeds");
+
+        let callee = signals[0].as_ref().expect("callee hit snaps");
+        assert_eq!(callee.qualified_name, "synthetic_codemap_crate::callee");
+        assert!(callee.fan_in >= 1, "caller uses callee, got {callee:?}");
+        assert!(callee.is_pub);
+        assert_eq!(callee.anchor_distance, Some(1));
+
+        let other = signals[1].as_ref().expect("other hit snaps");
+        assert_eq!(other.qualified_name, "synthetic_codemap_crate::other");
+        assert_eq!(other.fan_in, 0);
+        assert_eq!(other.anchor_distance, None);
+
+        let caller = signals[2].as_ref().expect("caller hit snaps");
+        assert_eq!(caller.anchor_distance, Some(0));
+
+        assert!(signals[3].is_none(), "file outside the snapshot yields None");
+    }
+
     #[test]
     fn callees_of_includes_called_function() {
         let fixture = shared_fixture();
@@ -0,0 +1,295 @@
+//! v18 — per-function complexity metrics.
+//!
+//! `fn_complexity` measures one `ast::Fn` straight off its syntax tree, so
+//! identifiers like `diff` or keywords inside comments and string literals
+//! never count. The same function backs the `analyze_complexity` file tool
+//! (parser-only, no snapshot needed) and `extract_complexity`, which stores
+//! a `ComplexityMetrics` record per local function Item for
+//! `complexity_hotspots`.
+//!
+//! Cyclomatic complexity is McCabe's 1 + decision points. Cognitive
+//! complexity follows the SonarSource definition: each `if` / `match` /
+//! loop costs 1 plus the current nesting level, `else if` / `else` /
+//! labeled `break` / `continue` cost 1 flat, each run of one boolean
+//! operator costs 1, and closures raise the nesting level without costing
+//! anything themselves. Direct recursion, which Sonar also counts, needs
+//! name resolution and is left to `recursion_check`.
+//!
+//! Runs with the per-item passes (after signatures), over the same
+//! `def_to_node` slice, so incremental builds recompute it for dirty crates
+//! only and carry clean crates' records over.
+
+use std::collections::HashMap;
+
+use ra_ap_hir::{Function, HasSource, attach_db};
+use ra_ap_hir_def::ModuleDefId;
+use ra_ap_ide_db::RootDatabase;
+use ra_ap_syntax::ast::{self, AstNode, BinaryOp};
+use ra_ap_syntax::{SyntaxKind, SyntaxNode};
+
+use super::ids::NodeId;
+use super::model::{ComplexityMetrics, ExtractionModel};
+
+pub(crate) fn extract_complexity(
+    model: &mut ExtractionModel,
+    db: &RootDatabase,
+    def_to_node: &HashMap<ModuleDefId, NodeId>,
+) {
+    attach_db(db, || {
+        for (&def_id, &node_id) in def_to_node {
+            let ModuleDefId::FunctionId(fn_id) = def_id else {
+                continue;
+            };
+            let Some(source) = Function::from(fn_id).source(db) else {
+                continue;
+            };
+            // Trait fns without a default body have nothing to measure.
+            if source.value.body().is_none() {
+                continue;
+            }
+            model.complexity.push((node_id, fn_complexity(&source.value)));
+        }
+    });
+}
+
+/// Complexity metrics of one function; see [`ComplexityMetrics`] for what
+/// each field counts. Items nested in the body (inner fns, impls, modules)
+/// are skipped; closures count toward `func`.
+pub fn fn_complexity(func: &ast::Fn) -> ComplexityMetrics {
+    let mut walker = Walker::default();
+    if let Some(body) = func.body() {
+        walker.walk(body.syntax(), 0);
+    }
+    let param_count = func
+        .param_list()
+        .map(|list| list.params().count() + usize::from(list.self_param().is_some()))
+        .unwrap_or(0);
+
+    ComplexityMetrics {
+        cyclomatic: 1 + walker.decisions,
+        cognitive: walker.cognitive,
+        max_nesting: walker.max_nesting,
+        param_count: param_count as u32,
+        loc: code_lines(func.syntax()),
+    }
+}
+
+#[derive(Default)]
+struct Walker {
+    decisions: u32,
+    cognitive: u32,
+    max_nesting: u32,
+}
+
+impl Walker {
+    fn walk(&mut self, node: &SyntaxNode, nesting: u32) {
+        for child in node.children() {
+            self.visit(&child, nesting);
+        }
+    }
+
+    /// Walk `node` one nesting level deeper than its control-flow parent.
+    fn nested(&mut self, node: &SyntaxNode, nesting: u32) {
+        self.max_nesting = self.max_nesting.max(nesting);
+        self.walk(node, nesting);
+    }
+
+    fn visit(&mut self, node: &SyntaxNode, nesting: u32) {
+        // Nested items are measured on their own.
+        if ast::Item::can_cast(node.kind()) {
+            return;
+        }
+        if let Some(if_expr) = ast::IfExpr::cast(node.clone()) {
+            self.visit_if(&if_expr, nesting, false);
+            return;
+        }
+        if let Some(match_expr) = ast::MatchExpr::cast(node.clone()) {
+            self.cognitive += 1 + nesting;
+            if let Some(scrutinee) = match_expr.expr() {
+                self.visit(scrutinee.syntax(), nesting);
+            }
+            let arms: Vec<ast::MatchArm> = match_expr
+                .match_arm_list()
+                .map(|list| list.arms().collect())
+                .unwrap_or_default();
+            self.decisions += arms.len().saturating_sub(1) as u32;
+            for arm in arms {
+                if arm.guard().is_some() {
+                    self.decisions += 1;
+                }
+                self.nested(arm.syntax(), nesting + 1);
+            }
+            return;
+        }
+        if let Some(op) = logic_op(node) {
+            self.decisions += 1;
+            // `a && b && c` is one run; `a && b || c` is two.
+            if node.parent().and_then(|parent| logic_op(&parent)) != Some(op) {
+                self.cognitive += 1;
+            }
+            self.walk(node, nesting);
+            return;
+        }
+
+        match node.kind() {
+            SyntaxKind::FOR_EXPR | SyntaxKind::WHILE_EXPR | SyntaxKind::LOOP_EXPR => {
+                // `loop` has no condition of its own; its exits are the
+                // `if`s around its `break`s.
+                if node.kind() != SyntaxKind::LOOP_EXPR {
+                    self.decisions += 1;
+                }
+                self.cognitive += 1 + nesting;
+                self.nested(node, nesting + 1);
+            }
+            SyntaxKind::LET_ELSE => {
+                self.decisions += 1;
+                self.cognitive += 1 + nesting;
+                self.nested(node, nesting + 1);
+            }
+            SyntaxKind::CLOSURE_EXPR => self.nested(node, nesting + 1),
+            SyntaxKind::TRY_EXPR => {
+                self.decisions += 1;
+                self.walk(node, nesting);
+            }
+            SyntaxKind::BREAK_EXPR | SyntaxKind::CONTINUE_EXPR => {
+                if node.children().any(|child| child.kind() == SyntaxKind::LIFETIME) {
+                    self.cognitive += 1;
+                }
+                self.walk(node, nesting);
+            }
+            _ => self.walk(node, nesting),
+        }
+    }
+
+    fn visit_if(&mut self, if_expr: &ast::IfExpr, nesting: u32, is_else_if: bool) {
+        self.decisions += 1;
+        self.cognitive += if is_else_if { 1 } else { 1 + nesting };
+        if let Some(condition) = if_expr.condition() {
+            self.visit(condition.syntax(), nesting);
+        }
+        if let Some(then_branch) = if_expr.then_branch() {
+            self.nested(then_branch.syntax(), nesting + 1);
+        }
+        match if_expr.else_branch() {
+            Some(ast::ElseBranch::IfExpr(else_if)) => self.visit_if(&else_if, nesting, true),
+            Some(ast::ElseBranch::Block(block)) => {
+                self.cognitive += 1;
+                self.nested(block.syntax(), nesting + 1);
+            }
+            None => {}
+        }
+    }
+}
+
+fn logic_op(node: &SyntaxNode) -> Option<BinaryOp> {
+    let op = ast::BinExpr::cast(node.clone())?.op_kind()?;
+    matches!(op, BinaryOp::LogicOp(_)).then_some(op)
+}
+
+/// Non-blank lines of `func` that hold code, starting at the first token
+/// after its doc comments and attributes.
+fn code_lines(func: &SyntaxNode) -> u32 {
+    let start = func
+        .children_with_tokens()
+        .find(|element| {
+            !matches!(
+                element.kind(),
+                SyntaxKind::COMMENT | SyntaxKind::ATTR | SyntaxKind::WHITESPACE
+            )
+        })
+        .map(|element| element.text_range().start())
+        .unwrap_or_else(|| func.text_range().start());
+    let offset = usize::from(start - func.text_range().start());
+    let text = func.text().to_string();
+    text[offset..]
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with("//"))
+        .count() as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ra_ap_syntax::{Edition, SourceFile};
+
+    fn metrics(src: &str) -> ComplexityMetrics {
+        let parsed = SourceFile::parse(src, Edition::Edition2024).tree();
+        let func = parsed
+            .syntax()
+            .descendants()
+            .find_map(ast::Fn::cast)
+            .expect("expected an `fn` in the test source");
+        fn_complexity(&func)
+    }
+
+    #[test]
+    fn straight_line_fn_is_one() {
+        let m = metrics("/// Docs\n#[inline]\nfn add(a: u32, b: u32) -> u32 {\n    a + b\n}\n");
+        assert_eq!(
+            m,
+            ComplexityMetrics {
+                cyclomatic: 1,
+                cognitive: 0,
+                max_nesting: 0,
+                param_count: 2,
+                loc: 3,
+            }
+        );
+    }
+
+    #[test]
+    fn identifiers_and_comments_do_not_count() {
+        // The old line scan counted `if` inside `diff`, `for` inside
+        // `format`, and the comment.
+        let m = metrics(
+            "fn diff_format(&self) -> String {\n    // if for while match\n    format!(\"if {}\", self.diff)\n}\n",
+        );
+        assert_eq!(m.cyclomatic, 1);
+        assert_eq!(m.cognitive, 0);
+        assert_eq!(m.param_count, 1);
+        assert_eq!(m.loc, 3);
+    }
+
+    #[test]
+    fn branches_loops_and_operators() {
+        let m = metrics(
+            "fn f(xs: &[u32], flag: bool) -> Result<u32, E> {
+    let mut total = 0;
+    for x in xs {                    // +1 cyc, +1 cog
+        if *x > 3 && flag {          // +2 cyc, +2 (nesting 1) +1 (&&) cog
+            total += x;
+        } else if *x == 0 {          // +1 cyc, +1 cog
+            continue;
+        } else {                     // +1 cog
+            total -= 1;
+        }
+    }
+    let v = parse(total)?;           // +1 cyc
+    match v {                        // +1 cog
+        0 => Ok(0),                  // 3 arms: +2 cyc
+        n if n > 10 => Ok(n),        // guard: +1 cyc
+        n => Ok(n * 2),
+    }
+}",
+        );
+        assert_eq!(m.cyclomatic, 1 + 1 + 2 + 1 + 1 + 2 + 1);
+        assert_eq!(m.cognitive, 1 + 2 + 1 + 1 + 1 + 1);
+        assert_eq!(m.max_nesting, 2);
+        assert_eq!(m.param_count, 2);
+    }
+
+    #[test]
+    fn closures_nest_and_inner_items_are_skipped() {
+        let m = metrics(
+            "fn outer(items: Vec<u32>) -> Vec<u32> {
+    fn inner(x: u32) -> bool { if x > 1 { true } else { false } }
+    items.into_iter().filter(|x| { if inner(*x) { true } else { false } }).collect()
+}",
+        );
+        // Only the closure's `if` / `else` count, at nesting 1.
+        assert_eq!(m.cyclomatic, 2);
+        assert_eq!(m.cognitive, 2 + 1);
+        assert_eq!(m.max_nesting, 2);
+    }
+}
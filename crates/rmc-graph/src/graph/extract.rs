@@ -3,12 +3,16 @@
 //! Layer 2 milestone: Workspace, Crate, Module nodes plus Contains edges.
 //! Layer 3 adds the bindings pass which also creates Item nodes and
 //! ExternalSymbol stubs as a side effect.
+//!
+//! `extract_delta` is the incremental-rebuild entry point: it re-runs the
+//! per-item passes only for crates touched by an edit and copies the rest
+//! from the previous snapshot's model.
 
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::path::Path;
 
-use ra_ap_hir::Crate;
-use ra_ap_hir_def::ModuleId;
+use ra_ap_hir::{Crate, Field};
+use ra_ap_hir_def::{ModuleDefId, ModuleId};
 use ra_ap_hir_def::nameres::{DefMap, crate_def_map};
 use ra_ap_ide::RootDatabase;
 use ra_ap_vfs::Vfs;
@@ -16,19 +20,313 @@ use ra_ap_vfs::Vfs;
 use super::audit_util::resolve_workspace_relative;
 use super::attributes::extract_attributes;
 use super::bindings::extract_bindings;
+use super::complexity::extract_complexity;
+use super::fields::extract_fields;
 use super::ids::{NodeId, workspace_hash};
 use super::labels::{crate_display_name, module_path_segments};
 use super::impls::extract_impl_items;
 use super::loader::LoadedWorkspace;
+use super::macros::extract_macros;
 use super::model::{ExtractionModel, Node, NodeKind};
 use super::signatures::extract_signatures;
 use super::statics::extract_statics;
-use super::usages::extract_usages;
+use super::usages::{extract_usages, extract_usages_scoped};
 
 pub fn extract(loaded: &LoadedWorkspace) -> ExtractionModel {
     let timing = std::env::var_os("EXTRACT_TIMING").is_some();
     let t_total = std::time::Instant::now();
 
+    let Structure {
+        mut model,
+        module_node_for,
+        def_to_node,
+        field_to_node,
+        ..
+    } = extract_structure(loaded, timing);
+
+    // v8: per-Item attribute extraction. Runs after impls (so the v5 method /
+    // assoc-const / assoc-type and v7 enum-variant Items already exist in
+    // `model.nodes` and `def_to_node`) and before usages (purely for ordering
+    // — the two passes are independent).
+    let t = std::time::Instant::now();
+    extract_attributes(
+        &mut model,
+        &loaded.db,
+        &loaded.vfs,
+        &loaded.local_crates,
+        &def_to_node,
+    );
+    if timing {
+        let with_attrs = model
+            .nodes
+            .values()
+            .filter(|n| !n.attributes.is_empty())
+            .count();
+        eprintln!(
+            "extract: extract_attributes           {:>9.2?}  ({} items have attrs)",
+            t.elapsed(),
+            with_attrs
+        );
+    }
+
+    // v9 (Phase 5): per-function signature extraction. Runs after impls /
+    // attributes (so def_to_node already includes inherent/trait assoc fns)
+    // and before usages (purely for ordering — independent passes).
+    let t = std::time::Instant::now();
+    extract_signatures(&mut model, &loaded.db, &loaded.vfs, &def_to_node);
+    if timing {
+        eprintln!(
+            "extract: extract_signatures           {:>9.2?}  ({} signatures)",
+            t.elapsed(),
+            model.signatures.len()
+        );
+    }
+
+    // v10 (Phase 7 Path B): per-Static metadata extraction. Runs after
+    // signatures and before usages (independent passes — order is purely
+    // for the timing breakdown).
+    let t = std::time::Instant::now();
+    extract_statics(&mut model, &loaded.db, &loaded.vfs, &def_to_node);
+    if timing {
+        eprintln!(
+            "extract: extract_statics              {:>9.2?}  ({} statics)",
+            t.elapsed(),
+            model.statics.len()
+        );
+    }
+
+    // v18: per-fn complexity metrics, parsed from each fn's source node.
+    let t = std::time::Instant::now();
+    extract_complexity(&mut model, &loaded.db, &def_to_node);
+    if timing {
+        eprintln!(
+            "extract: extract_complexity           {:>9.2?}  ({} fns)",
+            t.elapsed(),
+            model.complexity.len()
+        );
+    }
+
+    let t = std::time::Instant::now();
+    extract_usages(
+        &mut model,
+        &loaded.db,
+        &loaded.vfs,
+        &loaded.local_crates,
+        &def_to_node,
+        &field_to_node,
+        &module_node_for,
+    );
+    if timing {
+        eprintln!(
+            "extract: extract_usages               {:>9.2?}  ({} usages)",
+            t.elapsed(),
+            model.usages.len()
+        );
+        eprintln!(
+            "extract: TOTAL                        {:>9.2?}",
+            t_total.elapsed()
+        );
+    }
+
+    model
+}
+
+/// Delta extraction against the model of the previously published snapshot.
+///
+/// The structural passes (crates, modules, bindings, impl items, macros, fields)
+/// always run over every local crate: they are cheap relative to usages, and
+/// they give a complete, deterministic `def_to_node` so NodeIds line up with
+/// the previous model. The per-item passes then only run where something could
+/// have changed:
+///
+///   * attributes / signatures / statics / complexity — items of *dirty*
+///     crates (crates owning a changed file, before or after the edit);
+///   * usages of dirty-crate items — searched across every local crate, which
+///     covers the reverse dependents' references;
+///   * usages of clean-crate items — searched only inside the files of dirty
+///     crates and their transitive reverse dependents, since a dependency's
+///     edit (a re-export swapped for a definition, a new glob-imported name)
+///     can change what a clean path resolves to.
+///
+/// Everything else (clean-crate node records, signatures, statics,
+/// complexity metrics, and usages of clean items from crates that depend on
+/// nothing dirty) is copied from `previous`. Returns `None` when the set of local crates
+/// changed — the caller falls back to a full `extract`.
+pub(crate) fn extract_delta(
+    loaded: &LoadedWorkspace,
+    previous: &ExtractionModel,
+    changed_files: &BTreeSet<String>,
+) -> Option<ExtractionModel> {
+    let timing = std::env::var_os("EXTRACT_TIMING").is_some();
+    let t_total = std::time::Instant::now();
+
+    let Structure {
+        mut model,
+        crate_node_for,
+        module_node_for,
+        def_to_node,
+        field_to_node,
+    } = extract_structure(loaded, timing);
+
+    let previous_crates: BTreeSet<NodeId> = previous
+        .nodes
+        .values()
+        .filter(|n| n.kind == NodeKind::Crate)
+        .map(|n| n.id)
+        .collect();
+    let current_crates: BTreeSet<NodeId> = crate_node_for.values().copied().collect();
+    if previous_crates != current_crates {
+        return None;
+    }
+
+    let t = std::time::Instant::now();
+    let mut dirty: HashSet<NodeId> = HashSet::new();
+    for &krate in &loaded.local_crates {
+        let Some(&crate_id) = crate_node_for.get(&krate) else {
+            continue;
+        };
+        let files = crate_source_files(&loaded.db, &loaded.vfs, &loaded.workspace_root, krate);
+        if changed_files.iter().any(|f| files.contains(f)) {
+            dirty.insert(crate_id);
+        }
+    }
+    // Deleted / renamed files only show up in the previous model.
+    for node in previous.nodes.values() {
+        if let (Some(file), Some(crate_id)) = (node.file.as_ref(), node.crate_id)
+            && changed_files.contains(file)
+        {
+            dirty.insert(crate_id);
+        }
+    }
+    let dirty_crates: Vec<Crate> = loaded
+        .local_crates
+        .iter()
+        .copied()
+        .filter(|k| crate_node_for.get(k).is_some_and(|id| dirty.contains(id)))
+        .collect();
+    // Crates whose name resolution may have moved: the dirty ones plus
+    // everything that (transitively) depends on them.
+    let mut resolve_dirty: HashSet<NodeId> = dirty.clone();
+    for &krate in &dirty_crates {
+        resolve_dirty.extend(
+            krate
+                .transitive_reverse_dependencies(&loaded.db)
+                .filter_map(|dependent| crate_node_for.get(&dependent).copied()),
+        );
+    }
+    let resolve_dirty_crates: Vec<Crate> = loaded
+        .local_crates
+        .iter()
+        .copied()
+        .filter(|k| crate_node_for.get(k).is_some_and(|id| resolve_dirty.contains(id)))
+        .collect();
+    if timing {
+        eprintln!(
+            "extract: delta dirty crates           {:>9.2?}  ({} + {} dependents of {} crates, {} changed files)",
+            t.elapsed(),
+            dirty_crates.len(),
+            resolve_dirty_crates.len() - dirty_crates.len(),
+            loaded.local_crates.len(),
+            changed_files.len()
+        );
+    }
+
+    let in_dirty = |model: &ExtractionModel, id: NodeId| {
+        model
+            .nodes
+            .get(&id)
+            .and_then(|n| n.crate_id)
+            .is_some_and(|c| dirty.contains(&c))
+    };
+    let dirty_defs: HashMap<ModuleDefId, NodeId> = def_to_node
+        .iter()
+        .filter(|&(_, &id)| in_dirty(&model, id))
+        .map(|(&def, &id)| (def, id))
+        .collect();
+
+    let t = std::time::Instant::now();
+    extract_attributes(
+        &mut model,
+        &loaded.db,
+        &loaded.vfs,
+        &dirty_crates,
+        &dirty_defs,
+    );
+    extract_signatures(&mut model, &loaded.db, &loaded.vfs, &dirty_defs);
+    extract_statics(&mut model, &loaded.db, &loaded.vfs, &dirty_defs);
+    extract_complexity(&mut model, &loaded.db, &dirty_defs);
+    if timing {
+        eprintln!(
+            "extract: delta attrs/sigs/statics     {:>9.2?}  ({} signatures, {} statics, {} fns)",
+            t.elapsed(),
+            model.signatures.len(),
+            model.statics.len(),
+            model.complexity.len()
+        );
+    }
+
+    let t = std::time::Instant::now();
+    let dirty_targets: HashSet<NodeId> = dirty_defs
+        .values()
+        .chain(field_to_node.values())
+        .copied()
+        .filter(|&id| in_dirty(&model, id))
+        .collect();
+    extract_usages_scoped(
+        &mut model,
+        &loaded.db,
+        &loaded.vfs,
+        &loaded.local_crates,
+        &def_to_node,
+        &field_to_node,
+        &module_node_for,
+        &|id| dirty_targets.contains(&id),
+    );
+    if !resolve_dirty_crates.is_empty() {
+        extract_usages_scoped(
+            &mut model,
+            &loaded.db,
+            &loaded.vfs,
+            &resolve_dirty_crates,
+            &def_to_node,
+            &field_to_node,
+            &module_node_for,
+            &|id| !dirty_targets.contains(&id),
+        );
+    }
+    if timing {
+        eprintln!(
+            "extract: delta usages                 {:>9.2?}  ({} usages)",
+            t.elapsed(),
+            model.usages.len()
+        );
+    }
+
+    merge_previous(&mut model, previous, &dirty, &resolve_dirty);
+    if timing {
+        eprintln!(
+            "extract: TOTAL (delta)                {:>9.2?}",
+            t_total.elapsed()
+        );
+    }
+
+    Some(model)
+}
+
+/// Products of the structural passes shared by `extract` and `extract_delta`.
+struct Structure {
+    model: ExtractionModel,
+    crate_node_for: HashMap<Crate, NodeId>,
+    module_node_for: HashMap<ModuleId, NodeId>,
+    def_to_node: HashMap<ModuleDefId, NodeId>,
+    /// v16: fields aren't `ModuleDefId`s, so their usage targets get a map
+    /// of their own.
+    field_to_node: HashMap<Field, NodeId>,
+}
+
+/// Workspace / crate / module nodes, bindings, Layer 4 impl items, v17
+/// macros and v16 fields for every local crate.
+fn extract_structure(loaded: &LoadedWorkspace, timing: bool) -> Structure {
     let workspace_hash = workspace_hash(&loaded.workspace_root);
     let workspace_id = NodeId::from_components(&[workspace_hash.as_str(), "workspace"]);
 
@@ -42,6 +340,10 @@ pub fn extract(loaded: &LoadedWorkspace) -> ExtractionModel {
         contains: Vec::new(),
         signatures: Vec::new(),
         statics: Vec::new(),
+        fields: Vec::new(),
+        macros: Vec::new(),
+        complexity: Vec::new(),
+        trait_impls: Vec::new(),
     };
 
     let workspace_display = loaded
@@ -131,86 +433,136 @@ pub fn extract(loaded: &LoadedWorkspace) -> ExtractionModel {
     );
     if timing {
         eprintln!(
-            "extract: extract_impl_items           {:>9.2?}  (+{} nodes; {} total)",
+            "extract: extract_impl_items           {:>9.2?}  (+{} nodes; {} total; {} trait impls)",
             t.elapsed(),
             model.nodes.len() - nodes_before_impls,
-            model.nodes.len()
+            model.nodes.len(),
+            model.trait_impls.len()
         );
     }
 
-    // v8: per-Item attribute extraction. Runs after impls (so the v5 method /
-    // assoc-const / assoc-type and v7 enum-variant Items already exist in
-    // `model.nodes` and `def_to_node`) and before usages (purely for ordering
-    // — the two passes are independent).
+    // v17: macros join `def_to_node` so the usages pass records their
+    // invocation sites.
     let t = std::time::Instant::now();
-    extract_attributes(
+    extract_macros(
         &mut model,
         &loaded.db,
         &loaded.vfs,
         &loaded.local_crates,
-        &def_to_node,
+        &crate_name_for,
+        &module_node_for,
+        &mut def_to_node,
     );
     if timing {
-        let with_attrs = model
-            .nodes
-            .values()
-            .filter(|n| !n.attributes.is_empty())
-            .count();
         eprintln!(
-            "extract: extract_attributes           {:>9.2?}  ({} items have attrs)",
+            "extract: extract_macros               {:>9.2?}  ({} macros)",
             t.elapsed(),
-            with_attrs
+            model.macros.len()
         );
     }
 
-    // v9 (Phase 5): per-function signature extraction. Runs after impls /
-    // attributes (so def_to_node already includes inherent/trait assoc fns)
-    // and before usages (purely for ordering — independent passes).
+    // v16: fields hang off struct / union Items and off the variant Items
+    // emitted by `extract_impl_items`.
     let t = std::time::Instant::now();
-    extract_signatures(&mut model, &loaded.db, &loaded.vfs, &def_to_node);
-    if timing {
-        eprintln!(
-            "extract: extract_signatures           {:>9.2?}  ({} signatures)",
-            t.elapsed(),
-            model.signatures.len()
-        );
-    }
-
-    // v10 (Phase 7 Path B): per-Static metadata extraction. Runs after
-    // signatures and before usages (independent passes — order is purely
-    // for the timing breakdown).
-    let t = std::time::Instant::now();
-    extract_statics(&mut model, &loaded.db, &loaded.vfs, &def_to_node);
-    if timing {
-        eprintln!(
-            "extract: extract_statics              {:>9.2?}  ({} statics)",
-            t.elapsed(),
-            model.statics.len()
-        );
-    }
-
-    let t = std::time::Instant::now();
-    extract_usages(
+    let field_to_node = extract_fields(
         &mut model,
         &loaded.db,
         &loaded.vfs,
-        &loaded.local_crates,
+        &crate_name_for,
         &def_to_node,
-        &module_node_for,
     );
     if timing {
         eprintln!(
-            "extract: extract_usages               {:>9.2?}  ({} usages)",
+            "extract: extract_fields               {:>9.2?}  ({} fields)",
             t.elapsed(),
-            model.usages.len()
-        );
-        eprintln!(
-            "extract: TOTAL                        {:>9.2?}",
-            t_total.elapsed()
+            model.fields.len()
         );
     }
 
-    model
+    Structure {
+        model,
+        crate_node_for,
+        module_node_for,
+        def_to_node,
+        field_to_node,
+    }
+}
+
+/// Workspace-relative paths of every module file in `krate`'s module tree.
+/// Same walk as the usage search scope; `include!`d files are not seen.
+fn crate_source_files(
+    db: &RootDatabase,
+    vfs: &Vfs,
+    workspace_root: &Path,
+    krate: Crate,
+) -> HashSet<String> {
+    let def_map = crate_def_map(db, krate.base());
+    def_map
+        .modules()
+        .filter(|(module_id, _)| !module_id.is_block_module(db))
+        .filter_map(|(module_id, _)| {
+            let file_id = def_map[module_id]
+                .definition_source_file_id()
+                .original_file(db)
+                .file_id(db);
+            resolve_workspace_relative(vfs, file_id, workspace_root)
+        })
+        .collect()
+}
+
+/// Fold the untouched part of `previous` into a freshly delta-extracted
+/// model. Clean-crate node records replace their re-emitted counterparts
+/// (the structural passes don't fill attributes or declaration spans);
+/// signatures / statics / complexity of clean items and usages of clean
+/// targets from consumer modules outside `resolve_dirty` are copied over
+/// verbatim. Records pointing at nodes the fresh model no longer has are
+/// dropped.
+fn merge_previous(
+    model: &mut ExtractionModel,
+    previous: &ExtractionModel,
+    dirty: &HashSet<NodeId>,
+    resolve_dirty: &HashSet<NodeId>,
+) {
+    let outside = |id: &NodeId, crates: &HashSet<NodeId>| {
+        previous
+            .nodes
+            .get(id)
+            .and_then(|n| n.crate_id)
+            .is_some_and(|c| !crates.contains(&c))
+    };
+    let clean = |id: &NodeId| outside(id, dirty);
+
+    for (id, node) in &previous.nodes {
+        if clean(id)
+            && let Some(slot) = model.nodes.get_mut(id)
+        {
+            *slot = node.clone();
+        }
+    }
+    for (id, sig) in &previous.signatures {
+        if clean(id) && model.nodes.contains_key(id) {
+            model.signatures.push((*id, sig.clone()));
+        }
+    }
+    for (id, meta) in &previous.statics {
+        if clean(id) && model.nodes.contains_key(id) {
+            model.statics.push((*id, meta.clone()));
+        }
+    }
+    for (id, metrics) in &previous.complexity {
+        if clean(id) && model.nodes.contains_key(id) {
+            model.complexity.push((*id, *metrics));
+        }
+    }
+    for usage in &previous.usages {
+        if clean(&usage.target)
+            && outside(&usage.consumer_module, resolve_dirty)
+            && model.nodes.contains_key(&usage.target)
+            && model.nodes.contains_key(&usage.consumer_module)
+        {
+            model.usages.push(usage.clone());
+        }
+    }
 }
 
 fn emit_crate(
@@ -0,0 +1,181 @@
+//! v16 — struct / union / enum-variant field extraction.
+//!
+//! Every field of a local ADT becomes an `ItemKind::Field` Item parented to
+//! its struct / union Item, or to its EnumVariant Item for variant fields.
+//! Visibility and attributes are read straight off the field's AST source
+//! and stored on the `Node`; the declared type (via `HirDisplay`) and the
+//! declaration index go into a `FieldMetadata` record.
+//!
+//! Runs with the structural passes, after `extract_impl_items` (which emits
+//! the variant Items), so incremental builds re-emit every field just like
+//! they re-emit variants. The returned `Field → NodeId` map drives the field
+//! half of `extract_usages` — `Field` is not a `ModuleDefId`, so fields can't
+//! ride along in `def_to_node`.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use ra_ap_hir::{
+    Adt, Crate, DisplayTarget, Field, FieldSource, HasCrate, HasSource, HirDisplay, ModuleDef,
+    Semantics, attach_db,
+};
+use ra_ap_hir_def::ModuleDefId;
+use ra_ap_ide::TryToNav;
+use ra_ap_ide_db::RootDatabase;
+use ra_ap_ide_db::defs::Definition;
+use ra_ap_syntax::AstNode;
+use ra_ap_syntax::ast::{self, HasVisibility};
+use ra_ap_vfs::Vfs;
+
+use super::attributes::outer_attributes;
+use super::audit_util::resolve_workspace_relative;
+use super::hir_trim::trim_hir_display;
+use super::ids::NodeId;
+use super::model::{ExtractionModel, FieldMetadata, ItemKind, Node, NodeKind};
+
+pub(crate) fn extract_fields(
+    model: &mut ExtractionModel,
+    db: &RootDatabase,
+    vfs: &Vfs,
+    crate_name_for: &HashMap<Crate, String>,
+    def_to_node: &HashMap<ModuleDefId, NodeId>,
+) -> HashMap<Field, NodeId> {
+    let workspace_root = model.workspace_root.clone();
+    let mut field_to_node: HashMap<Field, NodeId> = HashMap::new();
+
+    attach_db(db, || {
+        let sema = Semantics::new(db);
+        let mut display_targets: HashMap<Crate, DisplayTarget> = HashMap::new();
+
+        for (&def_id, &parent_id) in def_to_node {
+            // Dep-crate ADTs share the map as ExternalSymbol stubs — only
+            // local Items get field nodes.
+            if model
+                .nodes
+                .get(&parent_id)
+                .is_none_or(|n| n.kind != NodeKind::Item)
+            {
+                continue;
+            }
+            let (fields, krate, is_variant) = match ModuleDef::from(def_id) {
+                ModuleDef::Adt(Adt::Struct(s)) => (s.fields(db), s.krate(db), false),
+                ModuleDef::Adt(Adt::Union(u)) => (u.fields(db), u.krate(db), false),
+                ModuleDef::EnumVariant(v) => (v.fields(db), v.parent_enum(db).krate(db), true),
+                _ => continue,
+            };
+            let Some(crate_name) = crate_name_for.get(&krate) else {
+                continue;
+            };
+            let dt = *display_targets
+                .entry(krate)
+                .or_insert_with(|| krate.to_display_target(db));
+            for field in fields {
+                if let Some(node_id) = emit_field(
+                    model,
+                    &sema,
+                    vfs,
+                    &workspace_root,
+                    crate_name,
+                    dt,
+                    parent_id,
+                    is_variant,
+                    field,
+                ) {
+                    field_to_node.insert(field, node_id);
+                }
+            }
+        }
+    });
+
+    field_to_node
+}
+
+#[allow(clippy::too_many_arguments)]
+fn emit_field(
+    model: &mut ExtractionModel,
+    sema: &Semantics<'_, RootDatabase>,
+    vfs: &Vfs,
+    workspace_root: &Path,
+    crate_name: &str,
+    dt: DisplayTarget,
+    parent_id: NodeId,
+    is_variant: bool,
+    field: Field,
+) -> Option<NodeId> {
+    let db = sema.db;
+    let nav = Definition::Field(field).try_to_nav(sema)?.call_site;
+    // Declaration outside the workspace root (macro from a dep crate).
+    let rel_path = resolve_workspace_relative(vfs, nav.file_id, workspace_root)?;
+    let start: u32 = u32::from(nav.full_range.start());
+    let end: u32 = u32::from(nav.full_range.end());
+
+    let parent = model.nodes.get(&parent_id)?;
+    let crate_id = parent.crate_id;
+    let name = field.name(db).as_str().to_string();
+    let qualified = format!("{}.{name}", parent.qualified_name);
+
+    let byte_offset = start.to_string();
+    let node_id = NodeId::from_components(&[
+        model.workspace_hash.as_str(),
+        "field",
+        crate_name,
+        rel_path.as_str(),
+        byte_offset.as_str(),
+        name.as_str(),
+    ]);
+    if model.nodes.contains_key(&node_id) {
+        return Some(node_id);
+    }
+
+    let source = field.source(db).map(|src| src.value);
+    let (declared, attributes) = match &source {
+        Some(FieldSource::Named(f)) => (f.visibility(), outer_attributes(f)),
+        Some(FieldSource::Pos(f)) => (f.visibility(), outer_attributes(f)),
+        None => (None, Vec::new()),
+    };
+    // Variant fields are as visible as the enum itself; the syntax carries
+    // no modifier to record.
+    let visibility = (!is_variant).then(|| declared_visibility(declared));
+    let type_string = trim_hir_display(&field.ty(db).display(db, dt).to_string());
+
+    model.insert_node(Node {
+        id: node_id,
+        kind: NodeKind::Item,
+        display_name: name,
+        qualified_name: qualified,
+        crate_id,
+        parent_id: Some(parent_id),
+        item_kind: Some(ItemKind::Field),
+        file: Some(rel_path),
+        span: Some((start, end)),
+        visibility,
+        attributes,
+        crate_target_kind: None,
+    });
+    model.insert_contains(parent_id, node_id);
+    model.fields.push((
+        node_id,
+        FieldMetadata {
+            type_string,
+            index: field.index() as u32,
+            is_tuple: matches!(source, Some(FieldSource::Pos(_))),
+        },
+    ));
+    Some(node_id)
+}
+
+/// `pub`, `pub(crate)`, `pub(in a::b)` as written (whitespace collapsed), or
+/// `pub(self)` when there is no modifier. Shared with the macro and impl
+/// passes.
+pub(super) fn declared_visibility(vis: Option<ast::Visibility>) -> String {
+    match vis {
+        Some(vis) => vis
+            .syntax()
+            .text()
+            .to_string()
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" "),
+        None => "pub(self)".to_string(),
+    }
+}
@@ -14,12 +14,13 @@ use ra_ap_hir_def::nameres::crate_def_map;
 use ra_ap_syntax::SyntaxNode;
 use ra_ap_syntax::ast::{self, AstNode, HasLoopBody};
 use ra_ap_vfs::FileId;
+use rmc_config::ignore_engine::IgnoreEngine;
 use serde::{Deserialize, Serialize};
 
 use super::ast_resolve::resolve_call_to_function;
 use super::audit_util::{
     canonical_function_path, enclosed_by_cfg_test,
-    resolve_enclosing_function as enclosing_fn_for_body_offset, resolve_workspace_relative,
+    resolve_audited_file, resolve_enclosing_function as enclosing_fn_for_body_offset,
 };
 use super::ids::NodeId;
 use super::loader::LoadedWorkspace;
@@ -358,6 +359,7 @@ pub fn fn_body_audit(
     let workspace_root = loaded.workspace_root.clone();
     let db = &loaded.db;
     let vfs = &loaded.vfs;
+    let ignores = IgnoreEngine::load(&workspace_root)?;
 
     let mut findings: Vec<FnBodyFinding> = Vec::new();
     let mut file_text_cache: std::collections::HashMap<FileId, String> =
@@ -405,7 +407,7 @@ pub fn fn_body_audit(
         }
 
         for file_id in file_ids {
-            let rel_path = match resolve_workspace_relative(vfs, file_id, &workspace_root) {
+            let rel_path = match resolve_audited_file(vfs, file_id, &workspace_root, &ignores) {
                 Some(p) => p,
                 None => continue,
             };
@@ -50,6 +50,7 @@ macro_rules! define_id {
 define_id!(NodeId);
 define_id!(BindingId);
 define_id!(UsageId);
+define_id!(ImplId);
 
 pub(crate) fn workspace_hash(workspace_root: &Path) -> String {
     let mut hasher = Sha256::new();
@@ -4,27 +4,39 @@
 //! local trait declaration's items, emitting Item nodes for the methods,
 //! associated consts, and associated types found inside. These nodes become
 //! valid targets for `Definition::usages` so that `who_uses(Foo::bar)` and
-//! `who_uses(Trait::method)` can answer non-empty.
+//! `who_uses(Trait::method)` can answer non-empty. Inherent-impl items carry
+//! their declared visibility on the `Node`; trait items carry none.
 //!
 //! Trait *impl* method bodies (`impl T for Foo { fn m() {...} }`) are
 //! deliberately NOT extracted — RA's `Definition::usages` resolves call sites
 //! `x.m()` and `Foo::m()` back to the trait declaration's def, so the trait
 //! Item alone covers `who_uses` for trait dispatch. Adding impl-body items
 //! would emit duplicate nodes that just shadow the trait declaration.
+//!
+//! v13: trait impl blocks still emit no Item nodes, but each one is recorded
+//! as a `TraitImpl` (trait → self type, impl generics, file/span) on
+//! `ExtractionModel.trait_impls`. That relation backs
+//! `implementations_of(trait)` and `impls_of_type(type)`.
 
 use std::collections::HashMap;
 use std::path::Path;
 
-use ra_ap_hir::{AssocItem, Crate, Enum, EnumVariant, HasCrate, Impl, Semantics, Trait, attach_db};
+use ra_ap_hir::{
+    AssocItem, Crate, DisplayTarget, Enum, EnumVariant, GenericDef, HasCrate, HasSource,
+    HirDisplay, Impl, Semantics, Trait, attach_db,
+};
 use ra_ap_hir_def::{AdtId, ModuleDefId, TraitId};
 use ra_ap_ide::TryToNav;
 use ra_ap_ide_db::RootDatabase;
 use ra_ap_ide_db::defs::Definition;
+use ra_ap_syntax::ast::HasVisibility;
 use ra_ap_vfs::Vfs;
 
 use super::audit_util::resolve_workspace_relative;
+use super::fields::declared_visibility;
+use super::hir_trim::trim_hir_display;
 use super::ids::NodeId;
-use super::model::{ExtractionModel, ItemKind, Node, NodeKind};
+use super::model::{ExtractionModel, GenericBound, ItemKind, Node, NodeKind, TraitImpl};
 
 pub(crate) fn extract_impl_items(
     model: &mut ExtractionModel,
@@ -63,11 +75,26 @@ pub(crate) fn extract_impl_items(
                 None => continue,
             };
             let crate_name = crate_name_for.get(&krate).cloned().unwrap_or_default();
+            let dt = krate.to_display_target(db);
 
             // Inherent-impl items.
             for impl_ in Impl::all_in_crate(db, krate) {
-                // Only inherent impls (no trait): trait-impl bodies are deferred.
-                if impl_.trait_(db).is_some() {
+                // Only inherent impls (no trait) emit Items: trait-impl bodies
+                // are deferred. v13: trait impls are recorded as an
+                // `implements` relation instead.
+                if let Some(trait_) = impl_.trait_(db) {
+                    record_trait_impl(
+                        model,
+                        &sema,
+                        vfs,
+                        &workspace_root,
+                        dt,
+                        crate_node_id,
+                        &adt_node_for,
+                        &trait_node_for,
+                        impl_,
+                        trait_,
+                    );
                     continue;
                 }
                 let Some(adt) = impl_.self_ty(db).as_adt() else {
@@ -96,6 +123,7 @@ pub(crate) fn extract_impl_items(
                         crate_node_id,
                         &crate_name,
                         adt_node_id,
+                        true,
                         assoc,
                     );
                 }
@@ -117,6 +145,7 @@ pub(crate) fn extract_impl_items(
                         crate_node_id,
                         &crate_name,
                         trait_node_id,
+                        false,
                         assoc,
                     );
                 }
@@ -152,6 +181,10 @@ pub(crate) fn extract_impl_items(
     });
 }
 
+/// Emit an Item node for one method / assoc const / assoc type. Items of an
+/// inherent impl (`inherent`) record their declared visibility, read off the
+/// AST like a field's; trait-declaration items are as visible as the trait
+/// and record `None`.
 #[allow(clippy::too_many_arguments)]
 fn emit_assoc_item(
     model: &mut ExtractionModel,
@@ -162,6 +195,7 @@ fn emit_assoc_item(
     crate_node_id: NodeId,
     crate_name: &str,
     parent_node_id: NodeId,
+    inherent: bool,
     assoc: AssocItem,
 ) {
     let db = sema.db;
@@ -247,6 +281,15 @@ fn emit_assoc_item(
         name.as_str(),
     ]);
 
+    let visibility = inherent.then(|| {
+        let declared = match assoc {
+            AssocItem::Function(f) => f.source(db).and_then(|src| src.value.visibility()),
+            AssocItem::Const(c) => c.source(db).and_then(|src| src.value.visibility()),
+            AssocItem::TypeAlias(t) => t.source(db).and_then(|src| src.value.visibility()),
+        };
+        declared_visibility(declared)
+    });
+
     // If we somehow already have this id (re-entry during refactors), fold
     // into existing node and just register the def→node mapping.
     if !model.nodes.contains_key(&node_id) {
@@ -260,7 +303,7 @@ fn emit_assoc_item(
             item_kind: Some(item_kind),
             file: Some(rel_path),
             span: Some((start, end)),
-            visibility: None,
+            visibility,
             attributes: Vec::new(),
             crate_target_kind: None,
         });
@@ -273,12 +316,80 @@ fn emit_assoc_item(
     def_to_node.entry(def_id).or_insert(node_id);
 }
 
+/// v13: record one `impl Trait for Type` block as a `TraitImpl`. The trait
+/// and self type are resolved through the same `def_to_node` snapshots the
+/// inherent-impl walk uses, so an external trait only gets a `trait_id` when
+/// some local module imports it (and the bindings pass stubbed it). Impls
+/// whose declaration site can't be mapped back into the workspace (e.g.
+/// produced by a macro defined in a dep crate) are skipped.
+#[allow(clippy::too_many_arguments)]
+fn record_trait_impl(
+    model: &mut ExtractionModel,
+    sema: &Semantics<'_, RootDatabase>,
+    vfs: &Vfs,
+    workspace_root: &Path,
+    dt: DisplayTarget,
+    crate_node_id: NodeId,
+    adt_node_for: &HashMap<AdtId, NodeId>,
+    trait_node_for: &HashMap<TraitId, NodeId>,
+    impl_: Impl,
+    trait_: Trait,
+) {
+    let db = sema.db;
+    let nav = match impl_.try_to_nav(sema) {
+        Some(n) => n.call_site,
+        None => return, // synthetic; no declaration site to point at.
+    };
+    let rel_path = match resolve_workspace_relative(vfs, nav.file_id, workspace_root) {
+        Some(p) => p,
+        None => return,
+    };
+    let start: u32 = u32::from(nav.full_range.start());
+    let end: u32 = u32::from(nav.full_range.end());
+
+    let trait_id: TraitId = trait_.into();
+    let self_ty = impl_.self_ty(db);
+    let self_type_id = self_ty
+        .as_adt()
+        .and_then(|adt| adt_node_for.get(&AdtId::from(adt)).copied());
+    let self_type = trim_hir_display(&self_ty.display(db, dt).to_string());
+
+    let mut generics: Vec<GenericBound> = Vec::new();
+    for toc in GenericDef::from(impl_).type_or_const_params(db) {
+        let Some(tp) = toc.as_type_param(db) else {
+            continue;
+        };
+        if tp.is_implicit(db) {
+            continue;
+        }
+        let name = tp.name(db).as_str().to_string();
+        let bounds: Vec<String> = tp
+            .trait_bounds(db)
+            .into_iter()
+            .map(|t| t.name(db).as_str().to_string())
+            .collect();
+        generics.push(GenericBound { name, bounds });
+    }
+
+    model.trait_impls.push(TraitImpl {
+        crate_id: crate_node_id,
+        trait_id: trait_node_for.get(&trait_id).copied(),
+        trait_name: trait_.name(db).as_str().to_string(),
+        self_type_id,
+        self_type,
+        generics,
+        is_negative: impl_.is_negative(db),
+        file: rel_path,
+        span: (start, end),
+    });
+}
+
 /// v7: emit an Item node for one enum variant, parented to the host enum's
 /// Item NodeId. NodeId scheme mirrors `emit_assoc_item`'s
 /// `[workspace_hash, "enum_variant", crate, file, byte_offset, name]` so two
 /// enums in the same module declaring same-named variants don't collide.
 /// Visibility is `None` (variants inherit from the parent enum, just like
-/// Method/AssocConst/AssocType).
+/// trait-declaration items).
 #[allow(clippy::too_many_arguments)]
 fn emit_enum_variant(
     model: &mut ExtractionModel,
@@ -349,3 +460,171 @@ fn emit_enum_variant(
         .entry(ModuleDefId::EnumVariantId(variant_id))
         .or_insert(node_id);
 }
+
+#[cfg(test)]
+mod tests {
+    //! v13 fixture tests for the `implements` relation. Same strategy as the
+    //! `usages.rs` fixture: one synthetic tempdir crate, built through
+    //! `build_and_persist` once and shared via a `OnceLock`.
+    use crate::graph::model::TraitImpl;
+    use crate::graph::snapshot::{BuildOptions, OpenedSnapshot, build_and_persist, open_current};
+    use crate::graph::storage::{GraphEnvOptions, GraphPaths};
+    use std::sync::OnceLock;
+
+    const FIXTURE_LIB_RS: &str = r#"
+pub trait Shape {
+    fn area(&self) -> f64;
+}
+
+pub struct Square(pub f64);
+pub struct Circle(pub f64);
+pub struct Wrapper<T>(pub T);
+
+impl Square {
+    pub fn side(&self) -> f64 {
+        self.0
+    }
+}
+
+impl Shape for Square {
+    fn area(&self) -> f64 {
+        self.0 * self.0
+    }
+}
+
+impl Shape for Circle {
+    fn area(&self) -> f64 {
+        3.0 * self.0 * self.0
+    }
+}
+
+impl<T: Clone> Shape for Wrapper<T> {
+    fn area(&self) -> f64 {
+        0.0
+    }
+}
+
+impl Default for Square {
+    fn default() -> Self {
+        Square(1.0)
+    }
+}
+"#;
+
+    // Empty `[workspace]` keeps cargo metadata from walking up into an
+    // unrelated manifest (see the note in `usages.rs`).
+    const FIXTURE_CARGO_TOML: &str = r#"
+[package]
+name = "impls_fixture"
+version = "0.1.0"
+edition = "2021"
+
+[lib]
+path = "src/lib.rs"
+
+[workspace]
+"#;
+
+    struct SharedSnap {
+        _workspace_td: tempfile::TempDir,
+        _data_td: tempfile::TempDir,
+        snap: OpenedSnapshot,
+    }
+
+    fn fixture_snapshot() -> &'static OpenedSnapshot {
+        static CACHE: OnceLock<SharedSnap> = OnceLock::new();
+        &CACHE
+            .get_or_init(|| {
+                let workspace_td = tempfile::tempdir().expect("create workspace tempdir");
+                let workspace_path = workspace_td.path();
+                std::fs::write(
+                    workspace_path.join("Cargo.toml"),
+                    FIXTURE_CARGO_TOML.trim_start(),
+                )
+                .expect("write Cargo.toml");
+                std::fs::create_dir_all(workspace_path.join("src")).expect("create src dir");
+                std::fs::write(
+                    workspace_path.join("src").join("lib.rs"),
+                    FIXTURE_LIB_RS.trim_start(),
+                )
+                .expect("write lib.rs");
+
+                let data_td = tempfile::tempdir().expect("create data tempdir");
+                let opts = BuildOptions {
+                    data_dir_override: Some(data_td.path().to_path_buf()),
+                    ..Default::default()
+                };
+                let result = build_and_persist(workspace_path, opts)
+                    .expect("build_and_persist on impls fixture");
+                let paths = GraphPaths::for_workspace_in(data_td.path(), &result.workspace_root);
+                let snap = open_current(&paths, GraphEnvOptions::default())
+                    .expect("open_current succeeds")
+                    .expect("snapshot exists after build_and_persist");
+
+                SharedSnap {
+                    _workspace_td: workspace_td,
+                    _data_td: data_td,
+                    snap,
+                }
+            })
+            .snap
+    }
+
+    fn lookup(snap: &OpenedSnapshot, qualified_name: &str) -> crate::graph::ids::NodeId {
+        snap.lookup_by_qualified_name(qualified_name)
+            .expect("lookup_by_qualified_name failed")
+            .unwrap_or_else(|| panic!("fixture target `{qualified_name}` not in graph"))
+            .0
+    }
+
+    #[test]
+    fn implementations_of_lists_every_implementor() {
+        let snap = fixture_snapshot();
+        let shape = lookup(snap, "impls_fixture::Shape");
+        let impls = snap.implementations_of(shape).expect("implementations_of");
+
+        let mut self_types: Vec<&str> = impls.iter().map(|i| i.self_type.as_str()).collect();
+        self_types.sort();
+        assert_eq!(self_types, vec!["Circle", "Square", "Wrapper<T>"]);
+        assert!(impls.iter().all(|i| i.trait_id == Some(shape)));
+        assert!(impls.iter().all(|i| i.trait_name == "Shape" && !i.is_negative));
+        assert!(impls.iter().all(|i| i.file == "src/lib.rs" && i.span.0 < i.span.1));
+
+        let square = lookup(snap, "impls_fixture::Square");
+        let on_square = impls
+            .iter()
+            .find(|i| i.self_type == "Square")
+            .expect("impl Shape for Square");
+        assert_eq!(on_square.self_type_id, Some(square));
+    }
+
+    #[test]
+    fn impls_of_type_includes_external_traits_but_not_inherent_impls() {
+        let snap = fixture_snapshot();
+        let square = lookup(snap, "impls_fixture::Square");
+        let impls = snap.impls_of_type(square).expect("impls_of_type");
+
+        let mut traits: Vec<&str> = impls.iter().map(|i| i.trait_name.as_str()).collect();
+        traits.sort();
+        assert_eq!(traits, vec!["Default", "Shape"], "inherent impl must not appear");
+
+        // `Default` comes from the prelude — nothing imports it, so there is
+        // no stub node to key on.
+        let default_impl = impls.iter().find(|i| i.trait_name == "Default").unwrap();
+        assert_eq!(default_impl.trait_id, None);
+    }
+
+    #[test]
+    fn impl_generics_carry_declaration_bounds() {
+        let snap = fixture_snapshot();
+        let shape = lookup(snap, "impls_fixture::Shape");
+        let impls = snap.implementations_of(shape).expect("implementations_of");
+        let wrapper: &TraitImpl = impls
+            .iter()
+            .find(|i| i.self_type == "Wrapper<T>")
+            .expect("blanket-ish impl on Wrapper<T>");
+        assert_eq!(wrapper.generics.len(), 1);
+        assert_eq!(wrapper.generics[0].name, "T");
+        assert_eq!(wrapper.generics[0].bounds, vec!["Clone".to_string()]);
+    }
+}
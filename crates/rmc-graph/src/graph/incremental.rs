@@ -0,0 +1,309 @@
+//! Incremental rebuild support (v14).
+//!
+//! Reads back the parts of the previously published snapshot that
+//! `extract::extract_delta` carries over, and diffs the persisted per-file
+//! digests against the working tree to find what changed.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use anyhow::Result;
+
+use super::ids::NodeId;
+use super::model::ExtractionModel;
+use super::snapshot::OpenedSnapshot;
+use super::storage::is_manifest_file_name;
+
+/// Persisted `file_digests_by_path` of `snap`. Empty for snapshots written
+/// without digests (test fixtures).
+pub(crate) fn read_file_digests(snap: &OpenedSnapshot) -> Result<BTreeMap<String, [u8; 32]>> {
+    let rtxn = snap.read_txn()?;
+    let mut out = BTreeMap::new();
+    for entry in snap.dbs.file_digests_by_path.iter(&rtxn)? {
+        let (path, bytes) = entry?;
+        if bytes.len() != 32 {
+            continue;
+        }
+        let mut digest = [0u8; 32];
+        digest.copy_from_slice(bytes);
+        out.insert(path.to_string(), digest);
+    }
+    Ok(out)
+}
+
+/// Workspace-relative paths that were added, removed, or edited between
+/// `previous` and `current`.
+pub(crate) fn changed_paths(
+    previous: &BTreeMap<String, [u8; 32]>,
+    current: &[(String, [u8; 32])],
+) -> BTreeSet<String> {
+    let mut changed = BTreeSet::new();
+    let mut seen = BTreeSet::new();
+    for (path, digest) in current {
+        seen.insert(path.as_str());
+        if previous.get(path) != Some(digest) {
+            changed.insert(path.clone());
+        }
+    }
+    for path in previous.keys() {
+        if !seen.contains(path.as_str()) {
+            changed.insert(path.clone());
+        }
+    }
+    changed
+}
+
+/// True when any changed path is a `Cargo.toml` / `Cargo.lock` — the crate
+/// graph itself may have moved, so only a full extract is trustworthy.
+pub(crate) fn touches_manifest(changed: &BTreeSet<String>) -> bool {
+    changed.iter().any(|p| is_manifest_file_name(Path::new(p)))
+}
+
+/// The slice of the previous model that `extract_delta` merges back in:
+/// node records, signatures, statics, complexity metrics, and usages.
+/// Bindings, contains edges, trait impls, field metadata and macro metadata
+/// are re-extracted for every crate on each build, so they are left empty
+/// here.
+pub(crate) fn read_carryover_model(snap: &OpenedSnapshot) -> Result<ExtractionModel> {
+    let rtxn = snap.read_txn()?;
+    let workspace_hash = snap.manifest.workspace_hash.clone();
+    let mut model = ExtractionModel {
+        workspace_root: snap.manifest.workspace_root.clone().into(),
+        workspace_id: NodeId::from_components(&[workspace_hash.as_str(), "workspace"]),
+        workspace_hash,
+        nodes: BTreeMap::new(),
+        bindings: Vec::new(),
+        usages: Vec::new(),
+        contains: Vec::new(),
+        signatures: Vec::new(),
+        statics: Vec::new(),
+        fields: Vec::new(),
+        macros: Vec::new(),
+        complexity: Vec::new(),
+        trait_impls: Vec::new(),
+    };
+
+    for entry in snap.dbs.nodes_by_id.iter(&rtxn)? {
+        let (_id, node) = entry?;
+        model.nodes.insert(node.id, node);
+    }
+    for entry in snap.dbs.signatures_by_target.iter(&rtxn)? {
+        let (id_bytes, sig) = entry?;
+        model.signatures.push((node_id_from_bytes(id_bytes), sig));
+    }
+    for entry in snap.dbs.static_metadata_by_target.iter(&rtxn)? {
+        let (id_bytes, meta) = entry?;
+        model.statics.push((node_id_from_bytes(id_bytes), meta));
+    }
+    for entry in snap.dbs.complexity_by_target.iter(&rtxn)? {
+        let (id_bytes, metrics) = entry?;
+        model.complexity.push((node_id_from_bytes(id_bytes), metrics));
+    }
+    for entry in snap.dbs.usages_by_id.iter(&rtxn)? {
+        let (_id, usage) = entry?;
+        model.usages.push(usage);
+    }
+    Ok(model)
+}
+
+fn node_id_from_bytes(bytes: &[u8]) -> NodeId {
+    let mut arr = [0u8; 32];
+    arr.copy_from_slice(bytes);
+    NodeId(arr)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::snapshot::{BuildOptions, build_and_persist, open_current, usage_id_for};
+    use crate::graph::storage::{GraphEnvOptions, GraphPaths};
+    use std::fs;
+
+    /// Two-crate workspace: `app` depends on `base` and calls into it.
+    fn write_workspace(root: &Path) {
+        write_crates(
+            root,
+            &[
+                (
+                    "base",
+                    "",
+                    "pub fn helper() -> u32 { 1 }\n\npub struct Config { pub depth: u32 }\n",
+                ),
+                (
+                    "app",
+                    "base = { path = \"../base\" }\n",
+                    "pub fn run() -> u32 { base::helper() }\n",
+                ),
+            ],
+        );
+    }
+
+    /// A workspace of `(name, [dependencies] lines, lib.rs)` crates
+    fn write_crates(root: &Path, crates: &[(&str, &str, &str)]) {
+        let members: Vec<String> = crates.iter().map(|(name, ..)| format!("\"{name}\"")).collect();
+        fs::write(
+            root.join("Cargo.toml"),
+            format!(
+                "[workspace]\nmembers = [{}]\nresolver = \"2\"\n",
+                members.join(", ")
+            ),
+        )
+        .unwrap();
+        for &(name, deps, lib_rs) in crates {
+            fs::create_dir_all(root.join(name).join("src")).unwrap();
+            fs::write(
+                root.join(name).join("Cargo.toml"),
+                format!(
+                    "[package]\nname = \"{name}\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[dependencies]\n{deps}"
+                ),
+            )
+            .unwrap();
+            fs::write(root.join(name).join("src/lib.rs"), lib_rs).unwrap();
+        }
+    }
+
+    fn build(workspace: &Path, data_dir: &Path, force_rebuild: bool) -> (bool, OpenedSnapshot) {
+        let result = build_and_persist(workspace, BuildOptions {
+            force_rebuild,
+            data_dir_override: Some(data_dir.to_path_buf()),
+            ..Default::default()
+        })
+        .expect("build_and_persist");
+        let paths = GraphPaths::for_workspace_in(data_dir, &result.workspace_root);
+        let snap = open_current(&paths, GraphEnvOptions::default())
+            .unwrap()
+            .expect("snapshot published");
+        (result.incremental, snap)
+    }
+
+    fn assert_same_model(delta: &OpenedSnapshot, full: &OpenedSnapshot) {
+        let delta = read_carryover_model(delta).unwrap();
+        let full = read_carryover_model(full).unwrap();
+        assert_eq!(delta.nodes, full.nodes);
+        let mut delta_usages: Vec<_> = delta.usages.iter().map(|u| usage_id_for(u).0).collect();
+        let mut full_usages: Vec<_> = full.usages.iter().map(|u| usage_id_for(u).0).collect();
+        delta_usages.sort();
+        full_usages.sort();
+        assert_eq!(delta_usages, full_usages);
+        assert_eq!(delta.signatures.len(), full.signatures.len());
+        let mut delta_complexity = delta.complexity.clone();
+        let mut full_complexity = full.complexity.clone();
+        delta_complexity.sort_by_key(|(id, _)| *id);
+        full_complexity.sort_by_key(|(id, _)| *id);
+        assert_eq!(delta_complexity, full_complexity);
+    }
+
+    /// Edits to a dependent crate and to a dependency both go through
+    /// `extract_delta` and land on the same nodes / usages a forced full
+    /// rebuild produces.
+    #[test]
+    fn incremental_rebuild_matches_full_rebuild() {
+        let td = tempfile::tempdir().unwrap();
+        let workspace = td.path().join("ws");
+        fs::create_dir_all(&workspace).unwrap();
+        write_workspace(&workspace);
+        let data_dir = td.path().join("graphs");
+
+        build(&workspace, &data_dir, false);
+        // The first load may write Cargo.lock, which forces one more full
+        // build before digests settle.
+        build(&workspace, &data_dir, false);
+
+        fs::write(
+            workspace.join("app/src/lib.rs"),
+            "pub fn run() -> u32 { base::helper() }\n\npub fn twice() -> u32 { base::helper() + run() }\n",
+        )
+        .unwrap();
+        let (incremental, delta) = build(&workspace, &data_dir, false);
+        assert!(incremental, "app edit should rebuild incrementally");
+        let (_, full) = build(&workspace, &td.path().join("graphs-full"), true);
+        assert_same_model(&delta, &full);
+
+        fs::write(
+            workspace.join("base/src/lib.rs"),
+            "pub fn helper() -> u32 { 2 }\n\npub struct Config { pub depth: u32 }\n\npub fn unused() {}\n",
+        )
+        .unwrap();
+        let (incremental, delta) = build(&workspace, &data_dir, false);
+        assert!(incremental, "base edit should rebuild incrementally");
+        let (_, full) = build(&workspace, &td.path().join("graphs-full-2"), true);
+        assert_same_model(&delta, &full);
+        assert!(
+            delta.lookup_by_qualified_name("base::unused").unwrap().is_some(),
+            "new item in the dependency is extracted"
+        );
+    }
+
+    /// `app` reaches `util::helper` through `base`'s re-export and is never
+    /// edited itself; when `base` swaps the re-export for its own `helper`,
+    /// `app`'s clean usage must be re-resolved rather than carried over.
+    #[test]
+    fn dependency_edit_re_resolves_clean_dependents() {
+        let td = tempfile::tempdir().unwrap();
+        let workspace = td.path().join("ws");
+        fs::create_dir_all(&workspace).unwrap();
+        write_crates(
+            &workspace,
+            &[
+                ("util", "", "pub fn helper() -> u32 { 1 }\n"),
+                (
+                    "base",
+                    "util = { path = \"../util\" }\n",
+                    "pub use util::helper;\n",
+                ),
+                (
+                    "app",
+                    "base = { path = \"../base\" }\n",
+                    "pub fn run() -> u32 { base::helper() }\n",
+                ),
+            ],
+        );
+        let data_dir = td.path().join("graphs");
+        build(&workspace, &data_dir, false);
+        build(&workspace, &data_dir, false);
+
+        fs::write(
+            workspace.join("base/src/lib.rs"),
+            "pub fn helper() -> u32 { util::helper() + 1 }\n",
+        )
+        .unwrap();
+        let (incremental, delta) = build(&workspace, &data_dir, false);
+        assert!(incremental, "base edit should rebuild incrementally");
+        let (_, full) = build(&workspace, &td.path().join("graphs-full"), true);
+        assert_same_model(&delta, &full);
+    }
+
+    fn digest(b: u8) -> [u8; 32] {
+        [b; 32]
+    }
+
+    #[test]
+    fn changed_paths_reports_edits_additions_and_removals() {
+        let previous: BTreeMap<String, [u8; 32]> = [
+            ("src/lib.rs".to_string(), digest(1)),
+            ("src/a.rs".to_string(), digest(2)),
+            ("src/gone.rs".to_string(), digest(3)),
+        ]
+        .into_iter()
+        .collect();
+        let current = vec![
+            ("src/a.rs".to_string(), digest(9)),
+            ("src/lib.rs".to_string(), digest(1)),
+            ("src/new.rs".to_string(), digest(4)),
+        ];
+
+        let changed: Vec<String> = changed_paths(&previous, &current).into_iter().collect();
+        assert_eq!(changed, vec!["src/a.rs", "src/gone.rs", "src/new.rs"]);
+    }
+
+    #[test]
+    fn manifest_edits_force_full_rebuild() {
+        let changed: BTreeSet<String> = ["crates/a/Cargo.toml".to_string()].into_iter().collect();
+        assert!(touches_manifest(&changed));
+        let changed: BTreeSet<String> = ["crates/a/src/lib.rs".to_string()].into_iter().collect();
+        assert!(!touches_manifest(&changed));
+        let changed: BTreeSet<String> =
+            [crate::graph::storage::LOAD_CONFIG_DIGEST_KEY.to_string()].into_iter().collect();
+        assert!(touches_manifest(&changed));
+    }
+}
@@ -54,6 +54,8 @@ pub fn item_kind_short_label(k: ItemKind) -> &'static str {
         ItemKind::AssocType => "AssocType",
         ItemKind::Method => "Method",
         ItemKind::EnumVariant => "EnumVariant",
+        ItemKind::Field => "Field",
+        ItemKind::Macro => "Macro",
     }
 }
 
@@ -72,6 +74,8 @@ pub fn item_kind_display_label(k: ItemKind) -> &'static str {
         ItemKind::AssocType => "AssocType",
         ItemKind::Method => "Method",
         ItemKind::EnumVariant => "EnumVariant",
+        ItemKind::Field => "Field",
+        ItemKind::Macro => "Macro",
     }
 }
 
@@ -90,6 +94,8 @@ pub(crate) fn item_kind_id_label(kind: ItemKind) -> &'static str {
         ItemKind::AssocType => "assoc_type",
         ItemKind::Method => "method",
         ItemKind::EnumVariant => "enum_variant",
+        ItemKind::Field => "field",
+        ItemKind::Macro => "macro",
     }
 }
 
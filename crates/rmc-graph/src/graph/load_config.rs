@@ -0,0 +1,353 @@
+//! Per-workspace rust-analyzer load settings.
+//!
+//! The `[rust_analyzer]` table of `.rmc.toml` at the workspace root picks the
+//! cargo features, target triple and extra `cfg`s a workspace is loaded with,
+//! whether build scripts run and proc macros expand, and whether test,
+//! bench and example targets are loaded:
+//!
+//! ```toml
+//! [rust_analyzer]
+//! features = ["serde", "tokio"]   # omit to keep the loader's selection
+//! no_default_features = true
+//! target = "x86_64-pc-windows-msvc"
+//! cfg = ["tokio_unstable", 'backend="vulkan"']
+//! build_scripts = true            # run build scripts, load OUT_DIR code
+//! proc_macros = true              # expand with the sysroot proc-macro server
+//! test_targets = true             # load tests, benches, examples and cfg(test)
+//! ```
+//!
+//! [`loader::load`](super::loader::load) and the server's semantic loader
+//! both apply it on top of their own defaults; an unconfigured workspace
+//! loads exactly as before. A non-default config is folded into the snapshot
+//! fingerprint as a manifest-like input, so changing it forces a full
+//! hypergraph rebuild.
+
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use ra_ap_cfg::{CfgAtom, CfgDiff};
+use ra_ap_intern::Symbol;
+use ra_ap_load_cargo::{LoadCargoConfig, ProcMacroServerChoice};
+use ra_ap_project_model::{CargoConfig, CargoFeatures, CfgOverrides};
+use rmc_config::ignore_engine::PROJECT_CONFIG_FILE;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// The `[rust_analyzer]` table of `.rmc.toml`; see the module docs
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WorkspaceLoadConfig {
+    /// Cargo features to enable. `None` (with `no_default_features` off)
+    /// keeps the loader's own feature selection.
+    pub features: Option<Vec<String>>,
+    /// Leave each crate's `default` feature off
+    pub no_default_features: bool,
+    /// Target triple passed to cargo as `--target`; `None` uses the host
+    pub target: Option<String>,
+    /// Extra `cfg`s set on every crate, as `name` or `name="value"`
+    pub cfg: Vec<String>,
+    /// Run build scripts (`cargo check`) so the `cfg`s they emit and the
+    /// code they generate in `OUT_DIR` are seen
+    pub build_scripts: bool,
+    /// Expand proc macros with the sysroot's proc-macro server
+    pub proc_macros: bool,
+    /// Load integration tests, benches and examples as crates of their own
+    /// and set `cfg(test)`, so `#[cfg(test)]` modules are analysed too.
+    /// `cfg(test)` applies to library code as well: test-only items join
+    /// every audit and `#[cfg(not(test))]` items drop out of the graph.
+    pub test_targets: bool,
+}
+
+/// `.rmc.toml`; other tables are left to their own readers
+#[derive(Debug, Default, Deserialize)]
+struct ProjectConfig {
+    #[serde(default)]
+    rust_analyzer: WorkspaceLoadConfig,
+}
+
+impl WorkspaceLoadConfig {
+    /// Read the `[rust_analyzer]` table of `workspace_root/.rmc.toml`;
+    /// the default config when the file or table is missing.
+    pub fn load(workspace_root: &Path) -> Result<Self> {
+        let path = workspace_root.join(PROJECT_CONFIG_FILE);
+        let source = match std::fs::read_to_string(&path) {
+            Ok(source) => source,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => {
+                return Err(e).with_context(|| format!("failed to read {}", path.display()));
+            }
+        };
+        let config: ProjectConfig =
+            toml::from_str(&source).with_context(|| format!("invalid {}", path.display()))?;
+        let config = config.rust_analyzer;
+        for entry in &config.cfg {
+            parse_cfg(entry).with_context(|| {
+                format!("invalid cfg in [rust_analyzer] of {}", path.display())
+            })?;
+        }
+        Ok(config)
+    }
+
+    /// Whether this is the load an unconfigured workspace gets
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Cargo feature selection for this config; `None` when it leaves the
+    /// loader's own selection alone
+    pub fn cargo_features(&self) -> Option<CargoFeatures> {
+        if self.features.is_none() && !self.no_default_features {
+            return None;
+        }
+        Some(CargoFeatures::Selected {
+            features: self.features.clone().unwrap_or_default(),
+            no_default_features: self.no_default_features,
+        })
+    }
+
+    /// `base` with this config's features, target, extra `cfg`s and test
+    /// targets. Test targets also set `cfg(test)` on every local crate
+    /// (see [`Self::test_targets`]).
+    ///
+    /// `base`'s per-crate cfg overrides are kept. Its global override is
+    /// kept too unless the config names `cfg`s of its own: `CfgDiff` does
+    /// not expose its atoms, so the two global diffs cannot be combined and
+    /// that case is an error rather than a silent drop.
+    pub fn cargo_config(&self, base: CargoConfig) -> Result<CargoConfig> {
+        let enable = self
+            .cfg
+            .iter()
+            .map(|entry| parse_cfg(entry))
+            .collect::<Result<Vec<_>>>()?;
+        let mut cfg_overrides = base.cfg_overrides.clone();
+        if !enable.is_empty() {
+            if !cfg_overrides.global.is_empty() {
+                bail!(
+                    "[rust_analyzer] cfg cannot be combined with the loader's own global cfg \
+                     overrides"
+                );
+            }
+            cfg_overrides.global = CfgDiff::new(enable, Vec::new());
+        }
+        Ok(CargoConfig {
+            features: self.cargo_features().unwrap_or_else(|| base.features.clone()),
+            target: self.target.clone().or(base.target.clone()),
+            all_targets: self.test_targets || base.all_targets,
+            set_test: self.test_targets || base.set_test,
+            cfg_overrides,
+            ..base
+        })
+    }
+
+    /// `base` with this config's build-script and proc-macro settings
+    pub fn load_cargo_config(&self, base: LoadCargoConfig) -> LoadCargoConfig {
+        LoadCargoConfig {
+            load_out_dirs_from_check: self.build_scripts || base.load_out_dirs_from_check,
+            with_proc_macro_server: if self.proc_macros {
+                ProcMacroServerChoice::Sysroot
+            } else {
+                base.with_proc_macro_server
+            },
+            ..base
+        }
+    }
+
+    /// Stable digest of the config, stored among the snapshot's file digests
+    pub(crate) fn digest(&self) -> [u8; 32] {
+        let bytes = serde_json::to_vec(self).expect("load config serializes");
+        let mut digest = [0u8; 32];
+        digest.copy_from_slice(&Sha256::digest(&bytes));
+        digest
+    }
+}
+
+/// `name` or `name="value"` (quotes optional) as a cfg atom
+fn parse_cfg(entry: &str) -> Result<CfgAtom> {
+    let (key, value) = match entry.split_once('=') {
+        Some((key, value)) => (key.trim(), Some(value.trim().trim_matches('"'))),
+        None => (entry.trim(), None),
+    };
+    let is_ident = !key.is_empty()
+        && !key.starts_with(|c: char| c.is_ascii_digit())
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !is_ident {
+        bail!("`{entry}` is not `name` or `name=\"value\"`");
+    }
+    Ok(match value {
+        Some(value) => CfgAtom::KeyValue {
+            key: Symbol::intern(key),
+            value: Symbol::intern(value),
+        },
+        None => CfgAtom::Flag(Symbol::intern(key)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn load_from(source: &str) -> Result<WorkspaceLoadConfig> {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join(PROJECT_CONFIG_FILE), source).unwrap();
+        WorkspaceLoadConfig::load(dir.path())
+    }
+
+    #[test]
+    fn missing_file_or_table_is_default() {
+        let dir = TempDir::new().unwrap();
+        assert!(WorkspaceLoadConfig::load(dir.path()).unwrap().is_default());
+
+        let config = load_from("[index]\nexclude = [\"tests/**\"]\n").unwrap();
+        assert!(config.is_default());
+        assert!(config.cargo_features().is_none());
+    }
+
+    #[test]
+    fn reads_rust_analyzer_table() {
+        let config = load_from(
+            r#"
+[rust_analyzer]
+features = ["serde"]
+no_default_features = true
+target = "aarch64-unknown-linux-gnu"
+cfg = ["tokio_unstable", 'backend="vulkan"']
+build_scripts = true
+proc_macros = true
+test_targets = true
+"#,
+        )
+        .unwrap();
+
+        assert!(!config.is_default());
+        assert_eq!(config.target.as_deref(), Some("aarch64-unknown-linux-gnu"));
+        match config.cargo_features() {
+            Some(CargoFeatures::Selected {
+                features,
+                no_default_features,
+            }) => {
+                assert_eq!(features, vec!["serde".to_string()]);
+                assert!(no_default_features);
+            }
+            other => panic!("expected selected features, got {other:?}"),
+        }
+        assert_eq!(config.cfg.len(), 2);
+        assert!(config.build_scripts && config.proc_macros && config.test_targets);
+    }
+
+    #[test]
+    fn test_targets_turn_on_all_targets_and_cfg_test() {
+        let base = CargoConfig::default();
+        let config = WorkspaceLoadConfig::default().cargo_config(base.clone()).unwrap();
+        assert_eq!(config.all_targets, base.all_targets);
+        assert_eq!(config.set_test, base.set_test);
+
+        let config = WorkspaceLoadConfig {
+            test_targets: true,
+            ..Default::default()
+        }
+        .cargo_config(base)
+        .unwrap();
+        assert!(config.all_targets && config.set_test);
+    }
+
+    #[test]
+    fn base_cfg_overrides_survive() {
+        let atom = |name: &str| parse_cfg(name).unwrap();
+        let base = CargoConfig {
+            cfg_overrides: CfgOverrides {
+                global: CfgDiff::new(vec![atom("loom")], Vec::new()),
+                selective: [(
+                    "core_crate".to_string(),
+                    CfgDiff::new(Vec::new(), vec![atom("debug_assertions")]),
+                )]
+                .into_iter()
+                .collect(),
+            },
+            ..Default::default()
+        };
+
+        let kept = WorkspaceLoadConfig::default().cargo_config(base.clone()).unwrap();
+        assert_eq!(kept.cfg_overrides, base.cfg_overrides);
+
+        let mut selective_only = base.clone();
+        selective_only.cfg_overrides.global = CfgDiff::default();
+        let config = WorkspaceLoadConfig {
+            cfg: vec!["tokio_unstable".to_string()],
+            ..Default::default()
+        };
+        let merged = config.cargo_config(selective_only.clone()).unwrap();
+        assert_eq!(
+            merged.cfg_overrides.global,
+            CfgDiff::new(vec![atom("tokio_unstable")], Vec::new())
+        );
+        assert_eq!(
+            merged.cfg_overrides.selective,
+            selective_only.cfg_overrides.selective
+        );
+
+        assert!(config.cargo_config(base).is_err());
+    }
+
+    #[test]
+    fn no_default_features_alone_selects_nothing_extra() {
+        let config = load_from("[rust_analyzer]\nno_default_features = true\n").unwrap();
+        match config.cargo_features() {
+            Some(CargoFeatures::Selected {
+                features,
+                no_default_features,
+            }) => {
+                assert!(features.is_empty());
+                assert!(no_default_features);
+            }
+            other => panic!("expected selected features, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_keys_and_bad_cfgs() {
+        assert!(load_from("[rust_analyzer]\nall_features = true\n").is_err());
+        assert!(load_from("[rust_analyzer]\ncfg = [\"not a cfg\"]\n").is_err());
+        assert!(load_from("[rust_analyzer]\ncfg = [\"=value\"]\n").is_err());
+    }
+
+    #[test]
+    fn parse_cfg_accepts_flags_and_key_values() {
+        assert_eq!(
+            parse_cfg("tokio_unstable").unwrap(),
+            CfgAtom::Flag(Symbol::intern("tokio_unstable"))
+        );
+        let quoted = parse_cfg("backend=\"vulkan\"").unwrap();
+        assert_eq!(quoted, parse_cfg("backend = vulkan").unwrap());
+        assert_eq!(
+            quoted,
+            CfgAtom::KeyValue {
+                key: Symbol::intern("backend"),
+                value: Symbol::intern("vulkan"),
+            }
+        );
+    }
+
+    #[test]
+    fn digest_tracks_every_setting() {
+        let base = WorkspaceLoadConfig::default();
+        let with_target = WorkspaceLoadConfig {
+            target: Some("wasm32-unknown-unknown".to_string()),
+            ..Default::default()
+        };
+        let with_scripts = WorkspaceLoadConfig {
+            build_scripts: true,
+            ..Default::default()
+        };
+        let with_tests = WorkspaceLoadConfig {
+            test_targets: true,
+            ..Default::default()
+        };
+
+        assert_eq!(base.digest(), WorkspaceLoadConfig::default().digest());
+        assert_ne!(base.digest(), with_target.digest());
+        assert_ne!(base.digest(), with_scripts.digest());
+        assert_ne!(with_target.digest(), with_scripts.digest());
+        assert_ne!(with_scripts.digest(), with_tests.digest());
+    }
+}
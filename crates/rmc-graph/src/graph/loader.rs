@@ -14,6 +14,21 @@
 //! `CARGO_RESOLVER_LOCKFILE_PATH` env var instead of `--lockfile-path` to
 //! avoid mutating Cargo.lock (older versions used the flag, which broke on
 //! cargo versions where metadata didn't accept it).
+//!
+//! ### Load settings
+//!
+//! Features, target triple, extra `cfg`s, build scripts and proc macros come
+//! from the workspace's [`WorkspaceLoadConfig`]. Unconfigured, every feature
+//! is enabled for the host target and neither build scripts nor proc macros
+//! run.
+//!
+//! ### Test targets
+//!
+//! Only library and binary targets are kept by default, loaded without
+//! `cfg(test)`. With `test_targets` set, integration tests, benches and
+//! examples are kept as crates of their own (tagged through
+//! `crate_target_kind` like any other) and `#[cfg(test)]` modules are
+//! loaded, so call edges from test code reach the graph.
 
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
@@ -28,6 +43,7 @@ use ra_ap_project_model::{CargoConfig, CargoFeatures, RustLibSource};
 use ra_ap_vfs::Vfs;
 
 use super::audit_util::resolve_workspace_relative;
+use super::load_config::WorkspaceLoadConfig;
 
 pub struct LoadedWorkspace {
     pub workspace_root: PathBuf,
@@ -38,7 +54,17 @@ pub struct LoadedWorkspace {
     pub crate_target_kinds_by_root_file: HashMap<String, String>,
 }
 
+/// Load `directory` with the load settings in its `.rmc.toml`.
 pub fn load(directory: &Path) -> Result<LoadedWorkspace> {
+    let workspace_root = directory
+        .canonicalize()
+        .with_context(|| format!("failed to canonicalize {}", directory.display()))?;
+    let config = WorkspaceLoadConfig::load(&workspace_root)?;
+    load_with_config(&workspace_root, &config)
+}
+
+/// Load `directory` with explicit load settings.
+pub fn load_with_config(directory: &Path, config: &WorkspaceLoadConfig) -> Result<LoadedWorkspace> {
     let canonical = directory
         .canonicalize()
         .with_context(|| format!("failed to canonicalize {}", directory.display()))?;
@@ -46,16 +72,16 @@ pub fn load(directory: &Path) -> Result<LoadedWorkspace> {
     let (crate_target_kinds_by_name, crate_target_kinds_by_root_file) =
         load_crate_target_kinds(&workspace_root);
 
-    let cargo_config = CargoConfig {
+    let cargo_config = config.cargo_config(CargoConfig {
         sysroot: Some(RustLibSource::Discover),
         no_deps: false,
         features: CargoFeatures::All,
         all_targets: false,
         set_test: false,
         ..Default::default()
-    };
+    })?;
 
-    let load_config = LoadCargoConfig {
+    let load_config = config.load_cargo_config(LoadCargoConfig {
         load_out_dirs_from_check: false,
         with_proc_macro_server: ProcMacroServerChoice::None,
         // Build every workspace crate's DefMap in parallel during load. Without
@@ -64,7 +90,7 @@ pub fn load(directory: &Path) -> Result<LoadedWorkspace> {
         prefill_caches: true,
         num_worker_threads: num_cpus::get_physical(),
         proc_macro_processes: 1,
-    };
+    });
 
     let (db, vfs, _proc_macro) =
         load_workspace_at(&canonical, &cargo_config, &load_config, &|_| {})
@@ -75,6 +101,7 @@ pub fn load(directory: &Path) -> Result<LoadedWorkspace> {
         &vfs,
         &workspace_root,
         &crate_target_kinds_by_root_file,
+        config.test_targets,
     );
 
     Ok(LoadedWorkspace {
@@ -98,11 +125,13 @@ pub fn load(directory: &Path) -> Result<LoadedWorkspace> {
 /// its workspace-only mode — it correctly excludes crates.io deps, the
 /// sysroot, the rustc workspace, and proc-macro-host crates — with an
 /// additional Cargo target-kind filter for workspace-only extra targets.
+/// `include_test_targets` opts tests, benches and examples back in.
 fn filter_local_crates(
     db: &RootDatabase,
     vfs: &Vfs,
     workspace_root: &Path,
     crate_target_kinds_by_root_file: &HashMap<String, String>,
+    include_test_targets: bool,
 ) -> Vec<Crate> {
     Crate::all(db)
         .into_iter()
@@ -118,7 +147,7 @@ fn filter_local_crates(
                 *krate,
                 crate_target_kinds_by_root_file,
             ) {
-                Some(kind) => should_index_target_kind(kind),
+                Some(kind) => should_index_target_kind(kind, include_test_targets),
                 None => true,
             }
         })
@@ -142,8 +171,12 @@ fn crate_root_target_kind<'a>(
     crate_target_kinds_by_root_file.get(&root_file).map(String::as_str)
 }
 
-fn should_index_target_kind(kind: &str) -> bool {
-    matches!(kind, "lib" | "bin")
+fn should_index_target_kind(kind: &str, include_test_targets: bool) -> bool {
+    match kind {
+        "lib" | "bin" => true,
+        "test" | "bench" | "example" => include_test_targets,
+        _ => false,
+    }
 }
 
 fn load_crate_target_kinds(
@@ -191,6 +224,30 @@ fn load_crate_target_kinds(
     (by_name, by_root_file)
 }
 
+/// Directory of every workspace member package, keyed by package name with
+/// `-` normalized to `_`. Cheap compared to [`load`]: only runs
+/// `cargo metadata --no-deps`.
+pub fn workspace_crate_dirs(workspace_root: &Path) -> Result<HashMap<String, PathBuf>> {
+    let mut command = MetadataCommand::new();
+    command
+        .manifest_path(workspace_root.join("Cargo.toml"))
+        .no_deps();
+    let metadata = command
+        .exec()
+        .with_context(|| format!("cargo metadata failed for {}", workspace_root.display()))?;
+
+    let workspace_members: HashSet<_> = metadata.workspace_members.iter().cloned().collect();
+    Ok(metadata
+        .packages
+        .iter()
+        .filter(|package| workspace_members.contains(&package.id))
+        .filter_map(|package| {
+            let dir = package.manifest_path.parent()?.as_std_path().to_path_buf();
+            Some((normalize_crate_name(&package.name.to_string()), dir))
+        })
+        .collect())
+}
+
 fn normalize_crate_name(name: &str) -> String {
     name.replace('-', "_")
 }
@@ -283,13 +340,36 @@ mod tests {
 
     #[test]
     fn should_index_only_library_and_binary_targets() {
-        assert!(should_index_target_kind("lib"));
-        assert!(should_index_target_kind("bin"));
-        assert!(!should_index_target_kind("test"));
-        assert!(!should_index_target_kind("bench"));
-        assert!(!should_index_target_kind("example"));
-        assert!(!should_index_target_kind("build"));
-        assert!(!should_index_target_kind("unknown"));
+        assert!(should_index_target_kind("lib", false));
+        assert!(should_index_target_kind("bin", false));
+        assert!(!should_index_target_kind("test", false));
+        assert!(!should_index_target_kind("bench", false));
+        assert!(!should_index_target_kind("example", false));
+        assert!(!should_index_target_kind("build", false));
+        assert!(!should_index_target_kind("unknown", false));
+    }
+
+    #[test]
+    fn test_targets_mode_keeps_tests_benches_and_examples() {
+        assert!(should_index_target_kind("lib", true));
+        assert!(should_index_target_kind("test", true));
+        assert!(should_index_target_kind("bench", true));
+        assert!(should_index_target_kind("example", true));
+        assert!(!should_index_target_kind("build", true));
+        assert!(!should_index_target_kind("unknown", true));
+    }
+
+    #[test]
+    fn workspace_crate_dirs_maps_normalized_names_to_member_dirs() {
+        let workspace_root = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .parent()
+            .and_then(Path::parent)
+            .expect("workspace root is two levels above crates/rmc-graph");
+        let dirs = workspace_crate_dirs(workspace_root).expect("cargo metadata");
+
+        assert!(dirs["rmc_graph"].ends_with("crates/rmc-graph"));
+        assert!(dirs["rust_code_mcp"].ends_with("crates/rust-code-mcp"));
+        assert!(!dirs.contains_key("rmc-graph"));
     }
 
     #[test]
@@ -0,0 +1,204 @@
+//! v17 — macro definitions as Items.
+//!
+//! Every `macro_rules!`, `macro` (2.0) and procedural macro declared in a
+//! local crate becomes an `ItemKind::Macro` Item parented to its declaring
+//! module, with attributes and visibility read off the AST source and the
+//! flavor stored in a `MacroMetadata` record. A `macro_rules!` that shadows
+//! an earlier one of the same name is a separate Item with the same
+//! qualified name. Macros in block scopes (inside fn bodies) are skipped,
+//! like every other block-local item.
+//!
+//! Macros live outside the type / value namespaces the bindings pass walks,
+//! so no `Binding` is recorded for them. Instead each macro is added to
+//! `def_to_node` under its `ModuleDefId::MacroId`, which is all
+//! `extract_usages` needs to record invocation sites (`my_macro!(..)`,
+//! `#[derive(MyDerive)]`) with `consumer_function` attribution.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use ra_ap_hir::{Crate, HasSource, Macro, MacroKind as HirMacroKind, Semantics, attach_db};
+use ra_ap_hir_def::nameres::crate_def_map;
+use ra_ap_hir_def::per_ns::Item;
+use ra_ap_hir_def::{MacroId, ModuleDefId, ModuleId};
+use ra_ap_ide::TryToNav;
+use ra_ap_ide_db::RootDatabase;
+use ra_ap_ide_db::defs::Definition;
+use ra_ap_syntax::ast::{self, HasVisibility};
+use ra_ap_vfs::Vfs;
+
+use super::attributes::outer_attributes;
+use super::audit_util::resolve_workspace_relative;
+use super::fields::declared_visibility;
+use super::ids::NodeId;
+use super::labels::item_kind_id_label;
+use super::model::{ExtractionModel, ItemKind, MacroKind, MacroMetadata, Node, NodeKind};
+
+pub(crate) fn extract_macros(
+    model: &mut ExtractionModel,
+    db: &RootDatabase,
+    vfs: &Vfs,
+    local_crates: &[Crate],
+    crate_name_for: &HashMap<Crate, String>,
+    module_node_for: &HashMap<ModuleId, NodeId>,
+    def_to_node: &mut HashMap<ModuleDefId, NodeId>,
+) {
+    let workspace_root = model.workspace_root.clone();
+
+    attach_db(db, || {
+        let sema = Semantics::new(db);
+
+        for &krate in local_crates {
+            let Some(crate_name) = crate_name_for.get(&krate) else {
+                continue;
+            };
+            let def_map = crate_def_map(db, krate.base());
+            let Some(&root_node) = module_node_for.get(&def_map.crate_root(db)) else {
+                continue;
+            };
+
+            for (module_id, _) in def_map.modules() {
+                if module_id.is_block_module(db) {
+                    continue;
+                }
+                let Some(&module_node) = module_node_for.get(&module_id) else {
+                    continue;
+                };
+
+                // Path-scoped macros (`macro` 2.0, proc macros, exported
+                // `macro_rules!` at the crate root) plus the textually scoped
+                // `macro_rules!`. Both lists also carry macros declared in
+                // other modules; the owner check keeps one visit per macro.
+                let scope = &def_map[module_id].scope;
+                let mut macro_ids: Vec<MacroId> = scope
+                    .macros()
+                    .map(|(_, Item { def, .. })| def)
+                    .collect();
+                macro_ids.extend(scope.legacy_macros().flat_map(|(_, ids)| ids.iter().copied()));
+
+                for id in macro_ids {
+                    let def_id = ModuleDefId::MacroId(id);
+                    if def_to_node.contains_key(&def_id) {
+                        continue;
+                    }
+                    let mac = Macro::from(id);
+                    let owner: ModuleId = mac.module(db).into();
+                    if owner != module_id {
+                        continue;
+                    }
+                    if let Some(node_id) = emit_macro(
+                        model,
+                        &sema,
+                        vfs,
+                        &workspace_root,
+                        crate_name,
+                        module_node,
+                        root_node,
+                        mac,
+                    ) {
+                        def_to_node.insert(def_id, node_id);
+                    }
+                }
+            }
+        }
+    });
+}
+
+#[allow(clippy::too_many_arguments)]
+fn emit_macro(
+    model: &mut ExtractionModel,
+    sema: &Semantics<'_, RootDatabase>,
+    vfs: &Vfs,
+    workspace_root: &Path,
+    crate_name: &str,
+    module_node: NodeId,
+    root_node: NodeId,
+    mac: Macro,
+) -> Option<NodeId> {
+    let db = sema.db;
+    let nav = Definition::Macro(mac).try_to_nav(sema)?.call_site;
+    // Declaration outside the workspace root (generated by a dep's macro).
+    let rel_path = resolve_workspace_relative(vfs, nav.file_id, workspace_root)?;
+
+    let module = model.nodes.get(&module_node)?;
+    let crate_id = module.crate_id;
+    let module_qual = module.qualified_name.clone();
+    let name = mac.name(db).as_str().to_string();
+    let mut node_id = NodeId::from_components(&[
+        model.workspace_hash.as_str(),
+        "item",
+        crate_name,
+        module_qual.as_str(),
+        item_kind_id_label(ItemKind::Macro),
+        name.as_str(),
+    ]);
+    // A `macro_rules!` shadowing an earlier one of the same name gets its
+    // own Item, told apart by its declaration site.
+    if model.nodes.contains_key(&node_id) {
+        let start = u32::from(nav.full_range.start()).to_string();
+        node_id = NodeId::from_components(&[
+            model.workspace_hash.as_str(),
+            "item",
+            crate_name,
+            module_qual.as_str(),
+            item_kind_id_label(ItemKind::Macro),
+            name.as_str(),
+            rel_path.as_str(),
+            start.as_str(),
+        ]);
+    }
+
+    let kind = match mac.kind(db) {
+        HirMacroKind::Declarative | HirMacroKind::DeclarativeBuiltIn => MacroKind::Declarative,
+        HirMacroKind::ProcMacro => MacroKind::FunctionLike,
+        HirMacroKind::Derive | HirMacroKind::DeriveBuiltIn => MacroKind::Derive,
+        HirMacroKind::Attr | HirMacroKind::AttrBuiltIn => MacroKind::Attribute,
+    };
+    let source = mac.source(db).map(|src| src.value);
+    let attributes = source
+        .as_ref()
+        .map(|src| src.as_ref().either(outer_attributes, outer_attributes))
+        .unwrap_or_default();
+    let macro_export = kind == MacroKind::Declarative
+        && attributes.iter().any(|attr| attr.starts_with("#[macro_export"));
+    let macro_def = source.as_ref().and_then(|src| match src.as_ref().left()? {
+        ast::Macro::MacroDef(def) => Some(def.clone()),
+        ast::Macro::MacroRules(_) => None,
+    });
+    let visibility = match (kind, macro_def) {
+        (MacroKind::Declarative, _) if macro_export => "pub".to_string(),
+        (MacroKind::Declarative, Some(def)) => declared_visibility(def.visibility()),
+        (MacroKind::Declarative, None) => "pub(crate)".to_string(),
+        _ => "pub".to_string(),
+    };
+
+    // Exported macros are named from the crate root, wherever they are
+    // declared.
+    let qualified = if macro_export {
+        let root_qual = model.nodes.get(&root_node)?.qualified_name.as_str();
+        format!("{root_qual}::{name}")
+    } else {
+        format!("{module_qual}::{name}")
+    };
+
+    model.insert_node(Node {
+        id: node_id,
+        kind: NodeKind::Item,
+        display_name: name,
+        qualified_name: qualified,
+        crate_id,
+        parent_id: Some(module_node),
+        item_kind: Some(ItemKind::Macro),
+        file: Some(rel_path),
+        span: Some((
+            u32::from(nav.full_range.start()),
+            u32::from(nav.full_range.end()),
+        )),
+        visibility: Some(visibility),
+        attributes,
+        crate_target_kind: None,
+    });
+    model.insert_contains(module_node, node_id);
+    model.macros.push((node_id, MacroMetadata { kind, macro_export }));
+    Some(node_id)
+}
@@ -8,18 +8,24 @@ pub(in crate::graph) mod audit_util;
 mod attributes;
 mod bindings;
 mod channel_audit;
+mod co_change;
 pub mod codemap;
+mod complexity;
 mod derive_audit;
 mod docs_audit;
 #[cfg(feature = "semantic-embeddings")]
 mod embedding_cache;
 mod extract;
+mod fields;
 mod fn_body_audit;
 mod hir_trim;
 pub mod ids;
 mod impls;
+mod incremental;
 mod labels;
+mod load_config;
 mod loader;
+mod macros;
 mod math;
 pub mod model;
 mod query;
@@ -35,32 +41,43 @@ mod unsafe_audit;
 mod usages;
 
 pub use ids::{BindingId, NodeId};
+pub use complexity::fn_complexity;
 pub use extract::extract as extract_workspace_model;
 pub use labels::{item_kind_display_label, item_kind_short_label};
-pub use loader::{LoadedWorkspace, load};
+pub use load_config::WorkspaceLoadConfig;
+pub use loader::{LoadedWorkspace, load, load_with_config, workspace_crate_dirs};
 pub use model::{
-    Binding, BindingVisibility, ExtractionModel, FunctionSignature, ItemKind, Namespace, Node,
-    NodeKind, Usage,
+    Binding, BindingVisibility, ComplexityMetrics, ExtractionModel, FunctionSignature, ItemKind,
+    Namespace, Node, NodeKind, TraitImpl, Usage,
 };
 #[cfg(feature = "semantic-embeddings")]
 pub(crate) use model::EmbeddingRecord;
+pub use query::api_changes::{api_breaking_changes, run_api_breaking_changes};
 pub use query::audits::{
     ChannelCapacityAuditOptions, DeriveAuditOptions, FnBodyAuditOptions, GraphAuditError,
     MissingDocsAuditOptions, RecursionCheckOptions, run_channel_capacity_audit,
     run_derive_audit, run_fn_body_audit, run_missing_docs_audit, run_mut_static_audit,
     run_recursion_check, run_unsafe_audit,
 };
+pub use query::co_change::{
+    CoChangeOptions, run_item_churn, run_item_co_change, run_module_co_change,
+};
+pub use query::diff::{SnapshotSelector, diff_snapshots, run_snapshot_diff};
 pub use query::model::{
-    CallGraphNode, ChannelCapacityFinding, CrateDeadPub, CrateEdge, CrateMetric, CrateTypeItem,
-    DeadPubFinding, DeriveAuditFinding, EnrichedBinding, EnrichedCallSite, EnrichedCrateDeadPub,
-    EnrichedDeadPub, EnrichedUsage, FnBodyAuditFinding, FnBodyAuditOutput,
-    ForbiddenDependencyRule, ForbiddenDependencyViolation, FunctionFilter, FunctionWithSignature,
-    ItemWithAttribute, MissingDocsAuditFinding, ModuleDependency, ModuleDependencySymbol,
-    ModuleTreeNode, MutStaticAuditFinding, OverlapScope, OverlapsReport,
-    PubTypeAliasMasqueradingAsReexport, ReExportChain, RecursionCheckOutput, RecursionCycle,
-    RecursiveCallersCount, SelfKindFilter, SemanticOverlapScope, SemanticOverlapsOutput,
-    SimilarityCluster, SimilarityItem, SimilarityPair, UnsafeAuditFinding, UsageSummaryRow,
-    WorkspaceStats,
+    ApiBreakingChanges, ApiChange, ApiChangeKind, CallGraphNode, ChannelCapacityFinding,
+    ComplexityHotspot, ComplexityMetric, CrateDeadPub, CrateEdge, CrateMetric, CrateTypeItem,
+    DeadPubFinding, DeriveAuditFinding, DiffCallEdge, DiffCrateEdge, DiffItem, EnrichedBinding,
+    EnrichedCallSite, EnrichedCrateDeadPub, EnrichedDeadPub, EnrichedUsage, ExercisingTest,
+    FnBodyAuditFinding,
+    FnBodyAuditOutput, ForbiddenDependencyRule, ForbiddenDependencyViolation, FunctionFilter,
+    FunctionWithSignature, GitHistoryScan, ItemChurn, ItemChurnReport, ItemCoChange,
+    ItemCoChangeReport, ItemWithAttribute, MacroFanIn, MissingDocsAuditFinding, ModuleCoChange,
+    ModuleCoChangeReport, ModuleDependency, ModuleDependencySymbol, ModuleTreeNode, MovedItem,
+    MutStaticAuditFinding, OverlapScope, OverlapsReport, PubTypeAliasMasqueradingAsReexport,
+    ReExportChain, RecursionCheckOutput, RecursionCycle, RecursiveCallersCount, SelfKindFilter,
+    SemanticOverlapScope, SemanticOverlapsOutput, SignatureChange, SimilarityCluster,
+    SimilarityItem, SimilarityPair, SnapshotDiff, SnapshotDiffSide, TypeField, UnsafeAuditFinding,
+    UntestedPubItem, UntestedPubItems, UsageSummaryRow, VisibilityChange, WorkspaceStats,
 };
 #[cfg(feature = "semantic-embeddings")]
 pub use query::similarity::{
@@ -72,8 +89,8 @@ pub use skeleton::{
 };
 pub use snapshot::{
     BuildOptions, GraphSnapshotCleanupEntry, GraphSnapshotCleanupOptions,
-    GraphSnapshotCleanupReport, OpenedSnapshot, build_and_persist,
-    clear_all_workspace_snapshots, clear_workspace_snapshots, open_current,
-    open_current_for_workspace,
+    GraphSnapshotCleanupReport, OpenedSnapshot, SnapshotStaleness, build_and_persist,
+    clear_all_workspace_snapshots, clear_workspace_snapshots, mark_workspace_stale,
+    open_current, open_current_for_workspace, published_workspaces, workspace_staleness,
 };
-pub use storage::{GraphEnvOptions, GraphPaths};
+pub use storage::{GraphEnvOptions, GraphManifest, GraphPaths};
@@ -43,6 +43,23 @@ pub enum ItemKind {
     /// the enclosing module. Visibility is inherited from the enum (always
     /// `None` here — same shape as `Method`).
     EnumVariant,
+    /// v16: a named or tuple field of a struct, union or enum variant.
+    /// `parent_id` points at the struct/union Item or at the EnumVariant
+    /// Item. Tuple fields are named by position (`"0"`, `"1"`, ...). The
+    /// qualified name joins the parent with `.` (`my_crate::Config.max_size`)
+    /// so a field never collides with a same-named method. `visibility` holds
+    /// the declared modifier (`pub`, `pub(crate)`, `pub(self)` when omitted);
+    /// variant fields carry `None` since they share the enum's visibility.
+    Field,
+    /// v17: a `macro_rules!`, `macro` (2.0) or procedural macro declared in
+    /// a local crate. `parent_id` points at the declaring module. A
+    /// `#[macro_export]` macro is qualified from the crate root
+    /// (`my_crate::my_macro`), where callers name it; other macros keep
+    /// their module path. `visibility` is `pub` for exported and proc
+    /// macros, the declared modifier for `macro` items, and `pub(crate)` for
+    /// plain `macro_rules!` (textual scope never leaves the crate).
+    /// `MacroMetadata` records which flavor it is.
+    Macro,
 }
 
 impl ItemKind {
@@ -193,6 +210,62 @@ pub struct FunctionSignature {
     pub return_type: String,
     #[serde(default)]
     pub generics: Vec<GenericBound>,
+    /// v15: `true` when the declaration carries a body. Always set for free
+    /// and inherent fns; for trait declaration fns it marks a provided
+    /// default, so adding a trait method without one is a breaking change.
+    #[serde(default)]
+    pub has_body: bool,
+}
+
+impl FunctionSignature {
+    /// Declaration header without visibility or body, e.g.
+    /// `async fn fetch<T: Send>(&self, id: u32) -> Result<T>`. Unnamed
+    /// params render as `_arg{idx}`.
+    pub fn render(&self, name: &str) -> String {
+        let async_prefix = if self.is_async { "async " } else { "" };
+        let generics = if self.generics.is_empty() {
+            String::new()
+        } else {
+            let rendered = self
+                .generics
+                .iter()
+                .map(|generic| {
+                    if generic.bounds.is_empty() {
+                        generic.name.clone()
+                    } else {
+                        format!("{}: {}", generic.name, generic.bounds.join(" + "))
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("<{rendered}>")
+        };
+        let mut params = Vec::new();
+        if let Some(self_param) = self.self_param {
+            params.push(match self_param {
+                SelfKind::Owned => "self".to_string(),
+                SelfKind::Ref => "&self".to_string(),
+                SelfKind::RefMut => "&mut self".to_string(),
+            });
+        }
+        params.extend(self.params.iter().enumerate().map(|(idx, param)| {
+            let name = if param.name.is_empty() {
+                format!("_arg{idx}")
+            } else {
+                param.name.clone()
+            };
+            let ty = if param.ty.is_empty() { "()" } else { param.ty.as_str() };
+            format!("{name}: {ty}")
+        }));
+        let return_type = match self.return_type.trim() {
+            "" | "()" => String::new(),
+            trimmed => format!(" -> {trimmed}"),
+        };
+        format!(
+            "{async_prefix}fn {name}{generics}({}){return_type}",
+            params.join(", ")
+        )
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -246,6 +319,112 @@ pub struct StaticMetadata {
     pub is_mut: bool,
 }
 
+/// v16 — per-field metadata.
+///
+/// One `FieldMetadata` per field of a local struct, union or enum variant.
+/// `type_string` is RA's `HirDisplay` of the declared field type, rendered
+/// against the owning crate as `DisplayTarget`. `index` is the declaration
+/// position inside the parent, so fields sort back into source order.
+/// Stored on `ExtractionModel.fields` and persisted into the
+/// `field_metadata_by_target` LMDB sub-DB.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldMetadata {
+    #[serde(default)]
+    pub type_string: String,
+    #[serde(default)]
+    pub index: u32,
+    /// `true` for positional fields of a tuple struct / tuple variant.
+    #[serde(default)]
+    pub is_tuple: bool,
+}
+
+/// v17 — flavor of an `ItemKind::Macro` Item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MacroKind {
+    /// `macro_rules!` or a `macro` (2.0) item.
+    Declarative,
+    /// `#[proc_macro]`, invoked as `name!(...)`.
+    FunctionLike,
+    /// `#[proc_macro_derive(Name)]`.
+    Derive,
+    /// `#[proc_macro_attribute]`.
+    Attribute,
+}
+
+/// v17 — per-macro metadata.
+///
+/// One `MacroMetadata` per local macro Item. `macro_export` is `true` only
+/// for a `macro_rules!` carrying `#[macro_export]`. Stored on
+/// `ExtractionModel.macros` and persisted into the
+/// `macro_metadata_by_target` LMDB sub-DB.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MacroMetadata {
+    pub kind: MacroKind,
+    #[serde(default)]
+    pub macro_export: bool,
+}
+
+/// v18 — per-function complexity, computed from the fn's syntax tree.
+///
+/// One `ComplexityMetrics` per local function Item with a body (free fns,
+/// inherent assoc fns, trait fns with a default body). Closures count
+/// toward the enclosing fn; nested fn / impl items are measured on their
+/// own, or not at all when they are block-local. See
+/// `graph::complexity::fn_complexity` for how each metric is counted.
+/// Stored on `ExtractionModel.complexity` and persisted into the
+/// `complexity_by_target` LMDB sub-DB.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ComplexityMetrics {
+    /// McCabe: 1 + branches (`if`, `while`, `for`, `let .. else`, match
+    /// arms past the first, match guards, `&&` / `||`, `?`).
+    pub cyclomatic: u32,
+    /// Cognitive complexity: like cyclomatic, but nested control flow
+    /// costs more and a run of the same boolean operator counts once.
+    pub cognitive: u32,
+    /// Deepest nesting of control-flow blocks and closures; 0 for
+    /// straight-line code.
+    pub max_nesting: u32,
+    /// Declared parameters, `self` included.
+    pub param_count: u32,
+    /// Non-blank, non-comment lines from the signature to the closing
+    /// brace (doc comments and attributes excluded).
+    pub loc: u32,
+}
+
+/// v13 — one `impl Trait for Type` block declared in a local crate.
+///
+/// The impl block itself is not a graph node; this record is the
+/// `implements` relation between the trait and the self type. `trait_id`
+/// is `Some` when the trait resolves to a node in the graph (a local trait,
+/// or an external trait that some local module imports and therefore has an
+/// `ExternalSymbol` stub); `self_type_id` is `Some` when the self type is an
+/// ADT with a node. Blanket impls (`impl<T: Foo> Bar for T`) and impls on
+/// references / tuples / foreign types leave `self_type_id` as `None` —
+/// `self_type` always carries the `HirDisplay` rendering. Stored on
+/// `ExtractionModel.trait_impls` and persisted into the `impls_by_id`,
+/// `impls_by_trait` and `impls_by_self_type` LMDB sub-DBs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraitImpl {
+    /// Crate that declares the impl block.
+    pub crate_id: NodeId,
+    pub trait_id: Option<NodeId>,
+    /// Trait name as written at the declaration site (e.g. `"Display"`).
+    pub trait_name: String,
+    pub self_type_id: Option<NodeId>,
+    /// `HirDisplay` of the self type, rendered against the impl's crate.
+    pub self_type: String,
+    /// Generic type parameters declared on the impl block with their
+    /// declaration-site trait bounds (same caveat as
+    /// `FunctionSignature.generics`).
+    #[serde(default)]
+    pub generics: Vec<GenericBound>,
+    /// `true` for `impl !Trait for Type`.
+    #[serde(default)]
+    pub is_negative: bool,
+    pub file: String, // workspace-relative
+    pub span: (u32, u32),
+}
+
 /// v11 — per-Item embedding cache record for `semantic_overlaps`.
 ///
 /// Persisted into the `embeddings_by_target` sub-DB lazily by the
@@ -287,6 +466,18 @@ pub struct ExtractionModel {
     /// type stringified via `HirDisplay` and the `mut` flag. Persisted to
     /// `static_metadata_by_target`.
     pub statics: Vec<(NodeId, StaticMetadata)>,
+    /// v16: one entry per field of a local struct, union or enum variant.
+    /// Persisted to `field_metadata_by_target`.
+    pub fields: Vec<(NodeId, FieldMetadata)>,
+    /// v17: one entry per macro declared in a local crate. Persisted to
+    /// `macro_metadata_by_target`.
+    pub macros: Vec<(NodeId, MacroMetadata)>,
+    /// v18: one entry per local function Item with a body. Persisted to
+    /// `complexity_by_target`.
+    pub complexity: Vec<(NodeId, ComplexityMetrics)>,
+    /// v13: one entry per `impl Trait for Type` block in a local crate.
+    /// Persisted to `impls_by_id` and indexed by trait / self type.
+    pub trait_impls: Vec<TraitImpl>,
 }
 
 impl ExtractionModel {
@@ -0,0 +1,418 @@
+//! `api_breaking_changes` — semver check of one crate's pure-`pub` surface
+//! between two snapshots.
+//!
+//! Items are matched by `(qualified_name, item kind)` as in `diff`. Trait
+//! members and enum variants carry no visibility of their own, so they
+//! inherit their host Item's. Fields (v16) and inherent methods / associated
+//! items (v19) use their own modifier unless it is plain `pub`, in which
+//! case the host decides. Macros (v17) are on the surface when exported.
+//! Items under `::tests::` are never part of the surface.
+
+use std::collections::{BTreeMap, HashSet};
+
+use anyhow::Result;
+
+use super::super::ids::NodeId;
+use super::super::labels::item_kind_short_label;
+use super::super::model::{FunctionSignature, ItemKind, Node, NodeKind};
+use super::super::snapshot::OpenedSnapshot;
+use super::audits::{GraphAuditError, resolve_crate_filter};
+use super::diff::{SnapshotSelector, diff_side, open_selected_snapshot};
+use super::model::{ApiBreakingChanges, ApiChange, ApiChangeKind};
+use super::shared::{by_declaration_site, declared_visibility_map};
+
+type ItemKey = (String, &'static str);
+
+struct SurfaceItem {
+    item_kind: ItemKind,
+    display_name: String,
+    file: Option<String>,
+    visibility: Option<String>,
+    /// Host Item of a method / associated item / variant.
+    parent: Option<ItemKey>,
+    parent_is_trait: bool,
+    non_exhaustive: bool,
+    signature: Option<FunctionSignature>,
+}
+
+impl SurfaceItem {
+    fn is_pub(&self) -> bool {
+        self.visibility.as_deref() == Some("pub")
+    }
+}
+
+pub fn run_api_breaking_changes(
+    base: &SnapshotSelector,
+    head: &SnapshotSelector,
+    crate_name: &str,
+) -> Result<ApiBreakingChanges> {
+    let base = open_selected_snapshot(base)?;
+    let head = open_selected_snapshot(head)?;
+    api_breaking_changes(&base, &head, crate_name)
+}
+
+/// Breaking changes to `crate_name` going from `base` to `head`. The crate
+/// must exist in `base`; a crate missing from `head` reports every public
+/// item as removed.
+pub fn api_breaking_changes(
+    base: &OpenedSnapshot,
+    head: &OpenedSnapshot,
+    crate_name: &str,
+) -> Result<ApiBreakingChanges> {
+    let base_crate = resolve_crate_filter(base, Some(crate_name))?
+        .ok_or_else(|| GraphAuditError::UnknownCrateFilter(crate_name.to_owned()))?;
+    let before = crate_surface(base, base_crate)?;
+    let after = match head.lookup_by_qualified_name(crate_name)? {
+        Some(_) => match resolve_crate_filter(head, Some(crate_name))? {
+            Some(head_crate) => crate_surface(head, head_crate)?,
+            None => BTreeMap::new(),
+        },
+        None => BTreeMap::new(),
+    };
+
+    let mut changes = Vec::new();
+    for (key, old) in &before {
+        if !old.is_pub() {
+            continue;
+        }
+        let Some(new) = after.get(key) else {
+            changes.push(api_change(key, old, ApiChangeKind::ItemRemoved, None, None));
+            continue;
+        };
+        if !new.is_pub() {
+            changes.push(api_change(
+                key,
+                new,
+                ApiChangeKind::VisibilityNarrowed,
+                old.visibility.clone(),
+                Some(new.visibility.clone().unwrap_or_else(|| "private".to_string())),
+            ));
+            continue;
+        }
+        if matches!(old.item_kind, ItemKind::Struct | ItemKind::Enum)
+            && !old.non_exhaustive
+            && new.non_exhaustive
+        {
+            changes.push(api_change(key, new, ApiChangeKind::NonExhaustiveAdded, None, None));
+        }
+        if let (Some(old_sig), Some(new_sig)) = (&old.signature, &new.signature) {
+            let mut kinds = signature_change_kinds(old_sig, new_sig);
+            if new.parent_is_trait && old_sig.has_body && !new_sig.has_body {
+                kinds.push(ApiChangeKind::TraitMethodDefaultRemoved);
+            }
+            for kind in kinds {
+                changes.push(api_change(
+                    key,
+                    new,
+                    kind,
+                    Some(old_sig.render(&old.display_name)),
+                    Some(new_sig.render(&new.display_name)),
+                ));
+            }
+        }
+    }
+
+    // Additions only break callers when they land inside an existing public
+    // enum or trait.
+    for (key, new) in &after {
+        if before.contains_key(key) || !new.is_pub() {
+            continue;
+        }
+        let Some(host) = new.parent.as_ref().and_then(|parent| before.get(parent)) else {
+            continue;
+        };
+        if !host.is_pub() {
+            continue;
+        }
+        match new.item_kind {
+            ItemKind::EnumVariant if !host.non_exhaustive => {
+                changes.push(api_change(key, new, ApiChangeKind::EnumVariantAdded, None, None));
+            }
+            ItemKind::Method if new.parent_is_trait => {
+                if let Some(signature) = &new.signature
+                    && !signature.has_body
+                {
+                    let rendered = signature.render(&new.display_name);
+                    changes.push(api_change(
+                        key,
+                        new,
+                        ApiChangeKind::TraitMethodAdded,
+                        None,
+                        Some(rendered),
+                    ));
+                }
+            }
+            _ => {}
+        }
+    }
+    changes.sort_by(|a, b| (&a.qualified_name, a.change).cmp(&(&b.qualified_name, b.change)));
+
+    Ok(ApiBreakingChanges {
+        crate_name: crate_name.to_string(),
+        base: diff_side(base),
+        head: diff_side(head),
+        public_items_before: before.values().filter(|item| item.is_pub()).count(),
+        public_items_after: after.values().filter(|item| item.is_pub()).count(),
+        changes,
+    })
+}
+
+fn crate_surface(
+    snap: &OpenedSnapshot,
+    crate_id: NodeId,
+) -> Result<BTreeMap<ItemKey, SurfaceItem>> {
+    let rtxn = snap.env.read_txn()?;
+    let mut nodes: BTreeMap<NodeId, Node> = BTreeMap::new();
+    for entry in snap.dbs.nodes_by_id.iter(&rtxn)? {
+        let (key, node) = entry?;
+        if node.kind != NodeKind::Item
+            || node.crate_id != Some(crate_id)
+            || node.item_kind.is_none()
+            || node.qualified_name.contains("::tests::")
+        {
+            continue;
+        }
+        let mut id = [0u8; 32];
+        id.copy_from_slice(key);
+        nodes.insert(NodeId(id), node);
+    }
+    let item_ids: HashSet<NodeId> = nodes.keys().copied().collect();
+    let declared = declared_visibility_map(snap, &rtxn, &item_ids)?;
+
+    let mut items = BTreeMap::new();
+    for (id, node) in by_declaration_site(&nodes) {
+        let Some(kind) = node.item_kind else {
+            continue;
+        };
+        let host = node
+            .parent_id
+            .and_then(|parent_id| nodes.get(&parent_id).map(|parent| (parent_id, parent)));
+        let host_visibility = host.and_then(|(parent_id, parent)| {
+            declared.get(&parent_id).or_else(|| match kind {
+                // Variant fields sit two levels under the enum.
+                ItemKind::Field => parent.parent_id.and_then(|enum_id| declared.get(&enum_id)),
+                _ => None,
+            })
+        });
+        let visibility = match (kind, node.visibility.as_deref()) {
+            // A field's or inherent item's own modifier caps it; `pub` is
+            // only as visible as its host.
+            (
+                ItemKind::Field | ItemKind::Method | ItemKind::AssocConst | ItemKind::AssocType,
+                Some(vis),
+            ) if vis != "pub" => Some(vis.to_string()),
+            // Macros have no binding; the Item itself records the visibility.
+            (ItemKind::Macro, vis) => vis.map(str::to_string),
+            _ => declared.get(id).or(host_visibility).cloned(),
+        };
+        let parent = host.and_then(|(_, parent)| {
+            let parent_kind = item_kind_short_label(parent.item_kind?);
+            Some((parent.qualified_name.clone(), parent_kind))
+        });
+        let parent_is_trait =
+            host.is_some_and(|(_, parent)| parent.item_kind == Some(ItemKind::Trait));
+        let signature = snap.dbs.signatures_by_target.get(&rtxn, id.as_bytes())?;
+        // The first declaration site wins on duplicate names.
+        items
+            .entry((node.qualified_name.clone(), item_kind_short_label(kind)))
+            .or_insert_with(|| SurfaceItem {
+                item_kind: kind,
+                display_name: node.display_name.clone(),
+                file: node.file.clone(),
+                visibility,
+                parent,
+                parent_is_trait,
+                non_exhaustive: node.attributes.iter().any(|attr| is_non_exhaustive(attr)),
+                signature,
+            });
+    }
+    Ok(items)
+}
+
+fn is_non_exhaustive(attribute: &str) -> bool {
+    attribute
+        .trim()
+        .strip_prefix("#[")
+        .is_some_and(|rest| rest.trim_start().starts_with("non_exhaustive"))
+}
+
+fn signature_change_kinds(
+    old: &FunctionSignature,
+    new: &FunctionSignature,
+) -> Vec<ApiChangeKind> {
+    let mut kinds = Vec::new();
+    if old.self_param != new.self_param {
+        kinds.push(ApiChangeKind::ReceiverChanged);
+    }
+    if new.params.len() > old.params.len() {
+        kinds.push(ApiChangeKind::ParamAdded);
+    } else if new.params.len() < old.params.len() {
+        kinds.push(ApiChangeKind::ParamRemoved);
+    } else if old.params.iter().zip(&new.params).any(|(a, b)| a.ty != b.ty) {
+        kinds.push(ApiChangeKind::ParamTypeChanged);
+    }
+    if old.is_async != new.is_async || old.return_type.trim() != new.return_type.trim() {
+        kinds.push(ApiChangeKind::ReturnTypeChanged);
+    }
+    let tightened = new.generics.iter().any(|generic| {
+        match old.generics.iter().find(|previous| previous.name == generic.name) {
+            Some(previous) => generic.bounds.iter().any(|bound| !previous.bounds.contains(bound)),
+            None => true,
+        }
+    });
+    if tightened {
+        kinds.push(ApiChangeKind::GenericBoundsTightened);
+    }
+    kinds
+}
+
+fn api_change(
+    key: &ItemKey,
+    item: &SurfaceItem,
+    change: ApiChangeKind,
+    before: Option<String>,
+    after: Option<String>,
+) -> ApiChange {
+    ApiChange {
+        qualified_name: key.0.clone(),
+        item_kind: key.1.to_string(),
+        change,
+        before,
+        after,
+        file: item.file.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::model::BindingVisibility;
+    use crate::graph::test_support::{ModelFixture, fn_signature};
+
+    #[test]
+    fn classifies_breaking_changes_to_the_pub_surface() {
+        let data_dir = tempfile::tempdir().unwrap();
+
+        let mut base = ModelFixture::new("base");
+        let fetch = base.item("fix::fetch", ItemKind::Function, "src/lib.rs");
+        let gone = base.item("fix::gone", ItemKind::Function, "src/lib.rs");
+        let hidden = base.item("fix::hidden", ItemKind::Function, "src/lib.rs");
+        let internal = base.item("fix::internal", ItemKind::Function, "src/lib.rs");
+        let color = base.item("fix::Color", ItemKind::Enum, "src/lib.rs");
+        base.member(color, "fix::Color::Red", ItemKind::EnumVariant, "src/lib.rs");
+        let open = base.item("fix::Open", ItemKind::Enum, "src/lib.rs");
+        base.attribute(open, "#[non_exhaustive]");
+        let store = base.item("fix::Store", ItemKind::Trait, "src/lib.rs");
+        for id in [fetch, gone, hidden, color, open, store] {
+            base.declare(id, BindingVisibility::Public);
+        }
+        base.declare(internal, BindingVisibility::Crate(base.crate_id()));
+        base.signature(fetch, fn_signature("u32"));
+
+        let mut head = ModelFixture::new("head");
+        let fetch = head.item("fix::fetch", ItemKind::Function, "src/lib.rs");
+        let hidden = head.item("fix::hidden", ItemKind::Function, "src/lib.rs");
+        head.item("fix::internal", ItemKind::Function, "src/lib.rs");
+        let color = head.item("fix::Color", ItemKind::Enum, "src/lib.rs");
+        head.member(color, "fix::Color::Red", ItemKind::EnumVariant, "src/lib.rs");
+        head.member(color, "fix::Color::Blue", ItemKind::EnumVariant, "src/lib.rs");
+        let open = head.item("fix::Open", ItemKind::Enum, "src/lib.rs");
+        head.attribute(open, "#[non_exhaustive]");
+        head.member(open, "fix::Open::More", ItemKind::EnumVariant, "src/lib.rs");
+        let store = head.item("fix::Store", ItemKind::Trait, "src/lib.rs");
+        let get = head.member(store, "fix::Store::get", ItemKind::Method, "src/lib.rs");
+        let put = head.member(store, "fix::Store::put", ItemKind::Method, "src/lib.rs");
+        for id in [fetch, color, open, store] {
+            head.declare(id, BindingVisibility::Public);
+        }
+        head.declare(hidden, BindingVisibility::Crate(head.crate_id()));
+        let mut fetch_sig = fn_signature("u32");
+        fetch_sig.params.push(fetch_sig.params[0].clone());
+        fetch_sig.return_type = "bool".to_string();
+        head.signature(fetch, fetch_sig);
+        head.signature(get, FunctionSignature { has_body: false, ..fn_signature("u32") });
+        head.signature(put, fn_signature("u32"));
+
+        let (_base_ws, base) = base.persist(data_dir.path());
+        let (_head_ws, head) = head.persist(data_dir.path());
+        let report = api_breaking_changes(&base, &head, "fix").unwrap();
+
+        let changes: Vec<(&str, ApiChangeKind)> = report
+            .changes
+            .iter()
+            .map(|change| (change.qualified_name.as_str(), change.change))
+            .collect();
+        assert_eq!(
+            changes,
+            vec![
+                ("fix::Color::Blue", ApiChangeKind::EnumVariantAdded),
+                ("fix::Store::get", ApiChangeKind::TraitMethodAdded),
+                ("fix::fetch", ApiChangeKind::ParamAdded),
+                ("fix::fetch", ApiChangeKind::ReturnTypeChanged),
+                ("fix::gone", ApiChangeKind::ItemRemoved),
+                ("fix::hidden", ApiChangeKind::VisibilityNarrowed),
+            ]
+        );
+        let narrowed = report.changes.last().unwrap();
+        assert_eq!(narrowed.before.as_deref(), Some("pub"));
+        assert_eq!(narrowed.after.as_deref(), Some("pub(crate)"));
+        assert_eq!(report.changes[2].before.as_deref(), Some("fn fetch(id: u32)"));
+        assert_eq!(
+            report.changes[2].after.as_deref(),
+            Some("fn fetch(id: u32, id: u32) -> bool")
+        );
+        assert_eq!(report.public_items_before, 7);
+    }
+
+    #[test]
+    fn private_inherent_method_changes_are_not_breaking() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let mut sides = Vec::new();
+        for (name, evict_param) in [("base", "u32"), ("head", "u64")] {
+            let mut fixture = ModelFixture::new(name);
+            let cache = fixture.item("fix::Cache", ItemKind::Struct, "src/lib.rs");
+            fixture.declare(cache, BindingVisibility::Public);
+            let get = fixture.member(cache, "fix::Cache::get", ItemKind::Method, "src/lib.rs");
+            fixture.visibility(get, "pub");
+            fixture.signature(get, fn_signature("u32"));
+            let evict = fixture.member(cache, "fix::Cache::evict", ItemKind::Method, "src/lib.rs");
+            fixture.visibility(evict, "pub(self)");
+            fixture.signature(evict, fn_signature(evict_param));
+            sides.push(fixture.persist(data_dir.path()));
+        }
+
+        let report = api_breaking_changes(&sides[0].1, &sides[1].1, "fix").unwrap();
+        assert!(report.changes.is_empty(), "{:?}", report.changes);
+        assert_eq!(report.public_items_before, 2);
+    }
+
+    #[test]
+    fn duplicate_names_pick_the_first_declaration_site() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let mut sides = Vec::new();
+        // The NodeId order of the two `get`s differs between these fixtures.
+        for name in ["dup_base", "dup_head"] {
+            let mut fixture = ModelFixture::new(name);
+            let cache = fixture.item("fix::Cache", ItemKind::Struct, "src/lib.rs");
+            fixture.declare(cache, BindingVisibility::Public);
+            for (file, param) in [("src/a.rs", "u32"), ("src/b.rs", "u64")] {
+                let get =
+                    fixture.member_at(cache, "fix::Cache::get", ItemKind::Method, file, (0, 10));
+                fixture.visibility(get, "pub");
+                fixture.signature(get, fn_signature(param));
+            }
+            sides.push(fixture.persist(data_dir.path()));
+        }
+
+        let report = api_breaking_changes(&sides[0].1, &sides[1].1, "fix").unwrap();
+        assert!(report.changes.is_empty(), "{:?}", report.changes);
+    }
+
+    #[test]
+    fn unknown_crate_is_a_typed_error() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let (_ws, snap) = ModelFixture::new("only").persist(data_dir.path());
+        let err = api_breaking_changes(&snap, &snap, "missing").unwrap_err();
+        assert!(err.downcast_ref::<GraphAuditError>().is_some(), "{err:#}");
+    }
+}
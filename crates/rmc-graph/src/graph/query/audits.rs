@@ -73,12 +73,18 @@ pub enum GraphAuditError {
     },
     #[error("no snapshot at {directory}")]
     MissingSnapshot { directory: PathBuf },
+    #[error("no snapshot `{graph_id}` at {directory}")]
+    UnknownGraphId { directory: PathBuf, graph_id: String },
     #[error("no node found for qualified name `{0}`")]
     UnknownCrateFilter(String),
     #[error("`{name}` is a {kind:?}, expected a Crate or its root Module")]
     InvalidCrateFilterKind { name: String, kind: NodeKind },
     #[error("{0}")]
     InvalidPattern(String),
+    #[error("no item found for qualified name `{0}`")]
+    UnknownItem(String),
+    #[error("cannot read git history of {directory}: {reason}")]
+    GitHistoryUnavailable { directory: PathBuf, reason: String },
 }
 
 pub fn run_unsafe_audit(directory: &Path) -> Result<Vec<UnsafeAuditFinding>> {
@@ -318,7 +324,7 @@ fn sorted_pattern_names(
     patterns_used
 }
 
-fn canonicalize_directory(directory: &Path) -> Result<PathBuf> {
+pub(super) fn canonicalize_directory(directory: &Path) -> Result<PathBuf> {
     directory
         .canonicalize()
         .map_err(|source| GraphAuditError::InvalidDirectory {
@@ -328,7 +334,7 @@ fn canonicalize_directory(directory: &Path) -> Result<PathBuf> {
         .into())
 }
 
-fn open_directory_snapshot(directory: &Path) -> Result<OpenedSnapshot> {
+pub(super) fn open_directory_snapshot(directory: &Path) -> Result<OpenedSnapshot> {
     let paths = GraphPaths::for_workspace(directory);
     match super::super::snapshot::open_current(&paths, GraphEnvOptions::default())? {
         Some(snapshot) => Ok(snapshot),
@@ -339,7 +345,10 @@ fn open_directory_snapshot(directory: &Path) -> Result<OpenedSnapshot> {
     }
 }
 
-fn resolve_crate_filter(snap: &OpenedSnapshot, crate_name: Option<&str>) -> Result<Option<NodeId>> {
+pub(super) fn resolve_crate_filter(
+    snap: &OpenedSnapshot,
+    crate_name: Option<&str>,
+) -> Result<Option<NodeId>> {
     let Some(qn) = crate_name else {
         return Ok(None);
     };
@@ -1,8 +1,9 @@
 //! Query methods on `OpenedSnapshot` — calls family.
 //!
 //! Covers call-graph queries: `who_calls`, `calls_from`, `call_graph`,
-//! `callers_in_crate`, `recursive_callers_count`. Moved here from
-//! `graph::queries` in PR 09.
+//! `callers_in_crate`, `recursive_callers_count`, and `complexity_hotspots`
+//! (v18), which weighs per-fn complexity by the transitive caller count.
+//! Moved here from `graph::queries` in PR 09.
 
 use std::collections::HashSet;
 
@@ -10,9 +11,11 @@ use anyhow::Result;
 
 use super::super::ids::NodeId;
 use super::super::labels::usage_category_label;
-use super::super::model::Usage;
+use super::super::model::{ComplexityMetrics, ItemKind, Node, Usage};
 use super::super::snapshot::OpenedSnapshot;
-use super::model::{CallGraphNode, EnrichedCallSite, RecursiveCallersCount};
+use super::model::{
+    CallGraphNode, ComplexityHotspot, ComplexityMetric, EnrichedCallSite, RecursiveCallersCount,
+};
 
 impl OpenedSnapshot {
     /// Every non-import reference to `target_fn` whose call site is inside
@@ -331,4 +334,68 @@ impl OpenedSnapshot {
             truncated_at_depth,
         })
     }
+
+    /// v18: local fns ranked by `metric × (transitive_callers + 1)`, so a
+    /// tangled fn on a hot path outranks an equally tangled leaf nobody
+    /// calls. Callers are counted with `recursive_callers_count(fn, depth)`.
+    /// Fns whose `metric` is below `min_complexity` are dropped before the
+    /// caller walk; `crate_id` and `skip_test_items` (fns under `::tests::`)
+    /// filter the same way as `macros_by_fan_in`. Sorted by `score` desc,
+    /// ties broken by `qualified_name`.
+    pub fn complexity_hotspots(
+        &self,
+        crate_id: Option<NodeId>,
+        metric: ComplexityMetric,
+        depth: u32,
+        min_complexity: u32,
+        skip_test_items: bool,
+    ) -> Result<Vec<ComplexityHotspot>> {
+        let mut candidates: Vec<(NodeId, ComplexityMetrics, Node)> = Vec::new();
+        {
+            let rtxn = self.env.read_txn()?;
+            for entry in self.dbs.complexity_by_target.iter(&rtxn)? {
+                let (key, metrics) = entry?;
+                if metric.of(&metrics) < min_complexity {
+                    continue;
+                }
+                let mut id = [0u8; 32];
+                id.copy_from_slice(key);
+                let id = NodeId(id);
+                let Some(node) = self.dbs.nodes_by_id.get(&rtxn, id.as_bytes())? else {
+                    continue;
+                };
+                if crate_id.is_some_and(|c| node.crate_id != Some(c))
+                    || (skip_test_items && node.qualified_name.contains("::tests::"))
+                {
+                    continue;
+                }
+                candidates.push((id, metrics, node));
+            }
+        }
+
+        let mut rows: Vec<ComplexityHotspot> = Vec::with_capacity(candidates.len());
+        for (id, metrics, node) in candidates {
+            let callers = self.recursive_callers_count(id, depth)?;
+            let complexity = metric.of(&metrics);
+            rows.push(ComplexityHotspot {
+                target: id,
+                qualified_name: node.qualified_name,
+                display_name: node.display_name,
+                item_kind: node.item_kind.unwrap_or(ItemKind::Function),
+                file: node.file,
+                span: node.span,
+                metrics,
+                complexity,
+                direct_callers: callers.direct_callers,
+                transitive_callers: callers.transitive_callers,
+                score: u64::from(complexity) * (callers.transitive_callers as u64 + 1),
+            });
+        }
+        rows.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then_with(|| a.qualified_name.cmp(&b.qualified_name))
+        });
+        Ok(rows)
+    }
 }
@@ -0,0 +1,447 @@
+//! Git co-change queries (THEORY P11): per-Item churn, Items that change
+//! together, and modules whose Items change together.
+//!
+//! All three aggregate the commit → Item attribution produced by
+//! `graph::co_change::item_history`; see that module for how hunks are
+//! mapped onto snapshot spans.
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use anyhow::Result;
+
+use super::super::co_change::{
+    self, DEFAULT_MAX_COMMITS, DEFAULT_MAX_ITEMS_PER_COMMIT, GitHistoryOpts,
+    HARD_CAP_MAX_COMMITS, ItemHistory,
+};
+use super::super::ids::NodeId;
+use super::super::labels::item_kind_display_label;
+use super::super::model::NodeKind;
+use super::super::snapshot::OpenedSnapshot;
+use super::audits::{
+    GraphAuditError, canonicalize_directory, open_directory_snapshot, resolve_crate_filter,
+};
+use super::model::{
+    GitHistoryScan, ItemChurn, ItemChurnReport, ItemCoChange, ItemCoChangeReport,
+    ModuleCoChange, ModuleCoChangeReport,
+};
+
+pub(crate) const DEFAULT_MIN_CO_CHANGES: usize = 2;
+
+#[derive(Debug, Clone, Default)]
+pub struct CoChangeOptions {
+    pub max_commits: Option<usize>,
+    /// Any date `git log --since` accepts, e.g. `2024-01-01` or `6 months ago`.
+    pub since: Option<String>,
+    pub max_items_per_commit: Option<usize>,
+    pub crate_name: Option<String>,
+    /// Pairs sharing fewer commits are dropped. Ignored by `item_churn`.
+    pub min_co_changes: Option<usize>,
+    /// `item_co_change` only: drop pairs declared in the same module.
+    pub cross_module_only: bool,
+}
+
+impl CoChangeOptions {
+    fn history_opts(&self) -> GitHistoryOpts {
+        GitHistoryOpts {
+            max_commits: self
+                .max_commits
+                .unwrap_or(DEFAULT_MAX_COMMITS)
+                .clamp(1, HARD_CAP_MAX_COMMITS),
+            since: self.since.clone(),
+            max_items_per_commit: self
+                .max_items_per_commit
+                .unwrap_or(DEFAULT_MAX_ITEMS_PER_COMMIT)
+                .max(2),
+        }
+    }
+
+    fn min_co_changes(&self) -> usize {
+        self.min_co_changes.unwrap_or(DEFAULT_MIN_CO_CHANGES).max(1)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ItemInfo {
+    qualified_name: String,
+    item_kind: &'static str,
+    file: Option<String>,
+    crate_id: Option<NodeId>,
+    module: Option<NodeId>,
+}
+
+pub fn run_item_churn(directory: &Path, options: &CoChangeOptions) -> Result<ItemChurnReport> {
+    let canonical = canonicalize_directory(directory)?;
+    let snap = open_directory_snapshot(&canonical)?;
+    let crate_id_filter = resolve_crate_filter(&snap, options.crate_name.as_deref())?;
+    let history = co_change::item_history(&snap, &options.history_opts())?;
+    let infos = item_infos(&snap, &history)?;
+    Ok(item_churn(&history, &infos, crate_id_filter))
+}
+
+pub fn run_item_co_change(
+    directory: &Path,
+    target: Option<&str>,
+    options: &CoChangeOptions,
+) -> Result<ItemCoChangeReport> {
+    let canonical = canonicalize_directory(directory)?;
+    let snap = open_directory_snapshot(&canonical)?;
+    let crate_id_filter = resolve_crate_filter(&snap, options.crate_name.as_deref())?;
+    let target_id = match target {
+        Some(qn) => match snap.lookup_by_qualified_name(qn)? {
+            Some((id, node)) if node.kind == NodeKind::Item => Some(id),
+            _ => return Err(GraphAuditError::UnknownItem(qn.to_owned()).into()),
+        },
+        None => None,
+    };
+    let history = co_change::item_history(&snap, &options.history_opts())?;
+    let infos = item_infos(&snap, &history)?;
+    Ok(ItemCoChangeReport {
+        history: scan_summary(&history),
+        target: target.map(str::to_string),
+        pairs: item_co_change(&history, &infos, crate_id_filter, target_id, options),
+    })
+}
+
+pub fn run_module_co_change(
+    directory: &Path,
+    options: &CoChangeOptions,
+) -> Result<ModuleCoChangeReport> {
+    let canonical = canonicalize_directory(directory)?;
+    let snap = open_directory_snapshot(&canonical)?;
+    let crate_id_filter = resolve_crate_filter(&snap, options.crate_name.as_deref())?;
+    let history = co_change::item_history(&snap, &options.history_opts())?;
+    let infos = item_infos(&snap, &history)?;
+    let rtxn = snap.read_txn()?;
+    let mut modules: HashMap<NodeId, (String, Option<NodeId>)> = HashMap::new();
+    for module in infos.values().filter_map(|info| info.module) {
+        if modules.contains_key(&module) {
+            continue;
+        }
+        if let Some(node) = snap.node(&rtxn, module)? {
+            modules.insert(module, (node.qualified_name, node.crate_id));
+        }
+    }
+    Ok(ModuleCoChangeReport {
+        history: scan_summary(&history),
+        pairs: module_co_change(&history, &infos, &modules, crate_id_filter, options),
+    })
+}
+
+fn scan_summary(history: &ItemHistory) -> GitHistoryScan {
+    GitHistoryScan {
+        commits_scanned: history.commits_scanned,
+        commits_with_items: history.commits.len(),
+        commits_skipped_large: history.commits_skipped_large,
+        newest_commit: history.newest_commit.clone(),
+        oldest_commit: history.oldest_commit.clone(),
+    }
+}
+
+/// Node data for every Item the history touched, plus its declaring module
+/// (the first `Module` ancestor — methods and variants sit under their host
+/// Item first).
+fn item_infos(snap: &OpenedSnapshot, history: &ItemHistory) -> Result<HashMap<NodeId, ItemInfo>> {
+    let rtxn = snap.read_txn()?;
+    let mut infos: HashMap<NodeId, ItemInfo> = HashMap::new();
+    for item in history.commits.iter().flat_map(|commit| commit.items.iter()) {
+        if infos.contains_key(item) {
+            continue;
+        }
+        let Some(node) = snap.node(&rtxn, *item)? else {
+            continue;
+        };
+        let Some(item_kind) = node.item_kind else {
+            continue;
+        };
+        let mut module = None;
+        let mut parent = node.parent_id;
+        while let Some(parent_id) = parent {
+            let Some(parent_node) = snap.node(&rtxn, parent_id)? else {
+                break;
+            };
+            if parent_node.kind == NodeKind::Module {
+                module = Some(parent_id);
+                break;
+            }
+            parent = parent_node.parent_id;
+        }
+        infos.insert(
+            *item,
+            ItemInfo {
+                qualified_name: node.qualified_name,
+                item_kind: item_kind_display_label(item_kind),
+                file: node.file,
+                crate_id: node.crate_id,
+                module,
+            },
+        );
+    }
+    Ok(infos)
+}
+
+fn in_scope(info: &ItemInfo, crate_id_filter: Option<NodeId>) -> bool {
+    crate_id_filter.is_none_or(|crate_id| info.crate_id == Some(crate_id))
+}
+
+fn item_churn(
+    history: &ItemHistory,
+    infos: &HashMap<NodeId, ItemInfo>,
+    crate_id_filter: Option<NodeId>,
+) -> ItemChurnReport {
+    let mut rows: HashMap<NodeId, ItemChurn> = HashMap::new();
+    // Newest first, so the first commit seen for an Item is its last change.
+    for commit in &history.commits {
+        for item in &commit.items {
+            let Some(info) = infos.get(item).filter(|info| in_scope(info, crate_id_filter))
+            else {
+                continue;
+            };
+            rows.entry(*item)
+                .or_insert_with(|| ItemChurn {
+                    qualified_name: info.qualified_name.clone(),
+                    item_kind: info.item_kind.to_string(),
+                    file: info.file.clone(),
+                    commit_count: 0,
+                    last_commit: commit.sha.clone(),
+                    last_commit_time: commit.time,
+                })
+                .commit_count += 1;
+        }
+    }
+    let mut items: Vec<ItemChurn> = rows.into_values().collect();
+    items.sort_by(|a, b| {
+        b.commit_count
+            .cmp(&a.commit_count)
+            .then_with(|| a.qualified_name.cmp(&b.qualified_name))
+            .then_with(|| a.item_kind.cmp(&b.item_kind))
+    });
+    ItemChurnReport {
+        history: scan_summary(history),
+        items,
+    }
+}
+
+/// Count commits per key and per unordered key pair. Keys within one
+/// commit are deduplicated before pairing.
+fn pair_counts<K: Copy + Eq + std::hash::Hash + Ord>(
+    commits: impl Iterator<Item = Vec<K>>,
+) -> (HashMap<K, usize>, HashMap<(K, K), usize>) {
+    let mut singles: HashMap<K, usize> = HashMap::new();
+    let mut pairs: HashMap<(K, K), usize> = HashMap::new();
+    for mut keys in commits {
+        keys.sort();
+        keys.dedup();
+        for (index, a) in keys.iter().enumerate() {
+            *singles.entry(*a).or_insert(0) += 1;
+            for b in &keys[index + 1..] {
+                *pairs.entry((*a, *b)).or_insert(0) += 1;
+            }
+        }
+    }
+    (singles, pairs)
+}
+
+fn jaccard(co: usize, a: usize, b: usize) -> f64 {
+    let union = a + b - co;
+    if union == 0 { 0.0 } else { co as f64 / union as f64 }
+}
+
+fn item_co_change(
+    history: &ItemHistory,
+    infos: &HashMap<NodeId, ItemInfo>,
+    crate_id_filter: Option<NodeId>,
+    target: Option<NodeId>,
+    options: &CoChangeOptions,
+) -> Vec<ItemCoChange> {
+    let keep = |item: &NodeId| {
+        Some(*item) == target
+            || infos
+                .get(item)
+                .is_some_and(|info| in_scope(info, crate_id_filter))
+    };
+    let (singles, pairs) = pair_counts(history.commits.iter().map(|commit| {
+        commit
+            .items
+            .iter()
+            .copied()
+            .filter(|item| infos.contains_key(item) && keep(item))
+            .collect()
+    }));
+
+    let min_co_changes = options.min_co_changes();
+    let mut out = Vec::new();
+    for ((first, second), co) in pairs {
+        if co < min_co_changes {
+            continue;
+        }
+        let (a, b) = match target {
+            Some(target) if first == target => (first, second),
+            Some(target) if second == target => (second, first),
+            Some(_) => continue,
+            None if infos[&first].qualified_name <= infos[&second].qualified_name => {
+                (first, second)
+            }
+            None => (second, first),
+        };
+        let (a_info, b_info) = (&infos[&a], &infos[&b]);
+        let cross_module = a_info.module != b_info.module;
+        if options.cross_module_only && !cross_module {
+            continue;
+        }
+        out.push(ItemCoChange {
+            a_qualified_name: a_info.qualified_name.clone(),
+            a_item_kind: a_info.item_kind.to_string(),
+            b_qualified_name: b_info.qualified_name.clone(),
+            b_item_kind: b_info.item_kind.to_string(),
+            co_change_count: co,
+            a_commit_count: singles[&a],
+            b_commit_count: singles[&b],
+            jaccard: jaccard(co, singles[&a], singles[&b]),
+            cross_module,
+            cross_crate: a_info.crate_id != b_info.crate_id,
+        });
+    }
+    out.sort_by(|x, y| {
+        y.co_change_count
+            .cmp(&x.co_change_count)
+            .then_with(|| y.jaccard.total_cmp(&x.jaccard))
+            .then_with(|| x.a_qualified_name.cmp(&y.a_qualified_name))
+            .then_with(|| x.b_qualified_name.cmp(&y.b_qualified_name))
+    });
+    out
+}
+
+fn module_co_change(
+    history: &ItemHistory,
+    infos: &HashMap<NodeId, ItemInfo>,
+    modules: &HashMap<NodeId, (String, Option<NodeId>)>,
+    crate_id_filter: Option<NodeId>,
+    options: &CoChangeOptions,
+) -> Vec<ModuleCoChange> {
+    let (singles, pairs) = pair_counts(history.commits.iter().map(|commit| {
+        commit
+            .items
+            .iter()
+            .filter_map(|item| infos.get(item))
+            .filter(|info| in_scope(info, crate_id_filter))
+            .filter_map(|info| info.module)
+            .filter(|module| modules.contains_key(module))
+            .collect()
+    }));
+
+    let min_co_changes = options.min_co_changes();
+    let mut out = Vec::new();
+    for ((first, second), co) in pairs {
+        if co < min_co_changes {
+            continue;
+        }
+        let (mut a, mut b) = (first, second);
+        if modules[&b].0 < modules[&a].0 {
+            std::mem::swap(&mut a, &mut b);
+        }
+        let ((a_name, a_crate), (b_name, b_crate)) = (&modules[&a], &modules[&b]);
+        out.push(ModuleCoChange {
+            module_a: a_name.clone(),
+            module_b: b_name.clone(),
+            co_change_count: co,
+            a_commit_count: singles[&a],
+            b_commit_count: singles[&b],
+            jaccard: jaccard(co, singles[&a], singles[&b]),
+            cross_crate: a_crate != b_crate,
+        });
+    }
+    out.sort_by(|x, y| {
+        y.co_change_count
+            .cmp(&x.co_change_count)
+            .then_with(|| y.jaccard.total_cmp(&x.jaccard))
+            .then_with(|| x.module_a.cmp(&y.module_a))
+            .then_with(|| x.module_b.cmp(&y.module_b))
+    });
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::co_change::TouchedCommit;
+    use super::*;
+
+    fn id(n: u8) -> NodeId {
+        NodeId([n; 32])
+    }
+
+    fn info(qualified_name: &str, module: u8, crate_id: u8) -> ItemInfo {
+        ItemInfo {
+            qualified_name: qualified_name.to_string(),
+            item_kind: "Function",
+            file: None,
+            crate_id: Some(id(crate_id)),
+            module: Some(id(module)),
+        }
+    }
+
+    fn commit(sha: &str, items: &[u8]) -> TouchedCommit {
+        TouchedCommit {
+            sha: sha.to_string(),
+            time: 0,
+            items: items.iter().copied().map(id).collect(),
+        }
+    }
+
+    #[test]
+    fn aggregates_churn_item_pairs_and_module_pairs() {
+        let history = ItemHistory {
+            commits: vec![commit("c3", &[1, 2, 3]), commit("c2", &[1, 2]), commit("c1", &[1])],
+            commits_scanned: 4,
+            ..ItemHistory::default()
+        };
+        let infos: HashMap<NodeId, ItemInfo> = [
+            (id(1), info("a::parse", 10, 100)),
+            (id(2), info("b::render", 20, 100)),
+            (id(3), info("a::lex", 10, 100)),
+        ]
+        .into_iter()
+        .collect();
+
+        let churn = item_churn(&history, &infos, None);
+        assert_eq!(churn.history.commits_with_items, 3);
+        assert_eq!(churn.items[0].qualified_name, "a::parse");
+        assert_eq!(churn.items[0].commit_count, 3);
+        assert_eq!(churn.items[0].last_commit, "c3");
+
+        let options = CoChangeOptions::default();
+        let pairs = item_co_change(&history, &infos, None, None, &options);
+        assert_eq!(pairs.len(), 1);
+        assert_eq!(pairs[0].a_qualified_name, "a::parse");
+        assert_eq!(pairs[0].b_qualified_name, "b::render");
+        assert_eq!(pairs[0].co_change_count, 2);
+        assert!((pairs[0].jaccard - 2.0 / 3.0).abs() < 1e-9);
+        assert!(pairs[0].cross_module);
+
+        let targeted = CoChangeOptions {
+            min_co_changes: Some(1),
+            cross_module_only: true,
+            ..CoChangeOptions::default()
+        };
+        let pairs = item_co_change(&history, &infos, None, Some(id(2)), &targeted);
+        let partners: Vec<&str> = pairs.iter().map(|pair| pair.b_qualified_name.as_str()).collect();
+        assert!(pairs.iter().all(|pair| pair.a_qualified_name == "b::render"));
+        assert_eq!(partners, vec!["a::parse", "a::lex"]);
+
+        let modules: HashMap<NodeId, (String, Option<NodeId>)> = [
+            (id(10), ("a".to_string(), Some(id(100)))),
+            (id(20), ("b".to_string(), Some(id(100)))),
+        ]
+        .into_iter()
+        .collect();
+        let module_pairs = module_co_change(&history, &infos, &modules, None, &options);
+        assert_eq!(module_pairs.len(), 1);
+        assert_eq!(
+            (module_pairs[0].module_a.as_str(), module_pairs[0].module_b.as_str()),
+            ("a", "b")
+        );
+        assert_eq!(module_pairs[0].co_change_count, 2);
+        assert_eq!(module_pairs[0].a_commit_count, 3);
+        assert!(!module_pairs[0].cross_crate);
+    }
+}
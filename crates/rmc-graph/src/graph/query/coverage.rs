@@ -0,0 +1,230 @@
+//! Query methods on `OpenedSnapshot` — test reach.
+//!
+//! `tests_exercising` walks callers backward from a fn to the tests that
+//! reach it; `untested_pub_items` walks callees forward from every test and
+//! lists the `pub` fns and methods left unreached. A test is a fn carrying
+//! `#[test]`, `#[bench]` or a `#[<path>::test]` attribute (`tokio::test`,
+//! `test_log::test`, ...). Tests only reach the snapshot when it was built
+//! with `test_targets` set in the workspace's `[rust_analyzer]` config;
+//! otherwise both queries see no tests. Calls through trait objects and
+//! generic bounds resolve to the trait's declaration, not to its impls.
+
+use std::collections::{HashMap, HashSet};
+
+use anyhow::Result;
+
+use super::super::ids::NodeId;
+use super::super::model::{ItemKind, Node, NodeKind};
+use super::super::snapshot::OpenedSnapshot;
+use super::model::{ExercisingTest, UntestedPubItem, UntestedPubItems};
+use super::shared::declared_visibility_map;
+
+impl OpenedSnapshot {
+    /// Test fns that reach `target` within `depth` caller hops, sorted by
+    /// hop count then qualified name. Test helpers on the way are walked
+    /// through but not listed.
+    pub fn tests_exercising(&self, target: NodeId, depth: u32) -> Result<Vec<ExercisingTest>> {
+        let mut hops: HashMap<NodeId, u32> = HashMap::new();
+        let mut frontier = vec![target];
+        let mut hop = 0;
+        while hop < depth && !frontier.is_empty() {
+            hop += 1;
+            let mut next = Vec::new();
+            let rtxn = self.env.read_txn()?;
+            for fn_id in frontier.drain(..) {
+                for entry in self.usages_for_target(&rtxn, fn_id)? {
+                    let Some(caller) = entry?.consumer_function else {
+                        continue;
+                    };
+                    if caller != target && !hops.contains_key(&caller) {
+                        hops.insert(caller, hop);
+                        next.push(caller);
+                    }
+                }
+            }
+            frontier = next;
+        }
+
+        let rtxn = self.env.read_txn()?;
+        let mut crates: HashMap<NodeId, Option<Node>> = HashMap::new();
+        let mut out = Vec::new();
+        for (id, hops) in hops {
+            let Some(node) = self.dbs.nodes_by_id.get(&rtxn, id.as_bytes())? else {
+                continue;
+            };
+            if !is_test_fn(&node) {
+                continue;
+            }
+            let krate = match node.crate_id {
+                Some(crate_id) => match crates.get(&crate_id) {
+                    Some(krate) => krate.clone(),
+                    None => {
+                        let krate = self.dbs.nodes_by_id.get(&rtxn, crate_id.as_bytes())?;
+                        crates.insert(crate_id, krate.clone());
+                        krate
+                    }
+                },
+                None => None,
+            };
+            out.push(ExercisingTest {
+                test: id,
+                qualified_name: node.qualified_name,
+                crate_name: krate.as_ref().map(|k| k.qualified_name.clone()),
+                crate_target_kind: krate.and_then(|k| k.crate_target_kind),
+                file: node.file,
+                span: node.span,
+                hops,
+            });
+        }
+        out.sort_by(|a, b| {
+            a.hops
+                .cmp(&b.hops)
+                .then_with(|| a.qualified_name.cmp(&b.qualified_name))
+        });
+        Ok(out)
+    }
+
+    /// `pub` fns and methods of library crates (or of `crate_id` only) that
+    /// no test reaches within `depth` callee hops. Visibility follows
+    /// `api_breaking_changes`: an inherent method's own modifier, capped by
+    /// its host Item; trait methods take the trait's.
+    /// Items under `::tests::` and in test / bench / example crates are
+    /// never candidates. Sorted by qualified name.
+    pub fn untested_pub_items(
+        &self,
+        crate_id: Option<NodeId>,
+        depth: u32,
+    ) -> Result<UntestedPubItems> {
+        let rtxn = self.env.read_txn()?;
+        let mut crate_nodes: HashMap<NodeId, Node> = HashMap::new();
+        let mut tests: Vec<NodeId> = Vec::new();
+        let mut candidates: Vec<(NodeId, Node)> = Vec::new();
+        for entry in self.dbs.nodes_by_id.iter(&rtxn)? {
+            let (key, node) = entry?;
+            let mut id = [0u8; 32];
+            id.copy_from_slice(key);
+            let id = NodeId(id);
+            if node.kind == NodeKind::Crate {
+                crate_nodes.insert(id, node);
+            } else if is_test_fn(&node) {
+                tests.push(id);
+            } else if is_callable(&node)
+                && !node.qualified_name.contains("::tests::")
+                && crate_id.is_none_or(|c| node.crate_id == Some(c))
+            {
+                candidates.push((id, node));
+            }
+        }
+        candidates.retain(|(_, node)| {
+            let kind = node
+                .crate_id
+                .and_then(|c| crate_nodes.get(&c))
+                .and_then(|krate| krate.crate_target_kind.as_deref())
+                .unwrap_or("lib");
+            kind == "lib"
+        });
+
+        let visibility_ids: HashSet<NodeId> = candidates
+            .iter()
+            .flat_map(|(id, node)| std::iter::once(*id).chain(node.parent_id))
+            .collect();
+        let declared = declared_visibility_map(self, &rtxn, &visibility_ids)?;
+        candidates.retain(|(id, node)| {
+            let visibility = match (node.item_kind, node.visibility.as_deref()) {
+                (Some(ItemKind::Function), _) => declared.get(id).map(String::as_str),
+                // An inherent method's own modifier caps it; `pub` is only
+                // as visible as its host.
+                (_, Some(vis)) if vis != "pub" => Some(vis),
+                _ => declared
+                    .get(id)
+                    .or_else(|| node.parent_id.and_then(|parent| declared.get(&parent)))
+                    .map(String::as_str),
+            };
+            visibility == Some("pub")
+        });
+
+        // Forward walk from every test; anything it references counts as
+        // exercised, types and consts included.
+        let mut reached: HashSet<NodeId> = tests.iter().copied().collect();
+        let mut frontier = tests.clone();
+        let mut hop = 0;
+        while hop < depth && !frontier.is_empty() {
+            hop += 1;
+            let mut next = Vec::new();
+            for fn_id in frontier.drain(..) {
+                for entry in self.usages_for_consumer_function(&rtxn, fn_id)? {
+                    let callee = entry?.target;
+                    if reached.insert(callee) {
+                        next.push(callee);
+                    }
+                }
+            }
+            frontier = next;
+        }
+
+        let pub_item_count = candidates.len();
+        let mut items: Vec<UntestedPubItem> = candidates
+            .into_iter()
+            .filter(|(id, _)| !reached.contains(id))
+            .map(|(id, node)| UntestedPubItem {
+                target: id,
+                crate_name: node
+                    .crate_id
+                    .and_then(|c| crate_nodes.get(&c))
+                    .map(|krate| krate.qualified_name.clone()),
+                qualified_name: node.qualified_name,
+                item_kind: node.item_kind.unwrap_or(ItemKind::Function),
+                file: node.file,
+                span: node.span,
+            })
+            .collect();
+        items.sort_by(|a, b| a.qualified_name.cmp(&b.qualified_name));
+        Ok(UntestedPubItems {
+            test_fn_count: tests.len(),
+            pub_item_count,
+            items,
+        })
+    }
+}
+
+fn is_callable(node: &Node) -> bool {
+    node.kind == NodeKind::Item
+        && matches!(
+            node.item_kind,
+            Some(ItemKind::Function | ItemKind::Method | ItemKind::AssocFunction)
+        )
+}
+
+/// A fn carrying a test or bench attribute
+fn is_test_fn(node: &Node) -> bool {
+    is_callable(node) && node.attributes.iter().any(|attr| is_test_attribute(attr))
+}
+
+/// `#[test]`, `#[bench]`, or any attribute whose path ends in `::test`
+fn is_test_attribute(attribute: &str) -> bool {
+    let Some(rest) = attribute.trim().strip_prefix("#[") else {
+        return false;
+    };
+    let path = rest
+        .split(['(', ']', '='])
+        .next()
+        .unwrap_or_default()
+        .trim();
+    matches!(path.rsplit("::").next(), Some("test" | "bench"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recognizes_test_attributes() {
+        assert!(is_test_attribute("#[test]"));
+        assert!(is_test_attribute("#[bench]"));
+        assert!(is_test_attribute("#[tokio::test]"));
+        assert!(is_test_attribute("#[tokio::test(flavor = \"multi_thread\")]"));
+        assert!(!is_test_attribute("#[cfg(test)]"));
+        assert!(!is_test_attribute("#[should_panic]"));
+        assert!(!is_test_attribute("/// #[test] in a doc line"));
+    }
+}
@@ -0,0 +1,370 @@
+//! `snapshot_diff` — semantic comparison of two published snapshots.
+//!
+//! Each side is first reduced to a name-keyed summary: node ids hash the
+//! workspace root, so the same Item in two worktrees carries two different
+//! ids and only `(qualified_name, item kind)` lines up.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::PathBuf;
+
+use anyhow::Result;
+
+use super::super::ids::NodeId;
+use super::super::labels::item_kind_short_label;
+use super::super::model::{ItemKind, Node, NodeKind};
+use super::super::snapshot::{OpenedSnapshot, open_current, open_specific};
+use super::super::storage::{GraphEnvOptions, GraphPaths};
+use super::audits::{GraphAuditError, canonicalize_directory};
+use super::model::{
+    DiffCallEdge, DiffCrateEdge, DiffItem, MovedItem, SignatureChange, SnapshotDiff,
+    SnapshotDiffSide, VisibilityChange,
+};
+use super::shared::{by_declaration_site, declared_visibility_map};
+
+/// Which snapshot of a workspace to open: its `CURRENT` one, or a retained
+/// older `graph_id`.
+#[derive(Debug, Clone)]
+pub struct SnapshotSelector {
+    pub directory: PathBuf,
+    pub graph_id: Option<String>,
+}
+
+pub fn run_snapshot_diff(base: &SnapshotSelector, head: &SnapshotSelector) -> Result<SnapshotDiff> {
+    let base = open_selected_snapshot(base)?;
+    let head = open_selected_snapshot(head)?;
+    diff_snapshots(&base, &head)
+}
+
+pub(super) fn open_selected_snapshot(selector: &SnapshotSelector) -> Result<OpenedSnapshot> {
+    let canonical = canonicalize_directory(&selector.directory)?;
+    let paths = GraphPaths::for_workspace(&canonical);
+    let Some(graph_id) = selector.graph_id.as_deref() else {
+        return open_current(&paths, GraphEnvOptions::default())?.ok_or_else(|| {
+            GraphAuditError::MissingSnapshot {
+                directory: canonical.clone(),
+            }
+            .into()
+        });
+    };
+    // graph ids are hex digests; anything else would escape the snapshot dir.
+    let well_formed = !graph_id.is_empty() && graph_id.chars().all(|c| c.is_ascii_hexdigit());
+    let opened = if well_formed {
+        open_specific(&paths, graph_id, GraphEnvOptions::default())?
+    } else {
+        None
+    };
+    opened.ok_or_else(|| {
+        GraphAuditError::UnknownGraphId {
+            directory: canonical.clone(),
+            graph_id: graph_id.to_string(),
+        }
+        .into()
+    })
+}
+
+type ItemKey = (String, &'static str);
+/// `(display_name, item kind, signature)` — what must match for an Item
+/// that changed qualified name to be reported as moved.
+type MoveKey<'a> = (&'a str, &'static str, Option<&'a str>);
+
+struct ItemRecord {
+    display_name: String,
+    file: Option<String>,
+    visibility: Option<String>,
+    signature: Option<String>,
+}
+
+struct SnapshotSummary {
+    items: BTreeMap<ItemKey, ItemRecord>,
+    crate_edges: BTreeSet<DiffCrateEdge>,
+    call_edges: BTreeSet<DiffCallEdge>,
+}
+
+/// Compare two opened snapshots. `base` is the "before" side.
+pub fn diff_snapshots(base: &OpenedSnapshot, head: &OpenedSnapshot) -> Result<SnapshotDiff> {
+    let before = summarize(base)?;
+    let after = summarize(head)?;
+
+    let mut removed: Vec<(&ItemKey, &ItemRecord)> = Vec::new();
+    let mut moved_items = Vec::new();
+    let mut visibility_changes = Vec::new();
+    let mut signature_changes = Vec::new();
+    for (key, old) in &before.items {
+        let Some(new) = after.items.get(key) else {
+            removed.push((key, old));
+            continue;
+        };
+        let (qualified_name, kind) = key;
+        if old.file != new.file {
+            moved_items.push(MovedItem {
+                item_kind: kind.to_string(),
+                from_qualified_name: qualified_name.clone(),
+                to_qualified_name: qualified_name.clone(),
+                from_file: old.file.clone(),
+                to_file: new.file.clone(),
+            });
+        }
+        if old.visibility != new.visibility {
+            visibility_changes.push(VisibilityChange {
+                qualified_name: qualified_name.clone(),
+                item_kind: kind.to_string(),
+                from: old.visibility.clone(),
+                to: new.visibility.clone(),
+            });
+        }
+        if let (Some(from), Some(to)) = (&old.signature, &new.signature)
+            && from != to
+        {
+            signature_changes.push(SignatureChange {
+                qualified_name: qualified_name.clone(),
+                item_kind: kind.to_string(),
+                from: from.clone(),
+                to: to.clone(),
+            });
+        }
+    }
+    let added: Vec<(&ItemKey, &ItemRecord)> = after
+        .items
+        .iter()
+        .filter(|(key, _)| !before.items.contains_key(*key))
+        .collect();
+
+    // A removed/added pair moved modules when its move key is unique on both
+    // sides; ambiguous keys stay reported as plain removals and additions.
+    let removed_counts = move_key_counts(&removed);
+    let added_counts = move_key_counts(&added);
+    let pairable: HashMap<MoveKey<'_>, (&ItemKey, &ItemRecord)> = added
+        .iter()
+        .map(|&(key, record)| (move_key(key, record), (key, record)))
+        .filter(|(key, _)| {
+            removed_counts.get(key) == Some(&1) && added_counts.get(key) == Some(&1)
+        })
+        .collect();
+    let mut paired: HashSet<&ItemKey> = HashSet::new();
+    let mut removed_items = Vec::new();
+    for &(key, record) in &removed {
+        match pairable.get(&move_key(key, record)) {
+            Some(&(to_key, to_record)) => {
+                paired.insert(to_key);
+                moved_items.push(MovedItem {
+                    item_kind: key.1.to_string(),
+                    from_qualified_name: key.0.clone(),
+                    to_qualified_name: to_key.0.clone(),
+                    from_file: record.file.clone(),
+                    to_file: to_record.file.clone(),
+                });
+            }
+            None => removed_items.push(diff_item(key, record)),
+        }
+    }
+    let added_items = added
+        .iter()
+        .filter(|(key, _)| !paired.contains(key))
+        .map(|&(key, record)| diff_item(key, record))
+        .collect();
+    moved_items.sort_by(|a, b| {
+        (&a.from_qualified_name, &a.item_kind).cmp(&(&b.from_qualified_name, &b.item_kind))
+    });
+
+    Ok(SnapshotDiff {
+        base: diff_side(base),
+        head: diff_side(head),
+        added_items,
+        removed_items,
+        moved_items,
+        visibility_changes,
+        signature_changes,
+        added_crate_edges: after.crate_edges.difference(&before.crate_edges).cloned().collect(),
+        removed_crate_edges: before.crate_edges.difference(&after.crate_edges).cloned().collect(),
+        added_call_edges: after.call_edges.difference(&before.call_edges).cloned().collect(),
+        removed_call_edges: before.call_edges.difference(&after.call_edges).cloned().collect(),
+    })
+}
+
+fn summarize(snap: &OpenedSnapshot) -> Result<SnapshotSummary> {
+    let rtxn = snap.env.read_txn()?;
+    let mut nodes: BTreeMap<NodeId, Node> = BTreeMap::new();
+    for entry in snap.dbs.nodes_by_id.iter(&rtxn)? {
+        let (key, node) = entry?;
+        if node.kind != NodeKind::Item || node.item_kind.is_none() {
+            continue;
+        }
+        let mut id = [0u8; 32];
+        id.copy_from_slice(key);
+        nodes.insert(NodeId(id), node);
+    }
+    let item_ids: HashSet<NodeId> = nodes.keys().copied().collect();
+    let mut visibility = declared_visibility_map(snap, &rtxn, &item_ids)?;
+
+    let mut items = BTreeMap::new();
+    for (id, node) in by_declaration_site(&nodes) {
+        let Some(kind) = node.item_kind else {
+            continue;
+        };
+        let signature = snap
+            .dbs
+            .signatures_by_target
+            .get(&rtxn, id.as_bytes())?
+            .map(|signature| signature.render(&node.display_name));
+        // The first declaration site wins on duplicate names (e.g.
+        // same-named methods in two impl blocks).
+        items
+            .entry((node.qualified_name.clone(), item_kind_short_label(kind)))
+            .or_insert_with(|| ItemRecord {
+                display_name: node.display_name.clone(),
+                file: node.file.clone(),
+                visibility: visibility.remove(id),
+                signature,
+            });
+    }
+
+    let mut call_edges = BTreeSet::new();
+    for entry in snap.dbs.usages_by_id.iter(&rtxn)? {
+        let (_k, usage) = entry?;
+        let Some(caller) = usage.consumer_function.and_then(|id| nodes.get(&id)) else {
+            continue;
+        };
+        let Some(callee) = nodes.get(&usage.target) else {
+            continue;
+        };
+        if !callee.item_kind.is_some_and(ItemKind::is_callable) {
+            continue;
+        }
+        call_edges.insert(DiffCallEdge {
+            caller: caller.qualified_name.clone(),
+            callee: callee.qualified_name.clone(),
+        });
+    }
+    drop(rtxn);
+
+    let crate_edges = snap
+        .crate_edges()?
+        .into_iter()
+        .map(|edge| DiffCrateEdge {
+            consumer_crate: edge.consumer_crate,
+            producer_crate: edge.producer_crate,
+        })
+        .collect();
+    Ok(SnapshotSummary {
+        items,
+        crate_edges,
+        call_edges,
+    })
+}
+
+fn move_key<'a>(key: &'a ItemKey, record: &'a ItemRecord) -> MoveKey<'a> {
+    (&record.display_name, key.1, record.signature.as_deref())
+}
+
+fn move_key_counts<'a>(entries: &[(&'a ItemKey, &'a ItemRecord)]) -> HashMap<MoveKey<'a>, usize> {
+    let mut counts = HashMap::new();
+    for &(key, record) in entries {
+        *counts.entry(move_key(key, record)).or_insert(0) += 1;
+    }
+    counts
+}
+
+fn diff_item(key: &ItemKey, record: &ItemRecord) -> DiffItem {
+    DiffItem {
+        qualified_name: key.0.clone(),
+        item_kind: key.1.to_string(),
+        file: record.file.clone(),
+        visibility: record.visibility.clone(),
+    }
+}
+
+pub(super) fn diff_side(snap: &OpenedSnapshot) -> SnapshotDiffSide {
+    SnapshotDiffSide {
+        workspace_root: snap.manifest.workspace_root.clone(),
+        graph_id: snap.manifest.graph_id.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::model::BindingVisibility;
+    use crate::graph::test_support::{ModelFixture, fn_signature};
+
+    #[test]
+    fn reports_item_visibility_signature_and_call_edge_changes() {
+        let data_dir = tempfile::tempdir().unwrap();
+
+        let mut base = ModelFixture::new("base");
+        let run = base.item("fix::run", ItemKind::Function, "src/lib.rs");
+        let helper = base.item("fix::a::helper", ItemKind::Function, "src/a.rs");
+        let fetch = base.item("fix::fetch", ItemKind::Function, "src/lib.rs");
+        base.item("fix::Old", ItemKind::Struct, "src/lib.rs");
+        base.declare(fetch, BindingVisibility::Crate(base.crate_id()));
+        base.signature(fetch, fn_signature("u32"));
+        base.call(run, helper);
+
+        let mut head = ModelFixture::new("head");
+        let run = head.item("fix::run", ItemKind::Function, "src/run.rs");
+        let helper = head.item("fix::b::helper", ItemKind::Function, "src/b.rs");
+        let fetch = head.item("fix::fetch", ItemKind::Function, "src/lib.rs");
+        head.item("fix::New", ItemKind::Struct, "src/lib.rs");
+        head.declare(fetch, BindingVisibility::Public);
+        head.signature(fetch, fn_signature("u64"));
+        head.call(run, helper);
+        head.call(run, fetch);
+
+        let (_base_ws, base) = base.persist(data_dir.path());
+        let (_head_ws, head) = head.persist(data_dir.path());
+        let diff = diff_snapshots(&base, &head).unwrap();
+
+        let names = |items: &[DiffItem]| -> Vec<String> {
+            items.iter().map(|item| item.qualified_name.clone()).collect()
+        };
+        assert_eq!(names(&diff.added_items), vec!["fix::New"]);
+        assert_eq!(names(&diff.removed_items), vec!["fix::Old"]);
+
+        let moves: Vec<(&str, &str)> = diff
+            .moved_items
+            .iter()
+            .map(|moved| (moved.from_qualified_name.as_str(), moved.to_qualified_name.as_str()))
+            .collect();
+        assert_eq!(moves, vec![("fix::a::helper", "fix::b::helper"), ("fix::run", "fix::run")]);
+
+        assert_eq!(diff.visibility_changes.len(), 1);
+        assert_eq!(diff.visibility_changes[0].from.as_deref(), Some("pub(crate)"));
+        assert_eq!(diff.visibility_changes[0].to.as_deref(), Some("pub"));
+
+        assert_eq!(diff.signature_changes.len(), 1);
+        assert_eq!(diff.signature_changes[0].from, "fn fetch(id: u32)");
+        assert_eq!(diff.signature_changes[0].to, "fn fetch(id: u64)");
+
+        let calls: Vec<(&str, &str)> = diff
+            .added_call_edges
+            .iter()
+            .map(|edge| (edge.caller.as_str(), edge.callee.as_str()))
+            .collect();
+        assert_eq!(calls, vec![("fix::run", "fix::b::helper"), ("fix::run", "fix::fetch")]);
+        assert_eq!(diff.removed_call_edges.len(), 1);
+        assert!(diff.added_crate_edges.is_empty() && diff.removed_crate_edges.is_empty());
+    }
+
+    /// Same-named Items resolve to the same declaration on both sides,
+    /// whatever order their NodeIds sort in.
+    #[test]
+    fn duplicate_names_pick_the_first_declaration_site() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let mut sides = Vec::new();
+        // The fixture names make the NodeId order of the two `get`s differ
+        // between the sides.
+        for name in ["dup_base", "dup_head"] {
+            let mut fixture = ModelFixture::new(name);
+            let cache = fixture.item("fix::Cache", ItemKind::Struct, "src/lib.rs");
+            for (file, param) in [("src/a.rs", "u32"), ("src/b.rs", "u64")] {
+                let get =
+                    fixture.member_at(cache, "fix::Cache::get", ItemKind::Method, file, (0, 10));
+                fixture.signature(get, fn_signature(param));
+            }
+            sides.push(fixture.persist(data_dir.path()));
+        }
+
+        let diff = diff_snapshots(&sides[0].1, &sides[1].1).unwrap();
+        assert!(diff.signature_changes.is_empty(), "{:?}", diff.signature_changes);
+        assert!(diff.moved_items.is_empty());
+    }
+}
@@ -0,0 +1,54 @@
+//! Query methods on `OpenedSnapshot` — trait-impl family.
+//!
+//! Covers the v13 `implements` relation: `implementations_of` (every
+//! `impl Trait for _` of a trait) and `impls_of_type` (every
+//! `impl _ for Type` of a type).
+
+use anyhow::{Context, Result};
+use heed::types::Bytes;
+use heed::{Database, RoTxn};
+
+use super::super::ids::NodeId;
+use super::super::model::TraitImpl;
+use super::super::snapshot::OpenedSnapshot;
+
+impl OpenedSnapshot {
+    /// v13: every local `impl <trait_id> for _` block. `trait_id` may be a
+    /// local trait Item or an `ExternalSymbol` stub for an imported trait;
+    /// impls of external traits that no local module imports carry no
+    /// `trait_id` and are only reachable through `impls_of_type`. Sorted by
+    /// (file, span).
+    pub fn implementations_of(&self, trait_id: NodeId) -> Result<Vec<TraitImpl>> {
+        let rtxn = self.env.read_txn()?;
+        self.trait_impls_via(&rtxn, &self.dbs.impls_by_trait, trait_id)
+    }
+
+    /// v13: every local `impl _ for <type_id>` block, covering both local
+    /// and (imported) external traits. Blanket impls whose self type is a
+    /// type parameter are not indexed by type and don't show up here.
+    /// Sorted by (file, span).
+    pub fn impls_of_type(&self, type_id: NodeId) -> Result<Vec<TraitImpl>> {
+        let rtxn = self.env.read_txn()?;
+        self.trait_impls_via(&rtxn, &self.dbs.impls_by_self_type, type_id)
+    }
+
+    fn trait_impls_via(
+        &self,
+        rtxn: &RoTxn<'_, heed::WithoutTls>,
+        index: &Database<Bytes, Bytes>,
+        key: NodeId,
+    ) -> Result<Vec<TraitImpl>> {
+        let mut out: Vec<TraitImpl> = Vec::new();
+        for entry in index.get_duplicates(rtxn, key.as_bytes())?.into_iter().flatten() {
+            let (_k, iid_bytes) = entry?;
+            let trait_impl = self
+                .dbs
+                .impls_by_id
+                .get(rtxn, iid_bytes)?
+                .context("dangling ImplId in trait-impl index")?;
+            out.push(trait_impl);
+        }
+        out.sort_by(|a, b| a.file.cmp(&b.file).then(a.span.cmp(&b.span)));
+        Ok(out)
+    }
+}
@@ -2,14 +2,21 @@
 //!
 //! Split from the pre-refactor `graph::queries` mega-file across PRs 08-11.
 //! Result types live in `model`; method implementations on `OpenedSnapshot`
-//! are partitioned by concern (imports, usage, calls, crates, surface,
-//! audits, functions, modules, overlaps).
+//! are partitioned by concern (imports, usage, calls, coverage, crates,
+//! surface, audits, functions, impls, modules, overlaps); `diff` and
+//! `api_changes` compare two snapshots; `co_change` joins the snapshot
+//! with git history.
 
+pub(super) mod api_changes;
 pub(super) mod audits;
 pub(super) mod calls;
+pub(super) mod co_change;
+pub(super) mod coverage;
 pub(super) mod crates;
+pub(super) mod diff;
 pub(super) mod enrichment;
 pub(super) mod functions;
+pub(super) mod impls;
 pub(super) mod imports;
 pub(super) mod model;
 pub(super) mod modules;
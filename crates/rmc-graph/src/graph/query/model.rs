@@ -13,7 +13,9 @@ use rmcp::schemars;
 use serde::{Deserialize, Serialize};
 
 use super::super::ids::NodeId;
-use super::super::model::{BindingVisibility, FunctionSignature, ItemKind};
+use super::super::model::{
+    BindingVisibility, ComplexityMetrics, FunctionSignature, ItemKind, MacroKind,
+};
 
 /// One result of `dead_pub_in_crate`: a `pub` item with no cross-crate
 /// importers or references.
@@ -436,6 +438,77 @@ pub struct RecursiveCallersCount {
     pub truncated_at_depth: bool,
 }
 
+/// v18: which `ComplexityMetrics` field `complexity_hotspots` ranks by.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ComplexityMetric {
+    #[default]
+    Cyclomatic,
+    Cognitive,
+}
+
+impl ComplexityMetric {
+    pub fn of(self, metrics: &ComplexityMetrics) -> u32 {
+        match self {
+            Self::Cyclomatic => metrics.cyclomatic,
+            Self::Cognitive => metrics.cognitive,
+        }
+    }
+}
+
+/// v18: one row of `complexity_hotspots`. `complexity` is the ranked
+/// metric's value; `score` is `complexity × (transitive_callers + 1)`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ComplexityHotspot {
+    pub target: NodeId,
+    pub qualified_name: String,
+    pub display_name: String,
+    pub item_kind: ItemKind,
+    pub file: Option<String>,
+    pub span: Option<(u32, u32)>,
+    pub metrics: ComplexityMetrics,
+    pub complexity: u32,
+    pub direct_callers: usize,
+    pub transitive_callers: usize,
+    pub score: u64,
+}
+
+/// One row of `tests_exercising`: a test fn that reaches the target within
+/// `hops` caller hops (1 = the test calls it directly).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExercisingTest {
+    pub test: NodeId,
+    pub qualified_name: String,
+    pub crate_name: Option<String>,
+    /// Cargo target kind of the test's crate; `test` for integration tests,
+    /// `lib` / `bin` for `#[cfg(test)]` unit tests
+    pub crate_target_kind: Option<String>,
+    pub file: Option<String>,
+    pub span: Option<(u32, u32)>,
+    pub hops: u32,
+}
+
+/// One row of `untested_pub_items`: a `pub` fn or method no test reaches.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UntestedPubItem {
+    pub target: NodeId,
+    pub qualified_name: String,
+    pub item_kind: ItemKind,
+    pub crate_name: Option<String>,
+    pub file: Option<String>,
+    pub span: Option<(u32, u32)>,
+}
+
+/// Result of `untested_pub_items`. `test_fn_count` is the number of test
+/// fns the walk started from; zero means the snapshot was built without
+/// test targets, so every candidate is listed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UntestedPubItems {
+    pub test_fn_count: usize,
+    pub pub_item_count: usize,
+    pub items: Vec<UntestedPubItem>,
+}
+
 /// One row of `who_uses_summary`: aggregation of `usages_of(target)` results,
 /// grouped by `(consumer_module, target)`. Each row carries a per-category
 /// breakdown so callers can see whether the consumer reads / writes / tests
@@ -448,6 +521,26 @@ pub struct UsageSummaryRow {
     pub category_breakdown: BTreeMap<String, usize>,
 }
 
+/// v17: one row of `macros_by_fan_in` — a local macro with its invocation
+/// counts. `cross_crate_count` counts invocations from modules of another
+/// crate; `consumer_function_count` counts distinct calling functions
+/// (invocations outside any fn body don't add to it).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MacroFanIn {
+    pub target: NodeId,
+    pub qualified_name: String,
+    pub display_name: String,
+    pub kind: Option<MacroKind>,
+    pub macro_export: bool,
+    pub visibility: Option<String>,
+    pub file: Option<String>,
+    pub span: Option<(u32, u32)>,
+    pub invocation_count: usize,
+    pub consumer_module_count: usize,
+    pub consumer_function_count: usize,
+    pub cross_crate_count: usize,
+}
+
 /// Recursive node tree returned by `module_tree`.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ModuleTreeNode {
@@ -469,6 +562,22 @@ pub struct CrateTypeItem {
     pub visibility: Option<String>,
     pub file: Option<String>,
     pub span: Option<(u32, u32)>,
+    /// v16: the struct / union shape in declaration order. Empty for other
+    /// kinds and for unit structs.
+    #[serde(default)]
+    pub fields: Vec<TypeField>,
+}
+
+/// v16: one field of a struct, union or enum variant, as returned by
+/// `fields_of`. `visibility` is the declared modifier (`None` for variant
+/// fields); `type_string` is the `HirDisplay` rendering.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TypeField {
+    pub target: NodeId,
+    pub name: String,
+    pub type_string: String,
+    pub visibility: Option<String>,
+    pub attributes: Vec<String>,
 }
 
 /// Result of `workspace_stats`: counters across the whole snapshot.
@@ -649,3 +758,243 @@ pub struct MutStaticFinding {
     pub file: Option<String>,
     pub span: Option<(u32, u32)>,
 }
+
+/// One side of a `snapshot_diff`: the workspace the snapshot was built
+/// from and the `graph_id` that was opened.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SnapshotDiffSide {
+    pub workspace_root: String,
+    pub graph_id: String,
+}
+
+/// An Item present on only one side of a `snapshot_diff`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DiffItem {
+    pub qualified_name: String,
+    pub item_kind: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub visibility: Option<String>,
+}
+
+/// An Item that changed file or qualified name between the two snapshots.
+/// Renames are only reported when the name, kind and signature pair up
+/// unambiguously; otherwise the pair stays in `added` / `removed`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MovedItem {
+    pub item_kind: String,
+    pub from_qualified_name: String,
+    pub to_qualified_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from_file: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to_file: Option<String>,
+}
+
+/// Declared visibility of an Item on each side; `None` means no declaring
+/// binding was recorded.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct VisibilityChange {
+    pub qualified_name: String,
+    pub item_kind: String,
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+/// Rendered `FunctionSignature` of a function on each side.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SignatureChange {
+    pub qualified_name: String,
+    pub item_kind: String,
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct DiffCrateEdge {
+    pub consumer_crate: String,
+    pub producer_crate: String,
+}
+
+/// A local caller → local callee pair attributed from `Usage::consumer_function`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct DiffCallEdge {
+    pub caller: String,
+    pub callee: String,
+}
+
+/// Output of `diff_snapshots`. Items are matched by
+/// `(qualified_name, item kind)` because node ids embed the workspace hash
+/// and never line up across worktrees. Every list is sorted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SnapshotDiff {
+    pub base: SnapshotDiffSide,
+    pub head: SnapshotDiffSide,
+    pub added_items: Vec<DiffItem>,
+    pub removed_items: Vec<DiffItem>,
+    pub moved_items: Vec<MovedItem>,
+    pub visibility_changes: Vec<VisibilityChange>,
+    pub signature_changes: Vec<SignatureChange>,
+    pub added_crate_edges: Vec<DiffCrateEdge>,
+    pub removed_crate_edges: Vec<DiffCrateEdge>,
+    pub added_call_edges: Vec<DiffCallEdge>,
+    pub removed_call_edges: Vec<DiffCallEdge>,
+}
+
+/// Breaking-change class reported by `api_breaking_changes`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiChangeKind {
+    /// A pure-`pub` item no longer exists under its qualified name.
+    ItemRemoved,
+    /// A pure-`pub` item still exists but is no longer pure `pub`.
+    VisibilityNarrowed,
+    ParamAdded,
+    ParamRemoved,
+    ParamTypeChanged,
+    /// Return type or `async`-ness changed.
+    ReturnTypeChanged,
+    /// `self` receiver added, removed, or changed between owned / `&` / `&mut`.
+    ReceiverChanged,
+    /// A generic parameter was added or gained a bound.
+    GenericBoundsTightened,
+    /// A variant was added to a public enum without `#[non_exhaustive]`.
+    EnumVariantAdded,
+    /// A method without a default body was added to a public trait.
+    TraitMethodAdded,
+    /// A public trait method lost its default body.
+    TraitMethodDefaultRemoved,
+    /// A public struct or enum gained `#[non_exhaustive]`.
+    NonExhaustiveAdded,
+}
+
+impl ApiChangeKind {
+    /// The serialized `snake_case` name.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::ItemRemoved => "item_removed",
+            Self::VisibilityNarrowed => "visibility_narrowed",
+            Self::ParamAdded => "param_added",
+            Self::ParamRemoved => "param_removed",
+            Self::ParamTypeChanged => "param_type_changed",
+            Self::ReturnTypeChanged => "return_type_changed",
+            Self::ReceiverChanged => "receiver_changed",
+            Self::GenericBoundsTightened => "generic_bounds_tightened",
+            Self::EnumVariantAdded => "enum_variant_added",
+            Self::TraitMethodAdded => "trait_method_added",
+            Self::TraitMethodDefaultRemoved => "trait_method_default_removed",
+            Self::NonExhaustiveAdded => "non_exhaustive_added",
+        }
+    }
+}
+
+/// One breaking change. `before` / `after` carry the rendered signature or
+/// visibility that changed, when there is one.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ApiChange {
+    pub qualified_name: String,
+    pub item_kind: String,
+    pub change: ApiChangeKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+}
+
+/// Output of `api_breaking_changes` for one crate, sorted by
+/// `(qualified_name, change)`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ApiBreakingChanges {
+    pub crate_name: String,
+    pub base: SnapshotDiffSide,
+    pub head: SnapshotDiffSide,
+    pub public_items_before: usize,
+    pub public_items_after: usize,
+    pub changes: Vec<ApiChange>,
+}
+
+/// Extent of the git history behind a co-change report. Commits touching
+/// no snapshot Item are scanned but not counted in `commits_with_items`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GitHistoryScan {
+    pub commits_scanned: usize,
+    pub commits_with_items: usize,
+    /// Commits touching more Items than `max_items_per_commit`.
+    pub commits_skipped_large: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub newest_commit: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub oldest_commit: Option<String>,
+}
+
+/// Number of commits that changed one Item.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ItemChurn {
+    pub qualified_name: String,
+    pub item_kind: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    pub commit_count: usize,
+    pub last_commit: String,
+    /// Committer time of `last_commit`, in Unix seconds.
+    pub last_commit_time: i64,
+}
+
+/// Output of `item_churn`, sorted by `commit_count` descending.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ItemChurnReport {
+    pub history: GitHistoryScan,
+    pub items: Vec<ItemChurn>,
+}
+
+/// Two Items changed by the same commits.
+///
+/// * `co_change_count`: commits touching both.
+/// * `jaccard = co / (a_commits + b_commits - co)` — 1.0 means the two
+///   never change apart.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ItemCoChange {
+    pub a_qualified_name: String,
+    pub a_item_kind: String,
+    pub b_qualified_name: String,
+    pub b_item_kind: String,
+    pub co_change_count: usize,
+    pub a_commit_count: usize,
+    pub b_commit_count: usize,
+    pub jaccard: f64,
+    pub cross_module: bool,
+    pub cross_crate: bool,
+}
+
+/// Output of `item_co_change`, sorted by `co_change_count`, then
+/// `jaccard`, descending. With a `target`, every pair has it as `a`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ItemCoChangeReport {
+    pub history: GitHistoryScan,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    pub pairs: Vec<ItemCoChange>,
+}
+
+/// Two modules whose Items are changed by the same commits. Counts are
+/// commits, not Item pairs; `jaccard` is defined as on `ItemCoChange`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ModuleCoChange {
+    pub module_a: String,
+    pub module_b: String,
+    pub co_change_count: usize,
+    pub a_commit_count: usize,
+    pub b_commit_count: usize,
+    pub jaccard: f64,
+    pub cross_crate: bool,
+}
+
+/// Output of `module_co_change`, sorted like `ItemCoChangeReport`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ModuleCoChangeReport {
+    pub history: GitHistoryScan,
+    pub pairs: Vec<ModuleCoChange>,
+}
@@ -39,9 +39,40 @@ impl OpenedSnapshot {
         }
         let crate_id = crate_id
             .with_context(|| format!("no Crate node with qualified_name `{crate_name}`"))?;
+        self.module_tree_in_crate(&rtxn, crate_id, crate_id, depth)
+    }
+
+    /// Module/item tree rooted at any Crate or Module node — e.g. a single
+    /// submodule. Visibility is resolved across the root's owning crate.
+    pub fn module_subtree(&self, root_id: NodeId, depth: Option<usize>) -> Result<ModuleTreeNode> {
+        let rtxn = self.env.read_txn()?;
+        let root = self
+            .dbs
+            .nodes_by_id
+            .get(&rtxn, root_id.as_bytes())?
+            .context("module_subtree root not found")?;
+        let crate_id = match root.kind {
+            NodeKind::Crate => root_id,
+            NodeKind::Module => root
+                .crate_id
+                .with_context(|| format!("module `{}` has no owning crate", root.qualified_name))?,
+            other => anyhow::bail!(
+                "`{}` is a {other:?}, expected Crate or Module",
+                root.qualified_name
+            ),
+        };
+        self.module_tree_in_crate(&rtxn, root_id, crate_id, depth)
+    }
 
+    fn module_tree_in_crate(
+        &self,
+        rtxn: &RoTxn<'_, heed::WithoutTls>,
+        root_id: NodeId,
+        crate_id: NodeId,
+        depth: Option<usize>,
+    ) -> Result<ModuleTreeNode> {
         let mut declared_targets: HashSet<NodeId> = HashSet::new();
-        for entry in self.dbs.nodes_by_id.iter(&rtxn)? {
+        for entry in self.dbs.nodes_by_id.iter(rtxn)? {
             let (key, node) = entry?;
             if matches!(node.kind, NodeKind::Item | NodeKind::Module)
                 && node.crate_id == Some(crate_id)
@@ -51,9 +82,9 @@ impl OpenedSnapshot {
                 declared_targets.insert(NodeId(id));
             }
         }
-        let visibility = declared_visibility_map(self, &rtxn, &declared_targets)?;
+        let visibility = declared_visibility_map(self, rtxn, &declared_targets)?;
 
-        self.build_module_tree(&rtxn, crate_id, depth, 0, &visibility)
+        self.build_module_tree(rtxn, root_id, depth, 0, &visibility)
     }
 
     pub fn crate_types(
@@ -96,6 +127,10 @@ impl OpenedSnapshot {
             let Some(kind) = node.item_kind else {
                 continue;
             };
+            let fields = match kind {
+                ItemKind::Struct | ItemKind::Union => self.fields_in(&rtxn, id)?,
+                _ => Vec::new(),
+            };
             out.push(CrateTypeItem {
                 target: id,
                 qualified_name: node.qualified_name,
@@ -104,6 +139,7 @@ impl OpenedSnapshot {
                 visibility,
                 file: node.file,
                 span: node.span,
+                fields,
             });
         }
         out.sort_by(|a, b| a.qualified_name.cmp(&b.qualified_name));
@@ -158,8 +194,12 @@ impl OpenedSnapshot {
         let item_kind_label = node
             .item_kind
             .map(|k| format!("Item.{}", label_item_kind(k)));
+        // Fields have no declaring binding; they carry their own modifier.
         let visibility = match node.kind {
-            NodeKind::Item | NodeKind::Module => item_visibility.get(&node_id).cloned(),
+            NodeKind::Item | NodeKind::Module => item_visibility
+                .get(&node_id)
+                .cloned()
+                .or_else(|| node.visibility.clone()),
             _ => node.visibility.clone(),
         };
         Ok(ModuleTreeNode {
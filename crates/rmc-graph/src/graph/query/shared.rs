@@ -5,7 +5,7 @@
 //! traversal depth limit. Separated from `queries.rs` in PR 11 review
 //! fix-up so the legacy module can be a true facade.
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 use anyhow::{Context, Result};
 use heed::RoTxn;
@@ -63,6 +63,18 @@ pub(in crate::graph) fn declared_visibility_map(
     Ok(visibility)
 }
 
+/// `nodes` ordered by declaration site `(file, span)`, NodeId breaking
+/// ties. NodeIds hash the workspace root, so "first NodeId wins" would pick
+/// a different one of two same-named Items in each checkout; the source
+/// position picks the same one everywhere.
+pub(in crate::graph) fn by_declaration_site(
+    nodes: &BTreeMap<NodeId, Node>,
+) -> Vec<(&NodeId, &Node)> {
+    let mut ordered: Vec<(&NodeId, &Node)> = nodes.iter().collect();
+    ordered.sort_by(|(_, a), (_, b)| (&a.file, a.span).cmp(&(&b.file, b.span)));
+    ordered
+}
+
 pub(in crate::graph) fn format_binding_visibility(
     rtxn: &RoTxn<'_, heed::WithoutTls>,
     snap: &OpenedSnapshot,
@@ -53,7 +53,7 @@ pub enum GraphSimilarityError {
     InvalidCrateFilterKind { name: String, kind: NodeKind },
     #[error("`{name}` resolves to a Module with no crate_id")]
     ModuleMissingCrateId { name: String },
-    #[error("unknown item_kind `{0}`; expected Function | Struct | Enum | Union | Trait | TypeAlias | Const | Static | AssocFunction | AssocConst | AssocType | Method | EnumVariant")]
+    #[error("unknown item_kind `{0}`; expected Function | Struct | Enum | Union | Trait | TypeAlias | Const | Static | AssocFunction | AssocConst | AssocType | Method | EnumVariant | Field | Macro")]
     InvalidItemKindFilter(String),
     #[error("output_mode must be \"pairs\" or \"clusters\"; got `{0}`")]
     InvalidOutputMode(String),
@@ -356,6 +356,8 @@ fn parse_item_kind_filter(raw: Option<&str>) -> Result<Option<ItemKind>> {
         "assoctype" | "assoc_type" => ItemKind::AssocType,
         "method" => ItemKind::Method,
         "enumvariant" | "enum_variant" | "variant" => ItemKind::EnumVariant,
+        "field" => ItemKind::Field,
+        "macro" => ItemKind::Macro,
         other => return Err(GraphSimilarityError::InvalidItemKindFilter(other.to_string()).into()),
     };
     Ok(Some(kind))
@@ -383,6 +385,10 @@ fn enumerate_similarity_seeds(
             if node.item_kind != Some(want_kind) {
                 continue;
             }
+        } else if node.item_kind == Some(ItemKind::Field) {
+            // A one-line field body embeds as noise next to whole items;
+            // fields are only compared when asked for explicitly.
+            continue;
         }
         if node.file.is_none() || node.span.is_none() {
             continue;
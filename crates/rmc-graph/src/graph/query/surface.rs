@@ -1,6 +1,6 @@
 //! Query methods on `OpenedSnapshot` — public-surface family.
 //!
-//! Covers public-surface queries: `enum_variants`, `item_attributes`,
+//! Covers public-surface queries: `enum_variants`, `fields_of`, `item_attributes`,
 //! `items_with_attribute`, `pub_use_pub_type_audit`, `re_export_chain`,
 //! `dead_pub_in_crate`, `dead_pub_report`. Moved here from `graph::queries`
 //! in PR 10.
@@ -17,7 +17,7 @@ use super::shared::MAX_REEXPORT_HOPS;
 use super::super::snapshot::OpenedSnapshot;
 use super::model::{
     CrateDeadPub, DeadPubFinding, ItemWithAttribute, PubTypeAliasMasqueradingAsReexport,
-    ReExportChain, ReExportLink,
+    ReExportChain, ReExportLink, TypeField,
 };
 
 impl OpenedSnapshot {
@@ -70,6 +70,62 @@ impl OpenedSnapshot {
         Ok(out)
     }
 
+    /// v16: the fields of a struct, union or enum variant in declaration
+    /// order, with their `FieldMetadata` type strings. Any other input
+    /// returns an empty Vec, same as `enum_variants`.
+    pub fn fields_of(&self, parent: NodeId) -> Result<Vec<TypeField>> {
+        let rtxn = self.env.read_txn()?;
+        self.fields_in(&rtxn, parent)
+    }
+
+    pub(super) fn fields_in(
+        &self,
+        rtxn: &heed::RoTxn<'_, heed::WithoutTls>,
+        parent: NodeId,
+    ) -> Result<Vec<TypeField>> {
+        let mut child_ids: Vec<NodeId> = Vec::new();
+        if let Some(iter) = self
+            .dbs
+            .children_by_parent
+            .get_duplicates(rtxn, parent.as_bytes())?
+        {
+            for entry in iter {
+                let (_k, child_bytes) = entry?;
+                let mut id = [0u8; 32];
+                id.copy_from_slice(child_bytes);
+                child_ids.push(NodeId(id));
+            }
+        }
+        let mut out: Vec<(u32, TypeField)> = Vec::new();
+        for child_id in child_ids {
+            let Some(node) = self.dbs.nodes_by_id.get(rtxn, child_id.as_bytes())? else {
+                continue;
+            };
+            if node.item_kind != Some(ItemKind::Field) {
+                continue;
+            }
+            // A field without metadata sorts last rather than dropping out.
+            let (index, type_string) = self
+                .dbs
+                .field_metadata_by_target
+                .get(rtxn, child_id.as_bytes())?
+                .map(|meta| (meta.index, meta.type_string))
+                .unwrap_or((u32::MAX, String::new()));
+            out.push((
+                index,
+                TypeField {
+                    target: child_id,
+                    name: node.display_name,
+                    type_string,
+                    visibility: node.visibility,
+                    attributes: node.attributes,
+                },
+            ));
+        }
+        out.sort_by_key(|(index, _)| *index);
+        Ok(out.into_iter().map(|(_, field)| field).collect())
+    }
+
     /// v8: return the outer attributes and doc-comment lines (one entry per
     /// line) recorded for the Item at `target`. Empty Vec when the target
     /// has no attributes, isn't an Item, or doesn't exist. Order matches
@@ -341,7 +341,7 @@ fn private_visibility_blocks_export() {
 
 #[test]
 fn usages_of_loader_load_returns_at_least_one() {
-    // `loader::load` is called by `build_and_persist` in the same lib.
+    // `loader::load` is called by the `run_*_audit` entry points in the same lib.
     // Phase 2 must record at least one Usage row.
     let snap = shared_snapshot();
     let (load_fn_id, _) = snap
@@ -770,9 +770,9 @@ fn who_uses_summary_aggregates_by_consumer() {
 #[test]
 fn calls_from_returns_callees() {
     // Layer 10 — call graph: `build_and_persist` is a known caller of
-    // `loader::load`. `calls_from(build_and_persist)` should include the
-    // `loader::load` ref (plus a long tail of other refs from inside the
-    // body — at minimum the loader::load call must be present).
+    // `loader::load_with_config`. `calls_from(build_and_persist)` should
+    // include that ref (plus a long tail of other refs from inside the
+    // body — at minimum the loader call must be present).
     let snap = shared_snapshot();
     let (caller_id, _) = snap
         .lookup_by_qualified_name("rmc_graph::graph::snapshot::build_and_persist")
@@ -817,8 +817,9 @@ fn workspace_stats_has_basic_counts() {
 
 #[test]
 fn call_graph_returns_root_with_callees() {
-    // `build_and_persist` is a known caller of `loader::load` (and others);
-    // a depth-2 descent must produce a non-empty `callees` vec on the root.
+    // `build_and_persist` is a known caller of `loader::load_with_config`
+    // (and others); a depth-2 descent must produce a non-empty `callees` vec
+    // on the root.
     let snap = shared_snapshot();
     let (root_id, _) = snap
         .lookup_by_qualified_name("rmc_graph::graph::snapshot::build_and_persist")
@@ -868,7 +869,7 @@ fn call_graph_respects_depth_zero() {
 #[test]
 fn callers_in_crate_filters_correctly() {
     // `loader::load` is referenced from inside `rust_code_mcp` itself
-    // (e.g., from `build_and_persist`). Filtering by the workspace's own
+    // (e.g., from `run_unsafe_audit`). Filtering by the workspace's own
     // crate must return a strict subset of who_calls — equal or smaller.
     let snap = shared_snapshot();
     let (target_id, _) = snap
@@ -948,6 +949,187 @@ fn enum_variants_returns_expected_set() {
     }
 }
 
+/// v16: `fields_of` lists a struct's fields in declaration order. Pick
+/// `FieldMetadata` (model.rs) — three `pub` fields, each with a
+/// `#[serde(default)]` attribute.
+#[test]
+fn fields_of_field_metadata_in_declaration_order() {
+    let snap = shared_snapshot();
+    let (id, _) = snap
+        .lookup_by_qualified_name("rmc_graph::graph::model::FieldMetadata")
+        .unwrap()
+        .expect("FieldMetadata struct not in snapshot");
+    let fields = snap.fields_of(id).expect("fields_of failed");
+    let shape: Vec<(&str, &str)> = fields
+        .iter()
+        .map(|f| (f.name.as_str(), f.type_string.as_str()))
+        .collect();
+    assert_eq!(
+        shape,
+        vec![("type_string", "String"), ("index", "u32"), ("is_tuple", "bool")],
+    );
+    for field in &fields {
+        assert_eq!(field.visibility.as_deref(), Some("pub"));
+        assert!(
+            field.attributes.iter().any(|a| a.contains("serde(default)")),
+            "expected #[serde(default)] on {}, got {:?}",
+            field.name,
+            field.attributes
+        );
+    }
+
+    let (field_id, field_node) = snap
+        .lookup_by_qualified_name("rmc_graph::graph::model::FieldMetadata.index")
+        .unwrap()
+        .expect("FieldMetadata.index field not in snapshot");
+    assert_eq!(field_node.item_kind, Some(ItemKind::Field));
+    assert_eq!(field_node.parent_id, Some(id));
+    assert_eq!(field_id, fields[1].target);
+}
+
+/// v17: `macros_by_fan_in` lists `define_id!` (ids.rs), a crate-private
+/// `macro_rules!` invoked four times at module level — so no calling fn.
+#[test]
+fn macros_by_fan_in_counts_define_id_invocations() {
+    let snap = shared_snapshot();
+    let crate_id = crate_id_by_name("rmc_graph");
+    let rows = snap
+        .macros_by_fan_in(Some(crate_id), true)
+        .expect("macros_by_fan_in failed");
+    let row = rows
+        .iter()
+        .find(|row| row.qualified_name == "rmc_graph::graph::ids::define_id")
+        .unwrap_or_else(|| panic!("define_id missing from {rows:?}"));
+    assert_eq!(row.kind, Some(crate::graph::model::MacroKind::Declarative));
+    assert!(!row.macro_export);
+    assert_eq!(row.visibility.as_deref(), Some("pub(crate)"));
+    assert!(row.invocation_count >= 4, "got {row:?}");
+    assert_eq!(row.consumer_module_count, 1);
+    assert_eq!(row.consumer_function_count, 0);
+    assert_eq!(row.cross_crate_count, 0);
+    assert!(
+        rows.windows(2)
+            .all(|w| w[0].invocation_count >= w[1].invocation_count),
+        "macros_by_fan_in should sort by invocation_count desc"
+    );
+}
+
+/// v18: `complexity_hotspots` weighs complexity by transitive callers, so
+/// a moderately complex fn with two callers outranks a more complex leaf;
+/// fns under `min_complexity` and test fns are dropped.
+#[test]
+fn complexity_hotspots_weighs_complexity_by_callers() {
+    use crate::graph::model::ComplexityMetrics;
+    use crate::graph::test_support::ModelFixture;
+
+    let metrics = |cyclomatic, cognitive| ComplexityMetrics {
+        cyclomatic,
+        cognitive,
+        ..ComplexityMetrics::default()
+    };
+    let data_dir = tempfile::tempdir().unwrap();
+    let mut fixture = ModelFixture::new("hotspots");
+    let parse = fixture.item("fix::parse", ItemKind::Function, "src/lib.rs");
+    let leaf = fixture.item("fix::leaf", ItemKind::Function, "src/lib.rs");
+    let run = fixture.item("fix::run", ItemKind::Function, "src/lib.rs");
+    let main = fixture.item("fix::main", ItemKind::Function, "src/lib.rs");
+    let test_fn = fixture.item("fix::tests::big", ItemKind::Function, "src/lib.rs");
+    fixture.complexity(parse, metrics(8, 12));
+    fixture.complexity(leaf, metrics(12, 3));
+    fixture.complexity(run, metrics(2, 1));
+    fixture.complexity(main, metrics(1, 0));
+    fixture.complexity(test_fn, metrics(30, 30));
+    fixture.call(run, parse);
+    fixture.call(main, run);
+    let crate_id = fixture.crate_id();
+    let (_ws, snap) = fixture.persist(data_dir.path());
+
+    let rows = snap
+        .complexity_hotspots(Some(crate_id), ComplexityMetric::Cyclomatic, 3, 5, true)
+        .expect("complexity_hotspots failed");
+    let ranked: Vec<(&str, u64)> = rows
+        .iter()
+        .map(|row| (row.qualified_name.as_str(), row.score))
+        .collect();
+    assert_eq!(ranked, vec![("fix::parse", 24), ("fix::leaf", 12)]);
+    assert_eq!(rows[0].direct_callers, 1);
+    assert_eq!(rows[0].transitive_callers, 2);
+    assert_eq!(rows[0].complexity, 8);
+
+    let cognitive = snap
+        .complexity_hotspots(None, ComplexityMetric::Cognitive, 1, 0, false)
+        .expect("complexity_hotspots failed");
+    assert_eq!(cognitive[0].qualified_name, "fix::tests::big");
+    assert_eq!(cognitive[1].qualified_name, "fix::parse");
+    assert_eq!(cognitive[1].score, 24);
+    assert_eq!(cognitive.len(), 5);
+}
+
+/// `tests_exercising` walks callers back to `#[test]` fns through test
+/// helpers; `untested_pub_items` lists the `pub` fns and methods no test
+/// reaches within the walk depth.
+#[test]
+fn test_reach_queries_follow_calls_from_test_fns() {
+    use crate::graph::test_support::ModelFixture;
+
+    let data_dir = tempfile::tempdir().unwrap();
+    let mut fixture = ModelFixture::new("test_reach");
+    let parse = fixture.item("fix::parse", ItemKind::Function, "src/lib.rs");
+    let helper = fixture.item("fix::helper", ItemKind::Function, "src/lib.rs");
+    let unused = fixture.item("fix::unused", ItemKind::Function, "src/lib.rs");
+    let private = fixture.item("fix::private", ItemKind::Function, "src/lib.rs");
+    let config = fixture.item("fix::Config", ItemKind::Struct, "src/lib.rs");
+    let load = fixture.member(config, "fix::Config::load", ItemKind::Method, "src/lib.rs");
+    let validate = fixture.member(config, "fix::Config::validate", ItemKind::Method, "src/lib.rs");
+    let parses = fixture.item("fix::tests::parses", ItemKind::Function, "src/lib.rs");
+    let setup = fixture.item("fix::tests::setup", ItemKind::Function, "src/lib.rs");
+    let loads = fixture.item("fix::tests::loads", ItemKind::Function, "src/lib.rs");
+    for id in [parse, helper, unused, config] {
+        fixture.declare(id, BindingVisibility::Public);
+    }
+    fixture.declare(private, BindingVisibility::Private);
+    fixture.visibility(load, "pub");
+    // Private method of a pub type: never a candidate
+    fixture.visibility(validate, "pub(self)");
+    fixture.attribute(parses, "#[test]");
+    fixture.attribute(loads, "#[tokio::test]");
+    fixture.call(parses, setup);
+    fixture.call(setup, parse);
+    fixture.call(parse, helper);
+    fixture.call(loads, load);
+    let crate_id = fixture.crate_id();
+    let (_ws, snap) = fixture.persist(data_dir.path());
+
+    let tests = snap.tests_exercising(helper, 3).expect("tests_exercising failed");
+    let rows: Vec<(&str, u32)> = tests
+        .iter()
+        .map(|row| (row.qualified_name.as_str(), row.hops))
+        .collect();
+    assert_eq!(rows, vec![("fix::tests::parses", 3)]);
+    assert_eq!(tests[0].crate_name.as_deref(), Some("fix"));
+    assert!(snap.tests_exercising(helper, 2).unwrap().is_empty());
+
+    let untested = snap
+        .untested_pub_items(Some(crate_id), 8)
+        .expect("untested_pub_items failed");
+    assert_eq!(untested.test_fn_count, 2);
+    assert_eq!(untested.pub_item_count, 4);
+    let names: Vec<&str> = untested
+        .items
+        .iter()
+        .map(|row| row.qualified_name.as_str())
+        .collect();
+    assert_eq!(names, vec!["fix::unused"]);
+
+    let shallow = snap.untested_pub_items(None, 1).unwrap();
+    let names: Vec<&str> = shallow
+        .items
+        .iter()
+        .map(|row| row.qualified_name.as_str())
+        .collect();
+    assert_eq!(names, vec!["fix::helper", "fix::parse", "fix::unused"]);
+}
+
 /// v8: `item_attributes(target)` returns the outer attributes recorded
 /// on the Item Node. Pick `Node` struct (model.rs) — it carries a stable
 /// `#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]`.
@@ -1298,7 +1480,7 @@ fn crate_dependency_metric_smoke() {
 
 #[test]
 fn recursive_callers_count_grows_with_depth() {
-    // `loader::load` has at least one direct caller (`build_and_persist`),
+    // `loader::load` has at least one direct caller (`run_unsafe_audit`),
     // which itself has callers somewhere in the codebase. So the depth=3
     // count must be >= depth=1 count.
     let snap = shared_snapshot();
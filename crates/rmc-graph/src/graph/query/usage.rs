@@ -1,18 +1,18 @@
 //! Query methods on `OpenedSnapshot` — usage family.
 //!
 //! Covers who-imports / who-uses queries: `who_imports`, `usages_of`,
-//! `usages_in`, `who_uses_summary`. Moved here from `graph::queries` in
-//! PR 09.
+//! `usages_in`, `who_uses_summary`, `macros_by_fan_in`. Moved here from
+//! `graph::queries` in PR 09.
 
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 use anyhow::Result;
 
 use super::super::ids::NodeId;
 use super::super::labels::usage_category_label;
-use super::super::model::{Binding, BindingKind, Usage};
+use super::super::model::{Binding, BindingKind, ItemKind, Node, Usage};
 use super::super::snapshot::OpenedSnapshot;
-use super::model::UsageSummaryRow;
+use super::model::{MacroFanIn, UsageSummaryRow};
 
 impl OpenedSnapshot {
     /// All bindings in the workspace whose target is `target` (and that aren't
@@ -111,4 +111,81 @@ impl OpenedSnapshot {
         });
         Ok(rows)
     }
+
+    /// v17: every local macro ranked by fan-in. `crate_id` limits which
+    /// macros are listed, not which invocations count; `skip_test_items`
+    /// drops macros declared under `::tests::`. Sorted by
+    /// `invocation_count` desc, ties broken by `qualified_name`.
+    pub fn macros_by_fan_in(
+        &self,
+        crate_id: Option<NodeId>,
+        skip_test_items: bool,
+    ) -> Result<Vec<MacroFanIn>> {
+        let rtxn = self.env.read_txn()?;
+        let mut macros: Vec<(NodeId, Node)> = Vec::new();
+        for entry in self.dbs.nodes_by_id.iter(&rtxn)? {
+            let (key, node) = entry?;
+            if node.item_kind != Some(ItemKind::Macro)
+                || crate_id.is_some_and(|c| node.crate_id != Some(c))
+                || (skip_test_items && node.qualified_name.contains("::tests::"))
+            {
+                continue;
+            }
+            let mut id = [0u8; 32];
+            id.copy_from_slice(key);
+            macros.push((NodeId(id), node));
+        }
+
+        let mut crate_of_module: HashMap<NodeId, Option<NodeId>> = HashMap::new();
+        let mut rows: Vec<MacroFanIn> = Vec::with_capacity(macros.len());
+        for (id, node) in macros {
+            let mut usages: Vec<Usage> = Vec::new();
+            for entry in self.usages_for_target(&rtxn, id)? {
+                usages.push(entry?);
+            }
+            let mut modules: HashSet<NodeId> = HashSet::new();
+            let mut functions: HashSet<NodeId> = HashSet::new();
+            let mut cross_crate_count = 0;
+            for usage in &usages {
+                modules.insert(usage.consumer_module);
+                functions.extend(usage.consumer_function);
+                let consumer_crate = match crate_of_module.get(&usage.consumer_module) {
+                    Some(&krate) => krate,
+                    None => {
+                        let krate = self
+                            .dbs
+                            .nodes_by_id
+                            .get(&rtxn, usage.consumer_module.as_bytes())?
+                            .and_then(|module| module.crate_id);
+                        crate_of_module.insert(usage.consumer_module, krate);
+                        krate
+                    }
+                };
+                if consumer_crate != node.crate_id {
+                    cross_crate_count += 1;
+                }
+            }
+            let metadata = self.dbs.macro_metadata_by_target.get(&rtxn, id.as_bytes())?;
+            rows.push(MacroFanIn {
+                target: id,
+                qualified_name: node.qualified_name,
+                display_name: node.display_name,
+                kind: metadata.as_ref().map(|meta| meta.kind),
+                macro_export: metadata.is_some_and(|meta| meta.macro_export),
+                visibility: node.visibility,
+                file: node.file,
+                span: node.span,
+                invocation_count: usages.len(),
+                consumer_module_count: modules.len(),
+                consumer_function_count: functions.len(),
+                cross_crate_count,
+            });
+        }
+        rows.sort_by(|a, b| {
+            b.invocation_count
+                .cmp(&a.invocation_count)
+                .then_with(|| a.qualified_name.cmp(&b.qualified_name))
+        });
+        Ok(rows)
+    }
 }
@@ -8,6 +8,7 @@
 //! - return type (stringified)
 //! - is_async
 //! - generic type parameters with their bounds
+//! - whether the declaration has a body (a trait fn's provided default)
 //!
 //! The per-fn signature is stored on `ExtractionModel.signatures` as
 //! `(NodeId, FunctionSignature)` and persisted to the new
@@ -134,6 +135,7 @@ fn build_signature(
         params,
         return_type,
         generics,
+        has_body: func.has_body(db),
     })
 }
 
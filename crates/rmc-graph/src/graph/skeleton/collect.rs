@@ -385,7 +385,10 @@ fn bucket_items(
 }
 
 fn should_skip_direct_item(node: &Node) -> bool {
-    matches!(node.item_kind, Some(ItemKind::EnumVariant))
+    matches!(
+        node.item_kind,
+        Some(ItemKind::EnumVariant | ItemKind::Field | ItemKind::Macro)
+    )
         || matches!(
             node.item_kind,
             Some(ItemKind::Method | ItemKind::AssocConst | ItemKind::AssocType)
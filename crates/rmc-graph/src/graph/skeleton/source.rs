@@ -8,7 +8,7 @@ use ra_ap_syntax::{
 };
 
 use crate::graph::model::{
-    FunctionSignature, ItemKind, Node, SelfKind, StaticMetadata,
+    FunctionSignature, ItemKind, Node, StaticMetadata,
 };
 use crate::graph::snapshot::OpenedSnapshot;
 
@@ -240,6 +240,8 @@ fn declaration_name(item_kind: ItemKind, syntax: &SyntaxNode) -> Option<String>
         ItemKind::Const | ItemKind::AssocConst => ast::Const::cast(syntax.clone())?.name(),
         ItemKind::Static => ast::Static::cast(syntax.clone())?.name(),
         ItemKind::EnumVariant => ast::Variant::cast(syntax.clone())?.name(),
+        ItemKind::Field => ast::RecordField::cast(syntax.clone())?.name(),
+        ItemKind::Macro => ast::Macro::cast(syntax.clone())?.name(),
     }
     .map(|name| name.text().to_string())
 }
@@ -259,6 +261,13 @@ fn is_expected_item_syntax(item_kind: ItemKind, syntax: &SyntaxNode) -> bool {
         ItemKind::Const | ItemKind::AssocConst => ast::Const::cast(syntax.clone()).is_some(),
         ItemKind::Static => ast::Static::cast(syntax.clone()).is_some(),
         ItemKind::EnumVariant => ast::Variant::cast(syntax.clone()).is_some(),
+        ItemKind::Field => {
+            ast::RecordField::cast(syntax.clone()).is_some()
+                || ast::TupleField::cast(syntax.clone()).is_some()
+        }
+        ItemKind::Macro => {
+            ast::Macro::cast(syntax.clone()).is_some() || ast::Fn::cast(syntax.clone()).is_some()
+        }
     }
 }
 
@@ -471,7 +480,7 @@ fn fallback_declaration(item: &SkeletonItem, metadata: &FallbackMetadata) -> Str
                 format!("{vis}static {name}: () = ();")
             }
         }
-        Some(ItemKind::EnumVariant) | None => {
+        Some(ItemKind::EnumVariant | ItemKind::Field | ItemKind::Macro) | None => {
             format!("// item `{}` could not be rendered", item.node.qualified_name)
         }
     }
@@ -482,73 +491,7 @@ fn fallback_function_declaration(
     name: &str,
     signature: &FunctionSignature,
 ) -> String {
-    let async_prefix = if signature.is_async { "async " } else { "" };
-    let generics = render_generics(&signature.generics);
-    let params = render_function_params(signature);
-    let return_type = render_return_type(&signature.return_type);
-    format!(
-        "{vis}{async_prefix}fn {name}{generics}({params}){return_type} {{ /* ... */ }}"
-    )
-}
-
-fn render_generics(generics: &[crate::graph::model::GenericBound]) -> String {
-    if generics.is_empty() {
-        return String::new();
-    }
-    let rendered = generics
-        .iter()
-        .map(|generic| {
-            if generic.bounds.is_empty() {
-                generic.name.clone()
-            } else {
-                format!("{}: {}", generic.name, generic.bounds.join(" + "))
-            }
-        })
-        .collect::<Vec<_>>()
-        .join(", ");
-    format!("<{rendered}>")
-}
-
-fn render_function_params(signature: &FunctionSignature) -> String {
-    let mut params = Vec::new();
-    if let Some(self_param) = signature.self_param {
-        params.push(match self_param {
-            SelfKind::Owned => "self".to_string(),
-            SelfKind::Ref => "&self".to_string(),
-            SelfKind::RefMut => "&mut self".to_string(),
-        });
-    }
-    params.extend(
-        signature
-            .params
-            .iter()
-            .enumerate()
-            .map(|(idx, param)| render_param(idx, param)),
-    );
-    params.join(", ")
-}
-
-fn render_param(idx: usize, param: &crate::graph::model::Param) -> String {
-    let name = if param.name.is_empty() {
-        format!("_arg{idx}")
-    } else {
-        param.name.clone()
-    };
-    let ty = if param.ty.is_empty() {
-        "()"
-    } else {
-        param.ty.as_str()
-    };
-    format!("{name}: {ty}")
-}
-
-fn render_return_type(return_type: &str) -> String {
-    let trimmed = return_type.trim();
-    if trimmed.is_empty() || trimmed == "()" {
-        String::new()
-    } else {
-        format!(" -> {trimmed}")
-    }
+    format!("{vis}{} {{ /* ... */ }}", signature.render(name))
 }
 
 fn fallback_static_declaration(
@@ -576,7 +519,7 @@ fn visibility_prefix(visibility: Option<&str>) -> String {
 mod tests {
     use super::*;
     use crate::graph::ids::NodeId;
-    use crate::graph::model::{GenericBound, Node, NodeKind, Param};
+    use crate::graph::model::{GenericBound, Node, NodeKind, Param, SelfKind};
 
     fn first_syntax(src: &str, kind: ItemKind) -> SyntaxNode {
         let parsed = SourceFile::parse(src, ra_ap_syntax::Edition::Edition2024).tree();
@@ -802,6 +745,7 @@ mod tests {
                     name: "T".to_string(),
                     bounds: vec!["Send".to_string(), "Sync".to_string()],
                 }],
+                has_body: true,
             }),
             static_metadata: None,
         };
@@ -831,6 +775,7 @@ mod tests {
                 }],
                 return_type: "bool".to_string(),
                 generics: Vec::new(),
+                has_body: true,
             }),
             static_metadata: None,
         };
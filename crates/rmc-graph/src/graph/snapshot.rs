@@ -4,8 +4,13 @@
 //! computes a fingerprint, opens a new heed env in a staging dir, writes the
 //! whole model in one transaction, writes manifest.json, then atomically swaps
 //! the workspace's `CURRENT` pointer.
+//!
+//! Since v14 a build whose fingerprint changed starts from the previously
+//! published snapshot when it can: the persisted per-file digests name the
+//! changed files, and `extract::extract_delta` re-extracts only the crates
+//! that own them.
 
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex, OnceLock, Weak};
@@ -13,6 +18,7 @@ use std::time::{SystemTime, UNIX_EPOCH};
 
 use anyhow::{Context, Result, bail};
 use heed::{Env, RoTxn, RwTxn, WithoutTls};
+use serde::{Deserialize, Serialize};
 type GraphRoTxn<'e> = RoTxn<'e, WithoutTls>;
 type GraphRwTxn<'e> = RwTxn<'e>;
 
@@ -25,13 +31,15 @@ struct CachedGraphEnv {
 }
 
 use super::extract;
-use super::ids::{BindingId, NodeId, UsageId};
+use super::ids::{BindingId, ImplId, NodeId, UsageId};
+use super::incremental;
+use super::load_config::WorkspaceLoadConfig;
 use super::loader::{self, LoadedWorkspace};
-use super::model::{Binding, ExtractionModel, Namespace, Usage};
+use super::model::{Binding, ExtractionModel, Namespace, TraitImpl, Usage};
 use super::storage::{
     CURRENT_POINTER_FILENAME, GraphDatabases, GraphEnvOptions, GraphManifest, GraphPaths,
-    SCHEMA_VERSION, compute_fingerprint, default_data_dir, graph_id_for, read_manifest,
-    read_manifest_compatible, write_manifest,
+    SCHEMA_VERSION, compute_file_digests, default_data_dir, fingerprint_from_digests,
+    graph_id_for, read_manifest, read_manifest_compatible, write_manifest,
 };
 
 #[derive(Debug, Clone)]
@@ -39,6 +47,10 @@ pub struct BuildOptions {
     pub force_rebuild: bool,
     pub data_dir_override: Option<PathBuf>,
     pub env: GraphEnvOptions,
+    /// v14: when the fingerprint changed, start from the previous `CURRENT`
+    /// snapshot and re-extract only crates owning changed files. Ignored
+    /// under `force_rebuild`.
+    pub incremental: bool,
 }
 
 impl Default for BuildOptions {
@@ -47,6 +59,7 @@ impl Default for BuildOptions {
             force_rebuild: false,
             data_dir_override: None,
             env: GraphEnvOptions::default(),
+            incremental: true,
         }
     }
 }
@@ -60,6 +73,8 @@ pub struct BuildResult {
     pub binding_count: u64,
     pub usage_count: u64,
     pub reused: bool,
+    /// True when the model was delta-extracted from the previous snapshot.
+    pub incremental: bool,
     pub snapshot_path: PathBuf,
 }
 
@@ -68,6 +83,7 @@ struct SnapshotIdentity {
     workspace_root: PathBuf,
     paths: GraphPaths,
     fingerprint: String,
+    file_digests: Vec<(String, [u8; 32])>,
     graph_id: String,
     snapshot_dir: PathBuf,
     manifest_path: PathBuf,
@@ -159,6 +175,137 @@ fn clear_existing_graph_dir(
     }
 }
 
+/// Marker recording that sources changed after the `CURRENT` snapshot was
+/// built. Written by the server's watch mode; the snapshot stays readable,
+/// callers just learn that a `build_hypergraph` is due.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotStaleness {
+    /// When the first change since the last build was seen.
+    pub since_unix: u64,
+    /// When the most recent change was seen.
+    pub updated_unix: u64,
+    /// Workspace-relative paths reported changed, sorted and deduplicated.
+    pub changed_files: Vec<String>,
+}
+
+/// Cap on `SnapshotStaleness::changed_files`; past it the marker only keeps
+/// its timestamps fresh.
+const STALE_MARKER_MAX_FILES: usize = 512;
+
+/// Mark the workspace's published snapshot stale because `changed_files`
+/// (absolute or workspace-relative) changed. No-op when no snapshot has been
+/// published yet.
+pub fn mark_workspace_stale(workspace_root: &Path, changed_files: &[PathBuf]) -> Result<()> {
+    let paths = GraphPaths::for_workspace(workspace_root);
+    mark_stale(&paths, workspace_root, changed_files)
+}
+
+/// Read the stale marker for a canonical workspace root, if any.
+pub fn workspace_staleness(workspace_root: &Path) -> Result<Option<SnapshotStaleness>> {
+    read_staleness(&GraphPaths::for_workspace(workspace_root))
+}
+
+pub(crate) fn mark_stale(
+    paths: &GraphPaths,
+    workspace_root: &Path,
+    changed_files: &[PathBuf],
+) -> Result<()> {
+    if !paths.current_pointer_path.exists() {
+        return Ok(());
+    }
+    let now = now_unix()?;
+    let mut marker = read_staleness(paths)?.unwrap_or(SnapshotStaleness {
+        since_unix: now,
+        updated_unix: now,
+        changed_files: Vec::new(),
+    });
+    marker.updated_unix = now;
+    let mut files: BTreeSet<String> = marker.changed_files.drain(..).collect();
+    for path in changed_files {
+        if files.len() >= STALE_MARKER_MAX_FILES {
+            break;
+        }
+        let rel = path.strip_prefix(workspace_root).unwrap_or(path);
+        files.insert(rel.to_string_lossy().to_string());
+    }
+    marker.changed_files = files.into_iter().collect();
+
+    let tmp = paths.root_dir.join(format!("{STALE_MARKER_FILENAME}.tmp"));
+    fs::write(&tmp, serde_json::to_vec_pretty(&marker)?)
+        .with_context(|| format!("write tmp stale marker {}", tmp.display()))?;
+    fs::rename(&tmp, &paths.stale_marker_path)
+        .with_context(|| format!("rename {} → {}", tmp.display(), paths.stale_marker_path.display()))?;
+    Ok(())
+}
+
+pub(crate) fn read_staleness(paths: &GraphPaths) -> Result<Option<SnapshotStaleness>> {
+    if !paths.stale_marker_path.exists() {
+        return Ok(None);
+    }
+    let bytes = fs::read(&paths.stale_marker_path)
+        .with_context(|| format!("read {}", paths.stale_marker_path.display()))?;
+    Ok(serde_json::from_slice(&bytes).ok())
+}
+
+/// Drop the stale marker after a publish, unless a change was recorded after
+/// the build started reading sources — that edit may not be in the snapshot.
+fn clear_stale_marker(paths: &GraphPaths, build_started_unix: u64) -> Result<()> {
+    if let Some(marker) = read_staleness(paths)?
+        && marker.updated_unix >= build_started_unix
+    {
+        return Ok(());
+    }
+    if paths.stale_marker_path.exists() {
+        fs::remove_file(&paths.stale_marker_path)
+            .with_context(|| format!("remove {}", paths.stale_marker_path.display()))?;
+    }
+    Ok(())
+}
+
+/// Manifest of the snapshot `CURRENT` points at, read without opening the
+/// LMDB env. `None` when nothing schema-compatible is published.
+pub(crate) fn read_current_manifest(paths: &GraphPaths) -> Result<Option<GraphManifest>> {
+    if !paths.current_pointer_path.exists() {
+        return Ok(None);
+    }
+    let graph_id = fs::read_to_string(&paths.current_pointer_path)
+        .with_context(|| format!("read {}", paths.current_pointer_path.display()))?;
+    let manifest_path = paths.manifest_path(graph_id.trim());
+    if graph_id.trim().is_empty() || !manifest_path.exists() {
+        return Ok(None);
+    }
+    read_manifest_compatible(&manifest_path)
+}
+
+/// Manifests of every workspace with a published, schema-compatible snapshot
+/// under `data_dir` (the default graph data dir when `None`), sorted by
+/// workspace root. Unreadable workspace directories are skipped.
+pub fn published_workspaces(data_dir: Option<&Path>) -> Result<Vec<GraphManifest>> {
+    let base_dir = data_dir.map(Path::to_path_buf).unwrap_or_else(default_data_dir);
+    if !base_dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut manifests = Vec::new();
+    for entry in fs::read_dir(&base_dir).with_context(|| format!("read {}", base_dir.display()))? {
+        let entry = entry?;
+        if !entry.file_type()?.is_dir() {
+            continue;
+        }
+        let workspace_hash = entry.file_name().to_string_lossy().to_string();
+        let paths = GraphPaths::from_root_dir(workspace_hash, entry.path());
+        match read_current_manifest(&paths) {
+            Ok(Some(manifest)) => manifests.push(manifest),
+            Ok(None) => {}
+            Err(error) => tracing::warn!(
+                root_dir = %paths.root_dir.display(),
+                "skipping unreadable hypergraph snapshot: {error:#}"
+            ),
+        }
+    }
+    manifests.sort_by(|a, b| a.workspace_root.cmp(&b.workspace_root));
+    Ok(manifests)
+}
+
 /// Open the current published snapshot for a canonical workspace root.
 pub fn open_current_for_workspace(workspace_root: &Path) -> Result<Option<OpenedSnapshot>> {
     let paths = GraphPaths::for_workspace(workspace_root);
@@ -182,6 +329,7 @@ fn snapshot_identity(
     workspace_root: PathBuf,
     paths: GraphPaths,
     fingerprint: String,
+    file_digests: Vec<(String, [u8; 32])>,
 ) -> SnapshotIdentity {
     let graph_id = graph_id_for(&paths.workspace_hash, &fingerprint);
     let snapshot_dir = paths.snapshot_dir(&graph_id);
@@ -191,6 +339,7 @@ fn snapshot_identity(
         workspace_root,
         paths,
         fingerprint,
+        file_digests,
         graph_id,
         snapshot_dir,
         manifest_path,
@@ -200,27 +349,31 @@ fn snapshot_identity(
 fn compute_snapshot_identity(
     workspace_root: PathBuf,
     paths: GraphPaths,
+    load_config: &WorkspaceLoadConfig,
 ) -> Result<SnapshotIdentity> {
-    let fingerprint = compute_fingerprint(&workspace_root)?;
-    Ok(snapshot_identity(workspace_root, paths, fingerprint))
+    let file_digests = compute_file_digests(&workspace_root, load_config)?;
+    let fingerprint = fingerprint_from_digests(&file_digests);
+    Ok(snapshot_identity(workspace_root, paths, fingerprint, file_digests))
 }
 
 fn compute_snapshot_identity_timed(
     workspace_root: PathBuf,
     paths: GraphPaths,
+    load_config: &WorkspaceLoadConfig,
     timing: bool,
 ) -> Result<SnapshotIdentity> {
     if !timing {
-        return compute_snapshot_identity(workspace_root, paths);
+        return compute_snapshot_identity(workspace_root, paths, load_config);
     }
 
     let t = std::time::Instant::now();
-    let fingerprint = compute_fingerprint(&workspace_root)?;
+    let file_digests = compute_file_digests(&workspace_root, load_config)?;
+    let fingerprint = fingerprint_from_digests(&file_digests);
     eprintln!(
         "build:   compute_fingerprint          {:>9.2?}",
         t.elapsed()
     );
-    Ok(snapshot_identity(workspace_root, paths, fingerprint))
+    Ok(snapshot_identity(workspace_root, paths, fingerprint, file_digests))
 }
 
 fn try_reuse_existing_snapshot(identity: &SnapshotIdentity) -> Result<Option<BuildResult>> {
@@ -241,30 +394,45 @@ fn try_reuse_existing_snapshot(identity: &SnapshotIdentity) -> Result<Option<Bui
         binding_count: manifest.binding_count,
         usage_count: manifest.usage_count,
         reused: true,
+        incremental: false,
         snapshot_path: identity.snapshot_dir.clone(),
     }))
 }
 
 pub fn build_and_persist(directory: &Path, options: BuildOptions) -> Result<BuildResult> {
     let timing = std::env::var_os("EXTRACT_TIMING").is_some();
+    let started_unix = now_unix()?;
     let workspace_root = canonical_workspace_root(directory)?;
+    // Read once: the fingerprint and the loader must agree on the settings.
+    let load_config = WorkspaceLoadConfig::load(&workspace_root)?;
 
     let preflight_identity = if options.force_rebuild {
         None
     } else {
         let paths = graph_paths_for_workspace(&workspace_root, &options);
-        let identity = compute_snapshot_identity_timed(workspace_root.clone(), paths, timing)?;
+        let identity = compute_snapshot_identity_timed(
+            workspace_root.clone(),
+            paths,
+            &load_config,
+            timing,
+        )?;
         if let Some(result) = try_reuse_existing_snapshot(&identity)? {
             if timing {
                 eprintln!("build:   reused existing snapshot");
             }
+            // Edits that were reverted leave a marker behind even though the
+            // published snapshot matches the tree again.
+            let current = fs::read_to_string(&identity.paths.current_pointer_path).ok();
+            if current.as_deref().map(str::trim) == Some(identity.graph_id.as_str()) {
+                clear_stale_marker(&identity.paths, started_unix)?;
+            }
             return Ok(result);
         }
         Some(identity)
     };
 
     let t = std::time::Instant::now();
-    let loaded = loader::load(&workspace_root)?;
+    let loaded = loader::load_with_config(&workspace_root, &load_config)?;
     if timing {
         eprintln!(
             "build:   loader::load                 {:>9.2?}  ({} local crates)",
@@ -277,10 +445,23 @@ pub fn build_and_persist(directory: &Path, options: BuildOptions) -> Result<Buil
         identity
     } else {
         let paths = graph_paths_for_workspace(&loaded.workspace_root, &options);
-        compute_snapshot_identity_timed(loaded.workspace_root.clone(), paths, timing)?
+        compute_snapshot_identity_timed(
+            loaded.workspace_root.clone(),
+            paths,
+            &load_config,
+            timing,
+        )?
     };
     identity.paths.ensure_dirs()?;
 
+    // v14: seed from the previous `CURRENT` snapshot before the staging dir
+    // is cleared.
+    let previous = if options.force_rebuild || !options.incremental {
+        None
+    } else {
+        previous_for_delta(&identity, options.env, timing)
+    };
+
     if identity.snapshot_dir.exists() {
         fs::remove_dir_all(&identity.snapshot_dir)
             .with_context(|| format!("failed to clear stale {}", identity.snapshot_dir.display()))?;
@@ -295,13 +476,22 @@ pub fn build_and_persist(directory: &Path, options: BuildOptions) -> Result<Buil
             .with_context(|| format!("open heed env at {}", identity.snapshot_dir.display()))?
     };
 
-    let model = extract::extract(&loaded);
+    let (model, incremental) = match previous {
+        Some((previous_model, changed)) => {
+            match extract::extract_delta(&loaded, &previous_model, &changed) {
+                Some(model) => (model, true),
+                None => (extract::extract(&loaded), false),
+            }
+        }
+        None => (extract::extract(&loaded), false),
+    };
 
     let t = std::time::Instant::now();
     let (node_count, binding_count, usage_count) = write_model(
         &env,
         options.env,
         &model,
+        &identity.file_digests,
         &identity.paths.workspace_hash,
         &identity.fingerprint,
         &identity.graph_id,
@@ -330,6 +520,7 @@ pub fn build_and_persist(directory: &Path, options: BuildOptions) -> Result<Buil
     write_manifest(&identity.manifest_path, &manifest)?;
 
     publish_current(&identity.paths, &identity.graph_id)?;
+    clear_stale_marker(&identity.paths, started_unix)?;
 
     Ok(BuildResult {
         graph_id: identity.graph_id,
@@ -339,10 +530,55 @@ pub fn build_and_persist(directory: &Path, options: BuildOptions) -> Result<Buil
         binding_count,
         usage_count,
         reused: false,
+        incremental,
         snapshot_path: identity.snapshot_dir,
     })
 }
 
+/// Model and changed-file set of the `CURRENT` snapshot, when it can seed a
+/// delta extraction of `identity`. Any failure to read it only costs the
+/// speed-up, so errors are logged and turn into a full extract.
+fn previous_for_delta(
+    identity: &SnapshotIdentity,
+    env_opts: GraphEnvOptions,
+    timing: bool,
+) -> Option<(ExtractionModel, BTreeSet<String>)> {
+    let t = std::time::Instant::now();
+    let attempt = || -> Result<Option<(ExtractionModel, BTreeSet<String>)>> {
+        let Some(snap) = open_current(&identity.paths, env_opts)? else {
+            return Ok(None);
+        };
+        if snap.manifest.graph_id == identity.graph_id {
+            return Ok(None);
+        }
+        let digests = incremental::read_file_digests(&snap)?;
+        if digests.is_empty() {
+            return Ok(None);
+        }
+        let changed = incremental::changed_paths(&digests, &identity.file_digests);
+        if incremental::touches_manifest(&changed) {
+            return Ok(None);
+        }
+        let model = incremental::read_carryover_model(&snap)?;
+        Ok(Some((model, changed)))
+    };
+    let previous = attempt().unwrap_or_else(|e| {
+        tracing::warn!("incremental rebuild unavailable, doing a full extract: {e:#}");
+        None
+    });
+    if timing {
+        eprintln!(
+            "build:   read previous snapshot       {:>9.2?}  ({})",
+            t.elapsed(),
+            match &previous {
+                Some((_, changed)) => format!("{} changed files", changed.len()),
+                None => "full rebuild".to_string(),
+            }
+        );
+    }
+    previous
+}
+
 /// Lower-level entry for tests that already have a `LoadedWorkspace` in hand.
 pub(crate) fn persist_loaded(
     loaded: &LoadedWorkspace,
@@ -350,7 +586,8 @@ pub(crate) fn persist_loaded(
 ) -> Result<BuildResult> {
     let paths = graph_paths_for_workspace(&loaded.workspace_root, options);
     paths.ensure_dirs()?;
-    let identity = compute_snapshot_identity(loaded.workspace_root.clone(), paths)?;
+    let load_config = WorkspaceLoadConfig::load(&loaded.workspace_root)?;
+    let identity = compute_snapshot_identity(loaded.workspace_root.clone(), paths, &load_config)?;
 
     if identity.snapshot_dir.exists() {
         fs::remove_dir_all(&identity.snapshot_dir)?;
@@ -363,6 +600,7 @@ pub(crate) fn persist_loaded(
         &env,
         options.env,
         &model,
+        &identity.file_digests,
         &identity.paths.workspace_hash,
         &identity.fingerprint,
         &identity.graph_id,
@@ -389,6 +627,7 @@ pub(crate) fn persist_loaded(
         binding_count,
         usage_count,
         reused: false,
+        incremental: false,
         snapshot_path: identity.snapshot_dir,
     })
 }
@@ -397,6 +636,7 @@ fn write_model(
     env: &Env<WithoutTls>,
     _env_opts: GraphEnvOptions,
     model: &ExtractionModel,
+    file_digests: &[(String, [u8; 32])],
     workspace_hash: &str,
     fingerprint: &str,
     graph_id: &str,
@@ -474,6 +714,57 @@ fn write_model(
             .context("put static metadata")?;
     }
 
+    // 4c'. Field metadata (v16): one bincode-encoded FieldMetadata per local
+    // field NodeId, same shape as the static metadata table.
+    for (target, meta) in &model.fields {
+        dbs.field_metadata_by_target
+            .put(&mut wtxn, target.as_bytes(), meta)
+            .context("put field metadata")?;
+    }
+
+    // 4c''. Macro metadata (v17): one bincode-encoded MacroMetadata per local
+    // macro NodeId, same shape again.
+    for (target, meta) in &model.macros {
+        dbs.macro_metadata_by_target
+            .put(&mut wtxn, target.as_bytes(), meta)
+            .context("put macro metadata")?;
+    }
+
+    // 4c'''. Complexity metrics (v18): one bincode-encoded ComplexityMetrics
+    // per local fn NodeId with a body.
+    for (target, metrics) in &model.complexity {
+        dbs.complexity_by_target
+            .put(&mut wtxn, target.as_bytes(), metrics)
+            .context("put complexity metrics")?;
+    }
+
+    // 4d. Trait impls (v13): one bincode-encoded TraitImpl per local
+    // `impl Trait for Type` block, keyed by ImplId, plus DUP_SORT indexes
+    // from the trait / self-type NodeIds when those resolved to nodes.
+    for trait_impl in &model.trait_impls {
+        let iid = impl_id_for(trait_impl);
+        dbs.impls_by_id
+            .put(&mut wtxn, iid.as_bytes(), trait_impl)
+            .context("put trait impl")?;
+        if let Some(trait_id) = trait_impl.trait_id {
+            dbs.impls_by_trait
+                .put(&mut wtxn, trait_id.as_bytes(), iid.as_bytes())?;
+        }
+        if let Some(self_type_id) = trait_impl.self_type_id {
+            dbs.impls_by_self_type
+                .put(&mut wtxn, self_type_id.as_bytes(), iid.as_bytes())?;
+        }
+    }
+
+    // 4e. File digests (v14): one 32-byte sha256 per fingerprint input,
+    // keyed by workspace-relative path. The next build diffs these against
+    // the working tree to pick the crates to re-extract.
+    for (path, digest) in file_digests {
+        dbs.file_digests_by_path
+            .put(&mut wtxn, path.as_str(), digest.as_slice())
+            .context("put file digest")?;
+    }
+
     // 5. Meta
     dbs.meta_by_key
         .put(&mut wtxn, "workspace_hash", workspace_hash.as_bytes())?;
@@ -507,6 +798,7 @@ pub(crate) fn persist_test_model(
         model.workspace_root.clone(),
         paths,
         "test-fingerprint".to_string(),
+        Vec::new(),
     );
     fs::create_dir_all(&identity.snapshot_dir)
         .with_context(|| format!("create snapshot dir {}", identity.snapshot_dir.display()))?;
@@ -521,6 +813,7 @@ pub(crate) fn persist_test_model(
             &env,
             env_opts,
             model,
+            &[],
             &identity.paths.workspace_hash,
             &identity.fingerprint,
             &identity.graph_id,
@@ -572,6 +865,19 @@ pub(crate) fn usage_id_for(u: &Usage) -> UsageId {
     ])
 }
 
+/// Derive drives several impls from one attribute, all sharing the ADT's
+/// nav range — mixing in the trait name and self type keeps them distinct.
+pub(crate) fn impl_id_for(i: &TraitImpl) -> ImplId {
+    ImplId::from_components(&[
+        i.crate_id.to_hex().as_str(),
+        i.file.as_str(),
+        i.span.0.to_string().as_str(),
+        i.span.1.to_string().as_str(),
+        i.trait_name.as_str(),
+        i.self_type.as_str(),
+    ])
+}
+
 fn publish_current(paths: &GraphPaths, graph_id: &str) -> Result<()> {
     // Atomic on POSIX: write to a temp file, then rename.
     let tmp = paths.root_dir.join(format!("{CURRENT_POINTER_FILENAME}.tmp"));
@@ -622,6 +928,8 @@ impl OpenedSnapshot {
     /// `Node.file` and `Node.span` set. Returned map keys are workspace-
     /// relative file paths (matching `Node.file`); values are
     /// `(start_byte, end_byte, NodeId)` sorted by `start_byte` ascending.
+    /// Fields are left out so a position inside a struct resolves to the
+    /// struct, not to whichever field it happens to land on.
     ///
     /// Built once per `OpenedSnapshot` handle. At ~1,500 items the flat
     /// `Vec` + binary search is faster than a tree.
@@ -638,7 +946,9 @@ impl OpenedSnapshot {
             };
             for entry in iter {
                 let Ok((id_bytes, node)) = entry else { continue };
-                if !matches!(node.kind, super::model::NodeKind::Item) {
+                if !matches!(node.kind, super::model::NodeKind::Item)
+                    || node.item_kind == Some(super::model::ItemKind::Field)
+                {
                     continue;
                 }
                 let Some(ref file) = node.file else { continue };
@@ -793,6 +1103,7 @@ fn open_cached_env(
 mod tests {
     use super::*;
     use crate::graph::model::{BindingKind, NodeKind};
+    use crate::graph::storage::compute_fingerprint;
     use std::collections::BTreeMap;
     use std::path::Path;
     use std::sync::Arc;
@@ -896,6 +1207,58 @@ mod tests {
         (graph_id, snapshot_dir)
     }
 
+    #[test]
+    fn published_workspaces_lists_compatible_current_snapshots() {
+        let td = tempfile::tempdir().unwrap();
+        let data_dir = td.path().join("graphs");
+        let current = td.path().join("current");
+        let outdated = td.path().join("outdated");
+        let unpublished = td.path().join("unpublished");
+        for workspace in [&current, &outdated, &unpublished] {
+            create_non_cargo_workspace(workspace);
+        }
+
+        let (graph_id, _) = write_fake_snapshot(&current, &data_dir, SCHEMA_VERSION, true);
+        publish_current(&GraphPaths::for_workspace_in(&data_dir, &current), &graph_id).unwrap();
+        let (old_id, _) = write_fake_snapshot(&outdated, &data_dir, SCHEMA_VERSION - 1, true);
+        publish_current(&GraphPaths::for_workspace_in(&data_dir, &outdated), &old_id).unwrap();
+        write_fake_snapshot(&unpublished, &data_dir, SCHEMA_VERSION, true);
+
+        let manifests = published_workspaces(Some(&data_dir)).unwrap();
+        assert_eq!(manifests.len(), 1);
+        assert_eq!(manifests[0].graph_id, graph_id);
+        assert_eq!(manifests[0].workspace_root, current.display().to_string());
+
+        assert!(published_workspaces(Some(&td.path().join("missing"))).unwrap().is_empty());
+    }
+
+    #[test]
+    fn stale_marker_accumulates_and_clears_after_newer_build() {
+        let td = tempfile::tempdir().unwrap();
+        let workspace = td.path().join("workspace");
+        fs::create_dir_all(&workspace).unwrap();
+        let paths = GraphPaths::for_workspace_in(&td.path().join("graphs"), &workspace);
+        paths.ensure_dirs().unwrap();
+
+        // Nothing published yet: marking is a no-op.
+        mark_stale(&paths, &workspace, &[workspace.join("src/lib.rs")]).unwrap();
+        assert!(read_staleness(&paths).unwrap().is_none());
+
+        publish_current(&paths, "graph").unwrap();
+        mark_stale(&paths, &workspace, &[workspace.join("src/lib.rs")]).unwrap();
+        mark_stale(&paths, &workspace, &[PathBuf::from("src/a.rs"), workspace.join("src/lib.rs")])
+            .unwrap();
+        let marker = read_staleness(&paths).unwrap().expect("marker written");
+        assert_eq!(marker.changed_files, vec!["src/a.rs", "src/lib.rs"]);
+        assert!(marker.since_unix <= marker.updated_unix);
+
+        // A build that started before the last change keeps the marker.
+        clear_stale_marker(&paths, marker.updated_unix).unwrap();
+        assert!(read_staleness(&paths).unwrap().is_some());
+        clear_stale_marker(&paths, marker.updated_unix + 1).unwrap();
+        assert!(read_staleness(&paths).unwrap().is_none());
+    }
+
     #[test]
     fn preflight_reuse_returns_without_loading_workspace() {
         let td = tempfile::tempdir().unwrap();
@@ -1021,6 +1384,10 @@ mod tests {
             contains: Vec::new(),
             signatures: Vec::new(),
             statics: Vec::new(),
+            fields: Vec::new(),
+            macros: Vec::new(),
+            complexity: Vec::new(),
+            trait_impls: Vec::new(),
         };
         let snap = persist_test_model(data_dir.as_path(), &model, GraphEnvOptions::default())
             .expect("persist test model");
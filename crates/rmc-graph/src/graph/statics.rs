@@ -209,6 +209,10 @@ mod tests {
                             },
                         ),
                     ],
+                    fields: Vec::new(),
+                    macros: Vec::new(),
+                    complexity: Vec::new(),
+                    trait_impls: Vec::new(),
                 };
                 let env_opts = GraphEnvOptions {
                     map_size: 16 << 20,
@@ -28,7 +28,11 @@ use sha2::{Digest, Sha256};
 use walkdir::WalkDir;
 
 use super::ids::BindingId;
-use super::model::{Binding, EmbeddingRecord, FunctionSignature, Node, StaticMetadata, Usage};
+use super::load_config::WorkspaceLoadConfig;
+use super::model::{
+    Binding, ComplexityMetrics, EmbeddingRecord, FieldMetadata, FunctionSignature, MacroMetadata,
+    Node, StaticMetadata, TraitImpl, Usage,
+};
 
 // v2 (2026-05): added usages_by_id / usages_by_target / usages_by_consumer
 // sub-databases and `usage_count` to the manifest.
@@ -127,13 +131,66 @@ use super::model::{Binding, EmbeddingRecord, FunctionSignature, Node, StaticMeta
 // tests, benches, and build scripts unless callers opt them in. Existing v11
 // Node records are missing the appended bincode field, so old snapshots
 // auto-rebuild via the schema-versioned graph id.
-pub(crate) const SCHEMA_VERSION: u32 = 12;
+// v13 (2026-06): trait impls. `extract_impl_items` now records every
+// `impl Trait for Type` block in a local crate as a `TraitImpl` (trait
+// NodeId when resolvable, trait name, self-type NodeId when it is a graph
+// ADT, HirDisplay self type, impl generics with declaration-site bounds,
+// negative flag, file/span). Impl method bodies are still not emitted as
+// Items. Adds three sub-DBs next to `signatures_by_target`:
+//   * `impls_by_id` (ImplId → TraitImpl) — primary record
+//   * `impls_by_trait` (NodeId → ImplId, DUP_SORT)
+//   * `impls_by_self_type` (NodeId → ImplId, DUP_SORT)
+// Backs the new `implementations_of` / `impls_of_type` queries. Sixteen
+// named sub-DBs now exist, so `DEFAULT_MAX_DBS` is raised to 32 for
+// headroom. Old snapshots auto-rebuild because `graph_id_for` hashes
+// `SCHEMA_VERSION`.
+// v14 (2026-07): incremental rebuilds. Adds `file_digests_by_path`
+// (workspace-relative path → 32-byte sha256 of the file) holding the same
+// per-file digests that feed `compute_fingerprint`. `build_and_persist`
+// diffs them against the working tree to find the changed files, re-extracts
+// only the crates that own them, and copies every other record from the
+// previous snapshot. v13 snapshots have no digests, so the first v14 build
+// is a full one; they also auto-rebuild because `graph_id_for` hashes
+// `SCHEMA_VERSION`.
+// v15 (2026-10): `FunctionSignature` gains `has_body`, so a trait
+// declaration fn records whether it provides a default. Backs the
+// `trait_method_added` class of `api_breaking_changes`. v14 signature records
+// are missing the appended bincode field; old snapshots auto-rebuild because
+// `graph_id_for` hashes `SCHEMA_VERSION`.
+// v16 (2026-10): struct / union / enum-variant fields are Item nodes
+// (`ItemKind::Field`) parented to their ADT or variant, with visibility and
+// attributes on the `Node`. Field references land in `usages_by_*` like any
+// other Item, carrying the Read / Write split. Adds a
+// `field_metadata_by_target` sub-DB (NodeId → FieldMetadata) — NOT
+// DUP_SORT, one record per field — holding the HirDisplay type and the
+// declaration index. Old snapshots auto-rebuild because `graph_id_for`
+// hashes `SCHEMA_VERSION`.
+// v17 (2026-10): macros are Item nodes (`ItemKind::Macro`) parented to their
+// declaring module — `macro_rules!`, `macro` 2.0 and proc macros of local
+// crates. Invocation sites land in `usages_by_*` with `consumer_function`
+// attribution like any other reference. Adds a `macro_metadata_by_target`
+// sub-DB (NodeId → MacroMetadata) — NOT DUP_SORT, one record per macro —
+// holding the macro flavor and the `#[macro_export]` flag. Old snapshots
+// auto-rebuild because `graph_id_for` hashes `SCHEMA_VERSION`.
+// v18 (2026-10): per-function complexity. `extract_complexity` walks the
+// syntax tree of every local fn Item with a body and records cyclomatic and
+// cognitive complexity, max nesting depth, parameter count and LOC. Adds a
+// `complexity_by_target` sub-DB (NodeId → ComplexityMetrics) — NOT
+// DUP_SORT, one record per fn — backing `complexity_hotspots`. Old
+// snapshots auto-rebuild because `graph_id_for` hashes `SCHEMA_VERSION`.
+// v19 (2026-10): inherent-impl methods, assoc consts and assoc types record
+// their declared visibility on the `Node` (`pub(self)` when unmarked), so a
+// private method of a pub type is no longer taken for public surface. Trait
+// items and enum variants still inherit from their host. Old snapshots
+// auto-rebuild because `graph_id_for` hashes `SCHEMA_VERSION`.
+pub(crate) const SCHEMA_VERSION: u32 = 19;
 pub(crate) const CURRENT_POINTER_FILENAME: &str = "CURRENT";
+pub(crate) const STALE_MARKER_FILENAME: &str = "STALE";
 pub(crate) const SNAPSHOTS_DIRNAME: &str = "snapshots";
 pub(crate) const MANIFEST_FILENAME: &str = "manifest.json";
 
 const DEFAULT_MAP_SIZE: usize = 1 << 30; // 1 GiB
-const DEFAULT_MAX_DBS: u32 = 16;
+const DEFAULT_MAX_DBS: u32 = 32;
 const DEFAULT_MAX_READERS: u32 = 256;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -168,6 +225,9 @@ pub struct GraphPaths {
     pub workspace_hash: String,
     pub root_dir: PathBuf,
     pub current_pointer_path: PathBuf,
+    /// JSON `SnapshotStaleness` written by file watchers when sources change
+    /// after `CURRENT` was published; removed by the next build.
+    pub stale_marker_path: PathBuf,
     pub snapshots_dir: PathBuf,
 }
 
@@ -179,8 +239,15 @@ impl GraphPaths {
     pub fn for_workspace_in(base_dir: &Path, workspace_root: &Path) -> Self {
         let workspace_hash = super::ids::workspace_hash(workspace_root);
         let root_dir = base_dir.join(&workspace_hash);
+        Self::from_root_dir(workspace_hash, root_dir)
+    }
+
+    /// Paths for an existing `<base_dir>/<workspace_hash>` directory, for
+    /// callers enumerating the data dir without knowing the workspace root.
+    pub(crate) fn from_root_dir(workspace_hash: String, root_dir: PathBuf) -> Self {
         Self {
             current_pointer_path: root_dir.join(CURRENT_POINTER_FILENAME),
+            stale_marker_path: root_dir.join(STALE_MARKER_FILENAME),
             snapshots_dir: root_dir.join(SNAPSHOTS_DIRNAME),
             workspace_hash,
             root_dir,
@@ -214,11 +281,31 @@ pub(in crate::graph) fn is_source_walk_excluded(path: &Path) -> bool {
     })
 }
 
+/// Digest-list key standing in for the `[rust_analyzer]` table of
+/// `.rmc.toml`; not a real path.
+pub(crate) const LOAD_CONFIG_DIGEST_KEY: &str = ".rmc.toml#rust_analyzer";
+
 /// Hash inputs that determine whether a snapshot is still current.
 /// For v1: Cargo.toml + Cargo.lock + every `.rs` file under the workspace,
-/// excluding generated/tooling directories. Kept simple — change detection is
-/// a rebuild trigger, nothing more.
+/// excluding generated/tooling directories, plus the workspace's
+/// non-default [`WorkspaceLoadConfig`]. Kept simple — change detection is
+/// a rebuild trigger, nothing more. Builds go through
+/// `compute_file_digests` + `fingerprint_from_digests` so the digests can be
+/// persisted alongside.
+#[cfg(test)]
 pub(crate) fn compute_fingerprint(workspace_root: &Path) -> Result<String> {
+    let load_config = WorkspaceLoadConfig::load(workspace_root)?;
+    Ok(fingerprint_from_digests(&compute_file_digests(workspace_root, &load_config)?))
+}
+
+/// Per-file sha256 of every fingerprint input, as (workspace-relative path,
+/// digest) pairs sorted by path. v14 persists these so the next build can
+/// tell which files changed. `load_config` must be the one the build loads
+/// the workspace with, so the fingerprint matches what was extracted.
+pub(crate) fn compute_file_digests(
+    workspace_root: &Path,
+    load_config: &WorkspaceLoadConfig,
+) -> Result<Vec<(String, [u8; 32])>> {
     let mut entries: Vec<(String, [u8; 32])> = Vec::new();
 
     for entry in WalkDir::new(workspace_root)
@@ -236,10 +323,7 @@ pub(crate) fn compute_fingerprint(workspace_root: &Path) -> Result<String> {
             .and_then(|s| s.to_str())
             .map(|ext| ext == "rs")
             .unwrap_or(false)
-            || matches!(
-                path.file_name().and_then(|s| s.to_str()),
-                Some("Cargo.toml") | Some("Cargo.lock")
-            );
+            || is_manifest_file_name(path);
         if !interesting {
             continue;
         }
@@ -258,10 +342,20 @@ pub(crate) fn compute_fingerprint(workspace_root: &Path) -> Result<String> {
         entries.push((rel, digest));
     }
 
+    // Load settings reshape the crate graph like a manifest edit does. The
+    // default load adds no entry, so unconfigured fingerprints are unchanged.
+    if !load_config.is_default() {
+        entries.push((LOAD_CONFIG_DIGEST_KEY.to_string(), load_config.digest()));
+    }
+
     entries.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(entries)
+}
 
+/// Fold sorted per-file digests into the workspace fingerprint.
+pub(crate) fn fingerprint_from_digests(entries: &[(String, [u8; 32])]) -> String {
     let mut h = Sha256::new();
-    for (rel, digest) in &entries {
+    for (rel, digest) in entries {
         h.update(rel.as_bytes());
         h.update(&[0]);
         h.update(digest);
@@ -272,7 +366,17 @@ pub(crate) fn compute_fingerprint(workspace_root: &Path) -> Result<String> {
     for byte in final_digest {
         hex.push_str(&format!("{byte:02x}"));
     }
-    Ok(hex)
+    hex
+}
+
+/// `Cargo.toml` / `Cargo.lock`: an edit to either can reshape the crate
+/// graph, so incremental rebuilds treat it as "rebuild everything". So does
+/// a change to the load settings, recorded under [`LOAD_CONFIG_DIGEST_KEY`].
+pub(crate) fn is_manifest_file_name(path: &Path) -> bool {
+    matches!(
+        path.file_name().and_then(|s| s.to_str()),
+        Some("Cargo.toml") | Some("Cargo.lock") | Some(LOAD_CONFIG_DIGEST_KEY)
+    )
 }
 
 pub(crate) fn graph_id_for(workspace_hash: &str, fingerprint: &str) -> String {
@@ -308,11 +412,28 @@ pub struct GraphDatabases {
     /// v10: NodeId (target static) → StaticMetadata. NOT DUP_SORT — one
     /// record per local `static` item.
     pub static_metadata_by_target: Database<Bytes, SerdeBincode<StaticMetadata>>,
+    /// v16: NodeId (target field) → FieldMetadata. NOT DUP_SORT — one
+    /// record per local field.
+    pub field_metadata_by_target: Database<Bytes, SerdeBincode<FieldMetadata>>,
+    /// v17: NodeId (target macro) → MacroMetadata. NOT DUP_SORT — one
+    /// record per local macro.
+    pub macro_metadata_by_target: Database<Bytes, SerdeBincode<MacroMetadata>>,
+    /// v18: NodeId (target fn) → ComplexityMetrics. NOT DUP_SORT — one
+    /// record per local fn with a body.
+    pub complexity_by_target: Database<Bytes, SerdeBincode<ComplexityMetrics>>,
+    /// v13: ImplId → TraitImpl. One record per `impl Trait for Type` block
+    /// declared in a local crate.
+    pub impls_by_id: Database<Bytes, SerdeBincode<TraitImpl>>,
+    pub impls_by_trait: Database<Bytes, Bytes>, // NodeId → ImplId, DUP_SORT
+    pub impls_by_self_type: Database<Bytes, Bytes>, // NodeId → ImplId, DUP_SORT
     /// v11: lazy-populated cache for `semantic_overlaps`. NodeId →
     /// EmbeddingRecord. NOT DUP_SORT — one record per Item. Empty after
     /// `build_hypergraph`; `semantic_overlaps` writes entries on first use
     /// and reuses them on subsequent scans of unchanged items.
     pub embeddings_by_target: Database<Bytes, SerdeBincode<EmbeddingRecord>>,
+    /// v14: workspace-relative path → 32-byte sha256 of the file contents,
+    /// one entry per fingerprint input. Backs incremental rebuilds.
+    pub file_digests_by_path: Database<Str, Bytes>,
 }
 
 impl GraphDatabases {
@@ -365,12 +486,44 @@ impl GraphDatabases {
                 "static_metadata_by_target",
                 false,
             )?,
+            field_metadata_by_target: open_or_create_bytes_bincode(
+                env,
+                wtxn,
+                "field_metadata_by_target",
+                false,
+            )?,
+            macro_metadata_by_target: open_or_create_bytes_bincode(
+                env,
+                wtxn,
+                "macro_metadata_by_target",
+                false,
+            )?,
+            complexity_by_target: open_or_create_bytes_bincode(
+                env,
+                wtxn,
+                "complexity_by_target",
+                false,
+            )?,
+            impls_by_id: open_or_create_bytes_bincode(env, wtxn, "impls_by_id", false)?,
+            impls_by_trait: open_or_create_bytes_bytes(env, wtxn, "impls_by_trait", true)?,
+            impls_by_self_type: open_or_create_bytes_bytes(
+                env,
+                wtxn,
+                "impls_by_self_type",
+                true,
+            )?,
             embeddings_by_target: open_or_create_bytes_bincode(
                 env,
                 wtxn,
                 "embeddings_by_target",
                 false,
             )?,
+            file_digests_by_path: open_or_create_str_bytes(
+                env,
+                wtxn,
+                "file_digests_by_path",
+                false,
+            )?,
         })
     }
 
@@ -413,9 +566,30 @@ impl GraphDatabases {
             static_metadata_by_target: env
                 .open_database(rtxn, Some("static_metadata_by_target"))?
                 .context("static_metadata_by_target missing")?,
+            field_metadata_by_target: env
+                .open_database(rtxn, Some("field_metadata_by_target"))?
+                .context("field_metadata_by_target missing")?,
+            macro_metadata_by_target: env
+                .open_database(rtxn, Some("macro_metadata_by_target"))?
+                .context("macro_metadata_by_target missing")?,
+            complexity_by_target: env
+                .open_database(rtxn, Some("complexity_by_target"))?
+                .context("complexity_by_target missing")?,
+            impls_by_id: env
+                .open_database(rtxn, Some("impls_by_id"))?
+                .context("impls_by_id missing")?,
+            impls_by_trait: env
+                .open_database(rtxn, Some("impls_by_trait"))?
+                .context("impls_by_trait missing")?,
+            impls_by_self_type: env
+                .open_database(rtxn, Some("impls_by_self_type"))?
+                .context("impls_by_self_type missing")?,
             embeddings_by_target: env
                 .open_database(rtxn, Some("embeddings_by_target"))?
                 .context("embeddings_by_target missing")?,
+            file_digests_by_path: env
+                .open_database(rtxn, Some("file_digests_by_path"))?
+                .context("file_digests_by_path missing")?,
         }))
     }
 }
@@ -657,6 +831,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn fingerprint_changes_with_rust_analyzer_load_config_only() {
+        let td = tempfile::tempdir().unwrap();
+        let root = td.path();
+        write_minimal_crate(root);
+        let before = compute_fingerprint(root).unwrap();
+
+        // `[index]` rules do not affect the RA load.
+        fs::write(root.join(".rmc.toml"), "[index]\nexclude = [\"tests/**\"]\n").unwrap();
+        assert_eq!(before, compute_fingerprint(root).unwrap());
+
+        fs::write(
+            root.join(".rmc.toml"),
+            "[rust_analyzer]\ncfg = [\"tokio_unstable\"]\n",
+        )
+        .unwrap();
+        let with_cfg = compute_fingerprint(root).unwrap();
+        assert_ne!(before, with_cfg, "load settings must flip the fingerprint");
+
+        fs::write(
+            root.join(".rmc.toml"),
+            "[rust_analyzer]\ntarget = \"wasm32-unknown-unknown\"\n",
+        )
+        .unwrap();
+        assert_ne!(with_cfg, compute_fingerprint(root).unwrap());
+    }
+
     #[test]
     fn fingerprint_stable_when_only_path_metadata_changes() {
         // Sanity: re-running with no edits returns the same hash. Sentinel for
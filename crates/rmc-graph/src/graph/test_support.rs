@@ -3,14 +3,22 @@
 //! Moved from `queries.rs::tests::shared_snapshot` in PR 11 so the four
 //! sibling test modules (attributes, signatures, unsafe_audit, statics)
 //! can keep importing it after `queries.rs` is reduced to a facade. Tests
-//! living inside `queries.rs::tests` also call back here.
+//! living inside `queries.rs::tests` also call back here. `ModelFixture`
+//! builds small hand-written snapshots for the two-snapshot comparisons.
 
 #![cfg(test)]
 
 use std::path::Path;
 use std::sync::OnceLock;
 
-use super::snapshot::{BuildOptions, OpenedSnapshot, build_and_persist, open_current};
+use super::ids::NodeId;
+use super::model::{
+    Binding, BindingKind, BindingVisibility, ComplexityMetrics, ExtractionModel,
+    FunctionSignature, ItemKind, Namespace, Node, NodeKind, Param, Usage, UsageCategory,
+};
+use super::snapshot::{
+    BuildOptions, OpenedSnapshot, build_and_persist, open_current, persist_test_model,
+};
 use super::storage::{GraphEnvOptions, GraphPaths};
 
 // Build the snapshot once and share across all tests in this module.
@@ -40,3 +48,209 @@ pub(crate) fn shared_snapshot() -> &'static OpenedSnapshot {
         })
         .snap
 }
+
+/// Hand-built single-crate model (crate `fix`) for queries that compare two
+/// snapshots. Node ids mix in the fixture name, so two fixtures never share
+/// ids — just like two worktrees of one repository.
+pub(crate) struct ModelFixture {
+    name: &'static str,
+    crate_id: NodeId,
+    module_id: NodeId,
+    nodes: Vec<Node>,
+    bindings: Vec<Binding>,
+    usages: Vec<Usage>,
+    signatures: Vec<(NodeId, FunctionSignature)>,
+    complexity: Vec<(NodeId, ComplexityMetrics)>,
+}
+
+impl ModelFixture {
+    pub(crate) fn new(name: &'static str) -> Self {
+        let krate = fixture_node(name, "fix", NodeKind::Crate, None, None, "src/lib.rs");
+        let root = fixture_node(name, "fix", NodeKind::Module, None, Some(krate.id), "src/lib.rs");
+        Self {
+            name,
+            crate_id: krate.id,
+            module_id: root.id,
+            nodes: vec![krate, root],
+            bindings: Vec::new(),
+            usages: Vec::new(),
+            signatures: Vec::new(),
+            complexity: Vec::new(),
+        }
+    }
+
+    pub(crate) fn crate_id(&self) -> NodeId {
+        self.crate_id
+    }
+
+    /// Item declared in the crate root module.
+    pub(crate) fn item(&mut self, qualified_name: &str, kind: ItemKind, file: &str) -> NodeId {
+        self.member(self.module_id, qualified_name, kind, file)
+    }
+
+    /// Item whose parent is `parent` (a module, or the host Item of a
+    /// method / variant).
+    pub(crate) fn member(
+        &mut self,
+        parent: NodeId,
+        qualified_name: &str,
+        kind: ItemKind,
+        file: &str,
+    ) -> NodeId {
+        let node = fixture_node(
+            self.name,
+            qualified_name,
+            NodeKind::Item,
+            Some(kind),
+            Some(parent),
+            file,
+        );
+        let id = node.id;
+        self.nodes.push(node);
+        id
+    }
+
+    /// Like [`Self::member`], declared at `span` of `file`; the id mixes in
+    /// the declaration site, so one name can be declared more than once.
+    pub(crate) fn member_at(
+        &mut self,
+        parent: NodeId,
+        qualified_name: &str,
+        kind: ItemKind,
+        file: &str,
+        span: (u32, u32),
+    ) -> NodeId {
+        let mut node = fixture_node(
+            self.name,
+            qualified_name,
+            NodeKind::Item,
+            Some(kind),
+            Some(parent),
+            file,
+        );
+        node.id = NodeId::from_components(&[
+            self.name,
+            qualified_name,
+            &format!("{kind:?}"),
+            file,
+            &span.0.to_string(),
+            &span.1.to_string(),
+        ]);
+        node.span = Some(span);
+        let id = node.id;
+        self.nodes.push(node);
+        id
+    }
+
+    pub(crate) fn attribute(&mut self, target: NodeId, attribute: &str) {
+        if let Some(node) = self.nodes.iter_mut().find(|node| node.id == target) {
+            node.attributes.push(attribute.to_string());
+        }
+    }
+
+    /// Visibility recorded on the Node itself, as fields and inherent-impl
+    /// items carry it
+    pub(crate) fn visibility(&mut self, target: NodeId, visibility: &str) {
+        if let Some(node) = self.nodes.iter_mut().find(|node| node.id == target) {
+            node.visibility = Some(visibility.to_string());
+        }
+    }
+
+    pub(crate) fn declare(&mut self, target: NodeId, visibility: BindingVisibility) {
+        self.bindings.push(Binding {
+            from_module: self.module_id,
+            namespace: Namespace::Value,
+            visible_name: String::new(),
+            target,
+            kind: BindingKind::Declared,
+            visibility,
+            is_explicit_pub_use: false,
+        });
+    }
+
+    pub(crate) fn signature(&mut self, target: NodeId, signature: FunctionSignature) {
+        self.signatures.push((target, signature));
+    }
+
+    pub(crate) fn complexity(&mut self, target: NodeId, metrics: ComplexityMetrics) {
+        self.complexity.push((target, metrics));
+    }
+
+    pub(crate) fn call(&mut self, caller: NodeId, callee: NodeId) {
+        self.usages.push(Usage {
+            target: callee,
+            consumer_module: self.module_id,
+            file: "src/lib.rs".to_string(),
+            start: 0,
+            end: 1,
+            category: UsageCategory::Read,
+            consumer_function: Some(caller),
+        });
+    }
+
+    /// Persist under `data_dir`; the returned tempdir is the workspace root
+    /// and must outlive the snapshot.
+    pub(crate) fn persist(self, data_dir: &Path) -> (tempfile::TempDir, OpenedSnapshot) {
+        let workspace = tempfile::tempdir().unwrap();
+        let model = ExtractionModel {
+            workspace_root: workspace.path().to_path_buf(),
+            workspace_hash: format!("model-fixture-{}", self.name),
+            workspace_id: NodeId::from_components(&[self.name, "workspace"]),
+            nodes: self.nodes.into_iter().map(|node| (node.id, node)).collect(),
+            bindings: self.bindings,
+            usages: self.usages,
+            contains: Vec::new(),
+            signatures: self.signatures,
+            statics: Vec::new(),
+            fields: Vec::new(),
+            macros: Vec::new(),
+            complexity: self.complexity,
+            trait_impls: Vec::new(),
+        };
+        let snap = persist_test_model(data_dir, &model, GraphEnvOptions::default())
+            .expect("persist model fixture");
+        (workspace, snap)
+    }
+}
+
+fn fixture_node(
+    fixture: &str,
+    qualified_name: &str,
+    kind: NodeKind,
+    item_kind: Option<ItemKind>,
+    parent_id: Option<NodeId>,
+    file: &str,
+) -> Node {
+    let display_name = qualified_name.rsplit("::").next().unwrap_or(qualified_name);
+    Node {
+        id: NodeId::from_components(&[fixture, qualified_name, &format!("{kind:?}")]),
+        kind,
+        display_name: display_name.to_string(),
+        qualified_name: qualified_name.to_string(),
+        crate_id: Some(NodeId::from_components(&[fixture, "fix", "Crate"])),
+        parent_id,
+        item_kind,
+        file: Some(file.to_string()),
+        span: None,
+        visibility: None,
+        attributes: Vec::new(),
+        crate_target_kind: None,
+    }
+}
+
+/// `fn <name>(id: <param_ty>)` signature with a body.
+pub(crate) fn fn_signature(param_ty: &str) -> FunctionSignature {
+    FunctionSignature {
+        is_async: false,
+        self_param: None,
+        params: vec![Param {
+            name: "id".to_string(),
+            ty: param_ty.to_string(),
+            by_ref: false,
+            mutability: false,
+        }],
+        return_type: String::new(),
+        generics: Vec::new(),
+        has_body: true,
+    }
+}
@@ -13,7 +13,9 @@
 //! the per-block containing_function lookup).
 //!
 //! Scope mirrors impls.rs: only iterate local crates (workspace members).
-//! Files outside workspace_root are skipped defensively.
+//! Files outside workspace_root are skipped defensively, and so are files
+//! the project's ignore rules exclude (`.gitignore`, `.rmcignore`,
+//! `.rmc.toml`).
 
 use std::collections::HashSet;
 
@@ -22,9 +24,10 @@ use ra_ap_hir::{Semantics, attach_db};
 use ra_ap_hir_def::nameres::crate_def_map;
 use ra_ap_syntax::ast::{self, AstNode};
 use ra_ap_vfs::FileId;
+use rmc_config::ignore_engine::IgnoreEngine;
 use serde::{Deserialize, Serialize};
 
-use super::audit_util::resolve_workspace_relative;
+use super::audit_util::resolve_audited_file;
 use super::ids::NodeId;
 use super::loader::LoadedWorkspace;
 use super::snapshot::OpenedSnapshot;
@@ -54,6 +57,7 @@ pub(crate) fn unsafe_audit_impl(
     let workspace_root = loaded.workspace_root.clone();
     let db = &loaded.db;
     let vfs = &loaded.vfs;
+    let ignores = IgnoreEngine::load(&workspace_root)?;
 
     let mut findings: Vec<UnsafeFinding> = Vec::new();
 
@@ -77,10 +81,10 @@ pub(crate) fn unsafe_audit_impl(
 
         // 2. For each file, parse and walk its AST.
         for file_id in file_ids {
-            let rel_path = match resolve_workspace_relative(vfs, file_id, &workspace_root) {
+            let rel_path = match resolve_audited_file(vfs, file_id, &workspace_root, &ignores) {
                 Some(p) => p,
                 None => {
-                    tracing::trace!(?file_id, "skipping file outside workspace_root");
+                    tracing::trace!(?file_id, "skipping file outside workspace_root or ignored");
                     continue;
                 }
             };
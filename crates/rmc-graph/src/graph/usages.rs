@@ -12,20 +12,29 @@
 //! crates). Reference sites in dep-crate files are filtered out by
 //! `module_node_for.get(consumer_module_id)` returning None.
 //!
+//! v16: fields are searched the same way from the `field_to_node` map built
+//! by `fields.rs`; `writes_through_field` widens rust-analyzer's Write
+//! category so `who_uses(category = Write)` answers "who mutates this field".
+//!
+//! v17: macros sit in `def_to_node` too, so `my_macro!(..)` and
+//! `#[derive(MyDerive)]` invocations are recorded like any other reference.
+//!
 //! Cost on coding-agent: ~1.3 ms / item, ~1.4 s total (1087 items, 5.2k refs).
 //! See `examples/spike_usages.rs` for the timing harness.
 
 use std::collections::HashMap;
 use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::path::Path;
 
-use ra_ap_hir::{Crate, ModuleDef, Semantics, attach_db};
+use ra_ap_hir::{Access, Crate, Field, ModuleDef, Semantics, attach_db};
 use ra_ap_hir_def::{ModuleDefId, ModuleId};
 use ra_ap_hir_def::nameres::crate_def_map;
 use ra_ap_ide::TryToNav;
 use ra_ap_ide_db::{EditionedFileId, RootDatabase};
 use ra_ap_ide_db::defs::Definition;
 use ra_ap_ide_db::search::{ReferenceCategory, SearchScope};
-use ra_ap_syntax::AstNode;
+use ra_ap_syntax::algo::find_node_at_range;
+use ra_ap_syntax::{AstNode, TextRange, ast};
 use ra_ap_vfs::Vfs;
 
 use super::audit_util::resolve_workspace_relative;
@@ -38,15 +47,46 @@ pub(crate) fn extract_usages(
     vfs: &Vfs,
     local_crates: &[Crate],
     def_to_node: &HashMap<ModuleDefId, NodeId>,
+    field_to_node: &HashMap<Field, NodeId>,
+    module_node_for: &HashMap<ModuleId, NodeId>,
+) {
+    extract_usages_scoped(
+        model,
+        db,
+        vfs,
+        local_crates,
+        def_to_node,
+        field_to_node,
+        module_node_for,
+        &|_| true,
+    );
+}
+
+/// Delta-rebuild variant of [`extract_usages`]: only targets accepted by
+/// `include_target` are searched, and only inside `scope_crates`' module
+/// files. `def_to_node` must still be the complete map — it also drives
+/// `consumer_function` attribution.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn extract_usages_scoped(
+    model: &mut ExtractionModel,
+    db: &RootDatabase,
+    vfs: &Vfs,
+    scope_crates: &[Crate],
+    def_to_node: &HashMap<ModuleDefId, NodeId>,
+    field_to_node: &HashMap<Field, NodeId>,
     module_node_for: &HashMap<ModuleId, NodeId>,
+    include_target: &dyn Fn(NodeId) -> bool,
 ) {
     let workspace_root = model.workspace_root.clone();
-    let usage_scope = usage_search_scope(db, local_crates);
+    let usage_scope = usage_search_scope(db, scope_crates);
 
     attach_db(db, || {
         let sema = Semantics::new(db);
 
         for (&def_id, &target_node_id) in def_to_node {
+            if !include_target(target_node_id) {
+                continue;
+            }
             // Only compute usages for local Items. Module / ExternalSymbol nodes
             // also live in `def_to_node` and must be skipped.
             let Some(node) = model.nodes.get(&target_node_id) else {
@@ -58,8 +98,9 @@ pub(crate) fn extract_usages(
             let target_qualified_name = node.qualified_name.clone();
 
             // Convert ModuleDefId → Definition. Skip variants we don't model
-            // as Items (Module, BuiltinType, Macro) — those were already
-            // filtered upstream in bindings.rs::process_entry.
+            // as Items (Module, BuiltinType) — those were already filtered
+            // upstream in bindings.rs::process_entry. Macros (v17) come from
+            // macros.rs.
             let def: Definition = match ModuleDef::from(def_id) {
                 ModuleDef::Function(f) => Definition::Function(f),
                 ModuleDef::Adt(a) => Definition::Adt(a),
@@ -67,6 +108,7 @@ pub(crate) fn extract_usages(
                 ModuleDef::TypeAlias(t) => Definition::TypeAlias(t),
                 ModuleDef::Const(c) => Definition::Const(c),
                 ModuleDef::Static(s) => Definition::Static(s),
+                ModuleDef::Macro(m) => Definition::Macro(m),
                 _ => continue,
             };
 
@@ -88,84 +130,144 @@ pub(crate) fn extract_usages(
                 }
             }
 
-            let results = match catch_unwind(AssertUnwindSafe(|| {
-                def.usages(&sema).in_scope(&usage_scope).all()
-            })) {
-                Ok(results) => results,
-                Err(_) => {
-                    tracing::warn!(
-                        target: "rmc_graph::usages",
-                        ?target_node_id,
-                        qualified_name = %target_qualified_name,
-                        "skipping usage extraction for item after rust-analyzer panic"
-                    );
-                    continue;
-                }
+            record_usages(
+                model,
+                &sema,
+                vfs,
+                &workspace_root,
+                &usage_scope,
+                def_to_node,
+                module_node_for,
+                def,
+                target_node_id,
+                &target_qualified_name,
+            );
+        }
+
+        // v16: field reads and writes. The field pass already recorded the
+        // declaration span, so there is nothing to backfill.
+        for (&field, &target_node_id) in field_to_node {
+            if !include_target(target_node_id) {
+                continue;
+            }
+            let Some(node) = model.nodes.get(&target_node_id) else {
+                continue;
+            };
+            let target_qualified_name = node.qualified_name.clone();
+            record_usages(
+                model,
+                &sema,
+                vfs,
+                &workspace_root,
+                &usage_scope,
+                def_to_node,
+                module_node_for,
+                Definition::Field(field),
+                target_node_id,
+                &target_qualified_name,
+            );
+        }
+    });
+}
+
+/// Run `Definition::usages` for one target and push a `Usage` per
+/// non-import reference inside `usage_scope`.
+#[allow(clippy::too_many_arguments)]
+fn record_usages(
+    model: &mut ExtractionModel,
+    sema: &Semantics<'_, RootDatabase>,
+    vfs: &Vfs,
+    workspace_root: &Path,
+    usage_scope: &SearchScope,
+    def_to_node: &HashMap<ModuleDefId, NodeId>,
+    module_node_for: &HashMap<ModuleId, NodeId>,
+    def: Definition,
+    target_node_id: NodeId,
+    target_qualified_name: &str,
+) {
+    let db = sema.db;
+    let results = match catch_unwind(AssertUnwindSafe(|| {
+        def.usages(sema).in_scope(usage_scope).all()
+    })) {
+        Ok(results) => results,
+        Err(_) => {
+            tracing::warn!(
+                target: "rmc_graph::usages",
+                ?target_node_id,
+                qualified_name = %target_qualified_name,
+                "skipping usage extraction for item after rust-analyzer panic"
+            );
+            return;
+        }
+    };
+    for (ed_file_id, refs) in &results.references {
+        let file_id = ed_file_id.file_id(db);
+        // Only retain refs in workspace-local files. Dep-crate files
+        // canonicalize outside `workspace_root` and produce None here.
+        let rel_path = match resolve_workspace_relative(vfs, file_id, workspace_root) {
+            Some(p) => p,
+            None => continue,
+        };
+        let source = sema.parse(*ed_file_id);
+        let syntax = source.syntax();
+        for r in refs {
+            if r.category.contains(ReferenceCategory::IMPORT) {
+                continue;
+            }
+            // Consumer-module attribution stays on the file-root scope
+            // path — pre-Layer-10 behaviour. This keeps refs surfaced
+            // through macro expansion (where the token's parent may
+            // not be a useful ancestor) intact.
+            let Some(scope) = sema.scope_at_offset(syntax, r.range.start()) else {
+                continue;
             };
-            for (ed_file_id, refs) in &results.references {
-                let file_id = ed_file_id.file_id(db);
-                // Only retain refs in workspace-local files. Dep-crate files
-                // canonicalize outside `workspace_root` and produce None here.
-                let rel_path =
-                    match resolve_workspace_relative(vfs, file_id, &workspace_root) {
-                        Some(p) => p,
-                        None => continue,
-                    };
-                let source = sema.parse(*ed_file_id);
-                let syntax = source.syntax();
-                for r in refs {
-                    if r.category.contains(ReferenceCategory::IMPORT) {
-                        continue;
+            let consumer_module: ModuleId = scope.module().into();
+            let Some(&consumer_node_id) = module_node_for.get(&consumer_module) else {
+                continue;
+            };
+            // Layer 10 — call-graph attribution. `scope_at_offset`
+            // walks ancestors of the given node to find the
+            // containing definition; the file-root scope above gives
+            // a *module*-level resolver where `containing_function`
+            // is always `None`. Re-do the lookup with the token's
+            // parent so the resolver drills into the enclosing body.
+            // Closures attribute to their parent fn; refs in const
+            // initializers / trait bounds / enum discriminants give
+            // `None` (no enclosing fn).
+            let body_scope_node: Option<ra_ap_syntax::SyntaxNode> =
+                match syntax.token_at_offset(r.range.start()) {
+                    ra_ap_syntax::TokenAtOffset::None => None,
+                    ra_ap_syntax::TokenAtOffset::Single(t) => t.parent(),
+                    ra_ap_syntax::TokenAtOffset::Between(a, b) => {
+                        b.parent().or_else(|| a.parent())
                     }
-                    // Consumer-module attribution stays on the file-root scope
-                    // path — pre-Layer-10 behaviour. This keeps refs surfaced
-                    // through macro expansion (where the token's parent may
-                    // not be a useful ancestor) intact.
-                    let Some(scope) = sema.scope_at_offset(syntax, r.range.start()) else {
-                        continue;
-                    };
-                    let consumer_module: ModuleId = scope.module().into();
-                    let Some(&consumer_node_id) = module_node_for.get(&consumer_module) else {
-                        continue;
-                    };
-                    // Layer 10 — call-graph attribution. `scope_at_offset`
-                    // walks ancestors of the given node to find the
-                    // containing definition; the file-root scope above gives
-                    // a *module*-level resolver where `containing_function`
-                    // is always `None`. Re-do the lookup with the token's
-                    // parent so the resolver drills into the enclosing body.
-                    // Closures attribute to their parent fn; refs in const
-                    // initializers / trait bounds / enum discriminants give
-                    // `None` (no enclosing fn).
-                    let body_scope_node: Option<ra_ap_syntax::SyntaxNode> =
-                        match syntax.token_at_offset(r.range.start()) {
-                            ra_ap_syntax::TokenAtOffset::None => None,
-                            ra_ap_syntax::TokenAtOffset::Single(t) => t.parent(),
-                            ra_ap_syntax::TokenAtOffset::Between(a, b) => {
-                                b.parent().or_else(|| a.parent())
-                            }
-                        };
-                    let consumer_function = body_scope_node
-                        .as_ref()
-                        .and_then(|n| sema.scope_at_offset(n, r.range.start()))
-                        .and_then(|s| s.containing_function())
-                        .and_then(|f| {
-                            let id = ra_ap_hir_def::FunctionId::try_from(f).ok()?;
-                            def_to_node.get(&ModuleDefId::FunctionId(id)).copied()
-                        });
-                    model.usages.push(Usage {
-                        target: target_node_id,
-                        consumer_module: consumer_node_id,
-                        file: rel_path.clone(),
-                        start: u32::from(r.range.start()),
-                        end: u32::from(r.range.end()),
-                        category: classify_category(r.category),
-                        consumer_function,
-                    });
-                }
+                };
+            let consumer_function = body_scope_node
+                .as_ref()
+                .and_then(|n| sema.scope_at_offset(n, r.range.start()))
+                .and_then(|s| s.containing_function())
+                .and_then(|f| {
+                    let id = ra_ap_hir_def::FunctionId::try_from(f).ok()?;
+                    def_to_node.get(&ModuleDefId::FunctionId(id)).copied()
+                });
+            let mut category = classify_category(r.category);
+            if matches!(def, Definition::Field(_))
+                && category != UsageCategory::Write
+                && writes_through_field(sema, syntax, r.range)
+            {
+                category = UsageCategory::Write;
             }
+            model.usages.push(Usage {
+                target: target_node_id,
+                consumer_module: consumer_node_id,
+                file: rel_path.clone(),
+                start: u32::from(r.range.start()),
+                end: u32::from(r.range.end()),
+                category,
+                consumer_function,
+            });
         }
-    });
+    }
 }
 
 fn usage_search_scope(db: &RootDatabase, local_crates: &[Crate]) -> SearchScope {
@@ -187,6 +289,63 @@ fn usage_search_scope(db: &RootDatabase, local_crates: &[Crate]) -> SearchScope
     SearchScope::files(&files)
 }
 
+/// rust-analyzer only marks a field reference WRITE when it ends an
+/// assignment's left-hand side. "Who mutates this field" also has to count
+/// a struct-literal initializer, a `&mut` borrow, a `&mut self` method call
+/// on the field, and assignments to a sub-place (`x.f.len = 0`,
+/// `x.f[i] += 1`). References inside macro calls have no `NameRef` in the
+/// file's syntax tree and keep rust-analyzer's category.
+fn writes_through_field(
+    sema: &Semantics<'_, RootDatabase>,
+    syntax: &ra_ap_syntax::SyntaxNode,
+    range: TextRange,
+) -> bool {
+    let Some(name_ref) = find_node_at_range::<ast::NameRef>(syntax, range) else {
+        return false;
+    };
+    if ast::RecordExprField::for_field_name(&name_ref).is_some() {
+        return true;
+    }
+    let Some(field_expr) = name_ref.syntax().parent().and_then(ast::FieldExpr::cast) else {
+        return false;
+    };
+    // Climb to the outermost place expression built on this field.
+    let mut place = ast::Expr::from(field_expr);
+    while let Some(parent) = place.syntax().parent() {
+        let outer = if let Some(outer) = ast::FieldExpr::cast(parent.clone())
+            && outer.expr().as_ref() == Some(&place)
+        {
+            ast::Expr::from(outer)
+        } else if let Some(outer) = ast::IndexExpr::cast(parent.clone())
+            && outer.base().as_ref() == Some(&place)
+        {
+            ast::Expr::from(outer)
+        } else if let Some(outer) = ast::ParenExpr::cast(parent) {
+            ast::Expr::from(outer)
+        } else {
+            break;
+        };
+        place = outer;
+    }
+    let Some(parent) = place.syntax().parent() else {
+        return false;
+    };
+    if let Some(bin) = ast::BinExpr::cast(parent.clone()) {
+        matches!(bin.op_kind(), Some(ast::BinaryOp::Assignment { .. }))
+            && bin.lhs().as_ref() == Some(&place)
+    } else if let Some(reference) = ast::RefExpr::cast(parent.clone()) {
+        reference.mut_token().is_some()
+    } else if let Some(call) = ast::MethodCallExpr::cast(parent) {
+        call.receiver().as_ref() == Some(&place)
+            && sema
+                .resolve_method_call(&call)
+                .and_then(|method| method.self_param(sema.db))
+                .is_some_and(|self_param| matches!(self_param.access(sema.db), Access::Exclusive))
+    } else {
+        false
+    }
+}
+
 fn classify_category(c: ReferenceCategory) -> UsageCategory {
     // ReferenceCategory is bitflags. We've already stripped IMPORT before
     // reaching here. Order of preference: Write > Read > Test > Other.
@@ -218,7 +377,8 @@ mod tests {
     //! These tests load a *real* cargo workspace through rust-analyzer, so
     //! they pay the full RA load cost on first call (~3-5s release).
     //! Subsequent tests reuse the cached snapshot.
-    use crate::graph::model::{NodeKind, Usage};
+    use crate::graph::ids::NodeId;
+    use crate::graph::model::{ItemKind, NodeKind, Usage, UsageCategory};
     use crate::graph::snapshot::{BuildOptions, OpenedSnapshot, build_and_persist, open_current};
     use crate::graph::storage::{GraphEnvOptions, GraphPaths};
     use std::sync::OnceLock;
@@ -279,6 +439,67 @@ pub fn caller() {
     Foo::bar();
     let _ = read_const();
 }
+
+pub struct Counter {
+    pub hits: u32,
+    pub names: Vec<String>,
+}
+
+pub fn new_counter() -> Counter {
+    Counter { hits: 0, names: Vec::new() }
+}
+
+pub fn bump(c: &mut Counter) {
+    c.hits += 1;
+    c.names.push(String::new());
+}
+
+pub fn peek(c: &Counter) -> u32 {
+    c.hits
+}
+
+pub mod helpers {
+    #[macro_export]
+    macro_rules! square {
+        ($x:expr) => {
+            $x * $x
+        };
+    }
+
+    macro_rules! twice {
+        ($x:expr) => {
+            $x + $x
+        };
+    }
+
+    pub fn doubled(x: u32) -> u32 {
+        twice!(x)
+    }
+
+    macro_rules! pick {
+        () => {
+            1
+        };
+    }
+
+    pub fn first_pick() -> u32 {
+        pick!()
+    }
+
+    macro_rules! pick {
+        () => {
+            2
+        };
+    }
+
+    pub fn second_pick() -> u32 {
+        pick!()
+    }
+}
+
+pub fn squared(x: u32) -> u32 {
+    square!(x)
+}
 "#;
 
     // The empty `[workspace]` table makes this manifest a self-contained
@@ -384,6 +605,12 @@ path = "src/lib.rs"
         for u in &usages {
             assert!(u.file.contains("lib.rs"), "usage file should be lib.rs, got {}", u.file);
         }
+        // v19: inherent methods record their own modifier.
+        let (_, bar) = snap
+            .lookup_by_qualified_name("synthetic_crate::Foo::bar")
+            .unwrap()
+            .unwrap();
+        assert_eq!(bar.visibility.as_deref(), Some("pub"));
     }
 
     /// Pattern 2 — trait dispatch: `x.method()` where `x: T, T: Trait`.
@@ -404,6 +631,12 @@ path = "src/lib.rs"
         for u in &usages {
             assert!(u.file.contains("lib.rs"), "usage file should be lib.rs, got {}", u.file);
         }
+        // Trait members are as visible as the trait; nothing to record.
+        let (_, method) = snap
+            .lookup_by_qualified_name("synthetic_crate::Trait::method")
+            .unwrap()
+            .unwrap();
+        assert_eq!(method.visibility, None);
     }
 
     /// Pattern 3 — generic bound: `fn f<U: Bound>()` referencing a trait
@@ -508,4 +741,106 @@ path = "src/lib.rs"
             "expected >=1 compute() usage with consumer_function=None (the const K2 initializer), got 0"
         );
     }
+
+    /// Pattern 9 — v16 field usages. `c.hits += 1` is a write straight from
+    /// rust-analyzer; the struct-literal initializer and the `&mut self`
+    /// call `c.names.push(..)` are widened to writes by
+    /// `writes_through_field`; `c.hits` in `peek` stays a read.
+    #[test]
+    fn pattern9_field_reads_and_writes_are_classified() {
+        let snap = synthetic_snapshot();
+        let categories_in = |usages: &[Usage], function: &str| -> Vec<UsageCategory> {
+            let (fn_id, _) = snap
+                .lookup_by_qualified_name(&format!("synthetic_crate::{function}"))
+                .unwrap()
+                .unwrap_or_else(|| panic!("`{function}` not in graph"));
+            usages
+                .iter()
+                .filter(|u| u.consumer_function == Some(fn_id))
+                .map(|u| u.category)
+                .collect()
+        };
+
+        let hits = usages_for(snap, "synthetic_crate::Counter.hits");
+        assert_eq!(categories_in(&hits, "bump"), vec![UsageCategory::Write]);
+        assert_eq!(categories_in(&hits, "new_counter"), vec![UsageCategory::Write]);
+        assert_eq!(categories_in(&hits, "peek"), vec![UsageCategory::Read]);
+
+        let names = usages_for(snap, "synthetic_crate::Counter.names");
+        assert_eq!(categories_in(&names, "bump"), vec![UsageCategory::Write]);
+    }
+
+    /// Pattern 10 — macro invocation (v17). `square!` is `#[macro_export]`ed
+    /// from a submodule, so it is qualified from the crate root; `twice!`
+    /// stays textually scoped to `helpers`. Both invocations attribute to
+    /// the enclosing fn.
+    #[test]
+    fn pattern10_macro_invocations_captured() {
+        let snap = synthetic_snapshot();
+        for (macro_name, visibility, caller) in [
+            ("synthetic_crate::square", "pub", "synthetic_crate::squared"),
+            (
+                "synthetic_crate::helpers::twice",
+                "pub(crate)",
+                "synthetic_crate::helpers::doubled",
+            ),
+        ] {
+            let (_, node) = snap
+                .lookup_by_qualified_name(macro_name)
+                .unwrap()
+                .unwrap_or_else(|| panic!("`{macro_name}` not in graph"));
+            assert_eq!(node.item_kind, Some(ItemKind::Macro));
+            assert_eq!(node.visibility.as_deref(), Some(visibility));
+
+            let (caller_id, _) = snap
+                .lookup_by_qualified_name(caller)
+                .unwrap()
+                .unwrap_or_else(|| panic!("`{caller}` not in graph"));
+            let usages = usages_for(snap, macro_name);
+            assert!(
+                usages.iter().any(|u| u.consumer_function == Some(caller_id)),
+                "expected an invocation of `{macro_name}` from `{caller}`, got {usages:?}"
+            );
+        }
+    }
+
+    /// Pattern 11 — shadowed `macro_rules!` (v17). Both `pick!` definitions
+    /// are Items, and each invocation resolves to the one in textual scope
+    /// at the call site.
+    #[test]
+    fn pattern11_shadowed_macro_rules_are_separate_items() {
+        let snap = synthetic_snapshot();
+        let rtxn = snap.read_txn().unwrap();
+        let mut picks: Vec<(NodeId, Option<(u32, u32)>)> = snap
+            .dbs
+            .nodes_by_id
+            .iter(&rtxn)
+            .unwrap()
+            .map(|entry| entry.unwrap())
+            .filter(|(_, node)| node.qualified_name == "synthetic_crate::helpers::pick")
+            .map(|(key, node)| {
+                let mut id = [0u8; 32];
+                id.copy_from_slice(key);
+                (NodeId(id), node.span)
+            })
+            .collect();
+        picks.sort_by_key(|&(_, span)| span);
+        assert_eq!(picks.len(), 2, "expected both `pick!` definitions, got {picks:?}");
+
+        for ((macro_id, _), caller) in picks.iter().zip([
+            "synthetic_crate::helpers::first_pick",
+            "synthetic_crate::helpers::second_pick",
+        ]) {
+            let (caller_id, _) = snap
+                .lookup_by_qualified_name(caller)
+                .unwrap()
+                .unwrap_or_else(|| panic!("`{caller}` not in graph"));
+            let usages = snap.usages_of(*macro_id).unwrap();
+            assert!(!usages.is_empty(), "no invocation of `pick!` at {macro_id:?}");
+            assert!(
+                usages.iter().all(|u| u.consumer_function == Some(caller_id)),
+                "expected `pick!` at {macro_id:?} invoked only from `{caller}`, got {usages:?}"
+            );
+        }
+    }
 }
@@ -3,11 +3,102 @@
 //!
 //! `monitoring` implements this trait for `BackupManager`; `indexing` only knows
 //! about the trait.
+//!
+//! A backup is an [`IndexCheckpoint`]: the Merkle snapshot and metadata cache
+//! as they were before an index run changed anything. Runs with nothing to
+//! change take no backup, and file-watcher runs reuse a recent one. A run
+//! that fails removes the chunks it added and is rolled back at once; a run
+//! that never finished (the process died) leaves its run marker set, and the
+//! next run rolls back before starting. Either way the restored snapshot and
+//! cache make the next run redo every file the interrupted one touched, so
+//! the stores converge again.
+
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use anyhow::Context;
+
+use crate::indexing::indexer_core::IndexerCore;
+
+/// Merkle snapshot and metadata cache of one project/profile at a point
+/// where they agreed with the stores
+pub struct IndexCheckpoint {
+    /// Raw snapshot file; `None` before the project was first indexed
+    pub(crate) snapshot: Option<Vec<u8>>,
+    /// Raw metadata-cache entries under the indexer's cache-key salt
+    pub(crate) metadata: Vec<(String, Vec<u8>)>,
+}
+
+impl IndexCheckpoint {
+    /// Capture the snapshot at `snapshot_path` and `core`'s metadata cache
+    pub(crate) fn capture(snapshot_path: &Path, core: &IndexerCore) -> anyhow::Result<Self> {
+        let snapshot = match std::fs::read(snapshot_path) {
+            Ok(bytes) => Some(bytes),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => {
+                return Err(e).context(format!(
+                    "Failed to read snapshot {}",
+                    snapshot_path.display()
+                ));
+            }
+        };
+        Ok(Self {
+            snapshot,
+            metadata: core.export_metadata()?,
+        })
+    }
+
+    /// Put the snapshot back at `snapshot_path` (or remove it) and replace
+    /// `core`'s metadata cache
+    pub(crate) fn apply(&self, snapshot_path: &Path, core: &IndexerCore) -> anyhow::Result<()> {
+        match &self.snapshot {
+            Some(bytes) => {
+                let tmp_path = snapshot_path.with_extension("snapshot.restore");
+                std::fs::write(&tmp_path, bytes)
+                    .context(format!("Failed to write {}", tmp_path.display()))?;
+                std::fs::rename(&tmp_path, snapshot_path)
+                    .context(format!("Failed to restore {}", snapshot_path.display()))?;
+            }
+            None => match std::fs::remove_file(snapshot_path) {
+                Ok(()) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => {
+                    return Err(e).context(format!(
+                        "Failed to remove snapshot {}",
+                        snapshot_path.display()
+                    ));
+                }
+            },
+        }
+        core.restore_metadata(&self.metadata)?;
+        Ok(())
+    }
+
+    /// Number of metadata-cache entries in the checkpoint
+    pub fn cached_files(&self) -> usize {
+        self.metadata.len()
+    }
+}
+
+/// Store of index checkpoints plus a marker for the run in progress
+pub trait Backup: Send + Sync {
+    /// Store `checkpoint` as the newest backup, rotating old ones out;
+    /// returns where it was written
+    fn create_backup(&self, checkpoint: &IndexCheckpoint) -> anyhow::Result<PathBuf>;
+
+    /// Load the backup named `id`, or the newest one when `id` is `None`;
+    /// `Ok(None)` when there are no backups
+    fn load_backup(&self, id: Option<&str>) -> anyhow::Result<Option<(String, IndexCheckpoint)>>;
+
+    /// When the newest backup was taken; `Ok(None)` when there are none
+    fn latest_backup_at(&self) -> anyhow::Result<Option<SystemTime>>;
 
-use std::path::PathBuf;
+    /// Mark an index run as started
+    fn begin_run(&self) -> anyhow::Result<()>;
 
-use crate::indexing::merkle::FileSystemMerkle;
+    /// Clear the mark set by [`Backup::begin_run`]
+    fn end_run(&self) -> anyhow::Result<()>;
 
-pub(crate) trait Backup {
-    fn create_backup(&self, merkle: &FileSystemMerkle) -> anyhow::Result<PathBuf>;
+    /// Whether a run began and never ended
+    fn interrupted_run(&self) -> bool;
 }
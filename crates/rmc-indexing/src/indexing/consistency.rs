@@ -1,31 +1,54 @@
 //! Consistency checker for validating and repairing index integrity
 //!
-//! Verifies that Tantivy and vector store indexes are in sync
+//! Verifies that Tantivy and vector store indexes are in sync: compares
+//! their chunks by ID, and both against the metadata cache and the files
+//! on disk. [`ConsistencyReport`] sorts the drift into files to re-index and
+//! files to drop; [`UnifiedIndexer::repair_consistency`] acts on it.
 
 use rmc_engine::chunker::ChunkId;
+use rmc_engine::embeddings::EmbeddingBackend;
 use anyhow::{Context, Result};
-use std::collections::HashSet;
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
 use tantivy::{Index, IndexReader};
 use tantivy::schema::Value;
 use rmc_engine::vector_store::VectorStore;
 use rmc_engine::schema::ChunkSchema;
 
+use crate::indexing::unified::UnifiedIndexer;
+use crate::indexing::vector_index::VectorIndexSettings;
+
 /// Results from a consistency check
-#[derive(Debug, Clone)]
-pub(crate) struct ConsistencyReport {
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ConsistencyReport {
     /// Number of chunks in Tantivy
-    pub(crate) tantivy_count: usize,
+    pub tantivy_count: usize,
     /// Number of chunks in vector store
-    pub(crate) vector_count: usize,
+    pub vector_count: usize,
+    /// Number of files in the metadata cache
+    pub cached_file_count: usize,
     /// Chunk IDs present in Tantivy but missing from vector store
-    pub(crate) missing_from_vectors: Vec<ChunkId>,
+    pub missing_from_vectors: Vec<ChunkId>,
     /// Chunk IDs present in vector store but missing from Tantivy
-    pub(crate) missing_from_tantivy: Vec<ChunkId>,
+    pub missing_from_tantivy: Vec<ChunkId>,
+    /// Files on disk whose chunks are only in one store
+    pub orphaned_files: Vec<PathBuf>,
+    /// Files on disk recorded in the metadata cache with no chunks in
+    /// either store
+    pub unindexed_files: Vec<PathBuf>,
+    /// Files no longer on disk that still have chunks or cache entries
+    pub deleted_files: Vec<PathBuf>,
     /// Whether the indexes are consistent
-    pub(crate) is_consistent: bool,
+    pub is_consistent: bool,
 }
 
 impl ConsistencyReport {
+    /// Files a repair re-indexes: orphaned and unindexed ones
+    pub fn files_to_reindex(&self) -> impl Iterator<Item = &PathBuf> {
+        self.orphaned_files.iter().chain(&self.unindexed_files)
+    }
+
     /// Log a human-readable summary.
     ///
     /// MCP stdio servers must keep stdout reserved for JSON-RPC frames.
@@ -38,16 +61,38 @@ impl ConsistencyReport {
         tracing::info!(
             tantivy_count = self.tantivy_count,
             vector_count = self.vector_count,
+            cached_file_count = self.cached_file_count,
             is_consistent = self.is_consistent,
             missing_from_vectors_count = self.missing_from_vectors.len(),
             missing_from_vectors_preview = ?missing_from_vectors_preview,
             missing_from_tantivy_count = self.missing_from_tantivy.len(),
             missing_from_tantivy_preview = ?missing_from_tantivy_preview,
+            orphaned_files = self.orphaned_files.len(),
+            unindexed_files = self.unindexed_files.len(),
+            deleted_files = self.deleted_files.len(),
             "Index consistency report"
         );
     }
 }
 
+/// A file the repair could not fix
+#[derive(Debug, Clone, Serialize)]
+pub struct RepairFailure {
+    pub file_path: PathBuf,
+    pub error: String,
+}
+
+/// What [`UnifiedIndexer::repair_consistency`] did
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RepairSummary {
+    /// Files whose chunks were rebuilt in both stores
+    pub reindexed_files: Vec<PathBuf>,
+    /// Files whose chunks and cache entries were dropped: deleted from
+    /// disk, or no longer indexable
+    pub removed_files: Vec<PathBuf>,
+    pub failed_files: Vec<RepairFailure>,
+}
+
 /// Consistency checker for index integrity
 pub(crate) struct ConsistencyChecker {
     tantivy_index: Index,
@@ -69,39 +114,37 @@ impl ConsistencyChecker {
         }
     }
 
-    /// Check consistency between Tantivy and vector store indexes
-    pub(crate) async fn check(&self) -> Result<ConsistencyReport> {
+    /// Check consistency between Tantivy, the vector store, the metadata
+    /// cache's `cached_files` and the file system
+    pub(crate) async fn check(&self, cached_files: &[PathBuf]) -> Result<ConsistencyReport> {
         tracing::info!("Starting consistency check...");
 
-        // Get all chunk IDs from Tantivy
-        let tantivy_ids = self.get_tantivy_chunk_ids()?;
-        tracing::info!("Found {} chunks in Tantivy", tantivy_ids.len());
+        let tantivy_chunks = self.get_tantivy_chunk_paths()?;
+        tracing::info!("Found {} chunks in Tantivy", tantivy_chunks.len());
 
-        // Get count from vector store
-        let vector_count = self.vector_store.count().await
-            .map_err(|e| anyhow::anyhow!("Failed to count vector store chunks: {}", e))?;
-        tracing::info!("Found {} chunks in vector store", vector_count);
+        let vector_chunks: HashMap<ChunkId, PathBuf> = self
+            .vector_store
+            .chunk_file_paths()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to list vector store chunks: {}", e))?
+            .into_iter()
+            .map(|(id, path)| (id, PathBuf::from(path)))
+            .collect();
+        tracing::info!("Found {} chunks in vector store", vector_chunks.len());
 
-        // For now, we can only check counts
-        // TODO: Implement full chunk ID verification
-        //       (requires adding a method to list all chunk IDs)
-        let is_consistent = tantivy_ids.len() == vector_count;
+        let report = build_report(&tantivy_chunks, &vector_chunks, cached_files, Path::exists);
+        report.print_summary();
 
-        let report = ConsistencyReport {
-            tantivy_count: tantivy_ids.len(),
-            vector_count,
-            missing_from_vectors: Vec::new(), // Would require vector store ID listing
-            missing_from_tantivy: Vec::new(),
-            is_consistent,
-        };
-
-        tracing::info!("Consistency check complete: {}", if is_consistent { "OK" } else { "FAILED" });
+        tracing::info!(
+            "Consistency check complete: {}",
+            if report.is_consistent { "OK" } else { "FAILED" }
+        );
 
         Ok(report)
     }
 
-    /// Get all chunk IDs from Tantivy index
-    fn get_tantivy_chunk_ids(&self) -> Result<HashSet<ChunkId>> {
+    /// Get every live chunk ID in the Tantivy index with its file path
+    fn get_tantivy_chunk_paths(&self) -> Result<HashMap<ChunkId, PathBuf>> {
         let reader: IndexReader = self.tantivy_index
             .reader_builder()
             .reload_policy(tantivy::ReloadPolicy::Manual)
@@ -109,42 +152,163 @@ impl ConsistencyChecker {
             .context("Failed to create Tantivy reader")?;
 
         let searcher = reader.searcher();
-        let mut chunk_ids = HashSet::new();
+        let mut chunks = HashMap::new();
 
-        // Iterate through all documents
+        // Deleted documents stay in their segment until it is merged
         for segment_reader in searcher.segment_readers() {
             let store_reader = segment_reader
                 .get_store_reader(0)
                 .context("Failed to get store reader")?;
 
-            for doc_id in 0..segment_reader.max_doc() {
-                if let Ok(doc) = store_reader.get::<tantivy::TantivyDocument>(doc_id) {
-                    // Extract chunk_id field
-                    if let Some(chunk_id_field) = doc.get_first(self.schema.chunk_id) {
-                        if let Some(chunk_id_str) = chunk_id_field.as_str() {
-                            if let Ok(chunk_id) = ChunkId::from_string(chunk_id_str) {
-                                chunk_ids.insert(chunk_id);
-                            }
-                        }
-                    }
+            for doc_id in segment_reader.doc_ids_alive() {
+                let doc = store_reader
+                    .get::<tantivy::TantivyDocument>(doc_id)
+                    .context("Failed to read Tantivy document")?;
+                let chunk_id = doc
+                    .get_first(self.schema.chunk_id)
+                    .and_then(|value| value.as_str())
+                    .and_then(|value| ChunkId::from_string(value).ok());
+                let file_path = doc
+                    .get_first(self.schema.file_path)
+                    .and_then(|value| value.as_str());
+                if let (Some(chunk_id), Some(file_path)) = (chunk_id, file_path) {
+                    chunks.insert(chunk_id, PathBuf::from(file_path));
                 }
             }
         }
 
-        Ok(chunk_ids)
+        Ok(chunks)
     }
+}
 
-    /// Repair inconsistencies by reindexing missing chunks
-    ///
-    /// This is a placeholder for future implementation
-    pub(crate) async fn repair(&self, _report: &ConsistencyReport) -> Result<()> {
-        // TODO: Implement repair logic
-        // - For chunks missing from vector store: re-embed and re-index
-        // - For chunks missing from Tantivy: re-index from source or remove from vector store
-        anyhow::bail!("Repair not yet implemented. Use force reindex instead.");
+/// Chunks of `chunks` that `other` lacks, ordered by file then ID
+fn only_in<'a>(
+    chunks: &'a HashMap<ChunkId, PathBuf>,
+    other: &HashMap<ChunkId, PathBuf>,
+) -> Vec<(&'a PathBuf, ChunkId)> {
+    let mut missing: Vec<(&PathBuf, ChunkId)> = chunks
+        .iter()
+        .filter(|(id, _)| !other.contains_key(id))
+        .map(|(id, path)| (path, *id))
+        .collect();
+    missing.sort_by_key(|(path, id)| ((*path).clone(), id.to_string()));
+    missing
+}
+
+/// Compare the chunks of both stores and the cached files; `exists` tells
+/// which paths are still on disk
+fn build_report(
+    tantivy_chunks: &HashMap<ChunkId, PathBuf>,
+    vector_chunks: &HashMap<ChunkId, PathBuf>,
+    cached_files: &[PathBuf],
+    exists: impl Fn(&Path) -> bool,
+) -> ConsistencyReport {
+    let missing_from_vectors = only_in(tantivy_chunks, vector_chunks);
+    let missing_from_tantivy = only_in(vector_chunks, tantivy_chunks);
+
+    let stored_files: BTreeSet<&PathBuf> =
+        tantivy_chunks.values().chain(vector_chunks.values()).collect();
+    let drifted_files: BTreeSet<&PathBuf> = missing_from_vectors
+        .iter()
+        .chain(&missing_from_tantivy)
+        .map(|(path, _)| *path)
+        .collect();
+    let cached: BTreeSet<&PathBuf> = cached_files.iter().collect();
+
+    let deleted_files: Vec<PathBuf> = stored_files
+        .union(&cached)
+        .filter(|path| !exists(path.as_path()))
+        .map(|path| (*path).clone())
+        .collect();
+    let orphaned_files: Vec<PathBuf> = drifted_files
+        .into_iter()
+        .filter(|path| exists(path.as_path()))
+        .cloned()
+        .collect();
+    let unindexed_files: Vec<PathBuf> = cached
+        .difference(&stored_files)
+        .filter(|path| exists(path.as_path()))
+        .map(|path| (*path).clone())
+        .collect();
+
+    let is_consistent = missing_from_vectors.is_empty()
+        && missing_from_tantivy.is_empty()
+        && unindexed_files.is_empty()
+        && deleted_files.is_empty();
+
+    ConsistencyReport {
+        tantivy_count: tantivy_chunks.len(),
+        vector_count: vector_chunks.len(),
+        cached_file_count: cached_files.len(),
+        missing_from_vectors: missing_from_vectors.into_iter().map(|(_, id)| id).collect(),
+        missing_from_tantivy: missing_from_tantivy.into_iter().map(|(_, id)| id).collect(),
+        orphaned_files,
+        unindexed_files,
+        deleted_files,
+        is_consistent,
     }
 }
 
+/// Request for a consistency check, optionally followed by a repair.
+pub struct VerifyIndexRequest<'a> {
+    /// Project root; its `.rmc.toml` picks the ANN index a repair maintains
+    pub codebase_path: &'a Path,
+    pub cache_path: &'a Path,
+    pub tantivy_path: &'a Path,
+    pub collection_name: &'a str,
+    pub backend: EmbeddingBackend,
+    pub embedder_identity: &'a str,
+    /// Re-index orphaned and unindexed files and drop deleted ones
+    pub repair: bool,
+}
+
+/// Result of [`verify_project_index`].
+#[derive(Debug, Clone, Serialize)]
+pub struct VerifyIndexOutcome {
+    /// State before any repair
+    pub report: ConsistencyReport,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repair: Option<RepairSummary>,
+    /// State after the repair, when one ran
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after_repair: Option<ConsistencyReport>,
+}
+
+/// Check a project's indexes for drift and optionally repair it.
+///
+/// A repair only runs when the check found something; the re-check after
+/// it shows what is left (typically files that failed to re-index).
+pub async fn verify_project_index(request: VerifyIndexRequest<'_>) -> Result<VerifyIndexOutcome> {
+    let mut indexer = UnifiedIndexer::for_embedded_with_backend(
+        request.cache_path,
+        request.tantivy_path,
+        request.collection_name,
+        request.backend.dim(),
+        request.embedder_identity,
+        None,
+        request.backend.clone(),
+    )
+    .await?
+    .with_ann_index(VectorIndexSettings::load(request.codebase_path)?.ann_index_config()?);
+
+    let report = indexer.check_consistency().await?;
+    if !request.repair || report.is_consistent {
+        return Ok(VerifyIndexOutcome {
+            report,
+            repair: None,
+            after_repair: None,
+        });
+    }
+
+    let repair = indexer.repair_consistency(&report).await?;
+    let after_repair = indexer.check_consistency().await?;
+    Ok(VerifyIndexOutcome {
+        report,
+        repair: Some(repair),
+        after_repair: Some(after_repair),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -154,9 +318,8 @@ mod tests {
         let report = ConsistencyReport {
             tantivy_count: 100,
             vector_count: 100,
-            missing_from_vectors: Vec::new(),
-            missing_from_tantivy: Vec::new(),
             is_consistent: true,
+            ..ConsistencyReport::default()
         };
 
         assert!(report.is_consistent);
@@ -170,11 +333,62 @@ mod tests {
             tantivy_count: 100,
             vector_count: 95,
             missing_from_vectors: vec![ChunkId::new()],
-            missing_from_tantivy: Vec::new(),
             is_consistent: false,
+            ..ConsistencyReport::default()
         };
 
         assert!(!report.is_consistent);
         assert_eq!(report.missing_from_vectors.len(), 1);
     }
+
+    #[test]
+    fn test_build_report_classifies_drift() {
+        let path = PathBuf::from;
+        let shared = ChunkId::new();
+        let tantivy_only = ChunkId::new();
+        let vector_only = ChunkId::new();
+        let stale = ChunkId::new();
+
+        let tantivy = HashMap::from([
+            (shared, path("src/lib.rs")),
+            (tantivy_only, path("src/a.rs")),
+            (stale, path("src/gone.rs")),
+        ]);
+        let vectors = HashMap::from([
+            (shared, path("src/lib.rs")),
+            (vector_only, path("src/b.rs")),
+            (stale, path("src/gone.rs")),
+        ]);
+        let cached = vec![
+            path("src/lib.rs"),
+            path("src/a.rs"),
+            path("src/cached_only.rs"),
+            path("src/removed.rs"),
+        ];
+        let on_disk = ["src/lib.rs", "src/a.rs", "src/b.rs", "src/cached_only.rs"];
+
+        let report = build_report(&tantivy, &vectors, &cached, |p| {
+            on_disk.iter().any(|f| Path::new(f) == p)
+        });
+
+        assert!(!report.is_consistent);
+        assert_eq!(report.tantivy_count, 3);
+        assert_eq!(report.cached_file_count, 4);
+        assert_eq!(report.missing_from_vectors, vec![tantivy_only]);
+        assert_eq!(report.missing_from_tantivy, vec![vector_only]);
+        assert_eq!(report.orphaned_files, vec![path("src/a.rs"), path("src/b.rs")]);
+        assert_eq!(report.unindexed_files, vec![path("src/cached_only.rs")]);
+        assert_eq!(report.deleted_files, vec![path("src/gone.rs"), path("src/removed.rs")]);
+        assert_eq!(report.files_to_reindex().count(), 3);
+    }
+
+    #[test]
+    fn test_build_report_consistent_when_stores_agree() {
+        let id = ChunkId::new();
+        let chunks = HashMap::from([(id, PathBuf::from("src/lib.rs"))]);
+        let report = build_report(&chunks, &chunks, &[PathBuf::from("src/lib.rs")], |_| true);
+
+        assert!(report.is_consistent);
+        assert!(report.orphaned_files.is_empty());
+    }
 }
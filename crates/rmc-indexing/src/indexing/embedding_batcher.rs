@@ -3,13 +3,15 @@
 //! Extracted from `IndexerCore` to encapsulate embedding pipeline concerns:
 //! GPU-optimized batch embedding generation and memory-aware batch sizing.
 
-use rmc_engine::chunker::CodeChunk;
+use rmc_engine::chunker::{ChunkId, CodeChunk};
 use rmc_engine::embeddings::batching::{BatchPlan as EmbeddingBatchPlan, plan_batches};
 use rmc_engine::embeddings::{
     Embedding, EmbeddingGenerator, EmbeddingRuntime, EmbeddingTextLen, EmbeddingTokenCounter,
 };
+use rmc_engine::vector_store::VectorStore;
 use crate::indexing::IndexingError;
 use crate::metrics::MemoryMonitor;
+use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 use std::time::Instant;
 
@@ -27,6 +29,14 @@ pub(crate) struct EmbeddingBatcher {
     token_counter: Option<EmbeddingTokenCounter>,
 }
 
+/// Embeddings in chunk order, with how many were reused from the store.
+pub(crate) struct ChunkEmbeddings {
+    pub(crate) embeddings: Vec<Embedding>,
+    pub(crate) reused: usize,
+    /// IDs of the chunks embedded fresh, i.e. not yet in the store
+    pub(crate) fresh_ids: Vec<ChunkId>,
+}
+
 #[derive(Debug, Clone, Copy)]
 struct TokenLengthSummary {
     raw_tokens_total: usize,
@@ -86,20 +96,70 @@ impl EmbeddingBatcher {
         }
     }
 
-    /// Generate embeddings for chunks in batches.
+    /// Generate embeddings for chunks, reusing vectors already in `store`.
     ///
-    /// Uses GPU-optimized batch size to avoid OOM on GPU memory.
-    /// Chunks are formatted with their context and embedded via
-    /// `EmbeddingGenerator::embed_documents` (no instruction prefix).
-    pub(crate) async fn generate_embeddings_batched(
+    /// Chunk IDs hash every input of the embedding text, so a chunk whose
+    /// ID is already stored would be embedded exactly as it was then; only
+    /// the remaining chunks go to the embedding model. A failed lookup is
+    /// logged and falls back to embedding everything.
+    pub(crate) async fn generate_embeddings_reusing(
         &self,
         chunks: &[CodeChunk],
-    ) -> Result<Vec<Embedding>, IndexingError> {
-        let chunk_texts: Vec<String> = chunks
-            .iter()
-            .map(|c| c.format_for_embedding())
+        store: &VectorStore,
+    ) -> Result<ChunkEmbeddings, IndexingError> {
+        let ids: Vec<ChunkId> = chunks.iter().map(|chunk| chunk.id).collect();
+        let mut stored = match store.get_embeddings(&ids).await {
+            Ok(stored) => stored,
+            Err(err) => {
+                tracing::warn!(
+                    error = %err,
+                    "Stored embedding lookup failed; embedding every chunk"
+                );
+                HashMap::new()
+            }
+        };
+        let dim = self.embedding_generator.dimensions();
+        stored.retain(|_, embedding| embedding.len() == dim);
+
+        let missing: Vec<usize> = (0..chunks.len())
+            .filter(|&idx| !stored.contains_key(&chunks[idx].id))
             .collect();
+        let fresh = if missing.is_empty() {
+            Vec::new()
+        } else {
+            let texts = missing
+                .iter()
+                .map(|&idx| chunks[idx].format_for_embedding())
+                .collect();
+            self.embed_chunk_texts(texts).await?
+        };
+        let reused = chunks.len() - missing.len();
+        if reused > 0 {
+            tracing::info!(
+                chunks = chunks.len(),
+                reused,
+                embedded = missing.len(),
+                "Reused stored embeddings for unchanged chunks"
+            );
+        }
 
+        let fresh_ids = missing.iter().map(|&idx| chunks[idx].id).collect();
+        Ok(ChunkEmbeddings {
+            embeddings: merge_embeddings(chunks, stored, &missing, fresh)?,
+            reused,
+            fresh_ids,
+        })
+    }
+
+    /// Generate embeddings for formatted chunk texts in batches.
+    ///
+    /// Uses GPU-optimized batch size to avoid OOM on GPU memory.
+    /// Texts are embedded via `EmbeddingGenerator::embed_documents` (no
+    /// instruction prefix).
+    async fn embed_chunk_texts(
+        &self,
+        chunk_texts: Vec<String>,
+    ) -> Result<Vec<Embedding>, IndexingError> {
         let token_lengths = self.count_token_lengths(&chunk_texts);
 
         if self.embedding_generator.backend().runtime == EmbeddingRuntime::OpenRouter {
@@ -398,6 +458,39 @@ impl EmbeddingBatcher {
     }
 }
 
+/// Put stored and freshly generated embeddings back in `chunks` order.
+/// `missing` lists the indexes of the chunks `fresh` was generated for.
+fn merge_embeddings(
+    chunks: &[CodeChunk],
+    mut stored: HashMap<ChunkId, Embedding>,
+    missing: &[usize],
+    fresh: Vec<Embedding>,
+) -> Result<Vec<Embedding>, IndexingError> {
+    if fresh.len() != missing.len() {
+        return Err(IndexingError::Parser(format!(
+            "Generated {} embeddings for {} chunks",
+            fresh.len(),
+            missing.len()
+        )));
+    }
+    let mut slots: Vec<Option<Embedding>> = chunks
+        .iter()
+        .map(|chunk| stored.remove(&chunk.id))
+        .collect();
+    for (&idx, embedding) in missing.iter().zip(fresh) {
+        slots[idx] = Some(embedding);
+    }
+    slots
+        .into_iter()
+        .zip(chunks)
+        .map(|(slot, chunk)| {
+            slot.ok_or_else(|| {
+                IndexingError::Parser(format!("No embedding for chunk {}", chunk.id.to_string()))
+            })
+        })
+        .collect()
+}
+
 fn summarize_token_lengths(
     ordered_texts: &[(usize, String, Option<EmbeddingTextLen>)],
     batch_plan: &[EmbeddingBatchPlan],
@@ -749,4 +842,46 @@ mod tests {
             ]
         );
     }
+
+    fn chunk_with_id(id: ChunkId) -> CodeChunk {
+        CodeChunk {
+            id,
+            content: String::new(),
+            context: rmc_engine::chunker::ChunkContext {
+                file_path: std::path::PathBuf::from("src/lib.rs"),
+                module_path: vec![],
+                symbol_name: String::new(),
+                symbol_kind: "function".to_string(),
+                docstring: None,
+                imports: vec![],
+                outgoing_calls: vec![],
+                parent_symbol_name: None,
+                split_part: None,
+                split_total: None,
+                line_start: 1,
+                line_end: 1,
+            },
+            overlap_prev: None,
+            overlap_next: None,
+        }
+    }
+
+    #[test]
+    fn test_merge_embeddings_restores_chunk_order() {
+        let chunks: Vec<CodeChunk> = (0..3).map(|_| chunk_with_id(ChunkId::new())).collect();
+        let stored = HashMap::from([(chunks[1].id, vec![1.0])]);
+
+        let merged =
+            merge_embeddings(&chunks, stored, &[0, 2], vec![vec![0.0], vec![2.0]]).unwrap();
+
+        assert_eq!(merged, vec![vec![0.0], vec![1.0], vec![2.0]]);
+    }
+
+    #[test]
+    fn test_merge_embeddings_rejects_missing_vectors() {
+        let chunks: Vec<CodeChunk> = (0..2).map(|_| chunk_with_id(ChunkId::new())).collect();
+
+        assert!(merge_embeddings(&chunks, HashMap::new(), &[0, 1], vec![vec![0.0]]).is_err());
+        assert!(merge_embeddings(&chunks, HashMap::new(), &[0], vec![vec![0.0]]).is_err());
+    }
 }
@@ -8,7 +8,7 @@ use crate::indexing::IndexingError;
 use crate::metadata_cache::MetadataCache;
 use crate::security::SecretsScanner;
 use crate::security::SensitiveFileFilter;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 /// Handles file filtering, security scanning, and change detection.
 pub(crate) struct FileProcessor {
@@ -126,6 +126,44 @@ impl FileProcessor {
         Ok(())
     }
 
+    /// Paths of every file with metadata under the current cache-key salt
+    ///
+    /// Entries written under another salt belong to a previous chunking
+    /// configuration and are left out.
+    pub(crate) fn cached_files(&self) -> Result<Vec<PathBuf>, IndexingError> {
+        let keys = self
+            .metadata_cache
+            .list_files()
+            .map_err(|e| IndexingError::Cache(e.to_string()))?;
+        let prefix = self.cache_key_prefix();
+        Ok(keys
+            .into_iter()
+            .filter_map(|key| key.strip_prefix(&prefix).map(PathBuf::from))
+            .collect())
+    }
+
+    /// Drop a file's metadata so the next indexing pass treats it as new
+    pub(crate) fn forget_file(&self, file_path: &Path) -> Result<(), IndexingError> {
+        self.metadata_cache
+            .remove(&self.cache_key(file_path))
+            .map_err(|e| IndexingError::Cache(e.to_string()))
+    }
+
+    /// Raw metadata entries under the current cache-key salt, for backups
+    pub(crate) fn export_metadata(&self) -> Result<Vec<(String, Vec<u8>)>, IndexingError> {
+        self.metadata_cache
+            .export_prefix(&self.cache_key_prefix())
+            .map_err(|e| IndexingError::Cache(e.to_string()))
+    }
+
+    /// Replace the metadata under the current cache-key salt with entries
+    /// from [`Self::export_metadata`]
+    pub(crate) fn restore_metadata(&self, entries: &[(String, Vec<u8>)]) -> Result<(), IndexingError> {
+        self.metadata_cache
+            .replace_prefix(&self.cache_key_prefix(), entries)
+            .map_err(|e| IndexingError::Cache(e.to_string()))
+    }
+
     /// Get reference to metadata cache
     pub(crate) fn metadata_cache(&self) -> &MetadataCache {
         &self.metadata_cache
@@ -138,6 +176,15 @@ impl FileProcessor {
             .map_err(|e| IndexingError::Cache(e.to_string()))
     }
 
+    /// Prefix every cache key carries under the current salt
+    fn cache_key_prefix(&self) -> String {
+        if self.cache_key_salt.is_empty() {
+            String::new()
+        } else {
+            format!("{}::", self.cache_key_salt)
+        }
+    }
+
     fn cache_key(&self, file_path: &Path) -> String {
         let file_path_str = file_path.to_string_lossy();
         if self.cache_key_salt.is_empty() {
@@ -210,4 +257,28 @@ mod tests {
         let result = fp.clear_metadata_cache();
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_cached_files_and_forget_file_use_salted_keys() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache_path = temp_dir.path().join("cache");
+        let fp = FileProcessor::with_cache_key_salt(&cache_path, 10_000_000, "v2".to_string())
+            .unwrap();
+
+        let test_file = temp_dir.path().join("test.rs");
+        std::fs::write(&test_file, "fn test() {}").unwrap();
+        fp.update_file_metadata(&test_file, "fn test() {}").unwrap();
+        fp.metadata_cache()
+            .set(
+                "v1::old.rs",
+                &crate::metadata_cache::FileMetadata::from_content("", 0, 0),
+            )
+            .unwrap();
+
+        assert_eq!(fp.cached_files().unwrap(), vec![test_file.clone()]);
+
+        fp.forget_file(&test_file).unwrap();
+        assert!(fp.cached_files().unwrap().is_empty());
+        assert!(fp.has_file_changed(&test_file, "fn test() {}").unwrap());
+    }
 }
@@ -2,6 +2,7 @@
 
 use rmc_config::config::IndexerCoreConfig;
 use rmc_engine::embeddings::EmbeddingBackend;
+use rmc_engine::schema::CHUNK_SCHEMA_VERSION;
 use sha2::{Digest, Sha256};
 use std::path::{Path, PathBuf};
 
@@ -30,6 +31,9 @@ pub(crate) fn canonical_codebase_path(codebase_path: &Path) -> PathBuf {
 }
 
 /// Stable identity for all embedding-sensitive indexing artifacts.
+///
+/// Includes the Tantivy chunk schema version: a schema bump needs every file
+/// re-added to the rebuilt BM25 index, so it invalidates Merkle snapshots.
 pub(super) fn indexing_identity(
     codebase_path: &Path,
     backend: &EmbeddingBackend,
@@ -37,10 +41,11 @@ pub(super) fn indexing_identity(
 ) -> String {
     let canonical_path = canonical_codebase_path(codebase_path);
     format!(
-        "index:v1:path{}:embedder{}:chunking{}",
+        "index:v1:path{}:embedder{}:chunking{}:bm25v{}",
         canonical_path.to_string_lossy(),
         backend.identity(),
-        chunking_identity
+        chunking_identity,
+        CHUNK_SCHEMA_VERSION
     )
 }
 
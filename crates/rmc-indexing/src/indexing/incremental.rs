@@ -7,16 +7,24 @@
 //! 4. Reindexes only changed files
 //! 5. Saves new snapshot for next run
 //!
+//! With a [`Backup`] configured, the snapshot and metadata cache are backed
+//! up before a run changes anything and restored when it fails, or on the
+//! next run when the process died mid-run.
+//!
 //! This achieves 100-1000x speedup vs full reindexing for unchanged codebases.
 
 use rmc_engine::embeddings::EmbeddingBackend;
+use rmc_engine::vector_store::AnnIndexConfig;
+use rmc_config::ignore_engine::IgnoreEngine;
+use crate::indexing::backup::Backup;
 use crate::indexing::identity::{
     active_chunking_identity_for_backend, identity_hash, indexing_identity,
 };
 use crate::indexing::merkle::{ChangeSet, FileSystemMerkle};
-use crate::indexing::unified::{IndexFileResult, IndexStats, UnifiedIndexer};
+use crate::indexing::unified::{IndexFileResult, IndexStats, RunTrigger, UnifiedIndexer};
 use anyhow::Result;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use tracing;
 
 /// Get snapshot storage path for a codebase with the default backend.
@@ -70,6 +78,8 @@ struct IncrementalIndexerConfig {
     embedder_identity: String,
     codebase_loc: Option<usize>,
     backend: EmbeddingBackend,
+    backup: Option<Arc<dyn Backup>>,
+    ann_index: AnnIndexConfig,
 }
 
 impl IncrementalIndexer {
@@ -122,11 +132,26 @@ impl IncrementalIndexer {
                 embedder_identity: embedder_identity.to_string(),
                 codebase_loc,
                 backend,
+                backup: None,
+                ann_index: AnnIndexConfig::default(),
             },
             indexer: None,
         })
     }
 
+    /// Back up the Merkle snapshot and metadata cache before each run and
+    /// roll back to the backup when a run fails or was interrupted.
+    pub fn with_backup(mut self, backup: Arc<dyn Backup>) -> Self {
+        self.config.backup = Some(backup);
+        self
+    }
+
+    /// Replace the default ANN index policy for the vector store.
+    pub fn with_ann_index(mut self, config: AnnIndexConfig) -> Self {
+        self.config.ann_index = config;
+        self
+    }
+
     async fn ensure_indexer(&mut self) -> Result<&mut UnifiedIndexer> {
         if self.indexer.is_none() {
             let config = self.config.clone();
@@ -139,7 +164,12 @@ impl IncrementalIndexer {
                 config.codebase_loc,
                 config.backend,
             )
-            .await?;
+            .await?
+            .with_ann_index(config.ann_index);
+            let indexer = match config.backup {
+                Some(backup) => indexer.with_backup(backup),
+                None => indexer,
+            };
             self.indexer = Some(indexer);
         }
 
@@ -166,6 +196,7 @@ impl IncrementalIndexer {
 
         let snapshot_path = get_snapshot_path_for_backend(codebase_path, &self.config.backend);
         tracing::debug!("Snapshot path: {}", snapshot_path.display());
+        self.recover_interrupted_run(&snapshot_path).await?;
 
         // Step 1: Load previous snapshot (if exists)
         let old_merkle = match FileSystemMerkle::load_snapshot(&snapshot_path)? {
@@ -189,35 +220,160 @@ impl IncrementalIndexer {
         tracing::info!("Built Merkle tree with {} files", new_merkle.file_count());
 
         // Step 3: Determine indexing strategy
-        let stats = if let Some(old) = old_merkle {
+        let result = if let Some(old) = old_merkle {
             // Incremental: compare trees and index only changes
-            self.incremental_update(codebase_path, &old, &new_merkle)
-                .await?
+            self.incremental_update(codebase_path, &snapshot_path, &old, &new_merkle, true)
+                .await
         } else {
             // First time: full index with parallel processing
             tracing::info!("Performing full index (first time) with parallel processing");
-            self.ensure_indexer()
-                .await?
-                .index_directory_parallel(codebase_path)
-                .await?
+            match self.ensure_indexer().await {
+                Ok(indexer) => {
+                    indexer.begin_run(&snapshot_path, new_merkle.file_count(), RunTrigger::Full);
+                    indexer.index_directory_parallel(codebase_path).await
+                }
+                Err(e) => Err(e),
+            }
         };
 
         // Step 4: Save new snapshot for next time
-        new_merkle.save_snapshot(&snapshot_path)?;
+        self.finish_run(&snapshot_path, &new_merkle, result).await
+    }
+
+    /// Index only the paths a file watcher reported changed.
+    ///
+    /// Derives the new Merkle tree from the previous snapshot by re-hashing
+    /// `changed_paths` alone (see [`FileSystemMerkle::with_changed_paths`]),
+    /// then runs the same diff → reindex → save steps as
+    /// [`Self::index_with_change_detection`]. Without a previous snapshot
+    /// there is nothing to patch, and a changed ignore file can add or drop
+    /// files the watcher never reported, so both fall back to the full walk;
+    /// so does rolling back an interrupted run, whose restored snapshot is
+    /// behind files the watcher will not report again.
+    /// A due ANN index build or retrain is left to the next full run.
+    pub async fn index_changed_paths(
+        &mut self,
+        codebase_path: &Path,
+        changed_paths: &[PathBuf],
+    ) -> Result<IndexStats> {
+        let snapshot_path = get_snapshot_path_for_backend(codebase_path, &self.config.backend);
+        if self.recover_interrupted_run(&snapshot_path).await? {
+            tracing::info!(
+                "Rolled back an interrupted run for {} - falling back to full change detection",
+                codebase_path.display()
+            );
+            return self.index_with_change_detection(codebase_path).await;
+        }
+        let Some(old_merkle) = FileSystemMerkle::load_snapshot(&snapshot_path)? else {
+            tracing::info!(
+                "No previous snapshot for {} - falling back to full change detection",
+                codebase_path.display()
+            );
+            return self.index_with_change_detection(codebase_path).await;
+        };
+        if let Some(rule_file) = changed_paths.iter().find(|p| IgnoreEngine::is_rule_file(p)) {
+            tracing::info!(
+                "Ignore rules changed ({}) - falling back to full change detection",
+                rule_file.display()
+            );
+            return self.index_with_change_detection(codebase_path).await;
+        }
+
         tracing::info!(
-            "Saved new Merkle snapshot to {}",
-            snapshot_path.display()
+            "Refreshing Merkle tree for {} watched paths under {}",
+            changed_paths.len(),
+            codebase_path.display()
         );
+        let new_merkle = old_merkle.with_changed_paths(codebase_path, changed_paths)?;
+        let result = self
+            .incremental_update(codebase_path, &snapshot_path, &old_merkle, &new_merkle, false)
+            .await;
 
-        Ok(stats)
+        self.finish_run(&snapshot_path, &new_merkle, result).await
+    }
+
+    /// Restore the Merkle snapshot and metadata cache from the backup named
+    /// `id` (the newest when `None`), so the next run re-indexes every file
+    /// changed since. Returns the restored backup's id, or `None` when there
+    /// is no backup (or no backup store was configured).
+    pub async fn restore_backup(
+        &mut self,
+        codebase_path: &Path,
+        id: Option<&str>,
+    ) -> Result<Option<String>> {
+        let snapshot_path = get_snapshot_path_for_backend(codebase_path, &self.config.backend);
+        self.ensure_indexer().await?.roll_back(&snapshot_path, id)
+    }
+
+    /// Roll back to the newest backup when the previous run never finished.
+    ///
+    /// Its metadata-cache writes may describe chunks that Tantivy never
+    /// committed; the backup predates all of them. Returns whether it
+    /// rolled back.
+    async fn recover_interrupted_run(&mut self, snapshot_path: &Path) -> Result<bool> {
+        let interrupted = self
+            .config
+            .backup
+            .as_ref()
+            .is_some_and(|backup| backup.interrupted_run());
+        if !interrupted {
+            return Ok(false);
+        }
+        tracing::warn!("Previous index run was interrupted - rolling back to the last backup");
+        self.ensure_indexer().await?.roll_back(snapshot_path, None)?;
+        Ok(true)
+    }
+
+    /// Save `new_merkle` after a successful run, or roll back a failed one
+    /// after removing the chunks it added.
+    async fn finish_run(
+        &mut self,
+        snapshot_path: &Path,
+        new_merkle: &FileSystemMerkle,
+        result: Result<IndexStats>,
+    ) -> Result<IndexStats> {
+        let result = result.and_then(|stats| {
+            new_merkle.save_snapshot(snapshot_path)?;
+            tracing::info!("Saved new Merkle snapshot to {}", snapshot_path.display());
+            Ok(stats)
+        });
+        let Some(indexer) = self.indexer.as_mut() else {
+            return result;
+        };
+        match result {
+            Ok(stats) => {
+                indexer.end_run();
+                Ok(stats)
+            }
+            Err(e) => {
+                if let Err(discard_err) = indexer.discard_run_chunks().await {
+                    tracing::error!(
+                        "Failed to remove chunks of failed index run: {:#}",
+                        discard_err
+                    );
+                }
+                match indexer.roll_back(snapshot_path, None) {
+                    Ok(Some(id)) => tracing::warn!("Index run failed, rolled back to backup {}", id),
+                    Ok(None) => {}
+                    Err(rollback_err) => {
+                        tracing::error!("Failed to roll back failed index run: {:#}", rollback_err);
+                    }
+                }
+                Err(e)
+            }
+        }
     }
 
     /// Perform incremental update based on Merkle tree comparison
+    ///
+    /// `rebuild` allows building or retraining the ANN index afterwards.
     async fn incremental_update(
         &mut self,
         codebase_path: &Path,
+        snapshot_path: &Path,
         old_merkle: &FileSystemMerkle,
         new_merkle: &FileSystemMerkle,
+        rebuild: bool,
     ) -> Result<IndexStats> {
         // Fast path: if trees identical, no work needed!
         if !new_merkle.has_changes(old_merkle) {
@@ -249,7 +405,9 @@ impl IncrementalIndexer {
         );
 
         // Process changes
-        let mut stats = self.process_changes(codebase_path, changes).await?;
+        let mut stats = self
+            .process_changes(codebase_path, snapshot_path, changes, rebuild)
+            .await?;
         stats.total_files = new_merkle.file_count();
         Ok(stats)
     }
@@ -258,10 +416,14 @@ impl IncrementalIndexer {
     async fn process_changes(
         &mut self,
         _codebase_path: &Path,
+        snapshot_path: &Path,
         changes: ChangeSet,
+        rebuild: bool,
     ) -> Result<IndexStats> {
         let mut stats = IndexStats::default();
         let indexer = self.ensure_indexer().await?;
+        let trigger = if rebuild { RunTrigger::Full } else { RunTrigger::Watcher };
+        indexer.begin_run(snapshot_path, changes.total_changes(), trigger);
 
         // Handle deletions
         for deleted_path in &changes.deleted {
@@ -270,20 +432,25 @@ impl IncrementalIndexer {
             stats.skipped_files += 1;
         }
 
-        // Handle modifications (delete old + reindex)
+        // Handle modifications (reindex in place)
         for modified_path in &changes.modified {
             tracing::info!("Reindexing modified file: {}", modified_path.display());
-            // Delete old chunks first
-            indexer.delete_file_chunks(modified_path).await?;
-
+            // `index_file` replaces the file's chunks itself, keeping the
+            // stored embeddings of chunks whose IDs did not change. Old
+            // chunks are dropped explicitly only when it indexes nothing.
             match indexer.index_file(modified_path).await {
                 Ok(IndexFileResult::Indexed { chunks_count }) => {
                     stats.indexed_files += 1;
                     stats.total_chunks += chunks_count;
                 }
-                Ok(_) => stats.skipped_files += 1,
+                Ok(IndexFileResult::Unchanged) => stats.skipped_files += 1,
+                Ok(_) => {
+                    indexer.delete_file_chunks(modified_path).await?;
+                    stats.skipped_files += 1;
+                }
                 Err(e) => {
                     tracing::error!("Failed to index {}: {}", modified_path.display(), e);
+                    indexer.delete_file_chunks(modified_path).await?;
                     stats.skipped_files += 1;
                 }
             }
@@ -306,8 +473,9 @@ impl IncrementalIndexer {
             }
         }
 
-        // Commit changes to Tantivy
+        // Commit changes to Tantivy, then fold the upserts into the ANN index
         indexer.commit()?;
+        indexer.refresh_vector_index(rebuild).await;
 
         tracing::info!(
             "✓ Incremental update complete: {} files indexed, {} chunks",
@@ -335,8 +503,14 @@ impl IncrementalIndexer {
     /// This is used for force reindexing to ensure a completely clean slate.
     /// Note: This does NOT delete the Merkle snapshot - that should be handled separately
     /// by the caller (e.g., in index_tool.rs) before calling index_with_change_detection.
+    ///
+    /// Also clears the interrupted-run mark: there is nothing left to roll
+    /// back to that the forced reindex would not redo anyway.
     pub async fn clear_all_data(&mut self) -> Result<()> {
-        self.ensure_indexer().await?.clear_all_data().await
+        let indexer = self.ensure_indexer().await?;
+        indexer.clear_all_data().await?;
+        indexer.end_run();
+        Ok(())
     }
 }
 
@@ -550,4 +724,50 @@ mod tests {
 
         assert_eq!(stats.indexed_files, 1); // Only new file
     }
+
+    #[tokio::test]
+    #[ignore] // Requires embedding model
+    async fn test_watcher_run_recovers_interrupted_run_first() {
+        use crate::monitoring::backup::BackupManager;
+
+        let temp_dir = TempDir::new().unwrap();
+        let test_codebase = temp_dir.path().join("codebase");
+        std::fs::create_dir(&test_codebase).unwrap();
+        let a_rs = test_codebase.join("a.rs");
+        let b_rs = test_codebase.join("b.rs");
+        std::fs::write(&a_rs, "fn a() {}").unwrap();
+        std::fs::write(&b_rs, "fn b() {}").unwrap();
+        let manager = Arc::new(BackupManager::new(temp_dir.path().join("backups"), 7).unwrap());
+
+        let mut indexer = IncrementalIndexer::new(
+            &temp_dir.path().join("cache"),
+            &temp_dir.path().join("tantivy"),
+            "test_watcher_recovery",
+            1024, // Qwen3-Embedding-0.6B
+            "test-embedder:v1",
+            None,
+        )
+        .await
+        .unwrap()
+        .with_backup(manager.clone());
+        indexer.index_with_change_detection(&test_codebase).await.unwrap();
+
+        // A run that re-indexed b.rs and died before clearing its marker
+        std::fs::write(&b_rs, "fn b() { let _ = 2; }").unwrap();
+        indexer.index_with_change_detection(&test_codebase).await.unwrap();
+        manager.begin_run().unwrap();
+
+        // The rollback puts b.rs back in the diff even though the watcher
+        // only reports a.rs
+        std::fs::write(&a_rs, "fn a() { let _ = 2; }").unwrap();
+        let stats = indexer
+            .index_changed_paths(&test_codebase, std::slice::from_ref(&a_rs))
+            .await
+            .unwrap();
+
+        assert_eq!(stats.indexed_files, 2);
+        assert!(!manager.interrupted_run());
+
+        let _ = std::fs::remove_file(get_snapshot_path(&test_codebase));
+    }
 }
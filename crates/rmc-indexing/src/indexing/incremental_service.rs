@@ -1,13 +1,16 @@
 //! Server-facing incremental indexing facade.
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result};
 use rmc_engine::embeddings::EmbeddingBackend;
 
+use crate::indexing::backup::Backup;
 use crate::indexing::incremental::IncrementalIndexer;
 use crate::indexing::unified::IndexStats;
+use crate::indexing::vector_index::VectorIndexSettings;
 
 /// Request for an incremental indexing run.
 pub struct IncrementalIndexRequest<'a> {
@@ -20,6 +23,13 @@ pub struct IncrementalIndexRequest<'a> {
     pub snapshot_path: Option<&'a Path>,
     pub codebase_loc: Option<usize>,
     pub force_reindex: bool,
+    /// Paths a file watcher reported changed. When set (and not forcing a
+    /// reindex), only these are re-hashed against the previous Merkle
+    /// snapshot instead of walking the whole codebase.
+    pub changed_paths: Option<&'a [PathBuf]>,
+    /// Where runs back up the Merkle snapshot and metadata cache; a failed
+    /// or interrupted run is rolled back to the newest backup.
+    pub backup: Option<Arc<dyn Backup>>,
 }
 
 /// Result of an incremental indexing run.
@@ -41,6 +51,11 @@ pub async fn index_project_incrementally(
 trait IncrementalIndexRunner {
     async fn clear_all_data(&mut self) -> Result<()>;
     async fn index_with_change_detection(&mut self, codebase_path: &Path) -> Result<IndexStats>;
+    async fn index_changed_paths(
+        &mut self,
+        codebase_path: &Path,
+        changed_paths: &[PathBuf],
+    ) -> Result<IndexStats>;
 }
 
 impl IncrementalIndexRunner for IncrementalIndexer {
@@ -51,6 +66,14 @@ impl IncrementalIndexRunner for IncrementalIndexer {
     async fn index_with_change_detection(&mut self, codebase_path: &Path) -> Result<IndexStats> {
         IncrementalIndexer::index_with_change_detection(self, codebase_path).await
     }
+
+    async fn index_changed_paths(
+        &mut self,
+        codebase_path: &Path,
+        changed_paths: &[PathBuf],
+    ) -> Result<IndexStats> {
+        IncrementalIndexer::index_changed_paths(self, codebase_path, changed_paths).await
+    }
 }
 
 trait IncrementalIndexerFactory {
@@ -65,7 +88,7 @@ impl IncrementalIndexerFactory for RealIncrementalIndexerFactory {
     type Indexer = IncrementalIndexer;
 
     async fn create(&self, request: &IncrementalIndexRequest<'_>) -> Result<Self::Indexer> {
-        IncrementalIndexer::with_backend(
+        let indexer = IncrementalIndexer::with_backend(
             request.cache_path,
             request.tantivy_path,
             request.collection_name,
@@ -74,7 +97,14 @@ impl IncrementalIndexerFactory for RealIncrementalIndexerFactory {
             request.codebase_loc,
             request.backend.clone(),
         )
-        .await
+        .await?
+        .with_ann_index(
+            VectorIndexSettings::load(request.codebase_path)?.ann_index_config()?,
+        );
+        Ok(match &request.backup {
+            Some(backup) => indexer.with_backup(Arc::clone(backup)),
+            None => indexer,
+        })
     }
 }
 
@@ -105,9 +135,18 @@ where
         indexer.clear_all_data().await?;
     }
 
-    let stats = indexer
-        .index_with_change_detection(request.codebase_path)
-        .await?;
+    let stats = match request.changed_paths {
+        Some(changed_paths) if !request.force_reindex => {
+            indexer
+                .index_changed_paths(request.codebase_path, changed_paths)
+                .await?
+        }
+        _ => {
+            indexer
+                .index_with_change_detection(request.codebase_path)
+                .await?
+        }
+    };
 
     Ok(IncrementalIndexOutcome {
         stats,
@@ -115,6 +154,40 @@ where
     })
 }
 
+/// Request to restore a project's index state from a backup.
+pub struct RestoreIndexBackupRequest<'a> {
+    pub codebase_path: &'a Path,
+    pub cache_path: &'a Path,
+    pub tantivy_path: &'a Path,
+    pub collection_name: &'a str,
+    pub backend: EmbeddingBackend,
+    pub embedder_identity: &'a str,
+    pub backup: Arc<dyn Backup>,
+    /// Backup to restore; the newest when `None`
+    pub backup_id: Option<&'a str>,
+}
+
+/// Restore the Merkle snapshot and metadata cache from a backup.
+///
+/// Returns the restored backup's id, or `None` when there are no backups.
+/// The next incremental run re-indexes every file changed since the backup.
+pub async fn restore_index_backup(request: RestoreIndexBackupRequest<'_>) -> Result<Option<String>> {
+    let mut indexer = IncrementalIndexer::with_backend(
+        request.cache_path,
+        request.tantivy_path,
+        request.collection_name,
+        request.backend.dim(),
+        request.embedder_identity,
+        None,
+        request.backend.clone(),
+    )
+    .await?
+    .with_backup(request.backup);
+    indexer
+        .restore_backup(request.codebase_path, request.backup_id)
+        .await
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -208,6 +281,23 @@ mod tests {
             }
             Ok(state.stats.clone())
         }
+
+        async fn index_changed_paths(
+            &mut self,
+            codebase_path: &Path,
+            changed_paths: &[PathBuf],
+        ) -> Result<IndexStats> {
+            let mut state = self.state.lock().unwrap();
+            state.events.push(format!(
+                "index_changed:{}:{}",
+                codebase_path.display(),
+                changed_paths.len()
+            ));
+            if let Some(message) = state.index_error {
+                return Err(anyhow!(message));
+            }
+            Ok(state.stats.clone())
+        }
     }
 
     fn test_backend() -> EmbeddingBackend {
@@ -243,6 +333,8 @@ mod tests {
                 snapshot_path: Some(&snapshot_path),
                 codebase_loc: None,
                 force_reindex: true,
+                changed_paths: None,
+                backup: None,
             },
             &factory,
         )
@@ -262,6 +354,46 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn changed_paths_route_to_targeted_update_unless_forced() {
+        let temp_dir = TempDir::new().unwrap();
+        let codebase_path = temp_dir.path().join("codebase");
+        let cache_path = temp_dir.path().join("cache");
+        let tantivy_path = temp_dir.path().join("tantivy");
+        std::fs::create_dir(&codebase_path).unwrap();
+        let changed = vec![codebase_path.join("src/lib.rs")];
+
+        for (force_reindex, expected) in [
+            (false, format!("index_changed:{}:1", codebase_path.display())),
+            (true, format!("index:{}", codebase_path.display())),
+        ] {
+            let backend = test_backend();
+            let embedder_identity = backend.identity();
+            let factory = FakeFactory::default();
+            index_project_incrementally_with_factory(
+                IncrementalIndexRequest {
+                    codebase_path: &codebase_path,
+                    cache_path: &cache_path,
+                    tantivy_path: &tantivy_path,
+                    collection_name: "changed_paths",
+                    backend,
+                    embedder_identity: &embedder_identity,
+                    snapshot_path: None,
+                    codebase_loc: None,
+                    force_reindex,
+                    changed_paths: Some(&changed),
+                    backup: None,
+                },
+                &factory,
+            )
+            .await
+            .unwrap();
+
+            let state = factory.state.lock().unwrap();
+            assert_eq!(state.events.last(), Some(&expected));
+        }
+    }
+
     #[tokio::test]
     async fn passes_backend_construction_inputs_to_factory() {
         let temp_dir = TempDir::new().unwrap();
@@ -287,6 +419,8 @@ mod tests {
                 snapshot_path: Some(&snapshot_path),
                 codebase_loc: Some(42),
                 force_reindex: false,
+                changed_paths: None,
+                backup: None,
             },
             &factory,
         )
@@ -333,6 +467,8 @@ mod tests {
                 snapshot_path: None,
                 codebase_loc: None,
                 force_reindex: true,
+                changed_paths: None,
+                backup: None,
             },
             &factory,
         )
@@ -368,6 +504,8 @@ mod tests {
                 snapshot_path: None,
                 codebase_loc: None,
                 force_reindex: false,
+                changed_paths: None,
+                backup: None,
             },
             &factory,
         )
@@ -393,6 +531,8 @@ mod tests {
                 snapshot_path: None,
                 codebase_loc: None,
                 force_reindex: true,
+                changed_paths: None,
+                backup: None,
             },
             &factory,
         )
@@ -418,6 +558,8 @@ mod tests {
                 snapshot_path: None,
                 codebase_loc: None,
                 force_reindex: false,
+                changed_paths: None,
+                backup: None,
             },
             &factory,
         )
@@ -425,4 +567,55 @@ mod tests {
         .unwrap_err();
         assert_eq!(error.to_string(), "index failed");
     }
+
+    #[tokio::test]
+    #[ignore] // Requires embedding model
+    async fn watcher_runs_reuse_a_recent_backup_across_indexers() {
+        use crate::monitoring::backup::BackupManager;
+
+        let temp_dir = TempDir::new().unwrap();
+        let codebase_path = temp_dir.path().join("codebase");
+        let cache_path = temp_dir.path().join("cache");
+        let tantivy_path = temp_dir.path().join("tantivy");
+        std::fs::create_dir(&codebase_path).unwrap();
+        let lib_rs = codebase_path.join("lib.rs");
+        std::fs::write(&lib_rs, "pub fn first() {}\n").unwrap();
+        let manager = Arc::new(BackupManager::new(temp_dir.path().join("backups"), 7).unwrap());
+        let backend = EmbeddingBackend::default();
+        let embedder_identity = backend.identity();
+        let changed = vec![lib_rs.clone()];
+
+        let run = |watcher: bool| {
+            let request = IncrementalIndexRequest {
+                codebase_path: &codebase_path,
+                cache_path: &cache_path,
+                tantivy_path: &tantivy_path,
+                collection_name: "watcher_backup_throttle",
+                backend: backend.clone(),
+                embedder_identity: &embedder_identity,
+                snapshot_path: None,
+                codebase_loc: None,
+                force_reindex: false,
+                changed_paths: watcher.then_some(changed.as_slice()),
+                backup: Some(manager.clone() as Arc<dyn Backup>),
+            };
+            index_project_incrementally(request)
+        };
+        let backups = || manager.list_backups().unwrap().len();
+
+        run(false).await.unwrap();
+        assert_eq!(backups(), 1);
+
+        // Each call builds a fresh indexer; the throttle still applies
+        std::fs::write(&lib_rs, "pub fn second() {}\n").unwrap();
+        run(true).await.unwrap();
+        assert_eq!(backups(), 1);
+
+        std::fs::write(&lib_rs, "pub fn third() {}\n").unwrap();
+        run(false).await.unwrap();
+        assert_eq!(backups(), 2);
+
+        run(false).await.unwrap();
+        assert_eq!(backups(), 2);
+    }
 }
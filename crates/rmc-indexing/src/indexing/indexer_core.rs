@@ -4,12 +4,13 @@
 //! to focused sub-components:
 //! - [`FileProcessor`](super::file_processor::FileProcessor): security filtering, change detection, metadata cache
 //! - [`EmbeddingBatcher`](super::embedding_batcher::EmbeddingBatcher): GPU-optimized batch embedding, memory monitoring
-//! - [`Chunker`]: tree-sitter parsing and semantic code chunking
+//! - [`Chunker`]: tree-sitter parsing and semantic code chunking for Rust, section /
+//!   line-window chunking for TOML, markdown and other text files
 //!
 //! ## Processing Pipeline
 //!
 //! ```text
-//! File → Security Checks → Parse (tree-sitter) → Chunk → Embeddings
+//! File → Security Checks → Parse (tree-sitter, Rust only) → Chunk → Embeddings
 //!        ├─ Sensitive file filter  ─┐
 //!        ├─ Secrets scanner         ├─ FileProcessor
 //!        └─ Size limits            ─┘
@@ -25,22 +26,21 @@
 //! let core = IndexerCore::new(Path::new("./cache"), None)?;
 //!
 //! let processed = core.process_file_sync(Path::new("src/main.rs"))?;
-//! // generate_embeddings_batched is async — requires a runtime in real use.
+//! // generate_embeddings_reusing is async — requires a runtime in real use.
 //! let batch_size = core.calculate_safe_batch_size();
 //! # Ok(())
 //! # }
 //! ```
 
-use rmc_engine::chunker::{Chunker, ChunkSplitConfig, CodeChunk};
+use rmc_engine::chunker::{Chunker, ChunkSplitConfig, CodeChunk, FileKind};
 use rmc_config::config::IndexerCoreConfig;
-use rmc_engine::embeddings::{
-    Embedding, EmbeddingBackend, EmbeddingGenerator, EmbeddingTokenCounter,
-};
-use crate::indexing::embedding_batcher::EmbeddingBatcher;
+use rmc_engine::embeddings::{EmbeddingBackend, EmbeddingGenerator, EmbeddingTokenCounter};
+use crate::indexing::embedding_batcher::{ChunkEmbeddings, EmbeddingBatcher};
 use crate::indexing::file_processor::FileProcessor;
 use crate::indexing::IndexingError;
 use crate::metadata_cache::MetadataCache;
 use rmc_engine::parser::RustParser;
+use rmc_engine::vector_store::VectorStore;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex, OnceLock};
 use std::time::{Duration, Instant};
@@ -205,6 +205,26 @@ impl IndexerCore {
         self.file_processor.update_file_metadata(file_path, content)
     }
 
+    /// Paths of every file with metadata under the current cache-key salt
+    pub(crate) fn cached_files(&self) -> Result<Vec<PathBuf>, IndexingError> {
+        self.file_processor.cached_files()
+    }
+
+    /// Drop a file's metadata so the next indexing pass treats it as new
+    pub(crate) fn forget_file(&self, file_path: &Path) -> Result<(), IndexingError> {
+        self.file_processor.forget_file(file_path)
+    }
+
+    /// Raw metadata entries under the current cache-key salt, for backups
+    pub(crate) fn export_metadata(&self) -> Result<Vec<(String, Vec<u8>)>, IndexingError> {
+        self.file_processor.export_metadata()
+    }
+
+    /// Replace the current metadata with entries from [`Self::export_metadata`]
+    pub(crate) fn restore_metadata(&self, entries: &[(String, Vec<u8>)]) -> Result<(), IndexingError> {
+        self.file_processor.restore_metadata(entries)
+    }
+
     /// Get reference to metadata cache
     pub(crate) fn metadata_cache(&self) -> &MetadataCache {
         self.file_processor.metadata_cache()
@@ -244,16 +264,23 @@ impl IndexerCore {
             return Err(IndexingError::Parser("File unchanged".into()));
         }
 
-        // Parse with tree-sitter (CPU-intensive)
-        // Create fresh parser for thread safety
-        let mut parser = RustParser::new()
-            .map_err(|e| IndexingError::Parser(e.to_string()))?;
-        let parse_result = parser.parse_source_complete(&content)
-            .map_err(|e| IndexingError::Parser(e.to_string()))?;
-
-        // Chunk (CPU-intensive)
-        let chunks = self.chunker.chunk_file(file_path, &content, &parse_result)
-            .map_err(|e| IndexingError::Parser(e.to_string()))?;
+        // Chunk (CPU-intensive): Rust via tree-sitter, everything else by
+        // sections / line windows
+        let chunks = match FileKind::for_path(file_path) {
+            Some(FileKind::Rust) => {
+                // Create fresh parser for thread safety
+                let mut parser = RustParser::new()
+                    .map_err(|e| IndexingError::Parser(e.to_string()))?;
+                let parse_result = parser.parse_source_complete(&content)
+                    .map_err(|e| IndexingError::Parser(e.to_string()))?;
+                self.chunker.chunk_file(file_path, &content, &parse_result)
+                    .map_err(|e| IndexingError::Parser(e.to_string()))?
+            }
+            Some(kind) => self.chunker.chunk_text_file(file_path, &content, kind),
+            None => {
+                return Err(IndexingError::Parser("File filtered: unsupported file type".into()));
+            }
+        };
         let chunks = self.chunker.split_oversized_chunks(
             chunks,
             self.chunk_split_config,
@@ -277,15 +304,17 @@ impl IndexerCore {
 
     // --- Embedding delegation (EmbeddingBatcher) ---
 
-    /// Generate embeddings for chunks in batches.
+    /// Generate embeddings for chunks in batches, reusing the vectors
+    /// `store` already holds for unchanged chunk IDs.
     ///
     /// Uses GPU-optimized batch size to avoid OOM on GPU memory.
-    pub(crate) async fn generate_embeddings_batched(
+    pub(crate) async fn generate_embeddings_reusing(
         &self,
         chunks: &[CodeChunk],
-    ) -> Result<Vec<Embedding>, IndexingError> {
+        store: &VectorStore,
+    ) -> Result<ChunkEmbeddings, IndexingError> {
         self.embedding_batcher()?
-            .generate_embeddings_batched(chunks)
+            .generate_embeddings_reusing(chunks, store)
             .await
     }
 
@@ -6,13 +6,14 @@
 //! - Directory-level skipping (if directory hash unchanged, skip all children)
 
 use anyhow::Result;
+use rmc_engine::chunker::FileKind;
+use rmc_config::ignore_engine::IgnoreEngine;
 use rs_merkle::{Hasher, MerkleTree};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::time::SystemTime;
-use walkdir::WalkDir;
 
 /// SHA-256 hasher for Merkle tree
 #[derive(Clone)]
@@ -84,25 +85,27 @@ pub struct FileSystemMerkle {
 impl FileSystemMerkle {
     /// Build a Merkle tree from a directory
     ///
-    /// This scans all Rust files in the directory and creates a Merkle tree
-    /// from their content hashes.
+    /// This scans all indexable files in the directory (Rust sources plus the
+    /// TOML / markdown / text files `FileKind::for_path` knows) that the
+    /// project's ignore rules keep (see [`IgnoreEngine`]) and creates a
+    /// Merkle tree from their content hashes.
     pub fn from_directory(root: &Path) -> Result<Self> {
         tracing::info!("Building Merkle tree for {}", root.display());
 
+        let ignores = IgnoreEngine::load(root)?;
         let mut file_hashes = Vec::new();
         let mut file_to_node = HashMap::new();
 
-        // Collect all Rust files in sorted order with proper error handling (critical for consistency!)
+        // Collect all indexable files in sorted order with proper error handling (critical for consistency!)
         let mut files = Vec::new();
         let mut walk_errors = 0;
 
-        for entry in WalkDir::new(root) {
+        for entry in ignores.walk(root) {
             match entry {
-                Ok(e) if e.file_type().is_file()
-                       && e.path().extension() == Some(std::ffi::OsStr::new("rs")) => {
+                Ok(e) if e.file_type().is_file() && FileKind::is_indexed(e.path()) => {
                     files.push(e.path().to_path_buf());
                 }
-                Ok(_) => {}, // Directory or non-.rs file, skip silently
+                Ok(_) => {}, // Directory or non-indexed file, skip silently
                 Err(err) => {
                     let path = err.path().unwrap_or_else(|| Path::new("<unknown>"));
                     tracing::warn!(
@@ -159,6 +162,83 @@ impl FileSystemMerkle {
         })
     }
 
+    /// Derive the next tree from this one by re-hashing only `changed_paths`
+    ///
+    /// Used by watch mode, where the file watcher already names what changed
+    /// and walking the whole codebase would be wasted work. Each path under
+    /// `root` is handled like `from_directory(root)` would see it: existing
+    /// indexable files are (re)hashed, directories are walked for them, and
+    /// paths that no longer exist or are now ignored drop out together with
+    /// everything beneath them.
+    ///
+    /// A changed ignore file (`.gitignore`, `.rmcignore`, `.rmc.toml`) can
+    /// move files anywhere below it in or out of the project, so callers
+    /// should fall back to `from_directory` when one is among
+    /// `changed_paths` (see [`IgnoreEngine::is_rule_file`]).
+    pub fn with_changed_paths(&self, root: &Path, changed_paths: &[PathBuf]) -> Result<Self> {
+        let ignores = IgnoreEngine::load(root)?;
+        let mut file_to_node = self.file_to_node.clone();
+
+        for path in changed_paths {
+            if !path.exists() || ignores.is_ignored(path) {
+                file_to_node.retain(|known, _| !known.starts_with(path));
+                continue;
+            }
+            let files: Vec<PathBuf> = if path.is_dir() {
+                ignores
+                    .walk(path)
+                    .filter_map(|e| e.ok())
+                    .filter(|e| e.file_type().is_file() && FileKind::is_indexed(e.path()))
+                    .map(|e| e.path().to_path_buf())
+                    .collect()
+            } else if FileKind::is_indexed(path) {
+                vec![path.clone()]
+            } else {
+                continue;
+            };
+            for file in files {
+                // Deleted between the walk (or the `exists` check) and the
+                // read: same as a deletion
+                let read = std::fs::read(&file)
+                    .and_then(|content| Ok((content, std::fs::metadata(&file)?.modified()?)));
+                let (content, last_modified) = match read {
+                    Ok(read) => read,
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                        file_to_node.remove(&file);
+                        continue;
+                    }
+                    Err(e) => return Err(e.into()),
+                };
+                file_to_node.insert(
+                    file,
+                    FileNode {
+                        content_hash: Sha256Hasher::hash(&content),
+                        leaf_index: 0,
+                        last_modified,
+                    },
+                );
+            }
+        }
+
+        // Leaves stay in path order, same as `from_directory`.
+        let mut files: Vec<PathBuf> = file_to_node.keys().cloned().collect();
+        files.sort();
+        let mut file_hashes = Vec::with_capacity(files.len());
+        for (idx, path) in files.iter().enumerate() {
+            let node = file_to_node
+                .get_mut(path)
+                .expect("path collected from file_to_node");
+            node.leaf_index = idx;
+            file_hashes.push(node.content_hash);
+        }
+
+        Ok(Self {
+            tree: MerkleTree::<Sha256Hasher>::from_leaves(&file_hashes),
+            file_to_node,
+            snapshot_version: self.snapshot_version,
+        })
+    }
+
     /// Get the Merkle root hash
     ///
     /// This is used for fast "any changes?" check - if roots match, nothing changed
@@ -410,6 +490,40 @@ mod tests {
         assert_eq!(changes.total_changes(), 1);
     }
 
+    #[test]
+    fn test_with_changed_paths_matches_full_rebuild() {
+        let temp_dir = TempDir::new().unwrap();
+        let keep = temp_dir.path().join("keep.rs");
+        let edit = temp_dir.path().join("edit.rs");
+        let gone_dir = temp_dir.path().join("gone");
+        std::fs::write(&keep, "fn keep() {}").unwrap();
+        std::fs::write(&edit, "fn edit() {}").unwrap();
+        std::fs::create_dir(&gone_dir).unwrap();
+        std::fs::write(gone_dir.join("inner.rs"), "fn inner() {}").unwrap();
+
+        let old = FileSystemMerkle::from_directory(temp_dir.path()).unwrap();
+
+        std::fs::write(&edit, "fn edit() { println!(\"changed\"); }").unwrap();
+        std::fs::remove_dir_all(&gone_dir).unwrap();
+        let added = temp_dir.path().join("added.rs");
+        std::fs::write(&added, "fn added() {}").unwrap();
+
+        let patched = old
+            .with_changed_paths(
+                temp_dir.path(),
+                &[edit.clone(), gone_dir.clone(), added.clone()],
+            )
+            .unwrap();
+        let rebuilt = FileSystemMerkle::from_directory(temp_dir.path()).unwrap();
+        assert_eq!(patched.root_hash(), rebuilt.root_hash());
+        assert_eq!(patched.file_count(), 3);
+
+        let changes = patched.detect_changes(&old);
+        assert_eq!(changes.added, vec![added]);
+        assert_eq!(changes.modified, vec![edit]);
+        assert_eq!(changes.deleted, vec![gone_dir.join("inner.rs")]);
+    }
+
     #[test]
     fn test_snapshot_save_and_load() {
         let temp_dir = TempDir::new().unwrap();
@@ -1,6 +1,6 @@
 //! Indexing module - Unified pipeline for both Tantivy and vector store
 
-pub(crate) mod backup;
+mod backup;
 mod consistency;
 pub(crate) mod embedding_batcher;
 pub mod error;
@@ -17,11 +17,18 @@ pub mod search;
 mod tantivy_adapter;
 mod unified;
 mod unified_parallel;
+pub mod vector_index;
 
 pub(crate) use error::IndexingError;
+pub use backup::{Backup, IndexCheckpoint};
+pub use consistency::{
+    verify_project_index, ConsistencyReport, RepairFailure, RepairSummary, VerifyIndexOutcome,
+    VerifyIndexRequest,
+};
 pub use incremental::{get_snapshot_path, IncrementalIndexer};
 pub use incremental_service::{
-    index_project_incrementally, IncrementalIndexOutcome, IncrementalIndexRequest,
+    index_project_incrementally, restore_index_backup, IncrementalIndexOutcome,
+    IncrementalIndexRequest, RestoreIndexBackupRequest,
 };
 pub use merkle::{ChangeSet, FileSystemMerkle};
 pub use project_paths::{
@@ -31,3 +38,4 @@ pub use project_paths::{
 pub use search::open_bm25_search;
 pub use tantivy_adapter::TantivyAdapter;
 pub use unified::{IndexFileResult, IndexStats, UnifiedIndexer};
+pub use vector_index::VectorIndexSettings;
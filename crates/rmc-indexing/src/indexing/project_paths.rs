@@ -20,6 +20,7 @@ pub struct IndexingProjectPaths {
     pub snapshot_path: PathBuf,
     pub collection_name: String,
     pub vector_path: PathBuf,
+    pub backup_path: PathBuf,
 }
 
 /// Existing vector index discovered under a project's collection prefix.
@@ -57,6 +58,7 @@ impl IndexingProjectPaths {
             cache_path: data_root.join("cache").join(&dir_hash),
             tantivy_path: data_root.join("index").join(&dir_hash),
             vector_path: data_root.join("cache").join("vectors").join(&collection_name),
+            backup_path: data_root.join("backups").join(&collection_name),
             collection_name,
             snapshot_path,
             indexing_identity,
@@ -98,6 +100,7 @@ impl IndexingProjectPaths {
             cache_path: data_root.join("cache").join(&dir_hash),
             tantivy_path: data_root.join("index").join(&dir_hash),
             vector_path: vectors_root.join(&collection_name),
+            backup_path: data_root.join("backups").join(&collection_name),
             collection_name,
             snapshot_path,
             indexing_identity,
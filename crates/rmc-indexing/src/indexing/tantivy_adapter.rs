@@ -51,10 +51,10 @@
 //! # }
 //! ```
 
-use rmc_engine::chunker::CodeChunk;
+use rmc_engine::chunker::{ChunkId, CodeChunk};
 use rmc_config::config::TantivyConfig;
 use rmc_engine::schema::ChunkSchema;
-use rmc_engine::search::Bm25Search;
+use rmc_engine::search::{Bm25Search, is_test_chunk};
 use anyhow::{Context, Result};
 use std::path::Path;
 use tantivy::{doc, Index, IndexWriter};
@@ -74,16 +74,10 @@ impl TantivyAdapter {
     pub fn new(config: TantivyConfig) -> Result<Self> {
         let schema = ChunkSchema::new();
 
-        // Open or create index
-        let index = if config.index_path.join("meta.json").exists() {
-            Index::open_in_dir(&config.index_path)
-                .context("Failed to open Tantivy index")?
-        } else {
-            std::fs::create_dir_all(&config.index_path)
-                .context("Failed to create Tantivy directory")?;
-            Index::create_in_dir(&config.index_path, schema.schema())
-                .context("Failed to create Tantivy index")?
-        };
+        // Open or create index (rebuilt empty if written by an older ChunkSchema)
+        let index = schema
+            .open_or_create_index(&config.index_path)
+            .context("Failed to open or create Tantivy index")?;
 
         // Calculate total memory budget
         let total_memory_budget =
@@ -122,6 +116,7 @@ impl TantivyAdapter {
                 self.schema.module_path => chunk.context.module_path.join("::"),
                 self.schema.docstring => chunk.context.docstring.clone().unwrap_or_default(),
                 self.schema.chunk_json => chunk_json,
+                self.schema.is_test => is_test_chunk(chunk),
             ))
             .context("Failed to add document to Tantivy")?;
 
@@ -155,6 +150,15 @@ impl TantivyAdapter {
         Ok(())
     }
 
+    /// Delete the chunks with the given IDs
+    pub fn delete_chunks(&mut self, chunk_ids: &[ChunkId]) -> Result<()> {
+        for chunk_id in chunk_ids {
+            let term = tantivy::Term::from_field_text(self.schema.chunk_id, &chunk_id.to_string());
+            self.writer.delete_term(term);
+        }
+        Ok(())
+    }
+
     /// Delete all documents from the index
     pub fn delete_all(&mut self) -> Result<()> {
         self.writer.delete_all_documents()
@@ -9,16 +9,22 @@ use rmc_engine::chunker::{ChunkId, CodeChunk};
 use rmc_config::config::IndexerConfig;
 use rmc_engine::embeddings::{EmbeddingBackend, EmbeddingGenerator};
 use rmc_engine::search::Bm25Search;
+use crate::indexing::backup::{Backup, IndexCheckpoint};
+use crate::indexing::consistency::{
+    ConsistencyChecker, ConsistencyReport, RepairFailure, RepairSummary,
+};
+use crate::indexing::embedding_batcher::ChunkEmbeddings;
 use crate::indexing::indexer_core::IndexerCore;
 use crate::indexing::tantivy_adapter::TantivyAdapter;
 use crate::indexing::unified_parallel::{
-    collect_rust_files, parallel_parse_batch, process_batch_errors,
+    collect_source_files, parallel_parse_batch, process_batch_errors,
 };
 use crate::metrics::IndexingMetrics;
-use rmc_engine::vector_store::VectorStore;
+use rmc_engine::vector_store::{AnnIndexAction, AnnIndexConfig, VectorStore};
 use anyhow::{Context, Result};
 use std::path::Path;
-use std::time::{Duration, Instant};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
 use tantivy::Index;
 
 /// Statistics from an indexing operation
@@ -54,6 +60,20 @@ pub enum IndexFileResult {
     Skipped,
 }
 
+/// Watcher runs reuse a backup younger than this instead of writing a new
+/// one; a rollback then restores that backup and redoes a few more files.
+const WATCHER_BACKUP_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// What started an index run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RunTrigger {
+    /// An explicit index or change-detection run
+    Full,
+    /// A file-watcher pass; backed up at most once per
+    /// [`WATCHER_BACKUP_INTERVAL`]
+    Watcher,
+}
+
 /// Unified indexer that coordinates Tantivy and vector store operations
 pub struct UnifiedIndexer {
     /// Core indexing logic
@@ -68,6 +88,15 @@ pub struct UnifiedIndexer {
     /// callers (e.g. cache-version checks) that need to reconcile
     /// against the model that built this index.
     backend: EmbeddingBackend,
+    /// When the vector column gets an ANN index, checked after each
+    /// indexing pass
+    ann_index: AnnIndexConfig,
+    /// Where runs checkpoint the Merkle snapshot and metadata cache
+    /// before changing anything; `None` disables rollback
+    backup: Option<Arc<dyn Backup>>,
+    /// Chunks the current run added to the stores, removed again when the
+    /// run fails
+    run_chunk_ids: Vec<ChunkId>,
 }
 
 impl UnifiedIndexer {
@@ -154,6 +183,9 @@ impl UnifiedIndexer {
             vector_store,
             metrics: IndexingMetrics::new(),
             backend,
+            ann_index: AnnIndexConfig::default(),
+            backup: None,
+            run_chunk_ids: Vec::new(),
         })
     }
 
@@ -162,6 +194,146 @@ impl UnifiedIndexer {
         &self.backend
     }
 
+    /// Replace the default ANN index policy for the vector store (see
+    /// [`VectorIndexSettings`](crate::indexing::VectorIndexSettings)).
+    pub fn with_ann_index(mut self, config: AnnIndexConfig) -> Self {
+        self.ann_index = config;
+        self
+    }
+
+    /// Checkpoint runs into `backup` so they can be rolled back.
+    pub fn with_backup(mut self, backup: Arc<dyn Backup>) -> Self {
+        self.backup = Some(backup);
+        self
+    }
+
+    /// Back up the Merkle snapshot at `snapshot_path` and the metadata
+    /// cache, then mark a run of `changed_files` files as in progress.
+    ///
+    /// Call before the run changes either store. A run with nothing to
+    /// change writes no backup, and a [`RunTrigger::Watcher`] run reuses a
+    /// recent one. A backup that cannot be written is logged and the run
+    /// goes ahead without a rollback point.
+    pub(crate) fn begin_run(
+        &mut self,
+        snapshot_path: &Path,
+        changed_files: usize,
+        trigger: RunTrigger,
+    ) {
+        self.run_chunk_ids.clear();
+        if changed_files == 0 {
+            return;
+        }
+        let Some(backup) = &self.backup else {
+            return;
+        };
+        if trigger == RunTrigger::Watcher {
+            // Indexers are built per run, so the age comes from the store
+            let backup_age = match backup.latest_backup_at() {
+                Ok(at) => at.map(|at| SystemTime::now().duration_since(at).unwrap_or_default()),
+                Err(e) => {
+                    tracing::warn!("Failed to read the latest backup time: {:#}", e);
+                    None
+                }
+            };
+            if let Some(age) = backup_age.filter(|age| *age < WATCHER_BACKUP_INTERVAL) {
+                tracing::debug!("Watcher run reuses the backup taken {:?} ago", age);
+                if let Err(e) = backup.begin_run() {
+                    tracing::warn!("Failed to mark index run as started: {:#}", e);
+                }
+                return;
+            }
+        }
+        let result = IndexCheckpoint::capture(snapshot_path, &self.core)
+            .and_then(|checkpoint| backup.create_backup(&checkpoint))
+            .and_then(|_| backup.begin_run());
+        if let Err(e) = result {
+            tracing::warn!(
+                "Failed to back up index state, continuing without rollback: {:#}",
+                e
+            );
+        }
+    }
+
+    /// Clear the in-progress mark once the run's snapshot is saved.
+    pub(crate) fn end_run(&mut self) {
+        self.run_chunk_ids.clear();
+        if let Some(backup) = &self.backup {
+            if let Err(e) = backup.end_run() {
+                tracing::warn!("Failed to clear index run marker: {:#}", e);
+            }
+        }
+    }
+
+    /// Remove the chunks the current run added from Tantivy and the vector
+    /// store, ahead of rolling a failed run back.
+    ///
+    /// Chunks that were already stored before the run are kept: the
+    /// rolled-back cache may still describe them.
+    pub(crate) async fn discard_run_chunks(&mut self) -> Result<()> {
+        let chunk_ids = std::mem::take(&mut self.run_chunk_ids);
+        if chunk_ids.is_empty() {
+            return Ok(());
+        }
+        self.tantivy.delete_chunks(&chunk_ids)?;
+        self.tantivy.commit()?;
+        let count = chunk_ids.len();
+        self.vector_store
+            .delete_chunks(chunk_ids)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to delete chunks from vector store: {}", e))?;
+        tracing::info!("Removed {} chunks written by the failed run", count);
+        Ok(())
+    }
+
+    /// Restore the Merkle snapshot at `snapshot_path` and the metadata
+    /// cache from the backup named `id` (the newest when `None`).
+    ///
+    /// The stores are left as they are (a failed run's own chunks go
+    /// through [`Self::discard_run_chunks`] first): the restored snapshot
+    /// and cache make the next run re-index every file changed since the
+    /// backup, which replaces whatever chunks those files have now. Returns the
+    /// restored backup's id, or `None` when there is no backup.
+    pub fn roll_back(&self, snapshot_path: &Path, id: Option<&str>) -> Result<Option<String>> {
+        let Some(backup) = &self.backup else {
+            return Ok(None);
+        };
+        let Some((id, checkpoint)) = backup.load_backup(id)? else {
+            backup.end_run()?;
+            return Ok(None);
+        };
+        checkpoint.apply(snapshot_path, &self.core)?;
+        backup.end_run()?;
+        tracing::info!(
+            "Rolled back index state to backup {} ({} cached files)",
+            id,
+            checkpoint.cached_files()
+        );
+        Ok(Some(id))
+    }
+
+    /// Create, refresh or retrain the vector store's ANN index as the
+    /// configured policy plans it for the current row count.
+    ///
+    /// With `rebuild` off (watcher passes) a due build or retrain is
+    /// deferred to the next full run, so the pass never blocks on one.
+    /// Failures are logged rather than returned: without an up-to-date
+    /// index, vector search still answers from a flat scan of the
+    /// unindexed rows, only slower.
+    pub async fn refresh_vector_index(&self, rebuild: bool) -> Option<AnnIndexAction> {
+        let config = AnnIndexConfig {
+            rebuild,
+            ..self.ann_index.clone()
+        };
+        match self.vector_store.ensure_vector_index(&config).await {
+            Ok(action) => Some(action),
+            Err(e) => {
+                tracing::warn!("Failed to update ANN vector index: {}", e);
+                None
+            }
+        }
+    }
+
     /// Index a single file to both Tantivy and vector store
     pub async fn index_file(&mut self, file_path: &Path) -> Result<IndexFileResult> {
         let file_start = Instant::now();
@@ -203,19 +375,26 @@ impl UnifiedIndexer {
             file_path.display()
         );
 
-        // Generate embeddings
-        let embeddings = self
+        // Generate embeddings, reusing stored vectors for unchanged chunks
+        let ChunkEmbeddings {
+            embeddings,
+            reused,
+            fresh_ids,
+        } = self
             .core
-            .generate_embeddings_batched(&processed.chunks)
+            .generate_embeddings_reusing(&processed.chunks, &self.vector_store)
             .await?;
 
-        tracing::debug!("Generated {} embeddings", embeddings.len());
+        tracing::debug!("Generated {} embeddings ({} reused)", embeddings.len(), reused);
 
-        // Index to both stores
+        // Index to both stores, replacing whatever the file had before
         let chunks_count = processed.chunks.len();
+        self.tantivy.delete_file_chunks(file_path)?;
+        self.run_chunk_ids.extend(fresh_ids);
         self.tantivy.index_chunks(&processed.chunks)?;
 
         // Index to vector store
+        let chunk_ids: Vec<ChunkId> = processed.chunks.iter().map(|chunk| chunk.id).collect();
         let chunk_data: Vec<(ChunkId, Vec<f32>, CodeChunk)> = processed
             .chunks
             .into_iter()
@@ -224,6 +403,11 @@ impl UnifiedIndexer {
             .collect();
         self.vector_store.upsert_chunks(chunk_data).await
             .map_err(|e| anyhow::anyhow!("Failed to index chunks to vector store: {}", e))?;
+        self.vector_store
+            .delete_stale_chunks(&[file_path.to_string_lossy().to_string()], &chunk_ids)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to delete stale chunks from vector store: {}", e))?;
+        self.metrics.reused_embeddings += reused;
 
         // Update metadata cache
         self.core.update_file_metadata(file_path, &content)?;
@@ -256,17 +440,17 @@ impl UnifiedIndexer {
         let mut stats = IndexStats::default();
         self.metrics = IndexingMetrics::new();
 
-        // Find all Rust files
-        let rust_files = collect_rust_files(dir_path, &mut stats)?;
+        // Find all indexable files (Rust sources plus TOML / markdown / text)
+        let source_files = collect_source_files(dir_path, &mut stats)?;
 
-        if rust_files.is_empty() {
+        if source_files.is_empty() {
             return Ok(stats);
         }
 
-        tracing::info!("Found {} Rust files in {}", rust_files.len(), dir_path.display());
+        tracing::info!("Found {} source files in {}", source_files.len(), dir_path.display());
 
         // Index each file
-        for file in rust_files {
+        for file in source_files {
             match self.index_file(&file).await {
                 Ok(IndexFileResult::Indexed { chunks_count }) => {
                     stats.indexed_files += 1;
@@ -287,6 +471,7 @@ impl UnifiedIndexer {
 
         // Commit Tantivy changes
         self.tantivy.commit().context("Failed to commit Tantivy index")?;
+        self.refresh_vector_index(true).await;
 
         // Finalize metrics
         self.finalize_metrics(&stats, total_start.elapsed());
@@ -302,37 +487,6 @@ impl UnifiedIndexer {
         Ok(stats)
     }
 
-    /// Index an entire directory with automatic backup management
-    pub(crate) async fn index_directory_with_backup(
-        &mut self,
-        dir_path: &Path,
-        backup_manager: Option<&dyn crate::indexing::backup::Backup>,
-    ) -> Result<IndexStats> {
-        let stats = self.index_directory(dir_path).await?;
-
-        if let Some(manager) = backup_manager {
-            if stats.indexed_files > 0 && stats.indexed_files % 100 == 0 {
-                tracing::info!(
-                    "Creating Merkle snapshot backup after {} indexed files",
-                    stats.indexed_files
-                );
-
-                match crate::indexing::merkle::FileSystemMerkle::from_directory(dir_path) {
-                    Ok(merkle) => {
-                        if let Err(e) = manager.create_backup(&merkle) {
-                            tracing::warn!("Failed to create backup: {}", e);
-                        }
-                    }
-                    Err(e) => {
-                        tracing::warn!("Failed to build Merkle tree for backup: {}", e);
-                    }
-                }
-            }
-        }
-
-        Ok(stats)
-    }
-
     /// Index an entire directory using parallel processing
     pub async fn index_directory_parallel(&mut self, dir_path: &Path) -> Result<IndexStats> {
         let total_start = Instant::now();
@@ -341,25 +495,25 @@ impl UnifiedIndexer {
         let mut stats = IndexStats::default();
         self.metrics = IndexingMetrics::new();
 
-        // Find all Rust files
-        let rust_files = collect_rust_files(dir_path, &mut stats)?;
+        // Find all indexable files (Rust sources plus TOML / markdown / text)
+        let source_files = collect_source_files(dir_path, &mut stats)?;
 
-        if rust_files.is_empty() {
+        if source_files.is_empty() {
             return Ok(stats);
         }
 
-        tracing::info!("Found {} Rust files, processing in parallel", rust_files.len());
+        tracing::info!("Found {} source files, processing in parallel", source_files.len());
 
         // Calculate safe batch size
         let batch_size = self.core.calculate_safe_batch_size()?;
         tracing::info!("Using batch size: {}", batch_size);
 
         // Process in batches
-        for (batch_idx, file_batch) in rust_files.chunks(batch_size).enumerate() {
+        for (batch_idx, file_batch) in source_files.chunks(batch_size).enumerate() {
             tracing::info!(
                 "Processing batch {}/{} ({} files)",
                 batch_idx + 1,
-                (rust_files.len() + batch_size - 1) / batch_size,
+                (source_files.len() + batch_size - 1) / batch_size,
                 file_batch.len()
             );
 
@@ -398,6 +552,7 @@ impl UnifiedIndexer {
             // Commit after each batch
             self.tantivy.commit()?;
         }
+        self.refresh_vector_index(true).await;
 
         // Finalize metrics
         self.finalize_metrics(&stats, total_start.elapsed());
@@ -422,6 +577,71 @@ impl UnifiedIndexer {
         Ok(())
     }
 
+    /// Compare Tantivy, the vector store and the metadata cache chunk by
+    /// chunk and file by file
+    pub async fn check_consistency(&self) -> Result<ConsistencyReport> {
+        let cached_files = self.core.cached_files()?;
+        ConsistencyChecker::new(
+            self.tantivy.index().clone(),
+            self.vector_store.clone(),
+            self.tantivy.schema().clone(),
+        )
+        .check(&cached_files)
+        .await
+    }
+
+    /// Fix the drift a consistency check found
+    ///
+    /// Deleted files lose their chunks and cache entries. Orphaned and
+    /// unindexed files are re-indexed from scratch into both stores; one
+    /// that no longer yields chunks is dropped instead. A file that fails
+    /// is recorded and the repair moves on.
+    pub async fn repair_consistency(&mut self, report: &ConsistencyReport) -> Result<RepairSummary> {
+        let mut summary = RepairSummary::default();
+
+        for file_path in &report.deleted_files {
+            match self.remove_file(file_path).await {
+                Ok(()) => summary.removed_files.push(file_path.clone()),
+                Err(e) => {
+                    tracing::warn!("Failed to remove {}: {:#}", file_path.display(), e);
+                    summary.failed_files.push(RepairFailure {
+                        file_path: file_path.clone(),
+                        error: format!("{:#}", e),
+                    });
+                }
+            }
+        }
+
+        for file_path in report.files_to_reindex() {
+            match self.reindex_from_scratch(file_path).await {
+                Ok(IndexFileResult::Indexed { .. }) => {
+                    summary.reindexed_files.push(file_path.clone());
+                }
+                Ok(IndexFileResult::Unchanged | IndexFileResult::Skipped) => {
+                    summary.removed_files.push(file_path.clone());
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to re-index {}: {:#}", file_path.display(), e);
+                    summary.failed_files.push(RepairFailure {
+                        file_path: file_path.clone(),
+                        error: format!("{:#}", e),
+                    });
+                }
+            }
+        }
+
+        self.commit()?;
+        self.refresh_vector_index(true).await;
+
+        tracing::info!(
+            "✓ Repair complete: {} re-indexed, {} removed, {} failed",
+            summary.reindexed_files.len(),
+            summary.removed_files.len(),
+            summary.failed_files.len()
+        );
+        Ok(summary)
+    }
+
     /// Commit Tantivy changes
     pub fn commit(&mut self) -> Result<()> {
         self.tantivy.commit()
@@ -480,6 +700,25 @@ impl UnifiedIndexer {
 
     // Private helper methods
 
+    /// Drop a deleted file's chunks from both stores and its cache entry
+    async fn remove_file(&mut self, file_path: &Path) -> Result<()> {
+        self.delete_file_chunks(file_path).await?;
+        self.core.forget_file(file_path)?;
+        Ok(())
+    }
+
+    /// Re-index `file_path` ignoring its cache entry; a file that yields no
+    /// chunks has its old ones dropped
+    async fn reindex_from_scratch(&mut self, file_path: &Path) -> Result<IndexFileResult> {
+        // Without its cache entry the file cannot be skipped as unchanged
+        self.core.forget_file(file_path)?;
+        let result = self.index_file(file_path).await?;
+        if !matches!(result, IndexFileResult::Indexed { .. }) {
+            self.delete_file_chunks(file_path).await?;
+        }
+        Ok(result)
+    }
+
     async fn process_and_index_batch(
         &mut self,
         processed: &[crate::indexing::indexer_core::ProcessedFile],
@@ -501,18 +740,25 @@ impl UnifiedIndexer {
             processed.len()
         );
 
-        // Generate embeddings in GPU-optimized batches
-        let all_embeddings = self
+        // Generate embeddings in GPU-optimized batches, reusing stored
+        // vectors for chunks whose content-addressed ID is unchanged
+        let ChunkEmbeddings {
+            embeddings: all_embeddings,
+            reused,
+            fresh_ids,
+        } = self
             .core
-            .generate_embeddings_batched(&all_chunks)
+            .generate_embeddings_reusing(&all_chunks, &self.vector_store)
             .await?;
 
         let embed_duration = embed_start.elapsed();
         self.metrics.embed_duration += embed_duration;
+        self.metrics.reused_embeddings += reused;
 
         tracing::info!(
-            "Generated {} embeddings in {:.2}s ({:.1} chunks/sec)",
+            "Generated {} embeddings ({} reused) in {:.2}s ({:.1} chunks/sec)",
             all_embeddings.len(),
+            reused,
             embed_duration.as_secs_f64(),
             all_embeddings.len() as f64 / embed_duration.as_secs_f64()
         );
@@ -521,11 +767,17 @@ impl UnifiedIndexer {
         // Instead of N file-by-file calls, make single batched calls to each store
         let index_start = Instant::now();
 
-        // Batch all chunks for Tantivy (single call instead of N calls)
+        // Batch all chunks for Tantivy (single call instead of N calls),
+        // replacing whatever the files had before
         tracing::debug!("Indexing {} chunks to Tantivy...", all_chunks.len());
+        for processed_file in processed {
+            self.tantivy.delete_file_chunks(&processed_file.path)?;
+        }
+        self.run_chunk_ids.extend(fresh_ids);
         self.tantivy.index_chunks(&all_chunks)?;
 
         // Prepare all chunk data for vector store (single batch)
+        let all_chunk_ids: Vec<ChunkId> = all_chunks.iter().map(|chunk| chunk.id).collect();
         let all_chunk_data: Vec<(ChunkId, Vec<f32>, CodeChunk)> = all_chunks
             .into_iter()
             .zip(all_embeddings.into_iter())
@@ -539,6 +791,16 @@ impl UnifiedIndexer {
             .await
             .map_err(|e| anyhow::anyhow!("Failed to batch index chunks to vector store: {}", e))?;
 
+        // Drop rows left over from the files' previous content
+        let file_paths: Vec<String> = processed
+            .iter()
+            .map(|processed_file| processed_file.path.to_string_lossy().to_string())
+            .collect();
+        self.vector_store
+            .delete_stale_chunks(&file_paths, &all_chunk_ids)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to delete stale chunks from vector store: {}", e))?;
+
         let index_duration = index_start.elapsed();
         self.metrics.index_duration += index_duration;
 
@@ -666,4 +928,99 @@ pub fn test_function() {
         let count = vector_store.count().await.unwrap();
         assert!(count > 0, "Vector store should have at least one vector");
     }
+
+    /// Counts backups and remembers when the last was taken; never holds
+    /// one to load
+    #[derive(Default)]
+    struct CountingBackup {
+        created: std::sync::atomic::AtomicUsize,
+        latest: std::sync::Mutex<Option<SystemTime>>,
+    }
+
+    impl Backup for CountingBackup {
+        fn create_backup(&self, _checkpoint: &IndexCheckpoint) -> Result<std::path::PathBuf> {
+            self.created.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            *self.latest.lock().unwrap() = Some(SystemTime::now());
+            Ok(std::path::PathBuf::new())
+        }
+
+        fn load_backup(&self, _id: Option<&str>) -> Result<Option<(String, IndexCheckpoint)>> {
+            Ok(None)
+        }
+
+        fn latest_backup_at(&self) -> Result<Option<SystemTime>> {
+            Ok(*self.latest.lock().unwrap())
+        }
+
+        fn begin_run(&self) -> Result<()> {
+            Ok(())
+        }
+
+        fn end_run(&self) -> Result<()> {
+            Ok(())
+        }
+
+        fn interrupted_run(&self) -> bool {
+            false
+        }
+    }
+
+    #[tokio::test]
+    #[ignore] // Requires embedding model
+    async fn test_backups_skip_empty_runs_and_throttle_watcher_runs() {
+        let temp_dir = TempDir::new().unwrap();
+        let backend = EmbeddingBackend::default();
+        let backup = Arc::new(CountingBackup::default());
+        let mut indexer = UnifiedIndexer::for_embedded(
+            &temp_dir.path().join("cache"),
+            &temp_dir.path().join("tantivy"),
+            "test_backup_throttle",
+            backend.dim(),
+            &backend.identity(),
+            None,
+        )
+        .await
+        .unwrap()
+        .with_backup(backup.clone());
+        let snapshot_path = temp_dir.path().join("merkle.snapshot");
+        let created = || backup.created.load(std::sync::atomic::Ordering::SeqCst);
+
+        indexer.begin_run(&snapshot_path, 0, RunTrigger::Full);
+        assert_eq!(created(), 0);
+        indexer.begin_run(&snapshot_path, 3, RunTrigger::Watcher);
+        assert_eq!(created(), 1);
+        indexer.begin_run(&snapshot_path, 3, RunTrigger::Watcher);
+        assert_eq!(created(), 1);
+        indexer.begin_run(&snapshot_path, 3, RunTrigger::Full);
+        assert_eq!(created(), 2);
+    }
+
+    #[tokio::test]
+    #[ignore] // Requires embedding model
+    async fn test_discard_run_chunks_removes_the_runs_chunks() {
+        let temp_dir = TempDir::new().unwrap();
+        let backend = EmbeddingBackend::default();
+        let mut indexer = UnifiedIndexer::for_embedded(
+            &temp_dir.path().join("cache"),
+            &temp_dir.path().join("tantivy"),
+            "test_discard_run_chunks",
+            backend.dim(),
+            &backend.identity(),
+            None,
+        )
+        .await
+        .unwrap();
+        let test_file = temp_dir.path().join("test.rs");
+        std::fs::write(&test_file, "pub fn test_function() {}\n").unwrap();
+
+        indexer.begin_run(&temp_dir.path().join("merkle.snapshot"), 1, RunTrigger::Full);
+        indexer.index_file(&test_file).await.unwrap();
+        indexer.commit().unwrap();
+        assert!(indexer.vector_store_cloned().count().await.unwrap() > 0);
+
+        indexer.discard_run_chunks().await.unwrap();
+        assert_eq!(indexer.vector_store_cloned().count().await.unwrap(), 0);
+        let bm25 = indexer.create_bm25_search().unwrap();
+        assert!(bm25.search("test_function", 10).unwrap().is_empty());
+    }
 }
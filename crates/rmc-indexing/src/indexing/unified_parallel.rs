@@ -11,40 +11,30 @@ use crate::indexing::indexer_core::{IndexerCore, ProcessedFile};
 use crate::indexing::unified::IndexStats;
 use anyhow::Result;
 use rayon::prelude::*;
+use rmc_engine::chunker::FileKind;
+use rmc_config::ignore_engine::IgnoreEngine;
 use std::path::{Path, PathBuf};
-use walkdir::WalkDir;
 
-/// Walk `dir_path` and return all reachable `*.rs` files, skipping common
-/// VCS / build / generated directories (`target`, `vendor`, `.git`, `.jj`,
-/// `.direnv`, `.skeleton`).
+/// Walk `dir_path` and return all reachable indexable files (`*.rs` plus the
+/// TOML / markdown / text files [`FileKind::for_path`] knows) that the
+/// project's ignore rules keep: `.gitignore`, `.rmcignore`, the `[index]`
+/// globs of `.rmc.toml` and the built-in VCS / build / generated directory
+/// excludes (see [`IgnoreEngine`]).
 ///
 /// Pure traversal: does not touch `UnifiedIndexer` state. The caller passes
 /// in `stats` so we can populate `total_files` in one place.
-pub(super) fn collect_rust_files(
+pub(super) fn collect_source_files(
     dir_path: &Path,
     stats: &mut IndexStats,
 ) -> Result<Vec<PathBuf>> {
-    let mut rust_files = Vec::new();
+    let ignores = IgnoreEngine::load(dir_path)?;
+    let mut source_files = Vec::new();
     let mut walk_errors = 0;
 
-    let walker = WalkDir::new(dir_path)
-        .into_iter()
-        .filter_entry(|entry| {
-            let name = entry.file_name().to_string_lossy();
-            !(entry.file_type().is_dir()
-                && matches!(
-                    name.as_ref(),
-                    "target" | "vendor" | ".git" | ".jj" | ".direnv" | ".skeleton"
-                ))
-        });
-
-    for entry in walker {
+    for entry in ignores.walk(dir_path) {
         match entry {
-            Ok(e)
-                if e.file_type().is_file()
-                    && e.path().extension() == Some(std::ffi::OsStr::new("rs")) =>
-            {
-                rust_files.push(e.path().to_path_buf());
+            Ok(e) if e.file_type().is_file() && FileKind::is_indexed(e.path()) => {
+                source_files.push(e.path().to_path_buf());
             }
             Ok(_) => {}
             Err(err) => {
@@ -62,8 +52,8 @@ pub(super) fn collect_rust_files(
         );
     }
 
-    stats.total_files = rust_files.len();
-    Ok(rust_files)
+    stats.total_files = source_files.len();
+    Ok(source_files)
 }
 
 #[cfg(test)]
@@ -73,7 +63,7 @@ mod tests {
     use tempfile::TempDir;
 
     #[test]
-    fn collect_rust_files_skips_generated_skeleton_tree() {
+    fn collect_source_files_skips_generated_skeleton_tree() {
         let temp_dir = TempDir::new().expect("temp dir");
         let root = temp_dir.path();
         let src = root.join("src");
@@ -85,11 +75,54 @@ mod tests {
             .expect("write generated source");
 
         let mut stats = IndexStats::default();
-        let files = collect_rust_files(root, &mut stats).expect("collect rust files");
+        let files = collect_source_files(root, &mut stats).expect("collect source files");
 
         assert_eq!(stats.total_files, 1);
         assert_eq!(files, vec![src.join("lib.rs")]);
     }
+
+    #[test]
+    fn collect_source_files_includes_toml_and_docs() {
+        let temp_dir = TempDir::new().expect("temp dir");
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("src")).expect("create src");
+        fs::create_dir_all(root.join("docs")).expect("create docs");
+        fs::write(root.join("Cargo.toml"), "[package]\nname = \"demo\"\n").expect("write manifest");
+        fs::write(root.join("Cargo.lock"), "version = 3\n").expect("write lockfile");
+        fs::write(root.join("docs/design.md"), "# Design\n").expect("write docs");
+        fs::write(root.join("src/lib.rs"), "pub fn real() {}\n").expect("write source");
+        fs::write(root.join("src/logo.png"), [0u8; 4]).expect("write binary");
+
+        let mut stats = IndexStats::default();
+        let mut files = collect_source_files(root, &mut stats).expect("collect source files");
+        files.sort();
+
+        assert_eq!(
+            files,
+            vec![root.join("Cargo.toml"), root.join("docs/design.md"), root.join("src/lib.rs")]
+        );
+    }
+
+    #[test]
+    fn collect_source_files_honors_ignore_files_and_project_config() {
+        let temp_dir = TempDir::new().expect("temp dir");
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("src/generated")).expect("create generated");
+        fs::create_dir_all(root.join("tests/fixtures/big")).expect("create fixtures");
+        fs::write(root.join(".gitignore"), "/src/generated/\n").expect("write gitignore");
+        fs::write(root.join(".rmc.toml"), "[index]\nexclude = [\"tests/fixtures/**\"]\n")
+            .expect("write project config");
+        fs::write(root.join("src/lib.rs"), "pub fn real() {}\n").expect("write source");
+        fs::write(root.join("src/generated/api.rs"), "pub fn gen() {}\n")
+            .expect("write generated source");
+        fs::write(root.join("tests/fixtures/big/case.rs"), "fn case() {}\n")
+            .expect("write fixture");
+
+        let mut stats = IndexStats::default();
+        let files = collect_source_files(root, &mut stats).expect("collect source files");
+
+        assert_eq!(files, vec![root.join(".rmc.toml"), root.join("src/lib.rs")]);
+    }
 }
 
 /// PHASE 1 of `index_directory_parallel`: parse and chunk a batch of files
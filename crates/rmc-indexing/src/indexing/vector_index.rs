@@ -0,0 +1,144 @@
+//! Per-workspace ANN vector index settings.
+//!
+//! The `[vector_index]` table of `.rmc.toml` at the workspace root picks the
+//! index the indexer builds on the vector column and how searches query it:
+//!
+//! ```toml
+//! [vector_index]
+//! enabled = true          # false: never build an index, always scan
+//! kind = "ivf_hnsw_sq"    # or "ivf_pq" (default)
+//! min_rows = 50000        # rows before the first index is built
+//! nprobes = 32            # IVF partitions probed per query
+//! refine_factor = 10      # re-rank limit * refine_factor with exact distances
+//! ```
+//!
+//! An unconfigured workspace gets [`AnnIndexConfig::default`] and, since that
+//! builds a PQ index, queries refine with [`AnnIndexKind::default_refine_factor`]
+//! so quantized distances never reach fusion unrefined.
+
+use std::path::Path;
+
+use anyhow::{Context, Result, anyhow};
+use rmc_config::ignore_engine::PROJECT_CONFIG_FILE;
+use rmc_engine::vector_store::{AnnIndexConfig, AnnIndexKind, AnnSearchParams};
+use serde::Deserialize;
+
+/// The `[vector_index]` table of `.rmc.toml`; see the module docs
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct VectorIndexSettings {
+    /// Build and maintain an ANN index at all
+    pub enabled: Option<bool>,
+    /// `ivf_pq` or `ivf_hnsw_sq`
+    pub kind: Option<String>,
+    /// Rows the table must hold before the first index is built
+    pub min_rows: Option<usize>,
+    /// IVF partitions probed per query; unset uses LanceDB's default
+    pub nprobes: Option<usize>,
+    /// Exact re-rank multiplier; unset uses the index kind's default
+    pub refine_factor: Option<u32>,
+}
+
+/// `.rmc.toml`; other tables are left to their own readers
+#[derive(Debug, Default, Deserialize)]
+struct ProjectConfig {
+    #[serde(default)]
+    vector_index: VectorIndexSettings,
+}
+
+impl VectorIndexSettings {
+    /// Settings from `workspace_root/.rmc.toml`, or the defaults when the
+    /// file or table is absent
+    pub fn load(workspace_root: &Path) -> Result<Self> {
+        let path = workspace_root.join(PROJECT_CONFIG_FILE);
+        let source = match std::fs::read_to_string(&path) {
+            Ok(source) => source,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => {
+                return Err(e).with_context(|| format!("failed to read {}", path.display()));
+            }
+        };
+        let config: ProjectConfig =
+            toml::from_str(&source).with_context(|| format!("invalid {}", path.display()))?;
+        let settings = config.vector_index;
+        settings
+            .kind()
+            .with_context(|| format!("invalid [vector_index] in {}", path.display()))?;
+        Ok(settings)
+    }
+
+    /// Configured index kind, or the default one
+    pub fn kind(&self) -> Result<AnnIndexKind> {
+        match &self.kind {
+            None => Ok(AnnIndexKind::default()),
+            Some(kind) => AnnIndexKind::parse(kind).ok_or_else(|| {
+                anyhow!("unknown kind `{kind}`; expected `ivf_pq` or `ivf_hnsw_sq`")
+            }),
+        }
+    }
+
+    /// Index policy for the indexer
+    pub fn ann_index_config(&self) -> Result<AnnIndexConfig> {
+        let defaults = AnnIndexConfig::default();
+        Ok(AnnIndexConfig {
+            enabled: self.enabled.unwrap_or(defaults.enabled),
+            kind: self.kind()?,
+            min_rows: self.min_rows.unwrap_or(defaults.min_rows),
+            ..defaults
+        })
+    }
+
+    /// Query knobs for searches against the index
+    pub fn search_params(&self) -> Result<AnnSearchParams> {
+        Ok(AnnSearchParams::for_kind(
+            self.kind()?,
+            self.nprobes,
+            self.refine_factor,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmc_engine::vector_store::DEFAULT_PQ_REFINE_FACTOR;
+    use tempfile::TempDir;
+
+    #[test]
+    fn unconfigured_workspace_refines_the_default_pq_index() {
+        let dir = TempDir::new().unwrap();
+        let settings = VectorIndexSettings::load(dir.path()).unwrap();
+        assert_eq!(settings, VectorIndexSettings::default());
+        assert_eq!(settings.ann_index_config().unwrap().kind, AnnIndexKind::IvfPq);
+        assert_eq!(
+            settings.search_params().unwrap().refine_factor,
+            Some(DEFAULT_PQ_REFINE_FACTOR)
+        );
+    }
+
+    #[test]
+    fn reads_the_vector_index_table() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join(PROJECT_CONFIG_FILE),
+            "[index]\nexclude = [\"gen/**\"]\n\n\
+             [vector_index]\nkind = \"ivf_hnsw_sq\"\nmin_rows = 1000\nnprobes = 32\n",
+        )
+        .unwrap();
+        let settings = VectorIndexSettings::load(dir.path()).unwrap();
+        let config = settings.ann_index_config().unwrap();
+        assert_eq!(config.kind, AnnIndexKind::IvfHnswSq);
+        assert_eq!(config.min_rows, 1000);
+        assert!(config.enabled && config.rebuild);
+        let params = settings.search_params().unwrap();
+        assert_eq!(params.nprobes, Some(32));
+        assert_eq!(params.refine_factor, None);
+
+        std::fs::write(
+            dir.path().join(PROJECT_CONFIG_FILE),
+            "[vector_index]\nkind = \"flat\"\n",
+        )
+        .unwrap();
+        assert!(VectorIndexSettings::load(dir.path()).is_err());
+    }
+}
@@ -145,6 +145,31 @@ impl MetadataCache {
         Ok(files)
     }
 
+    /// Raw entries whose key starts with `prefix`, for backups
+    pub(crate) fn export_prefix(&self, prefix: &str) -> Result<Vec<(String, Vec<u8>)>, Box<dyn std::error::Error>> {
+        let mut entries = Vec::new();
+        for item in self.db.scan_prefix(prefix) {
+            let (key, value) = item?;
+            entries.push((String::from_utf8(key.to_vec())?, value.to_vec()));
+        }
+        Ok(entries)
+    }
+
+    /// Replace every entry under `prefix` with `entries` (as returned by
+    /// [`Self::export_prefix`]) and flush to disk
+    pub(crate) fn replace_prefix(&self, prefix: &str, entries: &[(String, Vec<u8>)]) -> Result<(), Box<dyn std::error::Error>> {
+        let mut batch = sled::Batch::default();
+        for item in self.db.scan_prefix(prefix).keys() {
+            batch.remove(item?);
+        }
+        for (key, value) in entries {
+            batch.insert(key.as_bytes(), value.as_slice());
+        }
+        self.db.apply_batch(batch)?;
+        self.db.flush()?;
+        Ok(())
+    }
+
     /// Clear all cached metadata (useful for re-indexing from scratch)
     pub(crate) fn clear(&self) -> Result<(), sled::Error> {
         self.db.clear()
@@ -263,6 +288,24 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_metadata_cache_replace_prefix_keeps_other_prefixes() -> Result<(), Box<dyn std::error::Error>> {
+        let temp_dir = TempDir::new()?;
+        let cache = MetadataCache::new(&temp_dir.path().join("cache"))?;
+        let metadata = FileMetadata::from_content("test", 123, 10);
+        cache.set("a::kept.rs", &metadata)?;
+        let backup = cache.export_prefix("a::")?;
+        cache.set("a::added.rs", &metadata)?;
+        cache.set("b::other.rs", &metadata)?;
+
+        cache.replace_prefix("a::", &backup)?;
+
+        assert!(cache.get("a::kept.rs")?.is_some());
+        assert!(cache.get("a::added.rs")?.is_none());
+        assert!(cache.get("b::other.rs")?.is_some());
+        Ok(())
+    }
+
     #[test]
     fn test_metadata_cache_persistence() -> Result<(), Box<dyn std::error::Error>> {
         let temp_dir = TempDir::new()?;
@@ -26,6 +26,9 @@ pub struct IndexingMetrics {
     pub embed_duration: Duration,
     pub index_duration: Duration,
 
+    // Chunks whose stored embedding was reused instead of re-embedded
+    pub reused_embeddings: usize,
+
     // Per-file latencies (for percentile calculation)
     pub file_latencies: Vec<Duration>,
 
@@ -130,6 +133,7 @@ impl IndexingMetrics {
             parse_percent,
             embed_duration_secs = self.embed_duration.as_secs_f64(),
             embed_percent,
+            reused_embeddings = self.reused_embeddings,
             index_duration_secs = self.index_duration.as_secs_f64(),
             index_percent,
             peak_memory_mb = self.peak_memory_bytes as f64 / 1_000_000.0,
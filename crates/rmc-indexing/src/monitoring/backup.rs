@@ -1,14 +1,55 @@
 //! Backup and restore functionality for Merkle tree snapshots
 //!
-//! Provides automatic backup rotation with configurable retention
+//! Provides automatic backup rotation with configurable retention. Each
+//! backup is a directory holding the Merkle snapshot and the metadata-cache
+//! entries of one project/profile, taken before an index run, plus a small
+//! summary of both so listing backups reads neither; a marker file records
+//! the run in progress so an interrupted one can be rolled back.
 
-use crate::indexing::FileSystemMerkle;
+use crate::indexing::{Backup, FileSystemMerkle, IndexCheckpoint};
 use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tracing;
 
+/// Backups kept per project/profile by default
+pub const DEFAULT_BACKUP_RETENTION: usize = 7;
+
+const BACKUP_PREFIX: &str = "backup_";
+const SNAPSHOT_FILE: &str = "merkle.snapshot";
+const METADATA_FILE: &str = "metadata.bin";
+const SUMMARY_FILE: &str = "summary.json";
+const RUN_MARKER_FILE: &str = "run.in_progress";
+
+/// Summary of one stored backup
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupInfo {
+    /// Name to pass to a restore
+    pub id: String,
+    /// Unix time the backup was taken, in milliseconds
+    pub created_at_ms: u64,
+    /// What the backup holds; `None` when it could not be read
+    #[serde(flatten)]
+    pub contents: Option<BackupContents>,
+    /// Why `contents` is missing
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Entry counts of one backup, written alongside it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupContents {
+    /// Files in the backed-up Merkle snapshot; `None` when the backup
+    /// predates the first index of the project
+    pub snapshot_files: Option<usize>,
+    /// Snapshot version, when there is a snapshot
+    pub snapshot_version: Option<u64>,
+    pub cached_files: usize,
+}
+
 /// Manages backups of Merkle tree snapshots
-pub(crate) struct BackupManager {
+pub struct BackupManager {
     backup_dir: PathBuf,
     retention_count: usize,
 }
@@ -18,35 +59,51 @@ impl BackupManager {
     ///
     /// # Arguments
     /// * `backup_dir` - Directory to store backups
-    /// * `retention_count` - Number of backups to keep (default: 7)
-    pub(crate) fn new(backup_dir: PathBuf, retention_count: usize) -> Result<Self> {
+    /// * `retention_count` - Number of backups to keep (default: [`DEFAULT_BACKUP_RETENTION`])
+    pub fn new(backup_dir: PathBuf, retention_count: usize) -> Result<Self> {
         std::fs::create_dir_all(&backup_dir)
             .context(format!("Failed to create backup directory: {}", backup_dir.display()))?;
 
         Ok(Self {
             backup_dir,
-            retention_count,
+            retention_count: retention_count.max(1),
         })
     }
 
-    /// Create a backup of the Merkle snapshot
+    /// Create a backup of an index checkpoint
     ///
-    /// Returns the path to the created backup file.
+    /// Returns the path to the created backup directory.
     /// Automatically rotates old backups according to retention policy.
-    pub(crate) fn create_backup(&self, merkle: &FileSystemMerkle) -> Result<PathBuf> {
-        let timestamp = std::time::SystemTime::now()
+    pub fn create_backup(&self, checkpoint: &IndexCheckpoint) -> Result<PathBuf> {
+        let mut timestamp = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .context("Failed to get system time")?
-            .as_secs();
+            .as_millis() as u64;
+        // Two runs within the same millisecond must not share a backup
+        while self.backup_path(timestamp).exists() {
+            timestamp += 1;
+        }
 
-        let backup_path = self.backup_dir.join(format!(
-            "merkle_v{}.{}.snapshot",
-            merkle.version(),
-            timestamp
-        ));
+        let backup_path = self.backup_path(timestamp);
+        let tmp_path = self.backup_dir.join(format!(".{BACKUP_PREFIX}{timestamp}.tmp"));
+        std::fs::create_dir_all(&tmp_path)
+            .context(format!("Failed to create {}", tmp_path.display()))?;
 
-        merkle
-            .save_snapshot(&backup_path)
+        if let Some(snapshot) = &checkpoint.snapshot {
+            std::fs::write(tmp_path.join(SNAPSHOT_FILE), snapshot)
+                .context("Failed to write snapshot backup")?;
+        }
+        let metadata = bincode::serialize(&checkpoint.metadata)
+            .context("Failed to serialize metadata cache backup")?;
+        std::fs::write(tmp_path.join(METADATA_FILE), metadata)
+            .context("Failed to write metadata cache backup")?;
+        let summary = serde_json::to_vec(&summarize(&tmp_path, checkpoint))
+            .context("Failed to serialize backup summary")?;
+        std::fs::write(tmp_path.join(SUMMARY_FILE), summary)
+            .context("Failed to write backup summary")?;
+
+        // A backup only becomes visible once complete
+        std::fs::rename(&tmp_path, &backup_path)
             .context(format!("Failed to save backup to {}", backup_path.display()))?;
 
         // Rotate old backups
@@ -58,73 +115,136 @@ impl BackupManager {
         Ok(backup_path)
     }
 
+    /// Load the backup named `id`
+    pub fn load(&self, id: &str) -> Result<IndexCheckpoint> {
+        if parse_backup_id(id).is_none() {
+            anyhow::bail!("Invalid backup id: {id}");
+        }
+        let path = self.backup_dir.join(id);
+        if !path.is_dir() {
+            anyhow::bail!("No backup named {id} in {}", self.backup_dir.display());
+        }
+
+        let snapshot_path = path.join(SNAPSHOT_FILE);
+        let snapshot = if snapshot_path.exists() {
+            Some(std::fs::read(&snapshot_path)
+                .context(format!("Failed to read {}", snapshot_path.display()))?)
+        } else {
+            None
+        };
+        let metadata_path = path.join(METADATA_FILE);
+        let metadata = std::fs::read(&metadata_path)
+            .context(format!("Failed to read {}", metadata_path.display()))?;
+        let metadata = bincode::deserialize(&metadata)
+            .context(format!("Failed to parse {}", metadata_path.display()))?;
+
+        Ok(IndexCheckpoint { snapshot, metadata })
+    }
+
     /// Restore from the latest backup
     ///
-    /// Returns `Ok(Some(merkle))` if a backup was found and restored,
+    /// Returns `Ok(Some((id, checkpoint)))` if a backup was found,
     /// `Ok(None)` if no backups exist.
-    pub(crate) fn restore_latest(&self) -> Result<Option<FileSystemMerkle>> {
-        let mut backups = self.list_backups()?;
-
-        if backups.is_empty() {
+    pub fn restore_latest(&self) -> Result<Option<(String, IndexCheckpoint)>> {
+        let Some(latest) = self.backup_ids()?.pop() else {
             tracing::info!("No backups found in {}", self.backup_dir.display());
             return Ok(None);
+        };
+
+        tracing::info!("Restoring from backup: {}", latest.1);
+        let checkpoint = self.load(&latest.1)?;
+        Ok(Some((latest.1, checkpoint)))
+    }
+
+    /// List all backups, newest first
+    ///
+    /// A backup whose summary can't be read is still listed, with the
+    /// reason in [`BackupInfo::error`].
+    pub fn list_backups(&self) -> Result<Vec<BackupInfo>> {
+        Ok(self
+            .backup_ids()?
+            .into_iter()
+            .rev()
+            .map(|(created_at_ms, id)| {
+                let (contents, error) = match self.contents(&id) {
+                    Ok(contents) => (Some(contents), None),
+                    Err(e) => (None, Some(format!("{e:#}"))),
+                };
+                BackupInfo { id, created_at_ms, contents, error }
+            })
+            .collect())
+    }
+
+    /// Entry counts of backup `id`, from its summary file. Backups written
+    /// before summaries existed are loaded and counted instead.
+    fn contents(&self, id: &str) -> Result<BackupContents> {
+        let path = self.backup_dir.join(id);
+        let summary_path = path.join(SUMMARY_FILE);
+        if !summary_path.exists() {
+            return Ok(summarize(&path, &self.load(id)?));
         }
+        let summary = std::fs::read(&summary_path)
+            .context(format!("Failed to read {}", summary_path.display()))?;
+        serde_json::from_slice(&summary)
+            .context(format!("Failed to parse {}", summary_path.display()))
+    }
 
-        // Sort by modification time (newest first)
-        backups.sort_by_key(|e| {
-            e.metadata()
-                .and_then(|m| m.modified())
-                .unwrap_or(std::time::SystemTime::UNIX_EPOCH)
-        });
-        backups.reverse();
+    /// When the newest backup was taken, from its `backup_<millis>` id
+    pub fn latest_backup_at(&self) -> Result<Option<SystemTime>> {
+        Ok(self
+            .backup_ids()?
+            .pop()
+            .map(|(created_at_ms, _)| UNIX_EPOCH + Duration::from_millis(created_at_ms)))
+    }
 
-        let latest = backups
-            .first()
-            .context("No backups available")?
-            .path();
+    /// Mark an index run as started
+    pub fn begin_run(&self) -> Result<()> {
+        std::fs::write(self.run_marker(), b"")
+            .context(format!("Failed to write {}", self.run_marker().display()))
+    }
 
-        tracing::info!("Restoring from backup: {}", latest.display());
+    /// Clear the run marker
+    pub fn end_run(&self) -> Result<()> {
+        match std::fs::remove_file(self.run_marker()) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e).context(format!("Failed to remove {}", self.run_marker().display())),
+        }
+    }
 
-        FileSystemMerkle::load_snapshot(&latest)
-            .context(format!("Failed to load snapshot from {}", latest.display()))
+    /// Whether a run began and never ended
+    pub fn interrupted_run(&self) -> bool {
+        self.run_marker().exists()
     }
 
-    /// List all backup files
-    pub(crate) fn list_backups(&self) -> Result<Vec<std::fs::DirEntry>> {
-        let backups: Vec<_> = std::fs::read_dir(&self.backup_dir)
+    /// Backup ids with their timestamps, oldest first
+    fn backup_ids(&self) -> Result<Vec<(u64, String)>> {
+        let mut backups: Vec<(u64, String)> = std::fs::read_dir(&self.backup_dir)
             .context(format!("Failed to read backup directory: {}", self.backup_dir.display()))?
             .filter_map(|e| e.ok())
-            .filter(|e| {
-                e.path()
-                    .extension()
-                    .and_then(|ext| ext.to_str())
-                    == Some("snapshot")
+            .filter(|e| e.path().is_dir())
+            .filter_map(|e| {
+                let id = e.file_name().into_string().ok()?;
+                Some((parse_backup_id(&id)?, id))
             })
             .collect();
-
+        backups.sort();
         Ok(backups)
     }
 
     /// Remove old backups to maintain retention policy
     fn rotate_backups(&self) -> Result<()> {
-        let mut backups = self.list_backups()?;
+        let backups = self.backup_ids()?;
 
         if backups.len() <= self.retention_count {
             return Ok(());
         }
 
-        // Sort by modification time (oldest first)
-        backups.sort_by_key(|e| {
-            e.metadata()
-                .and_then(|m| m.modified())
-                .unwrap_or(std::time::SystemTime::UNIX_EPOCH)
-        });
-
         // Remove oldest backups
         let to_remove = backups.len() - self.retention_count;
-        for backup in backups.iter().take(to_remove) {
-            let path = backup.path();
-            std::fs::remove_file(&path)
+        for (_, id) in backups.iter().take(to_remove) {
+            let path = self.backup_dir.join(id);
+            std::fs::remove_dir_all(&path)
                 .context(format!("Failed to remove old backup: {}", path.display()))?;
             tracing::info!("Deleted old backup: {}", path.display());
         }
@@ -132,20 +252,71 @@ impl BackupManager {
         Ok(())
     }
 
+    fn backup_path(&self, timestamp: u64) -> PathBuf {
+        self.backup_dir.join(format!("{BACKUP_PREFIX}{timestamp}"))
+    }
+
+    fn run_marker(&self) -> PathBuf {
+        self.backup_dir.join(RUN_MARKER_FILE)
+    }
+
     /// Get the backup directory path
-    pub(crate) fn backup_dir(&self) -> &Path {
+    pub fn backup_dir(&self) -> &Path {
         &self.backup_dir
     }
 
     /// Get the retention count
-    pub(crate) fn retention_count(&self) -> usize {
+    pub fn retention_count(&self) -> usize {
         self.retention_count
     }
 }
 
-impl crate::indexing::backup::Backup for BackupManager {
-    fn create_backup(&self, merkle: &FileSystemMerkle) -> anyhow::Result<PathBuf> {
-        BackupManager::create_backup(self, merkle)
+/// Timestamp of a `backup_<millis>` id
+fn parse_backup_id(id: &str) -> Option<u64> {
+    id.strip_prefix(BACKUP_PREFIX)?.parse().ok()
+}
+
+/// Entry counts of `checkpoint`, whose snapshot is stored in `dir`. An
+/// unreadable snapshot is counted as none rather than failing the backup.
+fn summarize(dir: &Path, checkpoint: &IndexCheckpoint) -> BackupContents {
+    let snapshot = FileSystemMerkle::load_snapshot(&dir.join(SNAPSHOT_FILE))
+        .unwrap_or_else(|e| {
+            tracing::warn!("Failed to load snapshot backup in {}: {:#}", dir.display(), e);
+            None
+        });
+    BackupContents {
+        snapshot_files: snapshot.as_ref().map(FileSystemMerkle::file_count),
+        snapshot_version: snapshot.as_ref().map(FileSystemMerkle::version),
+        cached_files: checkpoint.cached_files(),
+    }
+}
+
+impl Backup for BackupManager {
+    fn create_backup(&self, checkpoint: &IndexCheckpoint) -> Result<PathBuf> {
+        BackupManager::create_backup(self, checkpoint)
+    }
+
+    fn load_backup(&self, id: Option<&str>) -> Result<Option<(String, IndexCheckpoint)>> {
+        match id {
+            Some(id) => Ok(Some((id.to_string(), self.load(id)?))),
+            None => self.restore_latest(),
+        }
+    }
+
+    fn latest_backup_at(&self) -> Result<Option<SystemTime>> {
+        BackupManager::latest_backup_at(self)
+    }
+
+    fn begin_run(&self) -> Result<()> {
+        BackupManager::begin_run(self)
+    }
+
+    fn end_run(&self) -> Result<()> {
+        BackupManager::end_run(self)
+    }
+
+    fn interrupted_run(&self) -> bool {
+        BackupManager::interrupted_run(self)
     }
 }
 
@@ -154,6 +325,13 @@ mod tests {
     use super::*;
     use tempfile::TempDir;
 
+    fn checkpoint(snapshot: Option<&[u8]>, files: &[&str]) -> IndexCheckpoint {
+        IndexCheckpoint {
+            snapshot: snapshot.map(<[u8]>::to_vec),
+            metadata: files.iter().map(|f| (f.to_string(), vec![1, 2, 3])).collect(),
+        }
+    }
+
     #[test]
     fn test_backup_manager_creation() {
         let temp_dir = TempDir::new().unwrap();
@@ -182,5 +360,80 @@ mod tests {
 
         let result = manager.restore_latest().unwrap();
         assert!(result.is_none());
+        assert!(manager.latest_backup_at().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_backups_round_trip_and_rotate() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = BackupManager::new(temp_dir.path().join("backups"), 2).unwrap();
+        let codebase = temp_dir.path().join("codebase");
+        std::fs::create_dir(&codebase).unwrap();
+        std::fs::write(codebase.join("lib.rs"), "fn main() {}").unwrap();
+        let snapshot_path = temp_dir.path().join("merkle.snapshot");
+        FileSystemMerkle::from_directory(&codebase)
+            .unwrap()
+            .save_snapshot(&snapshot_path)
+            .unwrap();
+        let snapshot = std::fs::read(&snapshot_path).unwrap();
+
+        manager.create_backup(&checkpoint(None, &["a"])).unwrap();
+        manager.create_backup(&checkpoint(None, &["a", "b"])).unwrap();
+        manager.create_backup(&checkpoint(Some(&snapshot), &["a", "b", "c"])).unwrap();
+
+        let backups = manager.list_backups().unwrap();
+        assert_eq!(backups.len(), 2);
+        let contents: Vec<&BackupContents> =
+            backups.iter().map(|b| b.contents.as_ref().unwrap()).collect();
+        assert_eq!(contents[0].cached_files, 3);
+        assert_eq!(contents[1].cached_files, 2);
+        assert!(backups[0].created_at_ms > backups[1].created_at_ms);
+        assert_eq!(contents[0].snapshot_files, Some(1));
+        assert_eq!(contents[1].snapshot_files, None);
+        assert_eq!(
+            manager.latest_backup_at().unwrap(),
+            Some(UNIX_EPOCH + Duration::from_millis(backups[0].created_at_ms))
+        );
+
+        let (id, latest) = manager.restore_latest().unwrap().unwrap();
+        assert_eq!(id, backups[0].id);
+        assert_eq!(latest.snapshot, Some(snapshot));
+        assert_eq!(manager.load(&backups[1].id).unwrap().metadata.len(), 2);
+        assert!(manager.load("../elsewhere").is_err());
+    }
+
+    #[test]
+    fn test_list_backups_reads_summaries_and_reports_bad_entries() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = BackupManager::new(temp_dir.path().to_path_buf(), 7).unwrap();
+        let bad = manager.create_backup(&checkpoint(None, &["a"])).unwrap();
+        let good = manager.create_backup(&checkpoint(None, &["a", "b"])).unwrap();
+        let legacy = manager.create_backup(&checkpoint(None, &["a", "b", "c"])).unwrap();
+
+        // Listing never touches the metadata cache of a summarized backup
+        std::fs::write(good.join(METADATA_FILE), b"not bincode").unwrap();
+        std::fs::write(bad.join(SUMMARY_FILE), b"not json").unwrap();
+        std::fs::remove_file(legacy.join(SUMMARY_FILE)).unwrap();
+
+        let backups = manager.list_backups().unwrap();
+        assert_eq!(backups.len(), 3);
+        assert_eq!(backups[0].contents.as_ref().unwrap().cached_files, 3);
+        assert_eq!(backups[1].contents.as_ref().unwrap().cached_files, 2);
+        assert!(backups[2].contents.is_none());
+        assert!(backups[2].error.as_deref().unwrap().contains(SUMMARY_FILE));
+        assert!(backups[..2].iter().all(|b| b.error.is_none()));
+    }
+
+    #[test]
+    fn test_run_marker() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = BackupManager::new(temp_dir.path().to_path_buf(), 7).unwrap();
+
+        assert!(!manager.interrupted_run());
+        manager.begin_run().unwrap();
+        assert!(manager.interrupted_run());
+        manager.end_run().unwrap();
+        manager.end_run().unwrap();
+        assert!(!manager.interrupted_run());
     }
 }
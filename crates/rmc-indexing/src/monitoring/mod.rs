@@ -3,4 +3,5 @@
 mod backup;
 mod health;
 
+pub use backup::{BackupContents, BackupInfo, BackupManager, DEFAULT_BACKUP_RETENTION};
 pub use health::{ComponentHealth, HealthMonitor, HealthStatus, Status};
@@ -8,6 +8,10 @@ use glob::Pattern;
 use std::path::Path;
 
 /// Filter for sensitive files that should not be indexed
+///
+/// Applied after `rmc_config::ignore_engine` as a second layer: VCS metadata,
+/// build output and cargo home stay excluded even when a `.rmcignore` `!`
+/// rule or an `[index] include` glob re-includes them.
 pub(crate) struct SensitiveFileFilter {
     excluded_patterns: Vec<Pattern>,
 }
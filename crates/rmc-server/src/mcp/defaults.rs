@@ -1,12 +1,35 @@
 //! Operational defaults for MCP server startup and automatic work.
 
+use std::time::Duration;
+
 use rmc_engine::embeddings::{EmbeddingBackend, EmbeddingRuntime};
 
 pub const BACKGROUND_SYNC_ENV: &str = "RMC_BACKGROUND_SYNC";
 pub const BACKGROUND_SYNC_ENABLED_VALUES: &str = "1/true/yes/on";
 pub const AUTOMATIC_EMBEDDING_PROFILE: &str = "local-cpu-small";
+pub const WATCH_ENV: &str = "RMC_WATCH";
+pub const WATCH_DEBOUNCE_MS_ENV: &str = "RMC_WATCH_DEBOUNCE_MS";
+pub const DEFAULT_WATCH_DEBOUNCE_MS: u64 = 500;
 
 pub fn parse_background_sync_env(value: Option<&str>) -> bool {
+    parse_enabled_flag(value)
+}
+
+/// Watch mode is opt-in with the same truthy values as background sync.
+pub fn parse_watch_env(value: Option<&str>) -> bool {
+    parse_enabled_flag(value)
+}
+
+/// Debounce window for watch mode; unset or unparsable values fall back to
+/// `DEFAULT_WATCH_DEBOUNCE_MS`.
+pub fn parse_watch_debounce_env(value: Option<&str>) -> Duration {
+    let millis = value
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .unwrap_or(DEFAULT_WATCH_DEBOUNCE_MS);
+    Duration::from_millis(millis)
+}
+
+fn parse_enabled_flag(value: Option<&str>) -> bool {
     let Some(value) = value else {
         return false;
     };
@@ -57,6 +80,24 @@ mod tests {
         assert!(parse_background_sync_env(Some(" on ")));
     }
 
+    #[test]
+    fn watch_env_is_opt_in_with_default_debounce() {
+        assert!(!parse_watch_env(None));
+        assert!(parse_watch_env(Some("on")));
+        assert_eq!(
+            parse_watch_debounce_env(None),
+            Duration::from_millis(DEFAULT_WATCH_DEBOUNCE_MS)
+        );
+        assert_eq!(
+            parse_watch_debounce_env(Some(" 1200 ")),
+            Duration::from_millis(1200)
+        );
+        assert_eq!(
+            parse_watch_debounce_env(Some("soon")),
+            Duration::from_millis(DEFAULT_WATCH_DEBOUNCE_MS)
+        );
+    }
+
     #[test]
     fn automatic_embedding_backend_is_cpu_profile() {
         let backend = automatic_embedding_backend();
@@ -5,12 +5,15 @@
 
 pub mod defaults;
 pub mod project_paths;
+pub mod resource_state;
 pub mod runtime;
 pub mod search_cache;
 pub mod sync;
+mod watch;
 pub mod workspace_locks;
 
 pub use defaults::*;
+pub use resource_state::*;
 pub use runtime::*;
 pub use search_cache::*;
 pub use sync::*;
@@ -4,12 +4,14 @@
 //! backend-resolution logic used by the tools layer.
 
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 use rmcp::ErrorData as McpError;
 use rmc_engine::embeddings::{EmbeddingBackend, resolve_profile};
 use rmc_indexing::indexing::{
-    IndexedProfilePaths as IndexingIndexedProfilePaths, IndexingProjectPaths,
+    Backup, IndexedProfilePaths as IndexingIndexedProfilePaths, IndexingProjectPaths,
 };
+use rmc_indexing::monitoring::{BackupManager, DEFAULT_BACKUP_RETENTION};
 use rmc_indexing::indexing::project_paths::{
     dir_hash as indexing_dir_hash,
     read_embedder_identity as read_indexing_embedder_identity,
@@ -28,6 +30,7 @@ pub struct ProjectPaths {
     pub snapshot_path: PathBuf,
     pub collection_name: String,
     pub vector_path: PathBuf,
+    pub backup_path: PathBuf,
 }
 
 /// An existing vector index discovered under a project's collection prefix.
@@ -48,6 +51,7 @@ impl From<IndexingProjectPaths> for ProjectPaths {
             snapshot_path: paths.snapshot_path,
             collection_name: paths.collection_name,
             vector_path: paths.vector_path,
+            backup_path: paths.backup_path,
         }
     }
 }
@@ -143,6 +147,24 @@ impl ProjectPaths {
             .collect())
     }
 
+    /// Open the backup store for this project/profile.
+    ///
+    /// Returns `None` (logged) when the backup directory cannot be
+    /// created; indexing then runs without a rollback point.
+    pub(crate) fn backup_store(&self) -> Option<Arc<dyn Backup>> {
+        match BackupManager::new(self.backup_path.clone(), DEFAULT_BACKUP_RETENTION) {
+            Ok(manager) => Some(Arc::new(manager)),
+            Err(e) => {
+                tracing::warn!(
+                    "Index backups disabled for {}: {:#}",
+                    self.collection_name,
+                    e
+                );
+                None
+            }
+        }
+    }
+
     pub fn vectors_root() -> PathBuf {
         IndexingProjectPaths::vectors_root(&data_dir())
     }
@@ -0,0 +1,137 @@
+//! Runtime state behind the MCP resource surface
+//!
+//! Shared by every session of one server process:
+//! - Snapshot-publish events fan out over a broadcast channel, so each
+//!   session can notify its own resource subscribers
+//! - Rendered audit reports are memoized per workspace and keyed by the
+//!   snapshot `graph_id` they were computed from; a republished snapshot
+//!   simply misses the cache
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::broadcast;
+
+/// Events buffered per receiver before a slow session starts lagging.
+const SNAPSHOT_EVENT_CAPACITY: usize = 64;
+
+/// A hypergraph snapshot became the workspace's `CURRENT` one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotPublished {
+    pub workspace_hash: String,
+    pub graph_id: String,
+}
+
+#[derive(Debug, Clone)]
+struct CachedAuditReport {
+    graph_id: String,
+    json: Arc<String>,
+}
+
+#[derive(Clone)]
+pub struct ResourceState {
+    published: broadcast::Sender<SnapshotPublished>,
+    audit_reports: Arc<Mutex<HashMap<(String, String), CachedAuditReport>>>,
+}
+
+impl Default for ResourceState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ResourceState {
+    pub fn new() -> Self {
+        let (published, _) = broadcast::channel(SNAPSHOT_EVENT_CAPACITY);
+        Self {
+            published,
+            audit_reports: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Announce a newly published snapshot. Sessions without subscribers
+    /// have no receiver, so a send error is expected and ignored.
+    pub fn publish_snapshot(&self, event: SnapshotPublished) {
+        let _ = self.published.send(event);
+    }
+
+    pub fn subscribe_snapshots(&self) -> broadcast::Receiver<SnapshotPublished> {
+        self.published.subscribe()
+    }
+
+    /// Cached report `name` for the workspace, if it was rendered from
+    /// snapshot `graph_id`.
+    pub fn audit_report(
+        &self,
+        workspace_hash: &str,
+        name: &str,
+        graph_id: &str,
+    ) -> Option<Arc<String>> {
+        let reports = self
+            .audit_reports
+            .lock()
+            .expect("audit report cache mutex poisoned");
+        reports
+            .get(&(workspace_hash.to_string(), name.to_string()))
+            .filter(|cached| cached.graph_id == graph_id)
+            .map(|cached| Arc::clone(&cached.json))
+    }
+
+    /// Store a rendered report, replacing the one from an older snapshot.
+    pub fn store_audit_report(
+        &self,
+        workspace_hash: &str,
+        name: &str,
+        graph_id: &str,
+        json: String,
+    ) -> Arc<String> {
+        let json = Arc::new(json);
+        self.audit_reports
+            .lock()
+            .expect("audit report cache mutex poisoned")
+            .insert(
+                (workspace_hash.to_string(), name.to_string()),
+                CachedAuditReport {
+                    graph_id: graph_id.to_string(),
+                    json: Arc::clone(&json),
+                },
+            );
+        json
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn audit_reports_are_keyed_by_graph_id() {
+        let state = ResourceState::new();
+        state.store_audit_report("ws", "unsafe", "graph-1", "{}".to_string());
+
+        assert_eq!(
+            state.audit_report("ws", "unsafe", "graph-1").as_deref(),
+            Some(&"{}".to_string())
+        );
+        assert!(state.audit_report("ws", "unsafe", "graph-2").is_none());
+        assert!(state.audit_report("ws", "recursion", "graph-1").is_none());
+
+        state.store_audit_report("ws", "unsafe", "graph-2", "[]".to_string());
+        assert!(state.audit_report("ws", "unsafe", "graph-1").is_none());
+    }
+
+    #[tokio::test]
+    async fn snapshot_events_reach_every_receiver() {
+        let state = ResourceState::new();
+        let mut first = state.subscribe_snapshots();
+        let mut second = state.subscribe_snapshots();
+        let event = SnapshotPublished {
+            workspace_hash: "ws".to_string(),
+            graph_id: "graph-1".to_string(),
+        };
+        state.publish_snapshot(event.clone());
+
+        assert_eq!(first.recv().await.unwrap(), event);
+        assert_eq!(second.recv().await.unwrap(), event);
+    }
+}
@@ -11,7 +11,7 @@ use tokio::task::JoinHandle;
 use crate::semantic::{SemanticService, SemanticServiceStatus};
 
 use super::{
-    SearchRuntimeCache, SearchRuntimeCacheStatus, SyncManager, SyncManagerStatus,
+    ResourceState, SearchRuntimeCache, SearchRuntimeCacheStatus, SyncManager, SyncManagerStatus,
     WorkspaceLockRegistry,
 };
 
@@ -51,6 +51,7 @@ pub struct RuntimeSyncStatus {
     pub enabled: bool,
     pub tracked_count: usize,
     pub tracked_directories: Vec<String>,
+    pub watching: bool,
 }
 
 impl RuntimeSyncStatus {
@@ -59,6 +60,7 @@ impl RuntimeSyncStatus {
             enabled: false,
             tracked_count: 0,
             tracked_directories: Vec::new(),
+            watching: false,
         }
     }
 
@@ -67,6 +69,7 @@ impl RuntimeSyncStatus {
             enabled: true,
             tracked_count: status.tracked_count,
             tracked_directories: status.tracked_directories,
+            watching: status.watching,
         }
     }
 }
@@ -98,6 +101,7 @@ pub struct RuntimeState {
     workspace_locks: WorkspaceLockRegistry,
     search_cache: SearchRuntimeCache,
     semantic: Arc<Mutex<SemanticService>>,
+    resources: ResourceState,
     background_sync_enabled: Arc<AtomicBool>,
     background_sync_running: Arc<AtomicBool>,
 }
@@ -109,6 +113,7 @@ impl RuntimeState {
             workspace_locks: WorkspaceLockRegistry::new(),
             search_cache: SearchRuntimeCache::new(),
             semantic: Arc::new(Mutex::new(SemanticService::new())),
+            resources: ResourceState::new(),
             background_sync_enabled: Arc::new(AtomicBool::new(false)),
             background_sync_running: Arc::new(AtomicBool::new(false)),
         }
@@ -121,6 +126,7 @@ impl RuntimeState {
             workspace_locks,
             search_cache: SearchRuntimeCache::new(),
             semantic: Arc::new(Mutex::new(SemanticService::new())),
+            resources: ResourceState::new(),
             background_sync_enabled: Arc::new(AtomicBool::new(false)),
             background_sync_running: Arc::new(AtomicBool::new(false)),
         }
@@ -150,6 +156,10 @@ impl RuntimeState {
         Arc::clone(&self.semantic)
     }
 
+    pub fn resources(&self) -> &ResourceState {
+        &self.resources
+    }
+
     pub fn background_sync_enabled(&self) -> bool {
         self.background_sync_enabled.load(Ordering::SeqCst)
     }
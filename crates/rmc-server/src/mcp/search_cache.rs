@@ -3,7 +3,7 @@ use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 
 use rmc_engine::embeddings::EmbeddingGenerator;
-use rmc_engine::search::Bm25Search;
+use rmc_engine::search::{Bm25Search, CrossEncoderReranker};
 use rmc_engine::vector_store::VectorStore;
 use serde::Serialize;
 
@@ -66,6 +66,8 @@ pub struct SearchRuntimeCacheEntry {
 #[derive(Clone, Default)]
 pub struct SearchRuntimeCache {
     entries: Arc<Mutex<HashMap<SearchRuntimeCacheKey, SearchRuntimeCacheEntry>>>,
+    /// Workspace-independent, so invalidation leaves it loaded
+    cross_encoder: Arc<Mutex<Option<Arc<CrossEncoderReranker>>>>,
 }
 
 impl SearchRuntimeCache {
@@ -88,6 +90,20 @@ impl SearchRuntimeCache {
             .insert(key, entry);
     }
 
+    pub fn cross_encoder(&self) -> Option<Arc<CrossEncoderReranker>> {
+        self.cross_encoder
+            .lock()
+            .expect("search runtime cache mutex poisoned")
+            .clone()
+    }
+
+    pub fn set_cross_encoder(&self, reranker: Arc<CrossEncoderReranker>) {
+        *self
+            .cross_encoder
+            .lock()
+            .expect("search runtime cache mutex poisoned") = Some(reranker);
+    }
+
     pub fn invalidate_workspace(&self, workspace: &Path) -> usize {
         let mut entries = self
             .entries
@@ -5,13 +5,16 @@
 //! - Syncs every 5 minutes (configurable)
 //! - Uses the indexing incremental service for fast change detection
 //! - Tracks multiple directories independently
+//! - Optional watch mode: filesystem events for tracked directories trigger
+//!   a debounced, targeted sync and mark the hypergraph snapshot stale
 
 use rmc_indexing::indexing::{index_project_incrementally, IncrementalIndexRequest};
 use anyhow::Result;
 use serde::Serialize;
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
 use std::collections::HashSet;
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use tokio::sync::{watch, RwLock};
 use tracing;
@@ -19,6 +22,9 @@ use tracing;
 use rmc_engine::embeddings::EmbeddingBackend;
 
 use super::defaults::{automatic_embedding_profile_name, is_background_embedding_backend};
+use super::watch::{
+    is_hypergraph_input, is_watch_relevant, owning_workspace, spawn_watcher, Debouncer,
+};
 use super::workspace_locks::WorkspaceLockRegistry;
 
 fn normalize_directory(dir: &Path) -> PathBuf {
@@ -29,6 +35,7 @@ fn normalize_directory(dir: &Path) -> PathBuf {
 pub struct SyncManagerStatus {
     pub tracked_count: usize,
     pub tracked_directories: Vec<String>,
+    pub watching: bool,
 }
 
 /// Manages background synchronization of indexed codebases
@@ -39,6 +46,10 @@ pub struct SyncManager {
     interval: Duration,
     /// Per-workspace operation locks shared with tool endpoints
     workspace_locks: WorkspaceLockRegistry,
+    /// Debounce window for watch mode; `None` keeps polling only
+    watch_debounce: Option<Duration>,
+    /// Live filesystem watcher while watch mode is running
+    watcher: Mutex<Option<RecommendedWatcher>>,
 }
 
 impl SyncManager {
@@ -51,6 +62,8 @@ impl SyncManager {
             tracked_dirs: Arc::new(RwLock::new(HashSet::new())),
             interval: Duration::from_secs(interval_secs),
             workspace_locks: WorkspaceLockRegistry::new(),
+            watch_debounce: None,
+            watcher: Mutex::new(None),
         }
     }
 
@@ -62,9 +75,26 @@ impl SyncManager {
             tracked_dirs: Arc::new(RwLock::new(HashSet::new())),
             interval: Duration::from_secs(interval_secs),
             workspace_locks: WorkspaceLockRegistry::new(),
+            watch_debounce: None,
+            watcher: Mutex::new(None),
         }
     }
 
+    /// Enable watch mode on top of the periodic sync
+    ///
+    /// File events under tracked directories are debounced for `debounce`
+    /// and then synced right away, instead of waiting for the next interval.
+    /// The periodic sync keeps running as a safety net for dropped events.
+    pub fn with_watch(mut self, debounce: Duration) -> Self {
+        self.watch_debounce = Some(debounce);
+        self
+    }
+
+    /// Whether watch mode was enabled via [`Self::with_watch`].
+    pub fn watch_enabled(&self) -> bool {
+        self.watch_debounce.is_some()
+    }
+
     /// Get the workspace lock registry shared with this sync manager.
     pub fn workspace_locks(&self) -> WorkspaceLockRegistry {
         self.workspace_locks.clone()
@@ -78,6 +108,7 @@ impl SyncManager {
         let mut dirs = self.tracked_dirs.write().await;
         if dirs.insert(dir.clone()) {
             tracing::info!("Now tracking directory for sync: {}", dir.display());
+            self.watch_path(&dir);
         }
     }
 
@@ -107,6 +138,7 @@ impl SyncManager {
         let mut dirs = self.tracked_dirs.write().await;
         if dirs.remove(&dir) {
             tracing::info!("Stopped tracking directory: {}", dir.display());
+            self.unwatch_path(&dir);
             true
         } else {
             false
@@ -117,6 +149,9 @@ impl SyncManager {
     pub async fn untrack_all_directories(&self) -> usize {
         let mut dirs = self.tracked_dirs.write().await;
         let count = dirs.len();
+        for dir in dirs.iter() {
+            self.unwatch_path(dir);
+        }
         dirs.clear();
         if count > 0 {
             tracing::info!("Stopped tracking all directories: {}", count);
@@ -146,6 +181,28 @@ impl SyncManager {
         SyncManagerStatus {
             tracked_count: tracked_directories.len(),
             tracked_directories,
+            watching: self.watcher_slot().is_some(),
+        }
+    }
+
+    fn watcher_slot(&self) -> std::sync::MutexGuard<'_, Option<RecommendedWatcher>> {
+        self.watcher.lock().expect("file watcher mutex poisoned")
+    }
+
+    fn watch_path(&self, dir: &Path) {
+        if let Some(watcher) = self.watcher_slot().as_mut() {
+            match watcher.watch(dir, RecursiveMode::Recursive) {
+                Ok(()) => tracing::info!("Watching {} for changes", dir.display()),
+                Err(e) => tracing::warn!("Failed to watch {}: {}", dir.display(), e),
+            }
+        }
+    }
+
+    fn unwatch_path(&self, dir: &Path) {
+        if let Some(watcher) = self.watcher_slot().as_mut() {
+            if let Err(e) = watcher.unwatch(dir) {
+                tracing::debug!("Failed to unwatch {}: {}", dir.display(), e);
+            }
         }
     }
 
@@ -172,7 +229,20 @@ impl SyncManager {
     ///
     /// Cancellation is checked before the initial delayed sync and between
     /// periodic sync cycles. An in-flight workspace sync is allowed to finish.
-    pub async fn run_until_shutdown(self: Arc<Self>, mut shutdown: watch::Receiver<bool>) {
+    /// With watch mode enabled the watch loop runs alongside the periodic one.
+    pub async fn run_until_shutdown(self: Arc<Self>, shutdown: watch::Receiver<bool>) {
+        match self.watch_debounce {
+            Some(debounce) => {
+                tokio::join!(
+                    Arc::clone(&self).run_periodic(shutdown.clone()),
+                    self.run_watch(debounce, shutdown),
+                );
+            }
+            None => self.run_periodic(shutdown).await,
+        }
+    }
+
+    async fn run_periodic(self: Arc<Self>, mut shutdown: watch::Receiver<bool>) {
         tracing::info!(
             "Starting background sync with {}s interval; automatic/default profile {}; local CUDA profiles are skipped in background sync",
             self.interval.as_secs(),
@@ -212,6 +282,93 @@ impl SyncManager {
         }
     }
 
+    /// Watch tracked directories and sync changed paths once a workspace
+    /// has been quiet for `debounce`.
+    async fn run_watch(self: Arc<Self>, debounce: Duration, mut shutdown: watch::Receiver<bool>) {
+        let (watcher, mut events) = match spawn_watcher() {
+            Ok(started) => started,
+            Err(e) => {
+                tracing::error!("Failed to start file watcher; watch mode disabled: {}", e);
+                return;
+            }
+        };
+        *self.watcher_slot() = Some(watcher);
+        for dir in self.get_tracked_directories().await {
+            self.watch_path(&dir);
+        }
+        tracing::info!(
+            "Started watch mode with {}ms debounce",
+            debounce.as_millis()
+        );
+
+        let mut debouncer = Debouncer::new(debounce);
+        loop {
+            let deadline = debouncer.next_deadline();
+            tokio::select! {
+                Some(path) = events.recv() => {
+                    let tracked = self.get_tracked_directories().await;
+                    if let Some(workspace) = owning_workspace(&path, &tracked) {
+                        let relative = path.strip_prefix(workspace).unwrap_or(&path);
+                        if is_watch_relevant(relative) {
+                            debouncer.record(workspace.clone(), path, tokio::time::Instant::now());
+                        }
+                    }
+                }
+                _ = async {
+                    match deadline {
+                        Some(deadline) => tokio::time::sleep_until(deadline).await,
+                        None => std::future::pending().await,
+                    }
+                } => {
+                    let tracked = self.get_tracked_directories().await;
+                    for (workspace, paths) in debouncer.take_ready(tokio::time::Instant::now()) {
+                        if !tracked.contains(&workspace) {
+                            debouncer.forget(&workspace);
+                            continue;
+                        }
+                        if let Err(e) = self.sync_changed_paths(&workspace, &paths).await {
+                            tracing::error!("Failed to sync {}: {}", workspace.display(), e);
+                        }
+                    }
+                }
+                changed = shutdown.changed() => {
+                    if changed.is_err() || *shutdown.borrow() {
+                        tracing::info!("Watch mode shutdown requested");
+                        break;
+                    }
+                }
+            }
+        }
+
+        *self.watcher_slot() = None;
+    }
+
+    /// Handle one debounced batch from watch mode: flag the hypergraph
+    /// snapshot as stale (if a hypergraph input changed), then reindex just
+    /// the reported paths.
+    async fn sync_changed_paths(&self, dir: &Path, changed: &[PathBuf]) -> Result<()> {
+        tracing::info!(
+            "Watch: {} changed paths under {}",
+            changed.len(),
+            dir.display()
+        );
+        let graph_inputs: Vec<PathBuf> = changed
+            .iter()
+            .filter(|path| is_hypergraph_input(path))
+            .cloned()
+            .collect();
+        if !graph_inputs.is_empty()
+            && let Err(e) = rmc_graph::graph::mark_workspace_stale(dir, &graph_inputs)
+        {
+            tracing::warn!(
+                "Failed to mark hypergraph snapshot stale for {}: {}",
+                dir.display(),
+                e
+            );
+        }
+        self.sync_directory_paths(dir, Some(changed)).await
+    }
+
     /// Sync all tracked directories
     async fn handle_sync_all(&self) {
         let dirs = self.get_tracked_directories().await;
@@ -240,6 +397,15 @@ impl SyncManager {
     /// - < 10ms if no changes
     /// - Only reindexes changed files if changes detected
     async fn sync_directory(&self, dir: &Path) -> Result<()> {
+        self.sync_directory_paths(dir, None).await
+    }
+
+    /// [`Self::sync_directory`], optionally limited to watcher-reported paths
+    async fn sync_directory_paths(
+        &self,
+        dir: &Path,
+        changed_paths: Option<&[PathBuf]>,
+    ) -> Result<()> {
         use crate::mcp::project_paths::ProjectPaths;
 
         let _workspace_lock = self.workspace_locks.lock_exclusive(dir).await;
@@ -279,6 +445,8 @@ impl SyncManager {
                 snapshot_path: None,
                 codebase_loc: None,
                 force_reindex: false,
+                changed_paths,
+                backup: paths.backup_store(),
             })
             .await?;
             let stats = outcome.stats;
@@ -415,6 +583,38 @@ mod tests {
         assert_eq!(sync_manager.tracked_count().await, 2);
     }
 
+    #[tokio::test]
+    async fn watch_mode_is_opt_in_and_idle_until_running() {
+        let polling = SyncManager::with_defaults(300);
+        assert!(!polling.watch_enabled());
+
+        let watching = SyncManager::with_defaults(300).with_watch(Duration::from_millis(250));
+        assert!(watching.watch_enabled());
+        assert_eq!(watching.watch_debounce, Some(Duration::from_millis(250)));
+        // No watcher until `run_until_shutdown` starts the watch loop.
+        assert!(!watching.status().await.watching);
+    }
+
+    #[tokio::test]
+    async fn watch_mode_stops_on_shutdown() {
+        let sync_manager =
+            Arc::new(SyncManager::with_defaults(3600).with_watch(Duration::from_millis(50)));
+        let temp_dir = TempDir::new().unwrap();
+        sync_manager.track_directory(temp_dir.path().to_path_buf()).await;
+
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
+        let handle = tokio::spawn(Arc::clone(&sync_manager).run_until_shutdown(shutdown_rx));
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(sync_manager.status().await.watching);
+
+        shutdown_tx.send(true).unwrap();
+        tokio::time::timeout(Duration::from_secs(5), handle)
+            .await
+            .expect("sync loops stop after shutdown")
+            .unwrap();
+        assert!(!sync_manager.status().await.watching);
+    }
+
     #[tokio::test]
     async fn test_track_multiple_directories() {
         let sync_manager = SyncManager::with_defaults(300);
@@ -0,0 +1,228 @@
+//! Filesystem watch mode for tracked directories
+//!
+//! Opt-in companion to the periodic `SyncManager` loop:
+//! - `notify` delivers raw events on its own thread; they are forwarded into
+//!   a tokio channel
+//! - Events are filtered to index / hypergraph inputs (`.rs`, the TOML /
+//!   markdown / text files the indexer chunks, `Cargo.lock`, directories)
+//!   outside `target/`, `.git/` and `.skeleton/`
+//! - Paths are debounced per workspace, so an editor's save burst becomes
+//!   one targeted sync once the workspace has been quiet for the debounce
+//!   window
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use notify::{Event, EventKind, RecommendedWatcher};
+use rmc_engine::chunker::FileKind;
+use rmc_config::ignore_engine::PROJECT_CONFIG_FILE;
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+
+/// Start a recursive-capable watcher whose events arrive on the returned
+/// channel. Directories are added with `Watcher::watch` by the caller.
+pub(crate) fn spawn_watcher()
+-> notify::Result<(RecommendedWatcher, mpsc::UnboundedReceiver<PathBuf>)> {
+    let (tx, rx) = mpsc::unbounded_channel();
+    let watcher = notify::recommended_watcher(move |result: notify::Result<Event>| match result {
+        Ok(event) => {
+            if matches!(event.kind, EventKind::Access(_)) {
+                return;
+            }
+            for path in event.paths {
+                // Receiver gone means watch mode shut down; drop the event.
+                let _ = tx.send(path);
+            }
+        }
+        Err(err) => tracing::warn!("File watcher error: {}", err),
+    })?;
+    Ok((watcher, rx))
+}
+
+/// Whether a workspace-relative event path can affect the search index or
+/// the hypergraph. Paths without an extension are kept because a renamed or
+/// removed directory is reported as a single event for the directory.
+pub(crate) fn is_watch_relevant(relative: &Path) -> bool {
+    let excluded = relative.components().any(|c| {
+        let c = c.as_os_str();
+        c == "target" || c == ".git" || c == ".skeleton"
+    });
+    if excluded {
+        return false;
+    }
+    if relative.file_name().and_then(|name| name.to_str()) == Some("Cargo.lock") {
+        return true;
+    }
+    relative.extension().is_none() || FileKind::is_indexed(relative)
+}
+
+/// Whether a changed path can affect the hypergraph: Rust sources, Cargo
+/// manifests and lockfiles, `.rmc.toml` (its `[rust_analyzer]` load
+/// settings), and extension-less paths (directories). Docs and other text
+/// files only feed the search index.
+pub(crate) fn is_hypergraph_input(path: &Path) -> bool {
+    matches!(
+        path.file_name().and_then(|name| name.to_str()),
+        Some("Cargo.toml") | Some("Cargo.lock") | Some(PROJECT_CONFIG_FILE)
+    ) || path.extension().is_none_or(|ext| ext == "rs")
+}
+
+/// Longest tracked directory containing `path`.
+pub(crate) fn owning_workspace<'a>(path: &Path, tracked: &'a [PathBuf]) -> Option<&'a PathBuf> {
+    tracked
+        .iter()
+        .filter(|dir| path.starts_with(dir))
+        .max_by_key(|dir| dir.components().count())
+}
+
+struct PendingBatch {
+    paths: BTreeSet<PathBuf>,
+    last_event: Instant,
+}
+
+/// Per-workspace trailing-edge debouncer. A workspace's batch is released
+/// once no event for it arrived within `quiet`.
+pub(crate) struct Debouncer {
+    quiet: Duration,
+    pending: BTreeMap<PathBuf, PendingBatch>,
+}
+
+impl Debouncer {
+    pub(crate) fn new(quiet: Duration) -> Self {
+        Self {
+            quiet,
+            pending: BTreeMap::new(),
+        }
+    }
+
+    pub(crate) fn record(&mut self, workspace: PathBuf, path: PathBuf, now: Instant) {
+        let batch = self.pending.entry(workspace).or_insert_with(|| PendingBatch {
+            paths: BTreeSet::new(),
+            last_event: now,
+        });
+        batch.paths.insert(path);
+        batch.last_event = now;
+    }
+
+    /// Earliest instant at which some batch becomes ready.
+    pub(crate) fn next_deadline(&self) -> Option<Instant> {
+        self.pending
+            .values()
+            .map(|batch| batch.last_event + self.quiet)
+            .min()
+    }
+
+    /// Remove and return every batch that has been quiet for the debounce
+    /// window, as (workspace, sorted changed paths).
+    pub(crate) fn take_ready(&mut self, now: Instant) -> Vec<(PathBuf, Vec<PathBuf>)> {
+        let ready: Vec<PathBuf> = self
+            .pending
+            .iter()
+            .filter(|(_, batch)| batch.last_event + self.quiet <= now)
+            .map(|(workspace, _)| workspace.clone())
+            .collect();
+        ready
+            .into_iter()
+            .filter_map(|workspace| {
+                let batch = self.pending.remove(&workspace)?;
+                Some((workspace, batch.paths.into_iter().collect()))
+            })
+            .collect()
+    }
+
+    pub(crate) fn forget(&mut self, workspace: &Path) {
+        self.pending.remove(workspace);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn relevant_paths_cover_sources_manifests_docs_and_directories() {
+        assert!(is_watch_relevant(Path::new("src/lib.rs")));
+        assert!(is_watch_relevant(Path::new("crates/a/Cargo.toml")));
+        assert!(is_watch_relevant(Path::new("Cargo.lock")));
+        assert!(is_watch_relevant(Path::new("src/removed_dir")));
+        assert!(is_watch_relevant(Path::new("README.md")));
+        assert!(is_watch_relevant(Path::new("config.toml")));
+        assert!(!is_watch_relevant(Path::new("assets/logo.png")));
+        assert!(!is_watch_relevant(Path::new("target/debug/build/out.rs")));
+        assert!(!is_watch_relevant(Path::new(".git/index")));
+
+        assert!(is_hypergraph_input(Path::new("/ws/src/lib.rs")));
+        assert!(is_hypergraph_input(Path::new("/ws/crates/a/Cargo.toml")));
+        assert!(is_hypergraph_input(Path::new("/ws/src/removed_dir")));
+        assert!(is_hypergraph_input(Path::new("/ws/.rmc.toml")));
+        assert!(!is_hypergraph_input(Path::new("/ws/README.md")));
+        assert!(!is_hypergraph_input(Path::new("/ws/config.toml")));
+    }
+
+    #[test]
+    fn owning_workspace_prefers_the_most_specific_directory() {
+        let tracked = vec![PathBuf::from("/ws"), PathBuf::from("/ws/nested")];
+        assert_eq!(
+            owning_workspace(Path::new("/ws/nested/src/lib.rs"), &tracked),
+            Some(&PathBuf::from("/ws/nested"))
+        );
+        assert_eq!(
+            owning_workspace(Path::new("/ws/src/lib.rs"), &tracked),
+            Some(&PathBuf::from("/ws"))
+        );
+        assert_eq!(owning_workspace(Path::new("/other/lib.rs"), &tracked), None);
+    }
+
+    #[test]
+    fn debouncer_releases_a_batch_after_the_quiet_window() {
+        let quiet = Duration::from_millis(500);
+        let mut debouncer = Debouncer::new(quiet);
+        let start = Instant::now();
+        let ws = PathBuf::from("/ws");
+
+        debouncer.record(ws.clone(), ws.join("src/b.rs"), start);
+        debouncer.record(
+            ws.clone(),
+            ws.join("src/a.rs"),
+            start + Duration::from_millis(300),
+        );
+        debouncer.record(
+            ws.clone(),
+            ws.join("src/b.rs"),
+            start + Duration::from_millis(400),
+        );
+
+        // The last event pushed the deadline out.
+        assert_eq!(
+            debouncer.next_deadline(),
+            Some(start + Duration::from_millis(900))
+        );
+        assert!(debouncer.take_ready(start + Duration::from_millis(800)).is_empty());
+
+        let ready = debouncer.take_ready(start + Duration::from_millis(900));
+        assert_eq!(
+            ready,
+            vec![(ws.clone(), vec![ws.join("src/a.rs"), ws.join("src/b.rs")])]
+        );
+        assert_eq!(debouncer.next_deadline(), None);
+    }
+
+    #[test]
+    fn debouncer_tracks_workspaces_independently() {
+        let quiet = Duration::from_millis(100);
+        let mut debouncer = Debouncer::new(quiet);
+        let start = Instant::now();
+        let a = PathBuf::from("/a");
+        let b = PathBuf::from("/b");
+
+        debouncer.record(a.clone(), a.join("lib.rs"), start);
+        debouncer.record(b.clone(), b.join("lib.rs"), start + Duration::from_millis(80));
+
+        let ready = debouncer.take_ready(start + Duration::from_millis(100));
+        assert_eq!(ready, vec![(a.clone(), vec![a.join("lib.rs")])]);
+
+        debouncer.forget(&b);
+        assert_eq!(debouncer.next_deadline(), None);
+    }
+}
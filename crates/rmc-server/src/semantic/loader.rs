@@ -6,18 +6,22 @@ use ra_ap_project_model::{CargoConfig, CargoFeatures, RustLibSource};
 use ra_ap_ide::AnalysisHost;
 use ra_ap_vfs::Vfs;
 use anyhow::{Result, Context};
+use rmc_graph::graph::WorkspaceLoadConfig;
 
 /// Load a Cargo project for semantic analysis
 ///
 /// Uses no_deps=true for fast loading (~120ms).
 /// Only local project code is analyzed.
-pub(crate) fn load_project(path: &Path) -> Result<(AnalysisHost, Vfs)> {
-    load_project_with_config(path, fast_project_cargo_config())
+pub(crate) fn load_project(path: &Path, config: &WorkspaceLoadConfig) -> Result<(AnalysisHost, Vfs)> {
+    load_project_with_config(path, config, fast_project_cargo_config())
 }
 
 /// Load a Cargo project with full workspace dependency edges for rename.
-pub(super) fn load_project_full(path: &Path) -> Result<(AnalysisHost, Vfs)> {
-    load_project_with_config(path, full_workspace_cargo_config())
+pub(super) fn load_project_full(
+    path: &Path,
+    config: &WorkspaceLoadConfig,
+) -> Result<(AnalysisHost, Vfs)> {
+    load_project_with_config(path, config, full_workspace_cargo_config())
 }
 
 fn fast_project_cargo_config() -> CargoConfig {
@@ -39,14 +43,21 @@ fn full_workspace_cargo_config() -> CargoConfig {
     }
 }
 
-fn load_project_with_config(path: &Path, cargo_config: CargoConfig) -> Result<(AnalysisHost, Vfs)> {
-    let load_config = LoadCargoConfig {
+/// Load with `cargo_config` plus the workspace's features, target, `cfg`s,
+/// build-script and proc-macro settings from `.rmc.toml`
+fn load_project_with_config(
+    path: &Path,
+    config: &WorkspaceLoadConfig,
+    cargo_config: CargoConfig,
+) -> Result<(AnalysisHost, Vfs)> {
+    let cargo_config = config.cargo_config(cargo_config)?;
+    let load_config = config.load_cargo_config(LoadCargoConfig {
         load_out_dirs_from_check: false,
         with_proc_macro_server: ProcMacroServerChoice::None,
         prefill_caches: true,
         num_worker_threads: num_cpus::get_physical(),
         proc_macro_processes: 1,
-    };
+    });
 
     let (db, vfs, _) = load_workspace_at(path, &cargo_config, &load_config, &|_| {})
         .context("Failed to load workspace")?;
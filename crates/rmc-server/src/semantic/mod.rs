@@ -10,6 +10,7 @@ use std::path::{Path, PathBuf};
 use ra_ap_ide::AnalysisHost;
 use ra_ap_vfs::Vfs;
 use anyhow::Result;
+use rmc_graph::graph::WorkspaceLoadConfig;
 use serde::Serialize;
 
 pub(crate) use position::Location;
@@ -20,6 +21,8 @@ struct ProjectContext {
     host: AnalysisHost,
     vfs: Vfs,
     load_kind: LoadKind,
+    /// `[rust_analyzer]` settings the project was loaded with
+    load_config: WorkspaceLoadConfig,
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -113,28 +116,38 @@ impl SemanticService {
 
     fn get_or_load_kind(&mut self, project_path: &Path, requested: LoadKind) -> Result<()> {
         let canonical = project_path.canonicalize()?;
-
-        let needs_load = match self.projects.get(&canonical) {
-            Some(ctx) => requested == LoadKind::Full && ctx.load_kind == LoadKind::Fast,
-            None => true,
+        let load_config = WorkspaceLoadConfig::load(&canonical)?;
+
+        // An edited `[rust_analyzer]` table reloads at the kind already held.
+        let load_kind = match self.projects.get(&canonical) {
+            Some(ctx) if ctx.load_config != load_config => Some(match ctx.load_kind {
+                LoadKind::Full => LoadKind::Full,
+                LoadKind::Fast => requested,
+            }),
+            Some(ctx) if requested == LoadKind::Full && ctx.load_kind == LoadKind::Fast => {
+                Some(LoadKind::Full)
+            }
+            Some(_) => None,
+            None => Some(requested),
         };
 
-        if needs_load {
+        if let Some(load_kind) = load_kind {
             tracing::info!(
                 "Loading {:?} IDE for project: {}",
-                requested,
+                load_kind,
                 canonical.display()
             );
-            let (host, vfs) = match requested {
-                LoadKind::Fast => loader::load_project(&canonical)?,
-                LoadKind::Full => loader::load_project_full(&canonical)?,
+            let (host, vfs) = match load_kind {
+                LoadKind::Fast => loader::load_project(&canonical, &load_config)?,
+                LoadKind::Full => loader::load_project_full(&canonical, &load_config)?,
             };
             self.projects.insert(
                 canonical,
                 ProjectContext {
                     host,
                     vfs,
-                    load_kind: requested,
+                    load_kind,
+                    load_config,
                 },
             );
             tracing::info!("IDE loaded successfully");
@@ -249,6 +262,7 @@ impl SemanticService {
                 host: AnalysisHost::new(None),
                 vfs: Vfs::default(),
                 load_kind: LoadKind::Fast,
+                load_config: WorkspaceLoadConfig::default(),
             },
         );
     }
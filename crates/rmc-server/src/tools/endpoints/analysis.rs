@@ -10,7 +10,7 @@
 //! - **Symbol Analysis**: Find definitions and references (functions, structs, traits)
 //! - **Call Graph Analysis**: Map function call relationships
 //! - **Dependency Analysis**: Track imports and module relationships
-//! - **Complexity Metrics**: Calculate LOC, cyclomatic complexity, and function counts
+//! - **Complexity Metrics**: Per-function cyclomatic / cognitive complexity, nesting, LOC
 //!
 //! ## MCP Tools
 //!
@@ -52,7 +52,7 @@
 //! let metrics = analyze_complexity(
 //!     "/path/to/rust/project/src/main.rs"
 //! ).await?;
-//! // Returns: LOC, cyclomatic complexity, function count, etc.
+//! // Returns: file LOC and symbol counts plus a per-function complexity table
 //! # Ok(())
 //! # }
 //! ```
@@ -89,7 +89,10 @@ use std::path::Path;
 use std::sync::{Arc, Mutex};
 use tracing;
 
+use ra_ap_syntax::ast::{self, AstNode, HasName};
+use ra_ap_syntax::SourceFile;
 use rmc_engine::parser::RustParser;
+use rmc_graph::graph::{ComplexityMetrics, fn_complexity};
 
 use crate::semantic::SemanticService;
 
@@ -474,23 +477,15 @@ pub(crate) async fn analyze_complexity(file_path: &str) -> Result<CallToolResult
         .filter(|s| matches!(s.kind, rmc_engine::parser::SymbolKind::Trait))
         .count();
 
-    // Calculate cyclomatic complexity (simplified - count decision points)
-    let complexity_keywords = ["if", "else if", "while", "for", "match", "&&", "||"];
-    let cyclomatic_complexity: usize = source
-        .lines()
-        .map(|line| {
-            complexity_keywords
-                .iter()
-                .map(|kw| line.matches(kw).count())
-                .sum::<usize>()
-        })
-        .sum();
-
-    // Calculate average function complexity
-    let avg_complexity = if function_count > 0 {
-        cyclomatic_complexity as f64 / function_count as f64
-    } else {
+    // Per-function metrics straight off the syntax tree, the same walk the
+    // hypergraph persists for `complexity_hotspots`.
+    let functions = function_complexities(&source);
+    let total_cyclomatic: u32 = functions.iter().map(|(_, m)| m.cyclomatic).sum();
+    let total_cognitive: u32 = functions.iter().map(|(_, m)| m.cognitive).sum();
+    let avg_cyclomatic = if functions.is_empty() {
         0.0
+    } else {
+        f64::from(total_cyclomatic) / functions.len() as f64
     };
 
     let mut result = format!("Complexity analysis for '{}':\n\n", file_path_obj.display());
@@ -509,19 +504,64 @@ pub(crate) async fn analyze_complexity(file_path: &str) -> Result<CallToolResult
     result.push_str(&format!("Traits:                {}\n\n", trait_count));
 
     result.push_str("=== Complexity ===\n");
-    result.push_str(&format!(
-        "Total cyclomatic:      {}\n",
-        cyclomatic_complexity
-    ));
-    result.push_str(&format!("Avg per function:      {:.2}\n", avg_complexity));
+    result.push_str(&format!("Total cyclomatic:      {}\n", total_cyclomatic));
+    result.push_str(&format!("Total cognitive:       {}\n", total_cognitive));
+    result.push_str(&format!("Avg cyclomatic per fn: {:.2}\n", avg_cyclomatic));
 
     // Add call graph complexity
     let edge_count = parse_result.call_graph.edge_count();
     result.push_str(&format!("Function calls:        {}\n", edge_count));
 
+    if !functions.is_empty() {
+        result.push_str("\n=== Per Function (by cyclomatic) ===\n");
+        result.push_str("cyclomatic  cognitive  nesting  params  loc  function\n");
+        for (name, m) in &functions {
+            result.push_str(&format!(
+                "{:>10}  {:>9}  {:>7}  {:>6}  {:>3}  {}\n",
+                m.cyclomatic, m.cognitive, m.max_nesting, m.param_count, m.loc, name
+            ));
+        }
+    }
+
     Ok(CallToolResult::success(vec![Content::text(result)]))
 }
 
+/// Every fn in `source` with a body, named `Type::method` / `Trait::method`
+/// inside impls and traits, sorted by cyclomatic complexity desc.
+fn function_complexities(source: &str) -> Vec<(String, ComplexityMetrics)> {
+    let parsed = SourceFile::parse(source, ra_ap_syntax::Edition::Edition2024).tree();
+    let mut functions: Vec<(String, ComplexityMetrics)> = parsed
+        .syntax()
+        .descendants()
+        .filter_map(ast::Fn::cast)
+        .filter(|func| func.body().is_some())
+        .map(|func| {
+            let name = func.name().map(|n| n.text().to_string()).unwrap_or_default();
+            let owner = func.syntax().ancestors().skip(1).find_map(|node| {
+                if let Some(imp) = ast::Impl::cast(node.clone()) {
+                    imp.self_ty().map(|ty| ty.syntax().text().to_string())
+                } else {
+                    ast::Trait::cast(node)
+                        .and_then(|tr| tr.name())
+                        .map(|n| n.text().to_string())
+                }
+            });
+            let name = match owner {
+                Some(owner) => format!("{owner}::{name}"),
+                None => name,
+            };
+            (name, fn_complexity(&func))
+        })
+        .collect();
+    functions.sort_by(|a, b| {
+        b.1.cyclomatic
+            .cmp(&a.1.cyclomatic)
+            .then_with(|| b.1.cognitive.cmp(&a.1.cognitive))
+            .then_with(|| a.0.cmp(&b.0))
+    });
+    functions
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -564,6 +604,19 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn function_complexities_names_methods_and_ignores_identifiers() {
+        let source = "fn diff_format() -> String { format!(\"if\") }\n\
+            struct S;\n\
+            impl S {\n    fn pick(&self, a: bool, b: bool) -> u8 { if a && b { 1 } else { 0 } }\n}\n";
+        let functions = function_complexities(source);
+        let names: Vec<&str> = functions.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, vec!["S::pick", "diff_format"]);
+        assert_eq!(functions[0].1.cyclomatic, 3);
+        assert_eq!(functions[0].1.param_count, 3);
+        assert_eq!(functions[1].1.cyclomatic, 1);
+    }
+
     #[tokio::test]
     async fn test_rename_symbol_invalid_project() {
         let semantic = test_semantic();
@@ -1,17 +1,22 @@
 //! MCP tool for manual codebase indexing
 //!
 //! Provides the `index_codebase` tool which allows manual triggering of
-//! incremental indexing with optional force reindex.
+//! incremental indexing with optional force reindex, the `verify_index`
+//! tool which checks (and optionally repairs) Tantivy/LanceDB drift, and the
+//! `index_backups` tool which lists and restores pre-run index backups.
 
 use rmc_engine::embeddings::{EmbeddingBackend, Qwen3Variant};
 use rmc_indexing::indexing::{
-    index_project_incrementally, IncrementalIndexRequest, IndexStats,
+    index_project_incrementally, restore_index_backup, verify_project_index, ConsistencyReport,
+    IncrementalIndexRequest, IndexStats, RestoreIndexBackupRequest, VerifyIndexRequest,
 };
+use rmc_indexing::monitoring::{BackupManager, DEFAULT_BACKUP_RETENTION};
 use crate::mcp::defaults::{automatic_embedding_backend, is_background_embedding_backend};
 use crate::mcp::project_paths::{ProjectPaths, resolve_embedding_backend_for_mcp};
 use rmc_engine::vector_store::VectorStoreError;
 use rmcp::{ErrorData as McpError, model::CallToolResult, model::Content, schemars};
 use std::path::PathBuf;
+use std::sync::Arc;
 use tracing;
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
@@ -30,6 +35,38 @@ pub struct IndexCodebaseParams {
     pub embedding_profile: Option<String>,
 }
 
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct VerifyIndexParams {
+    #[schemars(description = "Absolute path to an indexed codebase directory")]
+    pub directory: String,
+    #[schemars(
+        description = "Optional embedding profile whose index to verify. Default when omitted: the same profile `index_codebase` would pick."
+    )]
+    pub embedding_profile: Option<String>,
+    #[schemars(
+        description = "Re-index orphaned and unindexed files and drop chunks of deleted files, then check again (default: false)"
+    )]
+    #[serde(default)]
+    pub repair: Option<bool>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct IndexBackupsParams {
+    #[schemars(description = "Absolute path to an indexed codebase directory")]
+    pub directory: String,
+    #[schemars(
+        description = "Optional embedding profile whose backups to use. Default when omitted: the same profile `index_codebase` would pick."
+    )]
+    pub embedding_profile: Option<String>,
+    #[schemars(
+        description = "Backup id to restore, or \"latest\". When omitted, backups are only listed."
+    )]
+    pub restore: Option<String>,
+}
+
+/// Chunk IDs listed per drift direction; the counts are always complete
+const CHUNK_ID_SAMPLE: usize = 20;
+
 /// Parse a user-supplied model string into a [`Qwen3Variant`].
 fn parse_variant(s: &str) -> Result<Qwen3Variant, String> {
     match s.to_ascii_lowercase().as_str() {
@@ -234,6 +271,8 @@ pub async fn index_codebase(
         snapshot_path: Some(&paths.snapshot_path),
         codebase_loc: None,
         force_reindex: force,
+        changed_paths: None,
+        backup: paths.backup_store(),
     })
     .await
     .map_err(|error| indexing_error_to_mcp(error, &dir))?;
@@ -273,6 +312,206 @@ pub async fn index_codebase(
     Ok(CallToolResult::success(vec![Content::text(result_text)]))
 }
 
+fn consistency_report_json(report: &ConsistencyReport) -> serde_json::Value {
+    let chunk_ids = |ids: &[rmc_engine::chunker::ChunkId]| {
+        serde_json::json!({
+            "count": ids.len(),
+            "sample": ids.iter().take(CHUNK_ID_SAMPLE).collect::<Vec<_>>(),
+        })
+    };
+    serde_json::json!({
+        "is_consistent": report.is_consistent,
+        "tantivy_count": report.tantivy_count,
+        "vector_count": report.vector_count,
+        "cached_file_count": report.cached_file_count,
+        "missing_from_vectors": chunk_ids(&report.missing_from_vectors),
+        "missing_from_tantivy": chunk_ids(&report.missing_from_tantivy),
+        "orphaned_files": report.orphaned_files,
+        "unindexed_files": report.unindexed_files,
+        "deleted_files": report.deleted_files,
+    })
+}
+
+/// Check an indexed codebase for drift between Tantivy, LanceDB and the
+/// metadata cache, optionally repairing it
+///
+/// This is the entry point for the `verify_index` MCP tool. Returns the
+/// report (and repair summary) as pretty-printed JSON.
+pub async fn verify_index(
+    params: VerifyIndexParams,
+    workspace_locks: &crate::mcp::WorkspaceLockRegistry,
+    search_cache: Option<&crate::mcp::SearchRuntimeCache>,
+) -> Result<CallToolResult, McpError> {
+    let dir = PathBuf::from(&params.directory);
+    let repair = params.repair.unwrap_or(false);
+
+    if !dir.is_dir() {
+        return Err(McpError::invalid_params(
+            format!("The specified path '{}' is not a directory", params.directory),
+            None,
+        ));
+    }
+
+    let backend = resolve_backend(params.embedding_profile.as_deref(), None, &dir)?;
+    let embedder_identity = backend.identity();
+    let paths = ProjectPaths::from_directory(&dir, &backend);
+    // Opening the indexer creates missing stores; refuse instead of
+    // reporting an empty index as consistent.
+    if !paths.tantivy_path.exists() || !paths.vector_path.join("metadata.json").exists() {
+        return Err(McpError::invalid_params(
+            format!(
+                "No {} index found for '{}'. Run index_codebase first.",
+                backend.profile.name(),
+                params.directory
+            ),
+            None,
+        ));
+    }
+
+    let _workspace_lock = workspace_locks.lock_exclusive(&dir).await;
+    tracing::info!("Verifying index: {} (repair: {})", dir.display(), repair);
+
+    let outcome = verify_project_index(VerifyIndexRequest {
+        codebase_path: &dir,
+        cache_path: &paths.cache_path,
+        tantivy_path: &paths.tantivy_path,
+        collection_name: &paths.collection_name,
+        backend: backend.clone(),
+        embedder_identity: &embedder_identity,
+        repair,
+    })
+    .await
+    .map_err(|error| indexing_error_to_mcp(error, &dir))?;
+
+    if outcome.repair.is_some() {
+        if let Some(search_cache) = search_cache {
+            search_cache.invalidate_workspace(&dir);
+        }
+    }
+
+    let mut output = serde_json::json!({
+        "directory": params.directory,
+        "profile": backend.profile.name(),
+        "collection": paths.collection_name,
+        "report": consistency_report_json(&outcome.report),
+    });
+    if let Some(summary) = &outcome.repair {
+        output["repair"] = serde_json::json!(summary);
+    }
+    if let Some(after) = &outcome.after_repair {
+        output["after_repair"] = consistency_report_json(after);
+    }
+
+    let text = serde_json::to_string_pretty(&output).map_err(|e| {
+        McpError::internal_error(format!("Failed to serialize verify_index report: {}", e), None)
+    })?;
+    Ok(CallToolResult::success(vec![Content::text(text)]))
+}
+
+/// List a project's index backups, or restore one
+///
+/// This is the entry point for the `index_backups` MCP tool. A backup of the
+/// Merkle snapshot and metadata cache is taken before every index run;
+/// restoring one makes the next `index_codebase` re-index every file changed
+/// since it was taken.
+pub async fn index_backups(
+    params: IndexBackupsParams,
+    workspace_locks: &crate::mcp::WorkspaceLockRegistry,
+) -> Result<CallToolResult, McpError> {
+    let dir = PathBuf::from(&params.directory);
+
+    if !dir.is_dir() {
+        return Err(McpError::invalid_params(
+            format!("The specified path '{}' is not a directory", params.directory),
+            None,
+        ));
+    }
+
+    let backend = resolve_backend(params.embedding_profile.as_deref(), None, &dir)?;
+    let paths = ProjectPaths::from_directory(&dir, &backend);
+    let manager = BackupManager::new(paths.backup_path.clone(), DEFAULT_BACKUP_RETENTION)
+        .map(Arc::new)
+        .map_err(|e| {
+            McpError::internal_error(format!("Failed to open index backups: {:#}", e), None)
+        })?;
+
+    let _workspace_lock = workspace_locks.lock_exclusive(&dir).await;
+
+    let mut restored = None;
+    if let Some(restore) = params.restore.as_deref() {
+        let backup_id = match restore {
+            "latest" => None,
+            id => Some(id),
+        };
+        if let Some(id) = backup_id {
+            let known = manager.list_backups().map_err(|e| {
+                McpError::internal_error(format!("Failed to list index backups: {:#}", e), None)
+            })?;
+            if !known.iter().any(|backup| backup.id == id) {
+                return Err(McpError::invalid_params(
+                    format!("No index backup named '{}' for '{}'", id, params.directory),
+                    None,
+                ));
+            }
+        }
+        // Opening the indexer creates missing stores; there is nothing to
+        // roll back without them.
+        if !paths.tantivy_path.exists() || !paths.vector_path.join("metadata.json").exists() {
+            return Err(McpError::invalid_params(
+                format!(
+                    "No {} index found for '{}'. Run index_codebase first.",
+                    backend.profile.name(),
+                    params.directory
+                ),
+                None,
+            ));
+        }
+
+        tracing::info!("Restoring index backup for {}: {}", dir.display(), restore);
+        let embedder_identity = backend.identity();
+        restored = restore_index_backup(RestoreIndexBackupRequest {
+            codebase_path: &dir,
+            cache_path: &paths.cache_path,
+            tantivy_path: &paths.tantivy_path,
+            collection_name: &paths.collection_name,
+            backend: backend.clone(),
+            embedder_identity: &embedder_identity,
+            backup: manager.clone(),
+            backup_id,
+        })
+        .await
+        .map_err(|error| indexing_error_to_mcp(error, &dir))?;
+        if restored.is_none() {
+            return Err(McpError::invalid_params(
+                format!("No index backups found for '{}'", params.directory),
+                None,
+            ));
+        }
+    }
+
+    let backups = manager.list_backups().map_err(|e| {
+        McpError::internal_error(format!("Failed to list index backups: {:#}", e), None)
+    })?;
+    let mut output = serde_json::json!({
+        "directory": params.directory,
+        "profile": backend.profile.name(),
+        "collection": paths.collection_name,
+        "interrupted_run": manager.interrupted_run(),
+        "backups": backups,
+    });
+    if let Some(id) = restored {
+        output["restored"] = serde_json::json!(id);
+        output["next_step"] = serde_json::json!(
+            "Run index_codebase to re-index the files changed since this backup"
+        );
+    }
+
+    let text = serde_json::to_string_pretty(&output).map_err(|e| {
+        McpError::internal_error(format!("Failed to serialize index_backups result: {}", e), None)
+    })?;
+    Ok(CallToolResult::success(vec![Content::text(text)]))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -310,6 +549,51 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_verify_index_requires_existing_index() {
+        let temp_dir = TempDir::new().unwrap();
+        let params = VerifyIndexParams {
+            directory: temp_dir.path().to_string_lossy().to_string(),
+            embedding_profile: None,
+            repair: Some(true),
+        };
+
+        let locks = crate::mcp::WorkspaceLockRegistry::new();
+        let error = verify_index(params, &locks, None).await.unwrap_err();
+        assert!(error.message.contains("Run index_codebase first"));
+    }
+
+    #[tokio::test]
+    async fn test_index_backups_lists_none_before_indexing() {
+        let temp_dir = TempDir::new().unwrap();
+        let params = IndexBackupsParams {
+            directory: temp_dir.path().to_string_lossy().to_string(),
+            embedding_profile: None,
+            restore: None,
+        };
+
+        let locks = crate::mcp::WorkspaceLockRegistry::new();
+        let result = index_backups(params, &locks).await.unwrap();
+        let text = result.content[0].as_text().unwrap().text.clone();
+        let output: serde_json::Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(output["backups"], serde_json::json!([]));
+        assert_eq!(output["interrupted_run"], serde_json::json!(false));
+    }
+
+    #[tokio::test]
+    async fn test_index_backups_rejects_unknown_backup_id() {
+        let temp_dir = TempDir::new().unwrap();
+        let params = IndexBackupsParams {
+            directory: temp_dir.path().to_string_lossy().to_string(),
+            embedding_profile: None,
+            restore: Some("backup_1".to_string()),
+        };
+
+        let locks = crate::mcp::WorkspaceLockRegistry::new();
+        let error = index_backups(params, &locks).await.unwrap_err();
+        assert!(error.message.contains("No index backup named 'backup_1'"));
+    }
+
     #[tokio::test]
     #[ignore] // Requires embedding model
     async fn test_index_codebase_success() {
@@ -9,12 +9,20 @@ use rmcp::{
 };
 use tokio::fs;
 use std::path::Path;
+use std::sync::Arc;
 use tracing;
 
+use rmc_engine::chunker::{MARKDOWN_SECTION_KIND, TEXT_WINDOW_KIND, TOML_SECTION_KIND};
 use rmc_engine::embeddings::{EmbeddingBackend, EmbeddingGenerator};
-use rmc_engine::search::{Bm25Search, HybridSearch};
-use rmc_indexing::indexing::{open_bm25_search, IndexStats, UnifiedIndexer};
+use rmc_engine::search::{
+    Bm25Search, CrossEncoderModel, CrossEncoderReranker, HybridSearch,
+    HybridSearchConfig, SearchFilter, TestScope,
+};
+use rmc_indexing::indexing::{
+    open_bm25_search, IndexStats, UnifiedIndexer, VectorIndexSettings,
+};
 use crate::mcp::{SearchRuntimeCache, SearchRuntimeCacheEntry, SearchRuntimeCacheKey};
+use crate::tools::graph::search_boost::{GraphReranker, features_reranker};
 use crate::mcp::project_paths::{
     ProjectPaths, read_embedder_identity, resolve_embedding_backend_for_mcp,
 };
@@ -153,6 +161,9 @@ async fn ensure_indexed(
     let resolved = resolve_query_backend(paths, backend)?;
     let backend = resolved.backend;
     let background_backend = backend.clone();
+    let ann_index = VectorIndexSettings::load(dir_path)
+        .and_then(|settings| settings.ann_index_config())
+        .map_err(|e| McpError::invalid_params(format!("{:#}", e), None))?;
 
     let mut indexer = UnifiedIndexer::for_embedded_with_backend(
         &paths.cache_path,
@@ -164,7 +175,8 @@ async fn ensure_indexed(
         backend,
     )
     .await
-    .map_err(|e| McpError::invalid_params(format!("Failed to initialize indexer: {}", e), None))?;
+    .map_err(|e| McpError::invalid_params(format!("Failed to initialize indexer: {}", e), None))?
+    .with_ann_index(ann_index);
 
     let stats = indexer
         .index_directory(dir_path)
@@ -251,6 +263,7 @@ pub(crate) async fn create_hybrid_search(
 ) -> Result<HybridSearch, McpError> {
     let resolved = resolve_query_backend(paths, configured_backend)?;
     let backend = resolved.backend;
+    let config = hybrid_search_config(workspace)?;
     let cache_key = search_cache.map(|_| {
         SearchRuntimeCacheKey::new(
             workspace,
@@ -267,10 +280,11 @@ pub(crate) async fn create_hybrid_search(
                 collection = paths.collection_name,
                 "Reusing cached hybrid search runtime"
             );
-            return Ok(HybridSearch::with_defaults(
+            return Ok(HybridSearch::new(
                 entry.embedding_generator,
                 entry.vector_store,
                 bm25_search.or(entry.bm25_search),
+                config,
             ));
         }
     }
@@ -312,17 +326,212 @@ pub(crate) async fn create_hybrid_search(
         );
     }
 
-    Ok(HybridSearch::with_defaults(
+    Ok(HybridSearch::new(
         embedding_generator,
         vector_store,
         bm25_search,
+        config,
     ))
 }
 
+/// Search config with the ANN query knobs from the `[vector_index]` table
+/// of the workspace's `.rmc.toml`
+fn hybrid_search_config(workspace: &Path) -> Result<HybridSearchConfig, McpError> {
+    let params = VectorIndexSettings::load(workspace)
+        .and_then(|settings| settings.search_params())
+        .map_err(|e| McpError::invalid_params(format!("{:#}", e), None))?;
+    Ok(HybridSearchConfig {
+        nprobes: params.nprobes,
+        refine_factor: params.refine_factor,
+        ..HybridSearchConfig::default()
+    })
+}
+
+const DEFAULT_SEARCH_LIMIT: usize = 10;
+const MAX_SEARCH_LIMIT: usize = 100;
+
+/// Symbol kinds the chunker emits (`SymbolKind::as_str`, plus the
+/// non-Rust file chunk kinds)
+const SEARCHABLE_SYMBOL_KINDS: &[&str] = &[
+    "function",
+    "struct",
+    "enum",
+    "trait",
+    "impl",
+    "module",
+    "const",
+    "static",
+    "type",
+    TOML_SECTION_KIND,
+    MARKDOWN_SECTION_KIND,
+    TEXT_WINDOW_KIND,
+];
+
+/// Filter and paging arguments of the `search` tool, as received
+#[derive(Debug, Default)]
+pub(crate) struct SearchOptions {
+    pub crate_name: Option<String>,
+    pub module: Option<String>,
+    pub symbol_kind: Option<Vec<String>>,
+    pub file_glob: Option<String>,
+    pub tests: Option<String>,
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+    pub rerank: Option<String>,
+    pub anchor: Option<String>,
+    pub explain: Option<bool>,
+}
+
+/// Second-stage reranker selected by the `rerank` argument
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum RerankMode {
+    #[default]
+    None,
+    Features,
+    CrossEncoder,
+    Graph,
+}
+
+fn parse_rerank_mode(value: Option<&str>) -> Result<RerankMode, McpError> {
+    let Some(value) = value.map(str::trim).filter(|v| !v.is_empty()) else {
+        return Ok(RerankMode::None);
+    };
+    match value.to_ascii_lowercase().as_str() {
+        "none" => Ok(RerankMode::None),
+        "features" => Ok(RerankMode::Features),
+        "cross_encoder" => Ok(RerankMode::CrossEncoder),
+        "graph" => Ok(RerankMode::Graph),
+        _ => Err(McpError::invalid_params(
+            format!(
+                "Invalid rerank '{}'. Expected \"none\", \"features\", \"cross_encoder\" or \"graph\"",
+                value
+            ),
+            None,
+        )),
+    }
+}
+
+/// Load the cross-encoder once per server; the first load may download
+/// the model.
+async fn load_cross_encoder(
+    search_cache: Option<&SearchRuntimeCache>,
+) -> Result<Arc<CrossEncoderReranker>, McpError> {
+    if let Some(reranker) = search_cache.and_then(SearchRuntimeCache::cross_encoder) {
+        return Ok(reranker);
+    }
+    let reranker = tokio::task::spawn_blocking(|| {
+        CrossEncoderReranker::new(CrossEncoderModel::default())
+    })
+    .await
+    .map_err(|e| McpError::internal_error(format!("reranker load task failed: {}", e), None))?
+    .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+    let reranker = Arc::new(reranker);
+    if let Some(cache) = search_cache {
+        cache.set_cross_encoder(Arc::clone(&reranker));
+    }
+    Ok(reranker)
+}
+
+/// Validate everything except `crate_name`, which needs `cargo metadata`
+/// and is resolved by [`resolve_crate_filter`].
+fn build_search_filter(dir_path: &Path, options: &SearchOptions) -> Result<SearchFilter, McpError> {
+    let mut filter = SearchFilter::default();
+
+    if let Some(module) = options.module.as_deref().map(str::trim).filter(|m| !m.is_empty()) {
+        let module = module.trim_start_matches("::");
+        filter.module_prefix = Some(if module == "crate" || module.starts_with("crate::") {
+            module.to_string()
+        } else {
+            format!("crate::{module}")
+        });
+    }
+
+    for kind in options.symbol_kind.iter().flatten() {
+        let kind = kind.trim().to_ascii_lowercase();
+        if !SEARCHABLE_SYMBOL_KINDS.contains(&kind.as_str()) {
+            return Err(McpError::invalid_params(
+                format!(
+                    "Unknown symbol_kind '{}'. Expected one of: {}",
+                    kind,
+                    SEARCHABLE_SYMBOL_KINDS.join(", ")
+                ),
+                None,
+            ));
+        }
+        if !filter.symbol_kinds.contains(&kind) {
+            filter.symbol_kinds.push(kind);
+        }
+    }
+
+    if let Some(glob) = options.file_glob.as_deref().map(str::trim).filter(|g| !g.is_empty()) {
+        let absolute = if Path::new(glob).is_absolute() {
+            glob.to_string()
+        } else {
+            let root = dir_path.display().to_string();
+            format!(
+                "{}/{}",
+                glob::Pattern::escape(root.trim_end_matches('/')),
+                glob.trim_start_matches("./")
+            )
+        };
+        let pattern = glob::Pattern::new(&absolute).map_err(|e| {
+            McpError::invalid_params(format!("Invalid file_glob '{}': {}", glob, e), None)
+        })?;
+        filter.file_glob = Some(pattern);
+    }
+
+    if let Some(tests) = options.tests.as_deref() {
+        filter.tests = TestScope::parse(tests).ok_or_else(|| {
+            McpError::invalid_params(
+                format!("Invalid tests '{}'. Expected \"include\", \"exclude\" or \"only\"", tests),
+                None,
+            )
+        })?;
+    }
+
+    Ok(filter)
+}
+
+/// Turn `crate_name` into a directory prefix expressed under `dir_path`, so
+/// it lines up with the file paths the indexer stored.
+async fn resolve_crate_filter(dir_path: &Path, crate_name: &str) -> Result<std::path::PathBuf, McpError> {
+    let wanted = crate_name.trim().replace('-', "_");
+    let root = dir_path.to_path_buf();
+    let crate_dirs = tokio::task::spawn_blocking(move || rmc_graph::graph::workspace_crate_dirs(&root))
+        .await
+        .map_err(|e| McpError::internal_error(format!("crate lookup task failed: {}", e), None))?
+        .map_err(|e| {
+            McpError::invalid_params(
+                format!("crate_name needs a Cargo workspace at '{}': {:#}", dir_path.display(), e),
+                None,
+            )
+        })?;
+
+    let Some(crate_dir) = crate_dirs.get(&wanted) else {
+        let mut known: Vec<&str> = crate_dirs.keys().map(String::as_str).collect();
+        known.sort_unstable();
+        return Err(McpError::invalid_params(
+            format!(
+                "Unknown crate_name '{}'. Workspace members: {}",
+                crate_name,
+                known.join(", ")
+            ),
+            None,
+        ));
+    };
+
+    let canonical_root = dir_path.canonicalize().unwrap_or_else(|_| dir_path.to_path_buf());
+    Ok(match crate_dir.strip_prefix(&canonical_root) {
+        Ok(relative) => dir_path.join(relative),
+        Err(_) => crate_dir.clone(),
+    })
+}
+
 /// Format search results into a display string
 fn format_results(
     results: &[rmc_engine::search::SearchResult],
     keyword: &str,
+    offset: usize,
     stats: Option<&IndexStats>,
     rebuilt: bool,
 ) -> String {
@@ -337,21 +546,29 @@ fn format_results(
         return s;
     }
 
+    let page = if offset > 0 {
+        format!(" (starting at rank {})", offset + 1)
+    } else {
+        String::new()
+    };
     let mut result_str = if rebuilt {
-        format!("Note: corrupt index detected and rebuilt.\n\nFound {} results for '{}':\n\n", results.len(), keyword)
+        format!("Note: corrupt index detected and rebuilt.\n\nFound {} results for '{}'{}:\n\n", results.len(), keyword, page)
     } else {
-        format!("Found {} results for '{}':\n\n", results.len(), keyword)
+        format!("Found {} results for '{}'{}:\n\n", results.len(), keyword, page)
     };
 
     for (idx, result) in results.iter().enumerate() {
         result_str.push_str(&format!(
             "{}. Score: {:.4} | File: {} | Symbol: {} ({})\n",
-            idx + 1,
+            offset + idx + 1,
             result.score,
             result.chunk.context.file_path.display(),
             result.chunk.context.symbol_name,
             result.chunk.context.symbol_kind,
         ));
+        if let Some(rerank_score) = result.rerank_score {
+            result_str.push_str(&format!("   Rerank score: {:.4}\n", rerank_score));
+        }
         result_str.push_str(&format!(
             "   Lines: {}-{}\n",
             result.chunk.context.line_start, result.chunk.context.line_end
@@ -380,6 +597,7 @@ pub(crate) async fn search(
     directory: &str,
     keyword: &str,
     embedding_profile: Option<&str>,
+    options: SearchOptions,
     sync_manager: Option<&std::sync::Arc<crate::mcp::SyncManager>>,
     workspace_locks: &crate::mcp::WorkspaceLockRegistry,
     search_cache: Option<&SearchRuntimeCache>,
@@ -399,6 +617,21 @@ pub(crate) async fn search(
         ));
     }
 
+    let mut filter = build_search_filter(dir_path, &options)?;
+    if let Some(crate_name) = options.crate_name.as_deref().filter(|name| !name.trim().is_empty()) {
+        filter.path_prefix = Some(resolve_crate_filter(dir_path, crate_name).await?);
+    }
+    let limit = options.limit.unwrap_or(DEFAULT_SEARCH_LIMIT).clamp(1, MAX_SEARCH_LIMIT);
+    let offset = options.offset.unwrap_or(0);
+    let rerank = parse_rerank_mode(options.rerank.as_deref())?;
+    let anchor = options.anchor.as_deref().map(str::trim).filter(|a| !a.is_empty());
+    if anchor.is_some() && rerank != RerankMode::Graph {
+        return Err(McpError::invalid_params(
+            "anchor requires rerank=\"graph\"".to_string(),
+            None,
+        ));
+    }
+
     let _workspace_lock = workspace_locks.lock_shared(dir_path).await;
 
     let requested_backend = resolve_embedding_backend_for_mcp(embedding_profile, dir_path)?;
@@ -446,20 +679,46 @@ pub(crate) async fn search(
     let hybrid_search =
         create_hybrid_search(dir_path, &paths, bm25, requested_backend.clone(), search_cache)
             .await?;
+    let hybrid_search = match rerank {
+        RerankMode::None => hybrid_search,
+        RerankMode::Features => {
+            hybrid_search.with_reranker(Arc::new(features_reranker(directory)?))
+        }
+        RerankMode::CrossEncoder => {
+            hybrid_search.with_reranker(load_cross_encoder(search_cache).await?)
+        }
+        RerankMode::Graph => {
+            hybrid_search.with_reranker(Arc::new(GraphReranker::open(directory, anchor)?))
+        }
+    };
 
     tracing::info!(
         profile = requested_backend.profile.name(),
+        rerank = ?rerank,
         "Performing hybrid search for: {}",
         keyword
     );
-    let results = hybrid_search
-        .search(keyword, 10)
+    if !options.explain.unwrap_or(false) {
+        let results = hybrid_search
+            .search_filtered(keyword, &filter, limit, offset)
+            .await
+            .map_err(|e| McpError::invalid_params(format!("Search failed: {}", e), None))?;
+        return Ok(CallToolResult::success(vec![Content::text(
+            format_results(&results, keyword, offset, stats.as_ref(), rebuilt),
+        )]));
+    }
+
+    let (results, explanation) = hybrid_search
+        .search_explained(keyword, &filter, limit, offset)
         .await
         .map_err(|e| McpError::invalid_params(format!("Search failed: {}", e), None))?;
-
-    Ok(CallToolResult::success(vec![Content::text(
-        format_results(&results, keyword, stats.as_ref(), rebuilt),
-    )]))
+    let explanation = serde_json::to_string_pretty(&explanation).map_err(|e| {
+        McpError::internal_error(format!("Failed to serialize explanation: {}", e), None)
+    })?;
+    Ok(CallToolResult::success(vec![
+        Content::text(format_results(&results, keyword, offset, stats.as_ref(), rebuilt)),
+        Content::text(explanation),
+    ]))
 }
 
 /// Find semantically similar code using vector search
@@ -547,6 +806,7 @@ mod tests {
             tantivy_path: vector_path.join("tantivy"),
             snapshot_path: vector_path.join("snapshot.json"),
             collection_name: "code_chunks_test_legacy".to_string(),
+            backup_path: vector_path.join("backups"),
             vector_path,
         }
     }
@@ -566,17 +826,80 @@ mod tests {
     #[tokio::test]
     async fn test_search_invalid_directory() {
         let locks = crate::mcp::WorkspaceLockRegistry::new();
-        let result = search("/nonexistent/directory", "test", None, None, &locks, None).await;
+        let result = search(
+            "/nonexistent/directory",
+            "test",
+            None,
+            SearchOptions::default(),
+            None,
+            &locks,
+            None,
+        )
+        .await;
         assert!(result.is_err());
     }
 
     #[tokio::test]
     async fn test_search_empty_keyword() {
         let locks = crate::mcp::WorkspaceLockRegistry::new();
-        let result = search("/tmp", "", None, None, &locks, None).await;
+        let result = search("/tmp", "", None, SearchOptions::default(), None, &locks, None).await;
         assert!(result.is_err());
     }
 
+    #[test]
+    fn build_search_filter_normalizes_and_validates_options() {
+        let dir = Path::new("/ws");
+        let filter = build_search_filter(dir, &SearchOptions {
+            module: Some("parser::ast".to_string()),
+            symbol_kind: Some(vec!["Function".to_string(), "function".to_string(), "struct".to_string()]),
+            file_glob: Some("crates/*/src/**".to_string()),
+            tests: Some("exclude".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(filter.module_prefix.as_deref(), Some("crate::parser::ast"));
+        assert_eq!(filter.symbol_kinds, vec!["function", "struct"]);
+        assert_eq!(
+            filter.file_glob.as_ref().map(|g| g.as_str()),
+            Some("/ws/crates/*/src/**")
+        );
+        assert_eq!(filter.tests, TestScope::Exclude);
+
+        let kept = build_search_filter(dir, &SearchOptions {
+            module: Some("crate::lib".to_string()),
+            file_glob: Some("/elsewhere/*.rs".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(kept.module_prefix.as_deref(), Some("crate::lib"));
+        assert_eq!(kept.file_glob.as_ref().map(|g| g.as_str()), Some("/elsewhere/*.rs"));
+
+        let docs = build_search_filter(dir, &SearchOptions {
+            symbol_kind: Some(vec!["Markdown_Section".to_string(), "toml_section".to_string()]),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(docs.symbol_kinds, vec!["markdown_section", "toml_section"]);
+
+        for bad in [
+            SearchOptions { symbol_kind: Some(vec!["macro".to_string()]), ..Default::default() },
+            SearchOptions { tests: Some("skip".to_string()), ..Default::default() },
+            SearchOptions { file_glob: Some("src/[".to_string()), ..Default::default() },
+        ] {
+            assert!(build_search_filter(dir, &bad).is_err(), "{bad:?}");
+        }
+    }
+
+    #[test]
+    fn parse_rerank_mode_defaults_to_none_and_rejects_unknown_values() {
+        assert_eq!(parse_rerank_mode(None).unwrap(), RerankMode::None);
+        assert_eq!(parse_rerank_mode(Some(" ")).unwrap(), RerankMode::None);
+        assert_eq!(parse_rerank_mode(Some("Features")).unwrap(), RerankMode::Features);
+        assert_eq!(parse_rerank_mode(Some("cross_encoder")).unwrap(), RerankMode::CrossEncoder);
+        assert_eq!(parse_rerank_mode(Some("graph")).unwrap(), RerankMode::Graph);
+        assert!(parse_rerank_mode(Some("llm")).is_err());
+    }
+
     #[tokio::test]
     async fn test_get_similar_code_invalid_directory() {
         let locks = crate::mcp::WorkspaceLockRegistry::new();
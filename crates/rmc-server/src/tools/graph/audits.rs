@@ -7,14 +7,16 @@
 //! Graph audit facade calls are synchronous, so endpoint handlers wrap them in
 //! `spawn_blocking` before rendering MCP responses.
 
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
 use rmc_graph::graph::{
-    ChannelCapacityAuditOptions, ChannelCapacityFinding, FnBodyAuditFinding, FnBodyAuditOptions,
-    GraphAuditError, MutStaticAuditFinding, RecursionCheckOptions, RecursionCycle,
-    UnsafeAuditFinding,
-    run_channel_capacity_audit, run_fn_body_audit, run_mut_static_audit, run_recursion_check,
-    run_unsafe_audit,
+    ApiChange, ChannelCapacityAuditOptions, ChannelCapacityFinding, DiffCallEdge, DiffCrateEdge,
+    DiffItem, FnBodyAuditFinding, FnBodyAuditOptions, GraphAuditError, MovedItem,
+    MutStaticAuditFinding, RecursionCheckOptions, RecursionCycle, SignatureChange,
+    SnapshotDiffSide, SnapshotSelector, UnsafeAuditFinding, VisibilityChange,
+    run_api_breaking_changes, run_channel_capacity_audit, run_fn_body_audit,
+    run_mut_static_audit, run_recursion_check, run_snapshot_diff, run_unsafe_audit,
 };
 use crate::tools::graph::response::*;
 
@@ -241,3 +243,157 @@ pub(crate) async fn fn_body_audit(
         findings,
     })
 }
+
+pub(crate) async fn snapshot_diff(
+    params: crate::tools::params::SnapshotDiffParams,
+) -> Result<CallToolResult, McpError> {
+    let base = SnapshotSelector {
+        directory: PathBuf::from(&params.base_directory),
+        graph_id: params.base_graph_id.clone(),
+    };
+    let head = SnapshotSelector {
+        directory: PathBuf::from(&params.head_directory),
+        graph_id: params.head_graph_id.clone(),
+    };
+    let diff = tokio::task::spawn_blocking(move || run_snapshot_diff(&base, &head))
+        .await
+        .map_err(|e| McpError::internal_error(format!("spawn_blocking join error: {e}"), None))?
+        .map_err(graph_audit_error("snapshot_diff"))?;
+
+    #[derive(serde::Serialize)]
+    struct DiffSide {
+        directory: String,
+        #[serde(flatten)]
+        snapshot: SnapshotDiffSide,
+    }
+    #[derive(serde::Serialize)]
+    struct Counts {
+        added_items: usize,
+        removed_items: usize,
+        moved_items: usize,
+        visibility_changes: usize,
+        signature_changes: usize,
+        added_crate_edges: usize,
+        removed_crate_edges: usize,
+        added_call_edges: usize,
+        removed_call_edges: usize,
+    }
+    #[derive(serde::Serialize)]
+    struct Resp {
+        base: DiffSide,
+        head: DiffSide,
+        counts: Counts,
+        limit: usize,
+        added_items: Vec<DiffItem>,
+        removed_items: Vec<DiffItem>,
+        moved_items: Vec<MovedItem>,
+        visibility_changes: Vec<VisibilityChange>,
+        signature_changes: Vec<SignatureChange>,
+        added_crate_edges: Vec<DiffCrateEdge>,
+        removed_crate_edges: Vec<DiffCrateEdge>,
+        added_call_edges: Vec<DiffCallEdge>,
+        removed_call_edges: Vec<DiffCallEdge>,
+    }
+    fn capped<T>(items: Vec<T>, limit: usize) -> Vec<T> {
+        items.into_iter().take(limit).collect()
+    }
+
+    let limit = params.limit.unwrap_or(DEFAULT_LIST_LIMIT);
+    let counts = Counts {
+        added_items: diff.added_items.len(),
+        removed_items: diff.removed_items.len(),
+        moved_items: diff.moved_items.len(),
+        visibility_changes: diff.visibility_changes.len(),
+        signature_changes: diff.signature_changes.len(),
+        added_crate_edges: diff.added_crate_edges.len(),
+        removed_crate_edges: diff.removed_crate_edges.len(),
+        added_call_edges: diff.added_call_edges.len(),
+        removed_call_edges: diff.removed_call_edges.len(),
+    };
+    json_result(&Resp {
+        base: DiffSide {
+            directory: params.base_directory,
+            snapshot: diff.base,
+        },
+        head: DiffSide {
+            directory: params.head_directory,
+            snapshot: diff.head,
+        },
+        counts,
+        limit,
+        added_items: capped(diff.added_items, limit),
+        removed_items: capped(diff.removed_items, limit),
+        moved_items: capped(diff.moved_items, limit),
+        visibility_changes: capped(diff.visibility_changes, limit),
+        signature_changes: capped(diff.signature_changes, limit),
+        added_crate_edges: capped(diff.added_crate_edges, limit),
+        removed_crate_edges: capped(diff.removed_crate_edges, limit),
+        added_call_edges: capped(diff.added_call_edges, limit),
+        removed_call_edges: capped(diff.removed_call_edges, limit),
+    })
+}
+
+pub(crate) async fn api_breaking_changes(
+    params: crate::tools::params::ApiBreakingChangesParams,
+) -> Result<CallToolResult, McpError> {
+    let base = SnapshotSelector {
+        directory: PathBuf::from(&params.base_directory),
+        graph_id: params.base_graph_id.clone(),
+    };
+    let head = SnapshotSelector {
+        directory: PathBuf::from(&params.head_directory),
+        graph_id: params.head_graph_id.clone(),
+    };
+    let crate_name = params.crate_name.clone();
+    let report =
+        tokio::task::spawn_blocking(move || run_api_breaking_changes(&base, &head, &crate_name))
+            .await
+            .map_err(|e| McpError::internal_error(format!("spawn_blocking join error: {e}"), None))?
+            .map_err(graph_audit_error("api_breaking_changes"))?;
+
+    #[derive(serde::Serialize)]
+    struct DiffSide {
+        directory: String,
+        #[serde(flatten)]
+        snapshot: SnapshotDiffSide,
+    }
+    #[derive(serde::Serialize)]
+    struct Resp {
+        crate_name: String,
+        base: DiffSide,
+        head: DiffSide,
+        public_items_before: usize,
+        public_items_after: usize,
+        change_count: usize,
+        counts_by_change: BTreeMap<&'static str, usize>,
+        #[serde(flatten)]
+        page: ListMeta,
+        changes: Vec<ApiChange>,
+    }
+
+    let mut counts_by_change: BTreeMap<&'static str, usize> = BTreeMap::new();
+    for change in &report.changes {
+        *counts_by_change.entry(change.change.label()).or_insert(0) += 1;
+    }
+    let change_count = report.changes.len();
+    let (page, mut changes) = page_list(report.changes, list_page(&params.pagination));
+    clear_locations_for_summary(&mut changes, page.summary, |change| change.file = None);
+
+    json_result(&Resp {
+        crate_name: report.crate_name,
+        base: DiffSide {
+            directory: params.base_directory,
+            snapshot: report.base,
+        },
+        head: DiffSide {
+            directory: params.head_directory,
+            snapshot: report.head,
+        },
+        public_items_before: report.public_items_before,
+        public_items_after: report.public_items_after,
+        change_count,
+        counts_by_change,
+        page,
+        changes,
+    })
+}
@@ -116,6 +116,15 @@ pub(crate) async fn handle_build_codemap(
                 ));
             }
         }
+        // Watch mode records the files it saw change since the last build.
+        if let Ok(Some(stale)) = rmc_graph::graph::workspace_staleness(workspace_root) {
+            pre_diagnostics.push(format!(
+                "snapshot marked stale by watch mode: {} file(s) changed since unix time {} (e.g. {}); call build_hypergraph to refresh",
+                stale.changed_files.len(),
+                stale.since_unix,
+                stale.changed_files.first().map(String::as_str).unwrap_or("-"),
+            ));
+        }
     }
 
     // ---------- build ----------
@@ -183,8 +192,8 @@ pub(crate) async fn handle_build_codemap(
 /// Adapt the search layer's `SearchResult` slice into the codemap-local
 /// `SeedHit` slice. Keeping this mapping on the tools side is what lets the
 /// `graph::codemap` algorithm core stay search-independent (PR 12 boundary
-/// fix).
-fn search_results_to_seed_hits(
+/// fix). Also used by the graph-boosted `search` ranking.
+pub(crate) fn search_results_to_seed_hits(
     results: &[rmc_engine::search::SearchResult],
 ) -> Vec<rmc_graph::graph::codemap::SeedHit> {
     results
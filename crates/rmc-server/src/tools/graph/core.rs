@@ -11,22 +11,26 @@ use std::path::PathBuf;
 use serde::Serialize;
 
 use rmc_graph::graph::{
-    BuildOptions, CallGraphNode, EnrichedBinding, EnrichedCallSite, EnrichedUsage, ModuleDependency,
-    ModuleDependencySymbol, ModuleTreeNode, NodeKind, RecursiveCallersCount, UsageSummaryRow,
-    WorkspaceStats, build_and_persist,
+    BuildOptions, CallGraphNode, EnrichedBinding, EnrichedCallSite, EnrichedUsage, ExercisingTest,
+    ModuleDependency, ModuleDependencySymbol, ModuleTreeNode, NodeKind, RecursiveCallersCount,
+    UsageSummaryRow, GraphPaths, WorkspaceStats, build_and_persist,
 };
+use crate::mcp::{ResourceState, SnapshotPublished};
 use crate::tools::graph::response::*;
 use crate::tools::params::{
     BuildHypergraphParams, CallGraphParams, CallersInCrateParams, CallsFromParams,
     GraphDeclaredReexportsParams, GraphExportsParams, GraphImportsParams, GraphReexportsParams,
-    ModuleDependenciesParams, ModuleTreeParams, RecursiveCallersCountParams, WhoCallsParams,
-    WhoImportsParams, WhoUsesParams, WhoUsesSummaryParams, WorkspaceStatsParams,
+    ModuleDependenciesParams, ModuleTreeParams, RecursiveCallersCountParams, TestsExercisingParams,
+    WhoCallsParams, WhoImportsParams, WhoUsesParams, WhoUsesSummaryParams, WorkspaceStatsParams,
 };
 
 use rmcp::{ErrorData as McpError, model::CallToolResult};
 
+/// Build or reuse the workspace snapshot. When a new snapshot is published
+/// and `resources` is given, resource subscribers are told about it.
 pub(crate) async fn build_hypergraph(
     params: BuildHypergraphParams,
+    resources: Option<&ResourceState>,
 ) -> Result<CallToolResult, McpError> {
     let dir = PathBuf::from(&params.directory);
     if !dir.exists() {
@@ -39,7 +43,7 @@ pub(crate) async fn build_hypergraph(
         force_rebuild: params.force_rebuild.unwrap_or(false),
         ..Default::default()
     };
-    // build_and_persist runs `loader::load` + the full extract pass + LMDB
+    // build_and_persist runs `loader::load` + the (full or delta) extract pass + LMDB
     // writes synchronously (4-18s wall-clock). Hand off to a blocking thread
     // so the tokio runtime worker stays free to handle other tool calls.
     let result = tokio::task::spawn_blocking(move || build_and_persist(&dir, opts))
@@ -47,6 +51,15 @@ pub(crate) async fn build_hypergraph(
         .map_err(|e| McpError::internal_error(format!("spawn_blocking join error: {e}"), None))?
         .map_err(|e| McpError::internal_error(format!("build_hypergraph failed: {e:#}"), None))?;
 
+    if !result.reused
+        && let Some(resources) = resources
+    {
+        resources.publish_snapshot(SnapshotPublished {
+            workspace_hash: GraphPaths::for_workspace(&result.workspace_root).workspace_hash,
+            graph_id: result.graph_id.clone(),
+        });
+    }
+
     json_result(&BuildHypergraphResponse {
         graph_id: result.graph_id,
         workspace_root: result.workspace_root.display().to_string(),
@@ -55,10 +68,35 @@ pub(crate) async fn build_hypergraph(
         binding_count: result.binding_count,
         usage_count: result.usage_count,
         reused: result.reused,
+        incremental: result.incremental,
         snapshot_path: result.snapshot_path.display().to_string(),
     })
 }
 
+#[derive(Debug, Serialize)]
+pub(crate) struct TestsExercisingResponse {
+    pub(crate) target: String,
+    pub(crate) depth: u32,
+    pub(crate) test_count: usize,
+    #[serde(flatten)]
+    pub(crate) page: ListMeta,
+    pub(crate) tests: Vec<ExercisingTestRendered>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct ExercisingTestRendered {
+    pub(crate) qualified_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) crate_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) crate_target_kind: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) file: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) span: Option<(u32, u32)>,
+    pub(crate) hops: u32,
+}
+
 #[derive(Debug, Serialize)]
 pub(crate) struct BuildHypergraphResponse {
     pub(crate) graph_id: String,
@@ -68,6 +106,7 @@ pub(crate) struct BuildHypergraphResponse {
     pub(crate) binding_count: u64,
     pub(crate) usage_count: u64,
     pub(crate) reused: bool,
+    pub(crate) incremental: bool,
     pub(crate) snapshot_path: String,
 }
 
@@ -219,6 +258,7 @@ pub(crate) async fn who_imports(params: WhoImportsParams) -> Result<CallToolResu
 }
 
 pub(crate) async fn who_uses(params: WhoUsesParams) -> Result<CallToolResult, McpError> {
+    let category = parse_usage_category(params.category.as_deref())?;
     let snap = open_workspace_snapshot(&params.directory)?;
     let (target_id, target_node) = snap
         .lookup_by_qualified_name(&params.target)
@@ -229,9 +269,12 @@ pub(crate) async fn who_uses(params: WhoUsesParams) -> Result<CallToolResult, Mc
                 None,
             )
         })?;
-    let usages = snap
+    let mut usages = snap
         .usages_of(target_id)
         .map_err(internal_error("usages_of"))?;
+    if let Some(category) = category {
+        usages.retain(|usage| usage.category == category);
+    }
 
     let page_req = list_page(&params.pagination);
     let usages = snap
@@ -396,6 +439,54 @@ pub(crate) async fn recursive_callers_count(
     json_result(&count)
 }
 
+pub(crate) async fn tests_exercising(
+    params: TestsExercisingParams,
+) -> Result<CallToolResult, McpError> {
+    const DEFAULT_DEPTH: u32 = 6;
+    const MAX_DEPTH: u32 = 12;
+    let depth = params.depth.unwrap_or(DEFAULT_DEPTH).min(MAX_DEPTH);
+    let snap = open_workspace_snapshot(&params.directory)?;
+    let (target_id, target_node) = snap
+        .lookup_by_qualified_name(&params.target)
+        .map_err(internal_error("lookup_by_qualified_name"))?
+        .ok_or_else(|| {
+            McpError::invalid_params(
+                format!("no node found for qualified name `{}`", params.target),
+                None,
+            )
+        })?;
+    let rows = snap
+        .tests_exercising(target_id, depth)
+        .map_err(internal_error("tests_exercising"))?;
+
+    let mut rendered: Vec<ExercisingTestRendered> = rows
+        .into_iter()
+        .map(|row: ExercisingTest| ExercisingTestRendered {
+            qualified_name: row.qualified_name,
+            crate_name: row.crate_name,
+            crate_target_kind: row.crate_target_kind,
+            file: row.file,
+            span: row.span,
+            hops: row.hops,
+        })
+        .collect();
+    let page_req = list_page(&params.pagination);
+    clear_locations_for_summary(&mut rendered, page_req.summary, |row| {
+        row.file = None;
+        row.span = None;
+    });
+    let test_count = rendered.len();
+    let (page, tests) = page_list(rendered, page_req);
+
+    json_result(&TestsExercisingResponse {
+        target: target_node.qualified_name,
+        depth,
+        test_count,
+        page,
+        tests,
+    })
+}
+
 pub(crate) async fn module_tree(params: ModuleTreeParams) -> Result<CallToolResult, McpError> {
     let snap = open_workspace_snapshot(&params.directory)?;
     let tree: ModuleTreeNode = snap
@@ -0,0 +1,131 @@
+//! Git co-change endpoint family (THEORY P11).
+//!
+//! Thin wrappers over the graph-owned co-change facade: each endpoint maps
+//! MCP parameters onto `CoChangeOptions`, runs the git walk on a blocking
+//! thread, paginates, serializes. Errors share the audit family's mapping,
+//! so a missing snapshot or a directory outside a git repository is
+//! reported as invalid params.
+
+use std::path::PathBuf;
+
+use rmc_graph::graph::{
+    CoChangeOptions, GitHistoryScan, ItemChurn, ItemCoChange, ModuleCoChange, run_item_churn,
+    run_item_co_change, run_module_co_change,
+};
+use crate::tools::graph::audits::graph_audit_error;
+use crate::tools::graph::response::*;
+use crate::tools::params::{
+    GitHistoryParams, ItemChurnParams, ItemCoChangeParams, ModuleCoChangeParams,
+};
+
+use rmcp::{ErrorData as McpError, model::CallToolResult};
+
+fn co_change_options(
+    history: &GitHistoryParams,
+    crate_name: Option<String>,
+    min_co_changes: Option<usize>,
+    cross_module_only: bool,
+) -> CoChangeOptions {
+    CoChangeOptions {
+        max_commits: history.max_commits,
+        since: history.since.clone(),
+        max_items_per_commit: history.max_items_per_commit,
+        crate_name,
+        min_co_changes,
+        cross_module_only,
+    }
+}
+
+pub(crate) async fn item_churn(params: ItemChurnParams) -> Result<CallToolResult, McpError> {
+    let directory = PathBuf::from(&params.directory);
+    let options = co_change_options(&params.history, params.crate_name.clone(), None, false);
+    let report = tokio::task::spawn_blocking(move || run_item_churn(&directory, &options))
+        .await
+        .map_err(|e| McpError::internal_error(format!("spawn_blocking join error: {e}"), None))?
+        .map_err(graph_audit_error("item_churn"))?;
+
+    #[derive(serde::Serialize)]
+    struct Resp {
+        directory: String,
+        history: GitHistoryScan,
+        #[serde(flatten)]
+        page: ListMeta,
+        items: Vec<ItemChurn>,
+    }
+    let (page, mut items) = page_list(report.items, list_page(&params.pagination));
+    clear_locations_for_summary(&mut items, page.summary, |item| item.file = None);
+    json_result(&Resp {
+        directory: params.directory,
+        history: report.history,
+        page,
+        items,
+    })
+}
+
+pub(crate) async fn item_co_change(params: ItemCoChangeParams) -> Result<CallToolResult, McpError> {
+    let directory = PathBuf::from(&params.directory);
+    let target = params.target.clone();
+    let options = co_change_options(
+        &params.history,
+        params.crate_name.clone(),
+        params.min_co_changes,
+        params.cross_module_only.unwrap_or(false),
+    );
+    let report = tokio::task::spawn_blocking(move || {
+        run_item_co_change(&directory, target.as_deref(), &options)
+    })
+    .await
+    .map_err(|e| McpError::internal_error(format!("spawn_blocking join error: {e}"), None))?
+    .map_err(graph_audit_error("item_co_change"))?;
+
+    #[derive(serde::Serialize)]
+    struct Resp {
+        directory: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        target: Option<String>,
+        history: GitHistoryScan,
+        #[serde(flatten)]
+        page: ListMeta,
+        pairs: Vec<ItemCoChange>,
+    }
+    let (page, pairs) = page_list(report.pairs, list_page(&params.pagination));
+    json_result(&Resp {
+        directory: params.directory,
+        target: report.target,
+        history: report.history,
+        page,
+        pairs,
+    })
+}
+
+pub(crate) async fn module_co_change(
+    params: ModuleCoChangeParams,
+) -> Result<CallToolResult, McpError> {
+    let directory = PathBuf::from(&params.directory);
+    let options = co_change_options(
+        &params.history,
+        params.crate_name.clone(),
+        params.min_co_changes,
+        false,
+    );
+    let report = tokio::task::spawn_blocking(move || run_module_co_change(&directory, &options))
+        .await
+        .map_err(|e| McpError::internal_error(format!("spawn_blocking join error: {e}"), None))?
+        .map_err(graph_audit_error("module_co_change"))?;
+
+    #[derive(serde::Serialize)]
+    struct Resp {
+        directory: String,
+        history: GitHistoryScan,
+        #[serde(flatten)]
+        page: ListMeta,
+        pairs: Vec<ModuleCoChange>,
+    }
+    let (page, pairs) = page_list(report.pairs, list_page(&params.pagination));
+    json_result(&Resp {
+        directory: params.directory,
+        history: report.history,
+        page,
+        pairs,
+    })
+}
@@ -5,13 +5,17 @@
 //! snapshot opening, error mapping, JSON serialization, common parsing).
 //! PR 05 added the `crates`, `audits`, and `surface` families. PR 06
 //! finished the split by extracting `similarity` and `codemap`, leaving
-//! `graph_tools.rs` as a pure facade over these submodules.
+//! `graph_tools.rs` as a pure facade over these submodules. `history` serves
+//! the git co-change tools. `search_boost` lends the snapshot to the `search`
+//! tool's graph ranking mode.
 
 pub(super) mod audits;
 pub(super) mod codemap;
 pub(super) mod core;
 pub(super) mod crates;
+pub(super) mod history;
 pub(super) mod response;
+pub(super) mod search_boost;
 pub(super) mod skeleton;
 pub(super) mod similarity;
 pub(super) mod surface;
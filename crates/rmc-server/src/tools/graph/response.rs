@@ -16,6 +16,7 @@ use rmcp::{
 };
 use serde::Serialize;
 
+use rmc_graph::graph::model::UsageCategory;
 use rmc_graph::graph::{
     ItemKind, Node, NodeId, NodeKind, OpenedSnapshot, OverlapScope, open_current_for_workspace,
 };
@@ -160,10 +161,12 @@ pub(crate) fn parse_item_kind_filter(s: Option<&str>) -> Result<Option<ItemKind>
         "assoctype" | "assoc_type" => ItemKind::AssocType,
         "method" => ItemKind::Method,
         "enumvariant" | "enum_variant" | "variant" => ItemKind::EnumVariant,
+        "field" => ItemKind::Field,
+        "macro" => ItemKind::Macro,
         other => {
             return Err(McpError::invalid_params(
                 format!(
-                    "unknown item_kind `{other}`; expected Function | Struct | Enum | Union | Trait | TypeAlias | Const | Static | AssocFunction | AssocConst | AssocType | Method | EnumVariant"
+                    "unknown item_kind `{other}`; expected Function | Struct | Enum | Union | Trait | TypeAlias | Const | Static | AssocFunction | AssocConst | AssocType | Method | EnumVariant | Field | Macro"
                 ),
                 None,
             ));
@@ -172,6 +175,25 @@ pub(crate) fn parse_item_kind_filter(s: Option<&str>) -> Result<Option<ItemKind>
     Ok(Some(kind))
 }
 
+pub(crate) fn parse_usage_category(s: Option<&str>) -> Result<Option<UsageCategory>, McpError> {
+    let Some(raw) = s else {
+        return Ok(None);
+    };
+    let category = match raw.to_ascii_lowercase().as_str() {
+        "read" => UsageCategory::Read,
+        "write" => UsageCategory::Write,
+        "test" => UsageCategory::Test,
+        "other" => UsageCategory::Other,
+        other => {
+            return Err(McpError::invalid_params(
+                format!("unknown category `{other}`; expected Read | Write | Test | Other"),
+                None,
+            ));
+        }
+    };
+    Ok(Some(category))
+}
+
 pub(crate) fn parse_overlap_scope(input: Option<&str>) -> Result<OverlapScope, McpError> {
     match input.unwrap_or("all") {
         "all" => Ok(OverlapScope::All),
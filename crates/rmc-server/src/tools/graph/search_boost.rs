@@ -0,0 +1,170 @@
+//! Graph-boosted ranking for the `search` tool.
+//!
+//! Plugs the hypergraph into the engine's reranking stage: each fused hit
+//! is snapped to its Item with `codemap::search_hit_signals`, then its
+//! fused score is blended with the Item's fan-in, pub-ness and distance to
+//! an optional anchor symbol. A heavily used `pub` definition thus
+//! outranks a one-off helper or a test with a similar text match.
+//!
+//! `features_reranker` lends the same fan-in to the model-free
+//! `FeatureReranker` behind `rerank="features"`.
+
+use std::path::{Path, PathBuf};
+
+use rmcp::ErrorData as McpError;
+
+use rmc_engine::chunker::CodeChunk;
+use rmc_engine::search::{FeatureReranker, Reranker, SearchError, SearchResult};
+use rmc_graph::graph::codemap::{HitGraphSignals, SeedHit, search_hit_signals};
+use rmc_graph::graph::{NodeId, OpenedSnapshot, open_current_for_workspace};
+
+use crate::tools::graph::codemap::search_results_to_seed_hits;
+use crate::tools::graph::response::*;
+
+/// Weight of the Item's fan-in, log-scaled against the busiest hit.
+const FAN_IN_WEIGHT: f32 = 0.6;
+/// Bonus for a plain `pub` Item.
+const PUB_WEIGHT: f32 = 0.15;
+/// Weight of `1 / (1 + hops)` from the anchor.
+const ANCHOR_WEIGHT: f32 = 0.6;
+
+/// Reranker backed by the workspace hypergraph snapshot.
+pub(crate) struct GraphReranker {
+    snap: OpenedSnapshot,
+    ws_root: PathBuf,
+    anchor: Option<NodeId>,
+}
+
+impl GraphReranker {
+    /// Open the workspace snapshot and resolve `anchor` (a qualified name).
+    pub(crate) fn open(directory: &str, anchor: Option<&str>) -> Result<Self, McpError> {
+        let snap = open_workspace_snapshot(directory)?;
+        let anchor = match anchor.map(str::trim).filter(|a| !a.is_empty()) {
+            Some(name) => {
+                let (id, _) = snap
+                    .lookup_by_qualified_name(name)
+                    .map_err(internal_error("lookup_by_qualified_name"))?
+                    .ok_or_else(|| {
+                        McpError::invalid_params(
+                            format!("no node found for anchor `{name}`"),
+                            None,
+                        )
+                    })?;
+                Some(id)
+            }
+            None => None,
+        };
+        let ws_root = PathBuf::from(&snap.manifest.workspace_root);
+        Ok(Self {
+            snap,
+            ws_root,
+            anchor,
+        })
+    }
+}
+
+impl Reranker for GraphReranker {
+    fn name(&self) -> &'static str {
+        "graph"
+    }
+
+    fn score(&self, _query: &str, results: &[SearchResult]) -> Result<Vec<f32>, SearchError> {
+        let hits = search_results_to_seed_hits(results);
+        let signals = search_hit_signals(&self.snap, &hits, &self.ws_root, self.anchor)
+            .map_err(|e| SearchError::Rerank(format!("graph signals: {e:#}")))?;
+        let top_score = results.iter().map(|r| r.score).fold(0.0_f32, f32::max);
+        let max_fan_in = signals.iter().flatten().map(|s| s.fan_in).max().unwrap_or(0);
+        Ok(results
+            .iter()
+            .zip(&signals)
+            .map(|(result, signals)| {
+                let fused = if top_score > 0.0 { result.score / top_score } else { 0.0 };
+                boosted_score(fused, signals.as_ref(), max_fan_in)
+            })
+            .collect())
+    }
+}
+
+/// `FeatureReranker` fed with each hit's Item fan-in from the workspace
+/// snapshot. Runs without fan-in only when no snapshot has been built.
+pub(crate) fn features_reranker(directory: &str) -> Result<FeatureReranker, McpError> {
+    let canonical = PathBuf::from(directory).canonicalize().map_err(|e| {
+        McpError::invalid_params(format!("failed to canonicalize {directory}: {e}"), None)
+    })?;
+    let Some(snap) = open_current_for_workspace(&canonical)
+        .map_err(internal_error("open_current_for_workspace"))?
+    else {
+        return Ok(FeatureReranker::new());
+    };
+    let ws_root = PathBuf::from(&snap.manifest.workspace_root);
+    Ok(FeatureReranker::new().with_fan_in(move |chunk| chunk_fan_in(&snap, &ws_root, chunk)))
+}
+
+/// Fan-in of the Item `chunk` snaps to; `None` when it does not snap.
+fn chunk_fan_in(snap: &OpenedSnapshot, ws_root: &Path, chunk: &CodeChunk) -> Option<u32> {
+    let hit = SeedHit {
+        file_path: chunk.context.file_path.clone(),
+        line_start: chunk.context.line_start as u32,
+        line_end: chunk.context.line_end as u32,
+        score: 0.0,
+    };
+    match search_hit_signals(snap, &[hit], ws_root, None) {
+        Ok(mut signals) => signals.pop().flatten().map(|signals| signals.fan_in),
+        Err(e) => {
+            tracing::warn!("fan-in lookup failed: {e:#}");
+            None
+        }
+    }
+}
+
+/// Blend a fused score normalized to `0..=1` with the hit's graph signals.
+/// Hits that did not snap to an Item keep their fused score.
+fn boosted_score(fused: f32, signals: Option<&HitGraphSignals>, max_fan_in: u32) -> f32 {
+    let Some(signals) = signals else {
+        return fused;
+    };
+    let mut score = fused;
+    if max_fan_in > 0 {
+        let fan_in = (1.0 + signals.fan_in as f32).ln() / (1.0 + max_fan_in as f32).ln();
+        score += FAN_IN_WEIGHT * fan_in;
+    }
+    if signals.is_pub {
+        score += PUB_WEIGHT;
+    }
+    if let Some(hops) = signals.anchor_distance {
+        score += ANCHOR_WEIGHT / (1 + hops) as f32;
+    }
+    score
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signals(fan_in: u32, is_pub: bool, anchor_distance: Option<u32>) -> HitGraphSignals {
+        HitGraphSignals {
+            node: NodeId([0; 32]),
+            qualified_name: "demo::item".to_string(),
+            fan_in,
+            is_pub,
+            anchor_distance,
+        }
+    }
+
+    #[test]
+    fn heavily_used_definition_outranks_one_off_helper() {
+        let helper = boosted_score(1.0, Some(&signals(1, false, None)), 40);
+        let definition = boosted_score(0.8, Some(&signals(40, true, None)), 40);
+        assert!(definition > helper, "{definition} <= {helper}");
+    }
+
+    #[test]
+    fn anchor_proximity_decays_with_hops_and_unsnapped_hits_keep_fused_score() {
+        let near = boosted_score(0.5, Some(&signals(0, false, Some(1))), 0);
+        let far = boosted_score(0.5, Some(&signals(0, false, Some(3))), 0);
+        let unreachable = boosted_score(0.5, Some(&signals(0, false, None)), 0);
+        assert!(near > far && far > unreachable);
+        assert_eq!(unreachable, 0.5);
+        assert_eq!(boosted_score(0.7, None, 10), 0.7);
+    }
+}
@@ -1,8 +1,9 @@
 //! Public-surface endpoint family.
 //!
 //! Endpoints that interrogate the workspace's public API and its hygiene —
-//! dead public items, enum variants, function signatures, attribute-tagged
-//! items, re-export chains, doc/derive audits, and name-overlap reports.
+//! dead public items, enum variants, trait impls, function signatures,
+//! attribute-tagged items, re-export chains, doc/derive audits, and
+//! name-overlap reports.
 //! Each endpoint follows the shape documented in `graph_tools.rs`: resolve
 //! directory, open snapshot, resolve qualified names, run the query,
 //! serialize.
@@ -12,20 +13,23 @@ use std::collections::HashSet;
 use serde::Serialize;
 
 use rmc_graph::graph::ItemWithAttribute;
+use rmc_graph::graph::model::{GenericBound, MacroKind};
 use rmc_graph::graph::{
-    CrateTypeItem, DeriveAuditFinding, DeriveAuditOptions, EnrichedCrateDeadPub,
-    EnrichedDeadPub, FunctionFilter, FunctionSignature, FunctionWithSignature, ItemKind,
-    MissingDocsAuditFinding, MissingDocsAuditOptions, Node, NodeId, NodeKind, OverlapsReport,
-    PubTypeAliasMasqueradingAsReexport, ReExportChain, SelfKindFilter,
+    ComplexityHotspot, ComplexityMetric, ComplexityMetrics, CrateTypeItem, DeriveAuditFinding,
+    DeriveAuditOptions, EnrichedCrateDeadPub, EnrichedDeadPub, FunctionFilter, FunctionSignature,
+    FunctionWithSignature, ItemKind, MacroFanIn, MissingDocsAuditFinding, MissingDocsAuditOptions,
+    Node, NodeId, NodeKind, OpenedSnapshot, OverlapsReport, PubTypeAliasMasqueradingAsReexport,
+    ReExportChain, SelfKindFilter, TraitImpl, UntestedPubItem,
     item_kind_display_label as item_kind_label, item_kind_short_label as short_item_kind_label,
     run_derive_audit, run_missing_docs_audit,
 };
 use crate::tools::graph::response::*;
 use crate::tools::params::{
-    CrateTypesParams, DeadPubParams, DeadPubReportParams, EnumVariantsParams,
-    FunctionSignatureParams,
-    FunctionsWithFilterParams, ItemAttributesParams, ItemsWithAttributeParams, OverlapsParams,
-    PubUsePubTypeAuditParams, ReExportChainParams,
+    ComplexityHotspotsParams, CrateTypesParams, DeadPubParams, DeadPubReportParams,
+    EnumVariantsParams, FindImplementationsParams, FunctionSignatureParams,
+    FunctionsWithFilterParams, ImplsOfTypeParams, ItemAttributesParams, ItemsWithAttributeParams,
+    MacrosByFanInParams, OverlapsParams, PubUsePubTypeAuditParams, ReExportChainParams,
+    UntestedPubItemsParams,
 };
 
 use rmcp::{ErrorData as McpError, model::CallToolResult};
@@ -186,6 +190,124 @@ pub(crate) async fn enum_variants(params: EnumVariantsParams) -> Result<CallTool
     })
 }
 
+pub(crate) async fn find_implementations(
+    params: FindImplementationsParams,
+) -> Result<CallToolResult, McpError> {
+    let snap = open_workspace_snapshot(&params.directory)?;
+    let (trait_id, trait_node) = snap
+        .lookup_by_qualified_name(&params.target)
+        .map_err(internal_error("lookup_by_qualified_name"))?
+        .ok_or_else(|| {
+            McpError::invalid_params(
+                format!("no node found for qualified name `{}`", params.target),
+                None,
+            )
+        })?;
+    if trait_node.kind == NodeKind::Item && trait_node.item_kind != Some(ItemKind::Trait) {
+        return Err(McpError::invalid_params(
+            format!(
+                "`{}` is not a Trait (got {:?}); find_implementations only lists trait impls",
+                params.target, trait_node.item_kind
+            ),
+            None,
+        ));
+    }
+    let impls = snap
+        .implementations_of(trait_id)
+        .map_err(internal_error("implementations_of"))?;
+    let page_req = list_page(&params.pagination);
+    let mut rows = enrich_trait_impls(&snap, impls)?;
+    clear_locations_for_summary(&mut rows, page_req.summary, |row| {
+        row.generics.clear();
+        row.file = None;
+        row.span = None;
+    });
+    let (page, implementations) = page_list(rows, page_req);
+    json_result(&FindImplementationsResponse {
+        trait_qualified_name: trait_node.qualified_name,
+        page,
+        implementations,
+    })
+}
+
+pub(crate) async fn impls_of_type(
+    params: ImplsOfTypeParams,
+) -> Result<CallToolResult, McpError> {
+    let snap = open_workspace_snapshot(&params.directory)?;
+    let (type_id, type_node) = snap
+        .lookup_by_qualified_name(&params.target)
+        .map_err(internal_error("lookup_by_qualified_name"))?
+        .ok_or_else(|| {
+            McpError::invalid_params(
+                format!("no node found for qualified name `{}`", params.target),
+                None,
+            )
+        })?;
+    if type_node.kind == NodeKind::Item
+        && !matches!(
+            type_node.item_kind,
+            Some(ItemKind::Struct | ItemKind::Enum | ItemKind::Union)
+        )
+    {
+        return Err(McpError::invalid_params(
+            format!(
+                "`{}` is not a struct, enum or union (got {:?}); impls_of_type only lists impls on ADTs",
+                params.target, type_node.item_kind
+            ),
+            None,
+        ));
+    }
+    let impls = snap
+        .impls_of_type(type_id)
+        .map_err(internal_error("impls_of_type"))?;
+    let page_req = list_page(&params.pagination);
+    let mut rows = enrich_trait_impls(&snap, impls)?;
+    clear_locations_for_summary(&mut rows, page_req.summary, |row| {
+        row.generics.clear();
+        row.file = None;
+        row.span = None;
+    });
+    let (page, impls) = page_list(rows, page_req);
+    json_result(&ImplsOfTypeResponse {
+        type_qualified_name: type_node.qualified_name,
+        page,
+        impls,
+    })
+}
+
+/// Resolve the trait / self-type / crate NodeIds of each `TraitImpl` to
+/// qualified names for the response rows.
+fn enrich_trait_impls(
+    snap: &OpenedSnapshot,
+    impls: Vec<TraitImpl>,
+) -> Result<Vec<EnrichedTraitImpl>, McpError> {
+    let rtxn = snap.read_txn().map_err(internal_error("read_txn"))?;
+    let qualified = |id: Option<NodeId>| -> Result<Option<String>, McpError> {
+        let Some(id) = id else {
+            return Ok(None);
+        };
+        Ok(snap
+            .node_by_id(&rtxn, id)
+            .map_err(internal_error("node_by_id"))?
+            .map(|n| n.qualified_name))
+    };
+    let mut out = Vec::with_capacity(impls.len());
+    for i in impls {
+        out.push(EnrichedTraitImpl {
+            trait_name: i.trait_name,
+            trait_qualified_name: qualified(i.trait_id)?,
+            self_type: i.self_type,
+            self_type_qualified_name: qualified(i.self_type_id)?,
+            krate: qualified(Some(i.crate_id))?.unwrap_or_default(),
+            generics: i.generics,
+            is_negative: i.is_negative,
+            file: Some(i.file),
+            span: Some(i.span),
+        });
+    }
+    Ok(out)
+}
+
 pub(crate) async fn item_attributes(
     params: ItemAttributesParams,
 ) -> Result<CallToolResult, McpError> {
@@ -389,6 +511,17 @@ pub(crate) async fn crate_types(
             visibility: item.visibility,
             file: item.file,
             span: item.span,
+            fields: item
+                .fields
+                .into_iter()
+                .map(|field| CrateTypeFieldRendered {
+                    target: field.target.to_hex(),
+                    name: field.name,
+                    type_string: field.type_string,
+                    visibility: field.visibility,
+                    attributes: field.attributes,
+                })
+                .collect(),
         })
         .collect();
     let page_req = list_page(&params.pagination);
@@ -407,6 +540,174 @@ pub(crate) async fn crate_types(
     })
 }
 
+pub(crate) async fn macros_by_fan_in(
+    params: MacrosByFanInParams,
+) -> Result<CallToolResult, McpError> {
+    let snap = open_workspace_snapshot(&params.directory)?;
+    let crate_id = match params.krate.as_deref() {
+        Some(krate) => Some(resolve_crate_or_root_module(&snap, krate)?),
+        None => None,
+    };
+    let skip_test_items = params.skip_test_items.unwrap_or(true);
+
+    let rows = snap
+        .macros_by_fan_in(crate_id, skip_test_items)
+        .map_err(internal_error("macros_by_fan_in"))?;
+
+    let mut rendered: Vec<MacroFanInRendered> = rows
+        .into_iter()
+        .map(|row: MacroFanIn| MacroFanInRendered {
+            target: row.target.to_hex(),
+            qualified_name: row.qualified_name,
+            display_name: row.display_name,
+            kind: row.kind,
+            macro_export: row.macro_export,
+            visibility: row.visibility,
+            file: row.file,
+            span: row.span,
+            invocation_count: row.invocation_count,
+            consumer_module_count: row.consumer_module_count,
+            consumer_function_count: row.consumer_function_count,
+            cross_crate_count: row.cross_crate_count,
+        })
+        .collect();
+    let page_req = list_page(&params.pagination);
+    clear_locations_for_summary(&mut rendered, page_req.summary, |row| {
+        row.file = None;
+        row.span = None;
+    });
+    let macro_count = rendered.len();
+    let (page, macros) = page_list(rendered, page_req);
+
+    json_result(&MacrosByFanInResponse {
+        krate: params.krate,
+        macro_count,
+        page,
+        macros,
+    })
+}
+
+pub(crate) async fn complexity_hotspots(
+    params: ComplexityHotspotsParams,
+) -> Result<CallToolResult, McpError> {
+    const DEFAULT_DEPTH: u32 = 3;
+    const MAX_DEPTH: u32 = 8;
+    const DEFAULT_MIN_COMPLEXITY: u32 = 5;
+    let metric = match params.metric.as_deref() {
+        None | Some("cyclomatic") => ComplexityMetric::Cyclomatic,
+        Some("cognitive") => ComplexityMetric::Cognitive,
+        Some(other) => {
+            return Err(McpError::invalid_params(
+                format!("metric must be one of `cyclomatic`, `cognitive`; got `{other}`"),
+                None,
+            ));
+        }
+    };
+    let depth = params.depth.unwrap_or(DEFAULT_DEPTH).min(MAX_DEPTH);
+    let min_complexity = params.min_complexity.unwrap_or(DEFAULT_MIN_COMPLEXITY);
+    let snap = open_workspace_snapshot(&params.directory)?;
+    let crate_id = match params.krate.as_deref() {
+        Some(krate) => Some(resolve_crate_or_root_module(&snap, krate)?),
+        None => None,
+    };
+    let skip_test_items = params.skip_test_items.unwrap_or(true);
+
+    let rows = snap
+        .complexity_hotspots(crate_id, metric, depth, min_complexity, skip_test_items)
+        .map_err(internal_error("complexity_hotspots"))?;
+
+    let mut rendered: Vec<ComplexityHotspotRendered> = rows
+        .into_iter()
+        .map(|row: ComplexityHotspot| ComplexityHotspotRendered {
+            target: row.target.to_hex(),
+            qualified_name: row.qualified_name,
+            display_name: row.display_name,
+            item_kind: item_kind_label(row.item_kind).to_string(),
+            file: row.file,
+            span: row.span,
+            metrics: row.metrics,
+            score: row.score,
+            complexity: row.complexity,
+            direct_callers: row.direct_callers,
+            transitive_callers: row.transitive_callers,
+        })
+        .collect();
+    let page_req = list_page(&params.pagination);
+    clear_locations_for_summary(&mut rendered, page_req.summary, |row| {
+        row.file = None;
+        row.span = None;
+    });
+    let function_count = rendered.len();
+    let (page, functions) = page_list(rendered, page_req);
+
+    json_result(&ComplexityHotspotsResponse {
+        krate: params.krate,
+        metric,
+        depth,
+        min_complexity,
+        function_count,
+        page,
+        functions,
+    })
+}
+
+pub(crate) async fn untested_pub_items(
+    params: UntestedPubItemsParams,
+) -> Result<CallToolResult, McpError> {
+    const DEFAULT_DEPTH: u32 = 6;
+    const MAX_DEPTH: u32 = 12;
+    let depth = params.depth.unwrap_or(DEFAULT_DEPTH).min(MAX_DEPTH);
+    let snap = open_workspace_snapshot(&params.directory)?;
+    let crate_id = match params.krate.as_deref() {
+        Some(krate) => Some(resolve_crate_or_root_module(&snap, krate)?),
+        None => None,
+    };
+
+    let report = snap
+        .untested_pub_items(crate_id, depth)
+        .map_err(internal_error("untested_pub_items"))?;
+    // Without tests every pub item would be listed as untested.
+    if report.test_fn_count == 0 {
+        return Err(McpError::invalid_params(
+            format!(
+                "the snapshot at {} has no test targets — set `test_targets = true` under \
+                 `[rust_analyzer]` in `.rmc.toml` and call build_hypergraph again",
+                params.directory
+            ),
+            None,
+        ));
+    }
+
+    let mut rendered: Vec<UntestedPubItemRendered> = report
+        .items
+        .into_iter()
+        .map(|row: UntestedPubItem| UntestedPubItemRendered {
+            qualified_name: row.qualified_name,
+            item_kind: item_kind_label(row.item_kind).to_string(),
+            crate_name: row.crate_name,
+            file: row.file,
+            span: row.span,
+        })
+        .collect();
+    let page_req = list_page(&params.pagination);
+    clear_locations_for_summary(&mut rendered, page_req.summary, |row| {
+        row.file = None;
+        row.span = None;
+    });
+    let untested_count = rendered.len();
+    let (page, items) = page_list(rendered, page_req);
+
+    json_result(&UntestedPubItemsResponse {
+        krate: params.krate,
+        depth,
+        test_fn_count: report.test_fn_count,
+        pub_item_count: report.pub_item_count,
+        untested_count,
+        page,
+        items,
+    })
+}
+
 pub(crate) async fn pub_use_pub_type_audit(
     params: PubUsePubTypeAuditParams,
 ) -> Result<CallToolResult, McpError> {
@@ -795,6 +1096,46 @@ pub(crate) struct EnrichedEnumVariant {
     pub(crate) span: Option<(u32, u32)>,
 }
 
+#[derive(Debug, Serialize)]
+pub(crate) struct FindImplementationsResponse {
+    pub(crate) trait_qualified_name: String,
+    #[serde(flatten)]
+    pub(crate) page: ListMeta,
+    pub(crate) implementations: Vec<EnrichedTraitImpl>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct ImplsOfTypeResponse {
+    pub(crate) type_qualified_name: String,
+    #[serde(flatten)]
+    pub(crate) page: ListMeta,
+    pub(crate) impls: Vec<EnrichedTraitImpl>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct EnrichedTraitImpl {
+    pub(crate) trait_name: String,
+    /// `None` for external traits that no local module imports.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) trait_qualified_name: Option<String>,
+    /// `HirDisplay` of the self type, e.g. `Wrapper<T>` or `&str`.
+    pub(crate) self_type: String,
+    /// `None` when the self type isn't a graph ADT (blanket impls, refs,
+    /// tuples, unimported foreign types).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) self_type_qualified_name: Option<String>,
+    #[serde(rename = "crate")]
+    pub(crate) krate: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) generics: Vec<GenericBound>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) is_negative: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) file: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) span: Option<(u32, u32)>,
+}
+
 #[derive(Debug, Serialize)]
 pub(crate) struct ItemAttributesResponse {
     pub(crate) target: String,
@@ -933,4 +1274,104 @@ pub(crate) struct CrateTypeRendered {
     pub(crate) file: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) span: Option<(u32, u32)>,
+    /// v16: struct / union fields in declaration order.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) fields: Vec<CrateTypeFieldRendered>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct MacrosByFanInResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) krate: Option<String>,
+    pub(crate) macro_count: usize,
+    #[serde(flatten)]
+    pub(crate) page: ListMeta,
+    pub(crate) macros: Vec<MacroFanInRendered>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct MacroFanInRendered {
+    pub(crate) target: String,
+    pub(crate) qualified_name: String,
+    pub(crate) display_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) kind: Option<MacroKind>,
+    pub(crate) macro_export: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) visibility: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) file: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) span: Option<(u32, u32)>,
+    pub(crate) invocation_count: usize,
+    pub(crate) consumer_module_count: usize,
+    pub(crate) consumer_function_count: usize,
+    pub(crate) cross_crate_count: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct ComplexityHotspotsResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) krate: Option<String>,
+    pub(crate) metric: ComplexityMetric,
+    pub(crate) depth: u32,
+    pub(crate) min_complexity: u32,
+    pub(crate) function_count: usize,
+    #[serde(flatten)]
+    pub(crate) page: ListMeta,
+    pub(crate) functions: Vec<ComplexityHotspotRendered>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct UntestedPubItemsResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) krate: Option<String>,
+    pub(crate) depth: u32,
+    /// Zero when the snapshot was built without test targets
+    pub(crate) test_fn_count: usize,
+    pub(crate) pub_item_count: usize,
+    pub(crate) untested_count: usize,
+    #[serde(flatten)]
+    pub(crate) page: ListMeta,
+    pub(crate) items: Vec<UntestedPubItemRendered>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct UntestedPubItemRendered {
+    pub(crate) qualified_name: String,
+    pub(crate) item_kind: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) crate_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) file: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) span: Option<(u32, u32)>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct ComplexityHotspotRendered {
+    pub(crate) target: String,
+    pub(crate) qualified_name: String,
+    pub(crate) display_name: String,
+    pub(crate) item_kind: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) file: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) span: Option<(u32, u32)>,
+    pub(crate) score: u64,
+    pub(crate) complexity: u32,
+    pub(crate) direct_callers: usize,
+    pub(crate) transitive_callers: usize,
+    pub(crate) metrics: ComplexityMetrics,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct CrateTypeFieldRendered {
+    pub(crate) target: String,
+    pub(crate) name: String,
+    pub(crate) type_string: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) visibility: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) attributes: Vec<String>,
 }
@@ -13,14 +13,21 @@ use super::similarity::*;
 use super::skeleton::*;
 use super::surface::*;
 
-use super::audits::graph_audit_error;
+use super::audits::{api_breaking_changes, graph_audit_error, snapshot_diff};
+use super::history::{item_churn, item_co_change, module_co_change};
+use super::search_boost::features_reranker;
 
-use rmc_graph::graph::{EnrichedUsage, GraphAuditError};
+use rmc_engine::chunker::{ChunkContext, ChunkId, CodeChunk};
+use rmc_engine::search::{FeatureReranker, SearchResult, rerank};
+use rmc_graph::graph::{EnrichedUsage, GraphAuditError, OpenedSnapshot};
 use ra_ap_syntax::SourceFile;
 use crate::tools::params::{
-    BuildHypergraphParams, CrateSkeletonParams, CrateTypesParams, DeadPubParams, GraphExportsParams,
-    GraphImportsParams, ListPaginationParams, ModuleDependenciesParams, WhoImportsParams,
-    WhoUsesParams, SimilarToItemParams,
+    ApiBreakingChangesParams, BuildHypergraphParams, ComplexityHotspotsParams,
+    CrateSkeletonParams, CrateTypesParams, DeadPubParams, GitHistoryParams, GraphExportsParams,
+    GraphImportsParams, ImplsOfTypeParams, ItemChurnParams, ItemCoChangeParams,
+    ListPaginationParams, MacrosByFanInParams, ModuleCoChangeParams, ModuleDependenciesParams,
+    SimilarToItemParams, SnapshotDiffParams, TestsExercisingParams, UntestedPubItemsParams,
+    WhoImportsParams, WhoUsesParams,
 };
 use rmcp::model::{CallToolResult, ErrorCode};
 use std::{
@@ -163,7 +170,7 @@ async fn ensure_default_snapshot(directory: &str) {
         build_hypergraph(BuildHypergraphParams {
             directory: directory.to_string(),
             force_rebuild: Some(true),
-        })
+        }, None)
         .await
         .expect("build_hypergraph");
         let _ = DEFAULT_SNAPSHOT_BUILT.set(());
@@ -182,6 +189,11 @@ async fn default_snapshot_graph_round_trips_with_crate_types_and_crate_skeleton(
     crate_dependency_metric_sort_by_instability_descending().await;
     crate_dependency_metric_unknown_sort_by_errors().await;
     crate_types_round_trip().await;
+    macros_by_fan_in_round_trip().await;
+    complexity_hotspots_round_trip().await;
+    test_reach_round_trip().await;
+    features_rerank_uses_snapshot_fan_in().await;
+    impls_of_type_round_trip().await;
     crate_skeleton_round_trip().await;
 }
 
@@ -191,10 +203,12 @@ async fn default_snapshot_graph_round_trips_with_crate_types_and_crate_skeleton(
 async fn mcp_round_trip_against_self() {
     let manifest_dir = test_project_root();
 
+    let resources = crate::mcp::ResourceState::new();
+    let mut published = resources.subscribe_snapshots();
     let build = build_hypergraph(BuildHypergraphParams {
         directory: manifest_dir.to_string(),
         force_rebuild: Some(true),
-    })
+    }, Some(&resources))
     .await
     .expect("build_hypergraph");
     let _ = DEFAULT_SNAPSHOT_BUILT.set(());
@@ -202,6 +216,8 @@ async fn mcp_round_trip_against_self() {
     let body = first_text(&build);
     assert!(body.contains("\"node_count\""), "build response: {body}");
     assert!(body.contains("\"binding_count\""));
+    let event = published.try_recv().expect("forced rebuild publishes a snapshot");
+    assert!(body.contains(&event.graph_id), "event graph_id {} not in {body}", event.graph_id);
 
     let imports = get_imports(GraphImportsParams {
         directory: manifest_dir.to_string(),
@@ -294,6 +310,7 @@ async fn who_uses_and_dead_pub_round_trip() {
     let users = who_uses(WhoUsesParams {
         directory: manifest_dir.to_string(),
         target: "rmc_server::tools::graph::response::json_result".to_string(),
+        category: None,
         pagination: ListPaginationParams::default(),
     })
     .await
@@ -621,6 +638,41 @@ async fn crate_dependency_metric_unknown_sort_by_errors() {
     );
 }
 
+async fn impls_of_type_round_trip() {
+    let manifest_dir = test_project_root();
+
+    ensure_default_snapshot(&manifest_dir).await;
+
+    let result = impls_of_type(ImplsOfTypeParams {
+        directory: manifest_dir.to_string(),
+        target: "rmc_server::tools::router::SearchToolRouter".to_string(),
+        pagination: ListPaginationParams::default(),
+    })
+    .await
+    .expect("impls_of_type");
+    let body = first_text(&result);
+    let v: serde_json::Value = serde_json::from_str(&body)
+        .unwrap_or_else(|e| panic!("response was not valid JSON: {e} — body: {body}"));
+    let impls = v
+        .get("impls")
+        .and_then(|x| x.as_array())
+        .expect("impls array present");
+    assert!(
+        impls.iter().any(|row| row["trait_name"] == "ServerHandler"),
+        "expected `impl ServerHandler for SearchToolRouter`: {body}"
+    );
+
+    // A function is not an ADT — reject with invalid_params.
+    let err = impls_of_type(ImplsOfTypeParams {
+        directory: manifest_dir.to_string(),
+        target: "rmc_server::tools::graph::response::json_result".to_string(),
+        pagination: ListPaginationParams::default(),
+    })
+    .await
+    .expect_err("impls_of_type on a fn must fail");
+    assert_eq!(err.code, ErrorCode::INVALID_PARAMS);
+}
+
 async fn crate_types_round_trip() {
     let manifest_dir = test_project_root();
 
@@ -714,6 +766,217 @@ async fn crate_types_round_trip() {
     );
 }
 
+async fn macros_by_fan_in_round_trip() {
+    let manifest_dir = test_project_root();
+
+    ensure_default_snapshot(&manifest_dir).await;
+
+    let result = macros_by_fan_in(MacrosByFanInParams {
+        directory: manifest_dir.to_string(),
+        krate: Some("rmc_graph".to_string()),
+        skip_test_items: None,
+        pagination: ListPaginationParams::default(),
+    })
+    .await
+    .expect("macros_by_fan_in");
+    let body = first_text(&result);
+    let v: serde_json::Value = serde_json::from_str(&body)
+        .unwrap_or_else(|e| panic!("response was not valid JSON: {e} — body: {body}"));
+    let define_id = v["macros"]
+        .as_array()
+        .and_then(|rows| {
+            rows.iter()
+                .find(|row| row["qualified_name"] == "rmc_graph::graph::ids::define_id")
+        })
+        .unwrap_or_else(|| panic!("expected define_id among rmc_graph macros: {body}"));
+    assert_eq!(define_id["kind"], "Declarative");
+    assert!(
+        define_id["invocation_count"].as_u64().unwrap_or(0) >= 4,
+        "define_id is invoked four times in ids.rs: {define_id}"
+    );
+
+    let err = macros_by_fan_in(MacrosByFanInParams {
+        directory: manifest_dir.to_string(),
+        krate: Some("rmc_graph::graph::ids::NodeId".to_string()),
+        skip_test_items: None,
+        pagination: ListPaginationParams::default(),
+    })
+    .await
+    .expect_err("a non-crate krate must be rejected");
+    assert_eq!(err.code, ErrorCode::INVALID_PARAMS);
+}
+
+async fn complexity_hotspots_round_trip() {
+    let manifest_dir = test_project_root();
+
+    ensure_default_snapshot(&manifest_dir).await;
+
+    let result = complexity_hotspots(ComplexityHotspotsParams {
+        directory: manifest_dir.to_string(),
+        krate: Some("rmc_graph".to_string()),
+        metric: Some("cognitive".to_string()),
+        depth: None,
+        min_complexity: Some(10),
+        skip_test_items: None,
+        pagination: ListPaginationParams::default(),
+    })
+    .await
+    .expect("complexity_hotspots");
+    let body = first_text(&result);
+    let v: serde_json::Value = serde_json::from_str(&body)
+        .unwrap_or_else(|e| panic!("response was not valid JSON: {e} — body: {body}"));
+    assert_eq!(v["metric"], "cognitive");
+    assert_eq!(v["depth"], 3);
+    let rows = v["functions"]
+        .as_array()
+        .unwrap_or_else(|| panic!("expected a functions array: {body}"));
+    assert!(!rows.is_empty(), "rmc_graph has fns with cognitive >= 10: {body}");
+    for row in rows {
+        let complexity = row["complexity"].as_u64().unwrap_or(0);
+        assert!(complexity >= 10, "min_complexity not applied: {row}");
+        assert_eq!(row["metrics"]["cognitive"].as_u64(), Some(complexity));
+        let transitive = row["transitive_callers"].as_u64().unwrap_or(0);
+        assert_eq!(row["score"].as_u64(), Some(complexity * (transitive + 1)));
+    }
+    assert!(
+        rows.windows(2)
+            .all(|w| w[0]["score"].as_u64() >= w[1]["score"].as_u64()),
+        "complexity_hotspots should sort by score desc: {body}"
+    );
+
+    let err = complexity_hotspots(ComplexityHotspotsParams {
+        directory: manifest_dir.to_string(),
+        krate: None,
+        metric: Some("halstead".to_string()),
+        depth: None,
+        min_complexity: None,
+        skip_test_items: None,
+        pagination: ListPaginationParams::default(),
+    })
+    .await
+    .expect_err("an unknown metric must be rejected");
+    assert_eq!(err.code, ErrorCode::INVALID_PARAMS);
+}
+
+/// The default snapshot is built without test targets, so no test reaches
+/// anything: `untested_pub_items` refuses to list every public callable and
+/// points at `test_targets` instead.
+async fn test_reach_round_trip() {
+    let manifest_dir = test_project_root();
+
+    ensure_default_snapshot(&manifest_dir).await;
+
+    let err = untested_pub_items(UntestedPubItemsParams {
+        directory: manifest_dir.to_string(),
+        krate: Some("rmc_server".to_string()),
+        depth: Some(40),
+        pagination: ListPaginationParams::default(),
+    })
+    .await
+    .expect_err("default snapshot has no tests");
+    assert_eq!(err.code, ErrorCode::INVALID_PARAMS);
+    assert!(err.message.contains("test_targets = true"), "{}", err.message);
+
+    let result = tests_exercising(TestsExercisingParams {
+        directory: manifest_dir.to_string(),
+        target: "rmc_server::tools::graph::response::json_result".to_string(),
+        depth: None,
+        pagination: ListPaginationParams::default(),
+    })
+    .await
+    .expect("tests_exercising");
+    let body = first_text(&result);
+    let v: serde_json::Value = serde_json::from_str(&body)
+        .unwrap_or_else(|e| panic!("response was not valid JSON: {e} — body: {body}"));
+    assert_eq!(v["target"], "rmc_server::tools::graph::response::json_result");
+    assert_eq!(v["depth"], 6);
+    assert_eq!(v["test_count"], 0, "{body}");
+}
+
+/// `rerank="features"` reads fan-in from the snapshot: the widely used
+/// `json_result` overtakes `boosted_score`, which fusion ranked first and
+/// which the reranker cannot otherwise tell apart for this query.
+async fn features_rerank_uses_snapshot_fan_in() {
+    let manifest_dir = test_project_root();
+
+    ensure_default_snapshot(&manifest_dir).await;
+
+    let fused = {
+        let snap = open_workspace_snapshot(&manifest_dir).expect("open snapshot");
+        let ws_root = PathBuf::from(&snap.manifest.workspace_root);
+        vec![
+            item_search_result(
+                &snap,
+                &ws_root,
+                "rmc_server::tools::graph::search_boost::boosted_score",
+            ),
+            item_search_result(
+                &snap,
+                &ws_root,
+                "rmc_server::tools::graph::response::json_result",
+            ),
+        ]
+    };
+    let symbols = |results: &[SearchResult]| {
+        results
+            .iter()
+            .map(|r| r.chunk.context.symbol_name.clone())
+            .collect::<Vec<_>>()
+    };
+
+    let plain = rerank(&FeatureReranker::new(), "zzz", fused.clone(), 10).expect("rerank");
+    assert_eq!(symbols(&plain), ["boosted_score", "json_result"]);
+
+    let reranker = features_reranker(&manifest_dir).expect("features_reranker");
+    let boosted = rerank(&reranker, "zzz", fused, 10).expect("rerank");
+    assert_eq!(symbols(&boosted), ["json_result", "boosted_score"]);
+}
+
+/// A search hit covering exactly the lines of the Item `qualified_name`.
+fn item_search_result(
+    snap: &OpenedSnapshot,
+    ws_root: &Path,
+    qualified_name: &str,
+) -> SearchResult {
+    let (_, node) = snap
+        .lookup_by_qualified_name(qualified_name)
+        .expect("lookup_by_qualified_name")
+        .unwrap_or_else(|| panic!("no node for {qualified_name}"));
+    let file = ws_root.join(node.file.expect("item file"));
+    let (start, end) = node.span.expect("item span");
+    let source = fs::read_to_string(&file).expect("read item source");
+    let line_of = |byte: u32| source[..byte as usize].matches('\n').count() + 1;
+    SearchResult {
+        chunk_id: ChunkId::new(),
+        score: 0.0,
+        bm25_score: None,
+        vector_score: None,
+        bm25_rank: None,
+        vector_rank: None,
+        rerank_score: None,
+        chunk: CodeChunk {
+            id: ChunkId::new(),
+            content: source[start as usize..end as usize].to_string(),
+            context: ChunkContext {
+                file_path: file.clone(),
+                module_path: vec![],
+                symbol_name: node.display_name,
+                symbol_kind: "function".to_string(),
+                docstring: None,
+                imports: vec![],
+                outgoing_calls: vec![],
+                parent_symbol_name: None,
+                split_part: None,
+                split_total: None,
+                line_start: line_of(start),
+                line_end: line_of(end),
+            },
+            overlap_prev: None,
+            overlap_next: None,
+        },
+    }
+}
+
 async fn crate_skeleton_round_trip() {
     let workspace_root = test_workspace_root();
     let workspace_skeleton_paths = WorkspaceSkeletonPathGuard::new(&workspace_root);
@@ -721,7 +984,7 @@ async fn crate_skeleton_round_trip() {
     build_hypergraph(BuildHypergraphParams {
         directory: workspace.clone(),
         force_rebuild: Some(true),
-    })
+    }, None)
     .await
     .expect("build workspace-root snapshot for crate_skeleton");
 
@@ -892,6 +1155,26 @@ fn graph_audit_error_maps_typed_audit_failures_to_invalid_params() {
     );
 }
 
+#[tokio::test]
+async fn snapshot_diff_rejects_missing_snapshots_and_bad_graph_ids() {
+    let workspace = tempfile::tempdir().expect("tempdir");
+    let directory = workspace.path().display().to_string();
+    let params = |base_graph_id: Option<&str>| SnapshotDiffParams {
+        base_directory: directory.clone(),
+        base_graph_id: base_graph_id.map(str::to_string),
+        head_directory: directory.clone(),
+        head_graph_id: None,
+        limit: None,
+    };
+
+    for graph_id in [None, Some("../../elsewhere"), Some("0123abcd")] {
+        let err = snapshot_diff(params(graph_id))
+            .await
+            .expect_err("diff without a snapshot must fail");
+        assert_eq!(err.code, ErrorCode::INVALID_PARAMS, "graph_id {graph_id:?}: {}", err.message);
+    }
+}
+
 #[test]
 fn graph_audit_error_maps_untyped_failures_to_internal_error() {
     let err = graph_audit_error("unsafe_audit")(anyhow::anyhow!("storage failed"));
@@ -1180,3 +1463,60 @@ async fn build_codemap_waits_for_workspace_lock_before_search_cache_use() {
     task.abort();
     drop(guard);
 }
+
+#[tokio::test]
+async fn api_breaking_changes_rejects_missing_snapshots() {
+    let workspace = tempfile::tempdir().expect("tempdir");
+    let directory = workspace.path().display().to_string();
+    let err = api_breaking_changes(ApiBreakingChangesParams {
+        base_directory: directory.clone(),
+        base_graph_id: Some("0123abcd".to_string()),
+        head_directory: directory,
+        head_graph_id: None,
+        crate_name: "my_crate".to_string(),
+        pagination: ListPaginationParams::default(),
+    })
+    .await
+    .expect_err("semver check without a snapshot must fail");
+    assert_eq!(err.code, ErrorCode::INVALID_PARAMS, "{}", err.message);
+}
+
+#[tokio::test]
+async fn co_change_tools_reject_missing_snapshots() {
+    let workspace = tempfile::tempdir().expect("tempdir");
+    let directory = workspace.path().display().to_string();
+
+    let errors = [
+        item_churn(ItemChurnParams {
+            directory: directory.clone(),
+            crate_name: None,
+            history: GitHistoryParams::default(),
+            pagination: ListPaginationParams::default(),
+        })
+        .await
+        .expect_err("item_churn without a snapshot must fail"),
+        item_co_change(ItemCoChangeParams {
+            directory: directory.clone(),
+            target: None,
+            crate_name: None,
+            min_co_changes: None,
+            cross_module_only: None,
+            history: GitHistoryParams::default(),
+            pagination: ListPaginationParams::default(),
+        })
+        .await
+        .expect_err("item_co_change without a snapshot must fail"),
+        module_co_change(ModuleCoChangeParams {
+            directory,
+            crate_name: None,
+            min_co_changes: None,
+            history: GitHistoryParams::default(),
+            pagination: ListPaginationParams::default(),
+        })
+        .await
+        .expect_err("module_co_change without a snapshot must fail"),
+    ];
+    for err in errors {
+        assert_eq!(err.code, ErrorCode::INVALID_PARAMS, "{}", err.message);
+    }
+}
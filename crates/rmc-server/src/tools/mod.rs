@@ -1,6 +1,8 @@
 // Phase 1: Modular structure
 mod endpoints;
 mod params;
+mod prompts;
+mod resources;
 mod router;
 
 // Hypergraph (Layer 7): MCP tools backed by the persisted graph snapshot.
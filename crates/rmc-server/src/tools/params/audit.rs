@@ -103,3 +103,103 @@ pub(crate) struct FnBodyAuditParams {
     #[serde(flatten)]
     pub pagination: ListPaginationParams,
 }
+
+#[derive(Debug, serde::Deserialize, serde::Serialize, schemars::JsonSchema)]
+pub(crate) struct SnapshotDiffParams {
+    #[schemars(description = "Workspace root of the base (\"before\") snapshot")]
+    pub base_directory: String,
+    #[schemars(description = "Optional retained `graph_id` of the base workspace. Default: its CURRENT snapshot.")]
+    #[serde(default)]
+    pub base_graph_id: Option<String>,
+    #[schemars(description = "Workspace root of the head (\"after\") snapshot. May equal `base_directory` when comparing two graph_ids.")]
+    pub head_directory: String,
+    #[schemars(description = "Optional retained `graph_id` of the head workspace. Default: its CURRENT snapshot.")]
+    #[serde(default)]
+    pub head_graph_id: Option<String>,
+    #[schemars(description = "Optional cap on entries returned per section; `counts` always reports the full totals. Default: 50.")]
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize, schemars::JsonSchema)]
+pub(crate) struct ApiBreakingChangesParams {
+    #[schemars(description = "Workspace root of the base (released / \"before\") snapshot")]
+    pub base_directory: String,
+    #[schemars(description = "Optional retained `graph_id` of the base workspace. Default: its CURRENT snapshot.")]
+    #[serde(default)]
+    pub base_graph_id: Option<String>,
+    #[schemars(description = "Workspace root of the head (\"after\") snapshot. May equal `base_directory` when comparing two graph_ids.")]
+    pub head_directory: String,
+    #[schemars(description = "Optional retained `graph_id` of the head workspace. Default: its CURRENT snapshot.")]
+    #[serde(default)]
+    pub head_graph_id: Option<String>,
+    #[schemars(description = "Crate qualified name whose pure-`pub` surface is compared (accepts a Crate or its root Module). Must exist in the base snapshot.")]
+    pub crate_name: String,
+    #[serde(flatten)]
+    pub pagination: ListPaginationParams,
+}
+
+/// Git log window shared by the co-change tools.
+#[derive(Debug, Default, Clone, serde::Deserialize, serde::Serialize, schemars::JsonSchema)]
+pub(crate) struct GitHistoryParams {
+    #[schemars(description = "Newest commits to scan (merges excluded). Default 500, hard cap 10000.")]
+    #[serde(default)]
+    pub max_commits: Option<usize>,
+    #[schemars(description = "Optional lower date bound, anything `git log --since` accepts (e.g. `2024-01-01`, `6 months ago`).")]
+    #[serde(default)]
+    pub since: Option<String>,
+    #[schemars(description = "Commits touching more items than this (formatting sweeps, mass renames) are skipped. Default 50.")]
+    #[serde(default)]
+    pub max_items_per_commit: Option<usize>,
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize, schemars::JsonSchema)]
+pub(crate) struct ItemChurnParams {
+    #[schemars(description = "Workspace root (directory containing Cargo.toml); must be inside a git repository")]
+    pub directory: String,
+    #[schemars(description = "Optional crate qualified name to scope the report. Default: all local crates.")]
+    #[serde(default)]
+    pub crate_name: Option<String>,
+    #[serde(flatten)]
+    pub history: GitHistoryParams,
+    #[serde(flatten)]
+    pub pagination: ListPaginationParams,
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize, schemars::JsonSchema)]
+pub(crate) struct ItemCoChangeParams {
+    #[schemars(description = "Workspace root (directory containing Cargo.toml); must be inside a git repository")]
+    pub directory: String,
+    #[schemars(description = "Optional item qualified name; only pairs containing it are returned, with it as `a`. Default: all pairs.")]
+    #[serde(default)]
+    pub target: Option<String>,
+    #[schemars(description = "Optional crate qualified name; both items of a pair (except `target`) must belong to it. Default: all local crates.")]
+    #[serde(default)]
+    pub crate_name: Option<String>,
+    #[schemars(description = "Minimum shared commits for a pair to be reported. Default 2.")]
+    #[serde(default)]
+    pub min_co_changes: Option<usize>,
+    #[schemars(description = "Drop pairs whose items are declared in the same module. Default false.")]
+    #[serde(default)]
+    pub cross_module_only: Option<bool>,
+    #[serde(flatten)]
+    pub history: GitHistoryParams,
+    #[serde(flatten)]
+    pub pagination: ListPaginationParams,
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize, schemars::JsonSchema)]
+pub(crate) struct ModuleCoChangeParams {
+    #[schemars(description = "Workspace root (directory containing Cargo.toml); must be inside a git repository")]
+    pub directory: String,
+    #[schemars(description = "Optional crate qualified name; only items of this crate are counted. Default: all local crates.")]
+    #[serde(default)]
+    pub crate_name: Option<String>,
+    #[schemars(description = "Minimum shared commits for a module pair to be reported. Default 2.")]
+    #[serde(default)]
+    pub min_co_changes: Option<usize>,
+    #[serde(flatten)]
+    pub history: GitHistoryParams,
+    #[serde(flatten)]
+    pub pagination: ListPaginationParams,
+}
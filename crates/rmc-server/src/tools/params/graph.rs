@@ -76,6 +76,8 @@ pub(crate) struct WhoUsesParams {
     pub directory: String,
     #[schemars(description = "Qualified name of the symbol whose non-import references you want (file:byte-range hits)")]
     pub target: String,
+    #[schemars(description = "Optional reference category filter: Read | Write | Test | Other (case-insensitive)")]
+    pub category: Option<String>,
     #[serde(flatten)]
     pub pagination: ListPaginationParams,
 }
@@ -142,6 +144,19 @@ pub(crate) struct RecursiveCallersCountParams {
     pub depth: Option<u32>,
 }
 
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub(crate) struct TestsExercisingParams {
+    #[schemars(description = "Workspace root (directory containing Cargo.toml)")]
+    pub directory: String,
+    #[schemars(description = "Qualified name of the function whose tests you want")]
+    pub target: String,
+    #[schemars(description = "Max caller hops from a test to the target (default 6, capped at 12)")]
+    #[serde(default)]
+    pub depth: Option<u32>,
+    #[serde(flatten)]
+    pub pagination: ListPaginationParams,
+}
+
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
 pub(crate) struct DeadPubParams {
     #[schemars(description = "Workspace root (directory containing Cargo.toml)")]
@@ -198,6 +213,26 @@ pub(crate) struct EnumVariantsParams {
     pub pagination: ListPaginationParams,
 }
 
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub(crate) struct FindImplementationsParams {
+    #[schemars(description = "Workspace root (directory containing Cargo.toml)")]
+    pub directory: String,
+    #[schemars(description = "Qualified name of the trait whose implementors you want (e.g. `my_crate::module::MyTrait`, or the stub name of an imported external trait such as `serde::Serialize`)")]
+    pub target: String,
+    #[serde(flatten)]
+    pub pagination: ListPaginationParams,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub(crate) struct ImplsOfTypeParams {
+    #[schemars(description = "Workspace root (directory containing Cargo.toml)")]
+    pub directory: String,
+    #[schemars(description = "Qualified name of the struct / enum / union whose trait impls you want (e.g. `my_crate::module::MyType`)")]
+    pub target: String,
+    #[serde(flatten)]
+    pub pagination: ListPaginationParams,
+}
+
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
 pub(crate) struct ItemAttributesParams {
     #[schemars(description = "Workspace root (directory containing Cargo.toml)")]
@@ -286,6 +321,57 @@ pub(crate) struct CrateTypesParams {
     pub pagination: ListPaginationParams,
 }
 
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub(crate) struct MacrosByFanInParams {
+    #[schemars(description = "Workspace root (directory containing Cargo.toml)")]
+    pub directory: String,
+    #[schemars(description = "Optional crate qualified name (or its root module) whose macros to list. Invocations from every crate are counted either way.")]
+    #[serde(default)]
+    pub krate: Option<String>,
+    #[schemars(description = "Drop macros declared inside `::tests::` modules. Default true.")]
+    #[serde(default)]
+    pub skip_test_items: Option<bool>,
+    #[serde(flatten)]
+    pub pagination: ListPaginationParams,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub(crate) struct ComplexityHotspotsParams {
+    #[schemars(description = "Workspace root (directory containing Cargo.toml)")]
+    pub directory: String,
+    #[schemars(description = "Optional crate qualified name (or its root module) whose functions to rank. Callers from every crate are counted either way.")]
+    #[serde(default)]
+    pub krate: Option<String>,
+    #[schemars(description = "Metric to rank by: `cyclomatic` (default) or `cognitive`.")]
+    #[serde(default)]
+    pub metric: Option<String>,
+    #[schemars(description = "Max caller hops counted into the score (default 3, capped at 8). 0 ranks by complexity alone.")]
+    #[serde(default)]
+    pub depth: Option<u32>,
+    #[schemars(description = "Drop functions whose metric is below this value (default 5).")]
+    #[serde(default)]
+    pub min_complexity: Option<u32>,
+    #[schemars(description = "Drop functions inside `::tests::` modules. Default true.")]
+    #[serde(default)]
+    pub skip_test_items: Option<bool>,
+    #[serde(flatten)]
+    pub pagination: ListPaginationParams,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub(crate) struct UntestedPubItemsParams {
+    #[schemars(description = "Workspace root (directory containing Cargo.toml)")]
+    pub directory: String,
+    #[schemars(description = "Optional crate qualified name (or its root module) whose items to list. Tests from every crate are walked either way.")]
+    #[serde(default)]
+    pub krate: Option<String>,
+    #[schemars(description = "Max callee hops walked from each test (default 6, capped at 12)")]
+    #[serde(default)]
+    pub depth: Option<u32>,
+    #[serde(flatten)]
+    pub pagination: ListPaginationParams,
+}
+
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
 pub(crate) struct WorkspaceStatsParams {
     #[schemars(description = "Workspace root (directory containing Cargo.toml)")]
@@ -12,6 +12,36 @@ pub(crate) struct SearchParams {
         description = "Optional embedding profile for vector search. Default: \"local-cpu-small\". One of: \"local-gpu-small\", \"local-cpu-small\", \"openrouter-qwen3-8b\", \"local-qwen3-4b\", \"local-qwen3-8b\"."
     )]
     pub embedding_profile: Option<String>,
+    #[schemars(description = "Optional workspace member crate name (`-` and `_` are interchangeable). Restricts results to files under that crate's directory.")]
+    #[serde(default)]
+    pub crate_name: Option<String>,
+    #[schemars(description = "Optional module path prefix, e.g. \"crate::parser\" or \"parser\". Matches the module and everything nested under it.")]
+    #[serde(default)]
+    pub module: Option<String>,
+    #[schemars(description = "Optional symbol kinds to keep. Any of: \"function\", \"struct\", \"enum\", \"trait\", \"impl\", \"module\", \"const\", \"static\", \"type\" for Rust code; \"toml_section\" (one `[table]` of a TOML file such as Cargo.toml), \"markdown_section\" (one heading of a markdown doc), \"text_window\" (line window of a .sql / .proto / .yaml / .graphql file).")]
+    #[serde(default)]
+    pub symbol_kind: Option<Vec<String>>,
+    #[schemars(description = "Optional file glob relative to `directory` (absolute globs are used as-is). `*` stays within one path segment, `**` crosses directories, e.g. \"crates/*/src/**\".")]
+    #[serde(default)]
+    pub file_glob: Option<String>,
+    #[schemars(description = "Optional test-code handling: \"include\" (default), \"exclude\" or \"only\". Test code is anything under `tests/`, in `tests.rs` / `*_test.rs`, a `tests` module, or carrying a test attribute.")]
+    #[serde(default)]
+    pub tests: Option<String>,
+    #[schemars(description = "Optional cap on returned results. Default: 10, max 100.")]
+    #[serde(default)]
+    pub limit: Option<usize>,
+    #[schemars(description = "Optional offset into the fused ranking, applied before `limit`. Default: 0.")]
+    #[serde(default)]
+    pub offset: Option<usize>,
+    #[schemars(description = "Optional second-stage reranking of the top 50 fused results (or `offset + limit` if larger): \"none\" (default), \"features\" (deterministic: exact symbol match, doc and path term overlap, test code demoted unless the query mentions tests), \"cross_encoder\" (local ONNX cross-encoder; downloads the model on first use and is slower) or \"graph\" (blends each hit's hypergraph fan-in, pub-ness and distance to `anchor` into the fused score; needs build_hypergraph).")]
+    #[serde(default)]
+    pub rerank: Option<String>,
+    #[schemars(description = "Optional qualified name of an anchor symbol, e.g. \"my_crate::server::run\". Only with rerank=\"graph\": hits within 3 call/use hops of it are boosted, closer ones more.")]
+    #[serde(default)]
+    pub anchor: Option<String>,
+    #[schemars(description = "Optional. When true, a JSON explanation follows the results: per hit the BM25 rank, score, matched query terms per field and Tantivy's score tree, the vector rank and cosine similarity, each engine's RRF contribution and any rerank score; plus the fusion weights, the filters applied and whether the search fell back to vector-only. Default: false.")]
+    #[serde(default)]
+    pub explain: Option<bool>,
 }
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
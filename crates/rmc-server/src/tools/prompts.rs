@@ -0,0 +1,282 @@
+//! MCP prompts built from the `skills/` audit recipes.
+//!
+//! Every `skills/<name>/SKILL.md` is compiled into the binary and served as
+//! the prompt `<name>`. Arguments are typed per skill:
+//! - `directory` (always required) — absolute workspace path; replaces the
+//!   recipe's `<absolute-path>` placeholders
+//! - `crate_name` — crate the recipe is scoped to
+//! - `target` — symbol, pattern, question or other recipe-specific subject
+//!
+//! The rendered prompt is a single user message: an argument header followed
+//! by the recipe body with its frontmatter stripped.
+
+use rmcp::{
+    ErrorData as McpError,
+    model::{
+        GetPromptResult, JsonObject, ListPromptsResult, Prompt, PromptArgument, PromptMessage,
+        PromptMessageRole,
+    },
+};
+
+/// How a skill uses an optional argument; the string is its description.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SkillArg {
+    Unused,
+    Optional(&'static str),
+    Required(&'static str),
+}
+
+#[derive(Debug, Clone, Copy)]
+struct SkillPrompt {
+    name: &'static str,
+    source: &'static str,
+    crate_name: SkillArg,
+    target: SkillArg,
+}
+
+macro_rules! skill {
+    ($name:literal, crate_name: $crate_name:expr, target: $target:expr $(,)?) => {
+        SkillPrompt {
+            name: $name,
+            source: include_str!(concat!("../../../../skills/", $name, "/SKILL.md")),
+            crate_name: $crate_name,
+            target: $target,
+        }
+    };
+}
+
+use SkillArg::{Optional, Required, Unused};
+
+const SKILLS: &[SkillPrompt] = &[
+    skill!("rmc-api-surface", crate_name: Required("Crate whose public API is audited"), target: Unused),
+    skill!(
+        "rmc-architecture-rules",
+        crate_name: Unused,
+        target: Optional("Rules file path or inline `consumer -> producer` forbidden edges"),
+    ),
+    skill!(
+        "rmc-attribute-audit",
+        crate_name: Optional("Crate to restrict the audit to"),
+        target: Required("Attribute pattern, e.g. `deprecated` or `cfg(test)`"),
+    ),
+    skill!("rmc-call-graph", crate_name: Unused, target: Required("Qualified name of the root fn")),
+    skill!("rmc-codemap", crate_name: Unused, target: Required("Task prompt or comma-separated seed qualified names")),
+    skill!("rmc-complexity", crate_name: Optional("Crate to restrict the scan to"), target: Optional("File path to focus on")),
+    skill!("rmc-crate-audit", crate_name: Required("Crate to audit"), target: Unused),
+    skill!(
+        "rmc-dependency-metric",
+        crate_name: Unused,
+        target: Optional("Ranking options, e.g. `sort_by=instability top_n=10`"),
+    ),
+    skill!("rmc-enum-variants", crate_name: Unused, target: Required("Qualified name of the enum")),
+    skill!("rmc-find-symbol", crate_name: Unused, target: Required("Symbol name or fragment")),
+    skill!("rmc-imports-exports", crate_name: Optional("Crate to focus on"), target: Unused),
+    skill!("rmc-method-api", crate_name: Unused, target: Required("Qualified name of the type")),
+    skill!(
+        "rmc-module-audit",
+        crate_name: Optional("Consumer crate to evaluate visibility from"),
+        target: Required("Qualified module path, e.g. `my_crate::net::client`"),
+    ),
+    skill!("rmc-mut-static-audit", crate_name: Unused, target: Unused),
+    skill!("rmc-reexport-chain", crate_name: Unused, target: Required("Qualified name or crate to trace")),
+    skill!("rmc-refactor-plan", crate_name: Unused, target: Required("Refactor question, optionally naming the target symbol")),
+    skill!("rmc-rename-symbol", crate_name: Unused, target: Required("Symbol name, optionally followed by the new name")),
+    skill!("rmc-semantic-overlaps", crate_name: Unused, target: Optional("Mode and target item or query")),
+    skill!(
+        "rmc-signature-search",
+        crate_name: Optional("Crate to search"),
+        target: Required("Signature filter, e.g. `async, returns Result`"),
+    ),
+    skill!(
+        "rmc-snapshot-diff",
+        crate_name: Unused,
+        target: Required("Absolute path of the second workspace (`directory` is the first)"),
+    ),
+    skill!("rmc-symbol-forensics", crate_name: Unused, target: Required("Qualified name of the symbol")),
+    skill!("rmc-test-vs-prod", crate_name: Unused, target: Required("Qualified name of the symbol")),
+    skill!("rmc-trait-audit", crate_name: Unused, target: Required("Qualified name of the trait")),
+    skill!("rmc-type-overlaps", crate_name: Optional("Crate to focus on"), target: Unused),
+    skill!("rmc-unsafe-audit", crate_name: Unused, target: Unused),
+    skill!("rmc-workspace-overview", crate_name: Unused, target: Unused),
+];
+
+impl SkillPrompt {
+    /// `(frontmatter description, body)` of the embedded `SKILL.md`.
+    fn parts(&self) -> (Option<&'static str>, &'static str) {
+        let Some(rest) = self.source.strip_prefix("---\n") else {
+            return (None, self.source.trim());
+        };
+        let Some((frontmatter, body)) = rest.split_once("\n---\n") else {
+            return (None, self.source.trim());
+        };
+        let description = frontmatter
+            .lines()
+            .find_map(|line| line.strip_prefix("description:"))
+            .map(|value| value.trim().trim_matches('"'));
+        (description, body.trim())
+    }
+
+    fn arguments(&self) -> Vec<PromptArgument> {
+        let mut arguments = vec![prompt_argument(
+            "directory",
+            "Absolute path of the Rust workspace",
+            true,
+        )];
+        for (name, arg) in [("crate_name", self.crate_name), ("target", self.target)] {
+            match arg {
+                Unused => {}
+                Optional(description) => arguments.push(prompt_argument(name, description, false)),
+                Required(description) => arguments.push(prompt_argument(name, description, true)),
+            }
+        }
+        arguments
+    }
+
+    fn to_prompt(self) -> Prompt {
+        let (description, _) = self.parts();
+        Prompt::new(self.name, description, Some(self.arguments()))
+    }
+
+    fn render(&self, arguments: Option<&JsonObject>) -> Result<GetPromptResult, McpError> {
+        let directory = string_argument(arguments, "directory")?.ok_or_else(|| {
+            McpError::invalid_params(format!("prompt `{}` requires `directory`", self.name), None)
+        })?;
+        let mut header = format!(
+            "Run the `{}` recipe below.\n\nWorkspace directory: `{directory}` — pass it as `directory` to every tool call.\n",
+            self.name
+        );
+        for (name, label, arg) in [
+            ("crate_name", "Crate", self.crate_name),
+            ("target", "Target", self.target),
+        ] {
+            let value = match arg {
+                Unused => None,
+                Optional(_) => string_argument(arguments, name)?,
+                Required(_) => Some(string_argument(arguments, name)?.ok_or_else(|| {
+                    McpError::invalid_params(
+                        format!("prompt `{}` requires `{name}`", self.name),
+                        None,
+                    )
+                })?),
+            };
+            if let Some(value) = value {
+                header.push_str(&format!("{label}: `{value}`\n"));
+            }
+        }
+
+        let (description, body) = self.parts();
+        let body = body.replace("<absolute-path>", directory);
+        Ok(GetPromptResult {
+            description: description.map(str::to_string),
+            messages: vec![PromptMessage::new_text(
+                PromptMessageRole::User,
+                format!("{header}\n{body}\n"),
+            )],
+        })
+    }
+}
+
+fn prompt_argument(name: &str, description: &str, required: bool) -> PromptArgument {
+    PromptArgument {
+        name: name.to_string(),
+        title: None,
+        description: Some(description.to_string()),
+        required: Some(required),
+    }
+}
+
+/// Non-empty string argument `name`; other JSON types are rejected.
+fn string_argument<'a>(
+    arguments: Option<&'a JsonObject>,
+    name: &str,
+) -> Result<Option<&'a str>, McpError> {
+    match arguments.and_then(|arguments| arguments.get(name)) {
+        None | Some(serde_json::Value::Null) => Ok(None),
+        Some(serde_json::Value::String(value)) => {
+            Ok(Some(value.trim()).filter(|value| !value.is_empty()))
+        }
+        Some(other) => Err(McpError::invalid_params(
+            format!("prompt argument `{name}` must be a string, got {other}"),
+            None,
+        )),
+    }
+}
+
+pub(crate) fn list_prompts() -> ListPromptsResult {
+    ListPromptsResult::with_all_items(SKILLS.iter().map(|skill| skill.to_prompt()).collect())
+}
+
+pub(crate) fn get_prompt(
+    name: &str,
+    arguments: Option<&JsonObject>,
+) -> Result<GetPromptResult, McpError> {
+    SKILLS
+        .iter()
+        .find(|skill| skill.name == name)
+        .ok_or_else(|| McpError::invalid_params(format!("unknown prompt `{name}`"), None))?
+        .render(arguments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn args(value: serde_json::Value) -> JsonObject {
+        value.as_object().cloned().expect("object")
+    }
+
+    fn text(result: &GetPromptResult) -> String {
+        serde_json::to_value(&result.messages[0].content)
+            .unwrap()
+            .get("text")
+            .and_then(|text| text.as_str())
+            .expect("text message")
+            .to_string()
+    }
+
+    #[test]
+    fn every_skill_directory_is_a_prompt() {
+        let skills_dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../../skills");
+        let mut on_disk: Vec<String> = std::fs::read_dir(skills_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        on_disk.sort();
+        let mut embedded: Vec<String> = SKILLS.iter().map(|skill| skill.name.to_string()).collect();
+        embedded.sort();
+        assert_eq!(embedded, on_disk);
+
+        for skill in SKILLS {
+            let (description, body) = skill.parts();
+            assert!(description.is_some(), "{} has no description", skill.name);
+            assert!(body.starts_with('#'), "{} body keeps frontmatter", skill.name);
+        }
+        assert_eq!(list_prompts().prompts.len(), SKILLS.len());
+    }
+
+    #[test]
+    fn renders_arguments_into_the_recipe() {
+        let result = get_prompt(
+            "rmc-crate-audit",
+            Some(&args(json!({ "directory": "/ws", "crate_name": "rmc_graph" }))),
+        )
+        .unwrap();
+        let text = text(&result);
+        assert!(text.contains("Workspace directory: `/ws`"));
+        assert!(text.contains("Crate: `rmc_graph`"));
+        assert!(text.contains("build_hypergraph(directory=/ws)"));
+        assert!(!text.contains("<absolute-path>"));
+        assert!(!text.starts_with("---"));
+        assert_eq!(result.description.as_deref(), Some("Audit one Rust crate."));
+    }
+
+    #[test]
+    fn rejects_missing_required_and_unknown_prompts() {
+        assert!(get_prompt("rmc-crate-audit", Some(&args(json!({ "directory": "/ws" })))).is_err());
+        assert!(get_prompt("rmc-unsafe-audit", None).is_err());
+        assert!(get_prompt("rmc-unsafe-audit", Some(&args(json!({ "directory": 7 })))).is_err());
+        assert!(get_prompt("rmc-missing", Some(&args(json!({ "directory": "/ws" })))).is_err());
+        assert!(get_prompt("rmc-unsafe-audit", Some(&args(json!({ "directory": "/ws" })))).is_ok());
+    }
+}
@@ -0,0 +1,538 @@
+//! MCP resources over persisted workspace state.
+//!
+//! A workspace is addressed by its graph data-dir hash
+//! (`GraphPaths::workspace_hash`), so only workspaces with a published
+//! hypergraph snapshot are listed or readable:
+//!
+//! - `rmc://<workspace>/snapshot/manifest` — published manifest plus the
+//!   watch-mode stale marker
+//! - `rmc://<workspace>/file/<path>` — a workspace-relative file
+//! - `rmc://<workspace>/module/<qualified>` — module tree and items below a
+//!   crate or module
+//! - `rmc://<workspace>/audit/<name>` — full audit report for the current
+//!   snapshot, rendered once per graph id (`unsafe`, `mut_static`,
+//!   `recursion`)
+//!
+//! Subscriptions are per session. `build_hypergraph` publishes a runtime-wide
+//! event; every session forwards it as `notifications/resources/updated` for
+//! its subscribed URIs under the republished workspace.
+
+use std::collections::BTreeSet;
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use rmcp::{
+    ErrorData as McpError, Peer, RoleServer,
+    model::{
+        AnnotateAble, ListResourceTemplatesResult, ListResourcesResult, RawResource,
+        RawResourceTemplate, ReadResourceResult, Resource, ResourceContents,
+        ResourceUpdatedNotificationParam,
+    },
+};
+use serde::Serialize;
+use tokio::sync::broadcast::{self, error::RecvError};
+
+use rmc_graph::graph::{
+    GraphManifest, ModuleTreeNode, NodeKind, RecursionCheckOptions, SnapshotStaleness,
+    open_current_for_workspace, published_workspaces, run_mut_static_audit, run_recursion_check,
+    run_unsafe_audit, workspace_staleness,
+};
+
+use crate::mcp::{ResourceState, SnapshotPublished};
+use crate::tools::graph::response::internal_error;
+
+pub(crate) const RESOURCE_SCHEME: &str = "rmc://";
+
+const JSON_MIME_TYPE: &str = "application/json";
+
+/// Audit reports exposed as resources. Only audits whose full output is a
+/// pure function of the snapshot (no per-call options) are listed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum AuditReport {
+    Unsafe,
+    MutStatic,
+    Recursion,
+}
+
+impl AuditReport {
+    const ALL: [AuditReport; 3] = [Self::Unsafe, Self::MutStatic, Self::Recursion];
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Unsafe => "unsafe",
+            Self::MutStatic => "mut_static",
+            Self::Recursion => "recursion",
+        }
+    }
+
+    fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|report| report.name() == name)
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            Self::Unsafe => "unsafe_audit findings for every local crate",
+            Self::MutStatic => "mut_static_audit findings (global mutable state)",
+            Self::Recursion => "recursion_check cycles with the default cycle length",
+        }
+    }
+
+    /// Run the audit against the published snapshot and render the full,
+    /// unpaginated report.
+    fn render(self, workspace_root: &Path, graph_id: &str) -> Result<String, McpError> {
+        match self {
+            Self::Unsafe => {
+                let findings =
+                    run_unsafe_audit(workspace_root).map_err(internal_error("unsafe_audit"))?;
+                to_json(&AuditResource::new(self, graph_id, findings.len(), findings))
+            }
+            Self::MutStatic => {
+                let findings = run_mut_static_audit(workspace_root)
+                    .map_err(internal_error("mut_static_audit"))?;
+                to_json(&AuditResource::new(self, graph_id, findings.len(), findings))
+            }
+            Self::Recursion => {
+                let output = run_recursion_check(workspace_root, RecursionCheckOptions::default())
+                    .map_err(internal_error("recursion_check"))?;
+                to_json(&AuditResource::new(
+                    self,
+                    graph_id,
+                    output.cycles.len(),
+                    output.cycles,
+                ))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum ResourceTarget {
+    Manifest,
+    File(PathBuf),
+    Module(String),
+    Audit(AuditReport),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ResourceUri {
+    pub(crate) workspace_hash: String,
+    pub(crate) target: ResourceTarget,
+}
+
+pub(crate) fn parse_resource_uri(uri: &str) -> Result<ResourceUri, McpError> {
+    let malformed = |reason: &str| {
+        McpError::invalid_params(format!("malformed resource URI `{uri}`: {reason}"), None)
+    };
+    let rest = uri
+        .strip_prefix(RESOURCE_SCHEME)
+        .ok_or_else(|| malformed("expected the rmc:// scheme"))?;
+    let (workspace_hash, path) = rest
+        .split_once('/')
+        .ok_or_else(|| malformed("missing resource path"))?;
+    if workspace_hash.is_empty() {
+        return Err(malformed("missing workspace"));
+    }
+    let target = match path.split_once('/') {
+        Some(("snapshot", "manifest")) => ResourceTarget::Manifest,
+        Some(("file", file)) => ResourceTarget::File(
+            workspace_relative_path(file)
+                .ok_or_else(|| malformed("file path must be workspace-relative without `..`"))?,
+        ),
+        Some(("module", qualified_name)) if !qualified_name.is_empty() => {
+            ResourceTarget::Module(qualified_name.to_string())
+        }
+        Some(("audit", name)) => ResourceTarget::Audit(
+            AuditReport::parse(name)
+                .ok_or_else(|| malformed("unknown audit; expected unsafe | mut_static | recursion"))?,
+        ),
+        _ => {
+            return Err(malformed(
+                "expected snapshot/manifest, file/<path>, module/<qualified_name> or audit/<name>",
+            ));
+        }
+    };
+    Ok(ResourceUri {
+        workspace_hash: workspace_hash.to_string(),
+        target,
+    })
+}
+
+/// `path` as a relative path that cannot leave the workspace root.
+fn workspace_relative_path(path: &str) -> Option<PathBuf> {
+    let mut relative = PathBuf::new();
+    for component in Path::new(path).components() {
+        match component {
+            Component::Normal(part) => relative.push(part),
+            Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    (!relative.as_os_str().is_empty()).then_some(relative)
+}
+
+fn workspace_uri(workspace_hash: &str) -> String {
+    format!("{RESOURCE_SCHEME}{workspace_hash}/")
+}
+
+pub(crate) fn list_resources() -> Result<ListResourcesResult, McpError> {
+    let mut resources = Vec::new();
+    for manifest in published_workspaces(None).map_err(internal_error("published_workspaces"))? {
+        let base = workspace_uri(&manifest.workspace_hash);
+        let label = Path::new(&manifest.workspace_root)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| manifest.workspace_root.clone());
+        resources.push(resource(
+            format!("{base}snapshot/manifest"),
+            format!("{label} snapshot manifest"),
+            format!("Published hypergraph snapshot of {}", manifest.workspace_root),
+        ));
+        for report in AuditReport::ALL {
+            resources.push(resource(
+                format!("{base}audit/{}", report.name()),
+                format!("{label} {} audit", report.name()),
+                format!("{} in {}", report.description(), manifest.workspace_root),
+            ));
+        }
+    }
+    Ok(ListResourcesResult::with_all_items(resources))
+}
+
+fn resource(uri: String, name: String, description: String) -> Resource {
+    let mut raw = RawResource::new(uri, name);
+    raw.description = Some(description);
+    raw.mime_type = Some(JSON_MIME_TYPE.to_string());
+    raw.no_annotation()
+}
+
+pub(crate) fn list_resource_templates() -> ListResourceTemplatesResult {
+    let templates = [
+        (
+            "rmc://{workspace}/file/{path}",
+            "workspace file",
+            "Workspace-relative file of a workspace with a published snapshot",
+            "text/plain",
+        ),
+        (
+            "rmc://{workspace}/module/{qualified_name}",
+            "module tree",
+            "Module tree and items below a crate or module of the published snapshot",
+            JSON_MIME_TYPE,
+        ),
+        (
+            "rmc://{workspace}/audit/{name}",
+            "audit report",
+            "Audit report for the published snapshot: unsafe, mut_static or recursion",
+            JSON_MIME_TYPE,
+        ),
+    ];
+    ListResourceTemplatesResult::with_all_items(
+        templates
+            .into_iter()
+            .map(|(uri_template, name, description, mime_type)| {
+                RawResourceTemplate {
+                    uri_template: uri_template.to_string(),
+                    name: name.to_string(),
+                    title: None,
+                    description: Some(description.to_string()),
+                    mime_type: Some(mime_type.to_string()),
+                }
+                .no_annotation()
+            })
+            .collect(),
+    )
+}
+
+pub(crate) async fn read_resource(
+    resources: &ResourceState,
+    uri: &str,
+) -> Result<ReadResourceResult, McpError> {
+    let parsed = parse_resource_uri(uri)?;
+    let manifest = published_workspace(&parsed.workspace_hash)?;
+    let workspace_root = PathBuf::from(&manifest.workspace_root);
+    let text = match parsed.target {
+        ResourceTarget::Manifest => manifest_json(&manifest, &workspace_root)?,
+        ResourceTarget::File(relative) => read_workspace_file(&workspace_root, &relative).await?,
+        ResourceTarget::Module(qualified_name) => module_json(&workspace_root, &qualified_name)?,
+        ResourceTarget::Audit(report) => audit_json(resources, &manifest, report)
+            .await?
+            .to_string(),
+    };
+    Ok(ReadResourceResult {
+        contents: vec![ResourceContents::text(text, uri)],
+    })
+}
+
+fn published_workspace(workspace_hash: &str) -> Result<GraphManifest, McpError> {
+    published_workspaces(None)
+        .map_err(internal_error("published_workspaces"))?
+        .into_iter()
+        .find(|manifest| manifest.workspace_hash == workspace_hash)
+        .ok_or_else(|| {
+            McpError::resource_not_found(
+                format!(
+                    "no published snapshot for workspace `{workspace_hash}` — call build_hypergraph first"
+                ),
+                None,
+            )
+        })
+}
+
+#[derive(Serialize)]
+struct ManifestResource<'a> {
+    manifest: &'a GraphManifest,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stale: Option<SnapshotStaleness>,
+}
+
+fn manifest_json(manifest: &GraphManifest, workspace_root: &Path) -> Result<String, McpError> {
+    let stale = workspace_staleness(workspace_root).map_err(internal_error("workspace_staleness"))?;
+    to_json(&ManifestResource { manifest, stale })
+}
+
+async fn read_workspace_file(workspace_root: &Path, relative: &Path) -> Result<String, McpError> {
+    let not_found = || {
+        McpError::resource_not_found(
+            format!("no file `{}` in {}", relative.display(), workspace_root.display()),
+            None,
+        )
+    };
+    let path = tokio::fs::canonicalize(workspace_root.join(relative))
+        .await
+        .map_err(|_| not_found())?;
+    // A symlink inside the workspace may still point outside it.
+    if !path.starts_with(workspace_root) || !path.is_file() {
+        return Err(not_found());
+    }
+    tokio::fs::read_to_string(&path).await.map_err(|e| {
+        McpError::invalid_params(
+            format!("{} is not readable as UTF-8 text: {e}", path.display()),
+            None,
+        )
+    })
+}
+
+#[derive(Serialize)]
+struct ModuleResource<'a> {
+    graph_id: &'a str,
+    tree: ModuleTreeNode,
+}
+
+fn module_json(workspace_root: &Path, qualified_name: &str) -> Result<String, McpError> {
+    let snap = open_current_for_workspace(workspace_root)
+        .map_err(internal_error("open_current_for_workspace"))?
+        .ok_or_else(|| {
+            McpError::resource_not_found(
+                format!(
+                    "no snapshot at {} — call build_hypergraph first",
+                    workspace_root.display()
+                ),
+                None,
+            )
+        })?;
+    let (module_id, _) = snap
+        .lookup_by_qualified_name(qualified_name)
+        .map_err(internal_error("lookup_by_qualified_name"))?
+        .filter(|(_, node)| matches!(node.kind, NodeKind::Crate | NodeKind::Module))
+        .ok_or_else(|| {
+            McpError::resource_not_found(
+                format!("no crate or module `{qualified_name}` in the snapshot"),
+                None,
+            )
+        })?;
+    let tree = snap
+        .module_subtree(module_id, None)
+        .map_err(internal_error("module_subtree"))?;
+    to_json(&ModuleResource {
+        graph_id: &snap.manifest.graph_id,
+        tree,
+    })
+}
+
+#[derive(Serialize)]
+struct AuditResource<'a, T> {
+    report: &'static str,
+    graph_id: &'a str,
+    finding_count: usize,
+    findings: T,
+}
+
+impl<'a, T> AuditResource<'a, T> {
+    fn new(report: AuditReport, graph_id: &'a str, finding_count: usize, findings: T) -> Self {
+        Self {
+            report: report.name(),
+            graph_id,
+            finding_count,
+            findings,
+        }
+    }
+}
+
+async fn audit_json(
+    resources: &ResourceState,
+    manifest: &GraphManifest,
+    report: AuditReport,
+) -> Result<Arc<String>, McpError> {
+    if let Some(json) =
+        resources.audit_report(&manifest.workspace_hash, report.name(), &manifest.graph_id)
+    {
+        return Ok(json);
+    }
+    let workspace_root = PathBuf::from(&manifest.workspace_root);
+    let graph_id = manifest.graph_id.clone();
+    let json = tokio::task::spawn_blocking(move || report.render(&workspace_root, &graph_id))
+        .await
+        .map_err(|e| McpError::internal_error(format!("spawn_blocking join error: {e}"), None))??;
+    Ok(resources.store_audit_report(
+        &manifest.workspace_hash,
+        report.name(),
+        &manifest.graph_id,
+        json,
+    ))
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<String, McpError> {
+    serde_json::to_string_pretty(value)
+        .map_err(|e| McpError::internal_error(format!("serialize: {e}"), None))
+}
+
+/// One session's resource subscriptions. Clones share state, so every clone
+/// of a session's router sees the same set.
+#[derive(Clone, Default)]
+pub(crate) struct ResourceSubscriptions {
+    uris: Arc<Mutex<BTreeSet<String>>>,
+    forwarding: Arc<AtomicBool>,
+}
+
+impl ResourceSubscriptions {
+    /// Subscribe to `uri`; the first subscription starts forwarding
+    /// snapshot events to `peer`.
+    pub(crate) fn subscribe(
+        &self,
+        uri: &str,
+        peer: Peer<RoleServer>,
+        resources: &ResourceState,
+    ) -> Result<(), McpError> {
+        parse_resource_uri(uri)?;
+        self.uris
+            .lock()
+            .expect("resource subscription mutex poisoned")
+            .insert(uri.to_string());
+        if !self.forwarding.swap(true, Ordering::SeqCst) {
+            tokio::spawn(forward_snapshot_events(
+                resources.subscribe_snapshots(),
+                Arc::clone(&self.uris),
+                peer,
+            ));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn unsubscribe(&self, uri: &str) {
+        self.uris
+            .lock()
+            .expect("resource subscription mutex poisoned")
+            .remove(uri);
+    }
+}
+
+/// Runs until the session's peer goes away or the runtime drops the channel.
+async fn forward_snapshot_events(
+    mut events: broadcast::Receiver<SnapshotPublished>,
+    uris: Arc<Mutex<BTreeSet<String>>>,
+    peer: Peer<RoleServer>,
+) {
+    loop {
+        let prefix = match events.recv().await {
+            Ok(event) => Some(workspace_uri(&event.workspace_hash)),
+            // Events were dropped; refresh every subscription rather than guess.
+            Err(RecvError::Lagged(_)) => None,
+            Err(RecvError::Closed) => return,
+        };
+        let updated: Vec<String> = uris
+            .lock()
+            .expect("resource subscription mutex poisoned")
+            .iter()
+            .filter(|uri| prefix.as_ref().is_none_or(|prefix| uri.starts_with(prefix)))
+            .cloned()
+            .collect();
+        for uri in updated {
+            if let Err(error) = peer
+                .notify_resource_updated(ResourceUpdatedNotificationParam { uri })
+                .await
+            {
+                tracing::debug!("Stopping resource notifications: {}", error);
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn target(uri: &str) -> ResourceTarget {
+        parse_resource_uri(uri).expect(uri).target
+    }
+
+    #[test]
+    fn parses_every_resource_kind() {
+        let parsed = parse_resource_uri("rmc://abc123/snapshot/manifest").unwrap();
+        assert_eq!(parsed.workspace_hash, "abc123");
+        assert_eq!(parsed.target, ResourceTarget::Manifest);
+
+        assert_eq!(
+            target("rmc://abc123/file/src/lib.rs"),
+            ResourceTarget::File(PathBuf::from("src/lib.rs"))
+        );
+        assert_eq!(
+            target("rmc://abc123/file/./crates/a/Cargo.toml"),
+            ResourceTarget::File(PathBuf::from("crates/a/Cargo.toml"))
+        );
+        assert_eq!(
+            target("rmc://abc123/module/rmc_graph::graph"),
+            ResourceTarget::Module("rmc_graph::graph".to_string())
+        );
+        assert_eq!(
+            target("rmc://abc123/audit/mut_static"),
+            ResourceTarget::Audit(AuditReport::MutStatic)
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_uris_and_escaping_paths() {
+        for uri in [
+            "file:///etc/passwd",
+            "rmc://abc123",
+            "rmc:///snapshot/manifest",
+            "rmc://abc123/snapshot/graph",
+            "rmc://abc123/module/",
+            "rmc://abc123/audit/dead_pub",
+            "rmc://abc123/file/",
+            "rmc://abc123/file/../secret.rs",
+            "rmc://abc123/file/src/../../secret.rs",
+            "rmc://abc123/file//etc/passwd",
+        ] {
+            assert!(parse_resource_uri(uri).is_err(), "{uri} should be rejected");
+        }
+    }
+
+    #[test]
+    fn templates_cover_parameterized_resources() {
+        let templates = list_resource_templates().resource_templates;
+        let uris: Vec<&str> = templates
+            .iter()
+            .map(|template| template.raw.uri_template.as_str())
+            .collect();
+        assert_eq!(
+            uris,
+            vec![
+                "rmc://{workspace}/file/{path}",
+                "rmc://{workspace}/module/{qualified_name}",
+                "rmc://{workspace}/audit/{name}",
+            ]
+        );
+    }
+}
@@ -13,7 +13,7 @@
 //! SearchToolRouter (this module)
 //!     ├─→ endpoints::query / endpoints::analysis (search, navigation, similarity)
 //!     ├─→ tools::graph                           (persisted-hypergraph endpoints)
-//!     ├─→ endpoints::index                       (hypergraph build, codebase index)
+//!     ├─→ endpoints::index                       (hypergraph build, codebase index, verify, backups)
 //!     ├─→ endpoints::health                      (health_check)
 //!     └─→ endpoints::cache                       (clear_cache)
 //! ```
@@ -27,9 +27,15 @@
 //! ```
 
 use rmcp::{
-    ErrorData as McpError, ServerHandler,
+    ErrorData as McpError, RoleServer, ServerHandler,
     handler::server::{router::tool::ToolRouter, wrapper::Parameters},
-    model::{CallToolResult, Implementation, ProtocolVersion, ServerCapabilities, ServerInfo},
+    model::{
+        CallToolResult, GetPromptRequestParam, GetPromptResult, Implementation,
+        ListPromptsResult, ListResourceTemplatesResult, ListResourcesResult,
+        PaginatedRequestParam, ProtocolVersion, ReadResourceRequestParam, ReadResourceResult,
+        ServerCapabilities, ServerInfo, SubscribeRequestParam, UnsubscribeRequestParam,
+    },
+    service::RequestContext,
     tool, tool_handler, tool_router,
 };
 
@@ -45,6 +51,8 @@ pub struct SearchToolRouter {
     tool_router: ToolRouter<Self>,
     /// Runtime-owned sync, cache, semantic, and lifecycle state.
     runtime: crate::mcp::RuntimeState,
+    /// This session's MCP resource subscriptions.
+    resource_subscriptions: crate::tools::resources::ResourceSubscriptions,
 }
 
 impl SearchToolRouter {
@@ -52,6 +60,7 @@ impl SearchToolRouter {
         Self {
             tool_router: Self::tool_router(),
             runtime: crate::mcp::RuntimeState::standalone(),
+            resource_subscriptions: Default::default(),
         }
     }
 
@@ -68,6 +77,7 @@ impl SearchToolRouter {
         Self {
             tool_router: Self::tool_router(),
             runtime,
+            resource_subscriptions: Default::default(),
         }
     }
 }
@@ -85,7 +95,7 @@ impl SearchToolRouter {
 
     /// Perform hybrid search (BM25 + Vector) on Rust code in the specified directory
     #[tool(
-        description = "Search for keywords in Rust code using hybrid search (BM25 + semantic vectors)"
+        description = "Search for keywords in Rust code and project files (Cargo.toml and other TOML, markdown docs, .sql / .proto / .yaml / .graphql) using hybrid search (BM25 + semantic vectors). Optional filters (`crate_name`, `module`, `symbol_kind`, `file_glob`, `tests`) are applied to both engines before rank fusion; page with `limit` / `offset`. Set `rerank` to \"features\" or \"cross_encoder\" to rescore the top of the fused list, e.g. to lift a definition above the tests that mention it (\"features\" also weighs hypergraph fan-in once build_hypergraph has run), or to \"graph\" to favor heavily used `pub` definitions and symbols near an optional `anchor` (needs build_hypergraph). Set `explain=true` to see why each hit ranked where it did."
     )]
     async fn search(
         &self,
@@ -93,12 +103,34 @@ impl SearchToolRouter {
             directory,
             keyword,
             embedding_profile,
+            crate_name,
+            module,
+            symbol_kind,
+            file_glob,
+            tests,
+            limit,
+            offset,
+            rerank,
+            anchor,
+            explain,
         }): Parameters<SearchParams>,
     ) -> Result<CallToolResult, McpError> {
         crate::tools::endpoints::query::search(
             &directory,
             &keyword,
             embedding_profile.as_deref(),
+            crate::tools::endpoints::query::SearchOptions {
+                crate_name,
+                module,
+                symbol_kind,
+                file_glob,
+                tests,
+                limit,
+                offset,
+                rerank,
+                anchor,
+                explain,
+            },
             self.runtime.background_sync_manager(),
             self.runtime.workspace_locks(),
             Some(self.runtime.search_cache()),
@@ -199,7 +231,7 @@ impl SearchToolRouter {
 
     /// Analyze code complexity metrics for a file
     #[tool(
-        description = "Analyze code complexity metrics (LOC, cyclomatic complexity, function count)"
+        description = "Analyze code complexity metrics for one file: LOC, symbol counts, and a per-function table of cyclomatic and cognitive complexity, max nesting depth, parameter count and LOC, computed from the syntax tree. For workspace-wide ranking weighted by callers, use complexity_hotspots."
     )]
     async fn analyze_complexity(
         &self,
@@ -259,6 +291,29 @@ impl SearchToolRouter {
         .await
     }
 
+    /// Check an index for Tantivy/LanceDB drift and optionally repair it
+    #[tool(description = "Verify that a codebase's BM25 (Tantivy) and vector (LanceDB) indexes agree. Reports chunk counts, chunks present in only one store (with a sample of IDs), the files they belong to, files recorded in the metadata cache with no chunks in either store, and deleted files that still have chunks or cache entries. Pass repair=true to re-index the orphaned and unindexed files, drop the deleted ones, and report the state after the repair. Returns JSON.")]
+    async fn verify_index(
+        &self,
+        Parameters(params): Parameters<crate::tools::endpoints::index::VerifyIndexParams>,
+    ) -> Result<CallToolResult, McpError> {
+        crate::tools::endpoints::index::verify_index(
+            params,
+            self.runtime.workspace_locks(),
+            Some(self.runtime.search_cache()),
+        )
+        .await
+    }
+
+    /// List or restore a codebase's pre-run index backups
+    #[tool(description = "List the backups taken before each index run of a codebase (Merkle snapshot plus metadata cache, newest first) and whether the last run was interrupted. Failed or interrupted runs are rolled back automatically; pass restore=\"<backup id>\" or restore=\"latest\" to roll back by hand, then run index_codebase to re-index the files changed since that backup. Returns JSON.")]
+    async fn index_backups(
+        &self,
+        Parameters(params): Parameters<crate::tools::endpoints::index::IndexBackupsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        crate::tools::endpoints::index::index_backups(params, self.runtime.workspace_locks()).await
+    }
+
     /// Clear corrupted cache, index, and vector store files
     #[tool(description = "Clear corrupted cache files to fix 'Failed to open MetadataCache' errors. Clears metadata cache, tantivy index, and vector store. Pass include_hypergraph=true to ALSO wipe the persisted hypergraph snapshot at <data_dir>/graphs/<workspace_hash>/ — forces the next build_hypergraph call to do a full re-index. Pass dry_run=true to report the directories that would be removed without deleting them. The response lists exactly which directories were or would be cleared.")]
     async fn clear_cache(
@@ -305,7 +360,7 @@ impl SearchToolRouter {
         &self,
         Parameters(params): Parameters<crate::tools::params::BuildHypergraphParams>,
     ) -> Result<CallToolResult, McpError> {
-        crate::tools::graph::core::build_hypergraph(params).await
+        crate::tools::graph::core::build_hypergraph(params, Some(self.runtime.resources())).await
     }
 
     #[tool(
@@ -369,7 +424,7 @@ impl SearchToolRouter {
     }
 
     #[tool(
-        description = "List every non-import reference to the given symbol (file path + byte range + read/write/test category). Complements who_imports, which only enumerates `use` edges."
+        description = "List every non-import reference to the given symbol (file path + byte range + read/write/test category). Struct and enum-variant fields are symbols too (`my_crate::Config.max_size`); their assignments, `&mut` borrows and struct-literal initializers count as Write. Optional `category` filter narrows to Read, Write, Test or Other. Complements who_imports, which only enumerates `use` edges."
     )]
     async fn who_uses(
         &self,
@@ -438,6 +493,16 @@ impl SearchToolRouter {
         crate::tools::graph::core::recursive_callers_count(params).await
     }
 
+    #[tool(
+        description = "Which tests exercise a function: reverse BFS from `target` over fn-body references, returning every test fn (`#[test]`, `#[bench]`, `#[tokio::test]` and other `#[<path>::test]`) that reaches it, with its crate, crate target kind (`test` for integration tests, `lib` / `bin` for `#[cfg(test)]` unit tests) and hop count, nearest first. Test helpers are walked through but not listed. `depth` defaults to 6 and is capped at 12. Tests are only in the snapshot when `.rmc.toml` sets `test_targets = true` under `[rust_analyzer]`. Requires build_hypergraph to have created a snapshot first."
+    )]
+    async fn tests_exercising(
+        &self,
+        Parameters(params): Parameters<crate::tools::params::TestsExercisingParams>,
+    ) -> Result<CallToolResult, McpError> {
+        crate::tools::graph::core::tests_exercising(params).await
+    }
+
     #[tool(
         description = "Scan a local crate for `pub` items with no cross-crate importer or reference — candidates for downgrading to `pub(crate)`. Conservative: may miss items used only through public type signatures."
     )]
@@ -458,6 +523,16 @@ impl SearchToolRouter {
         crate::tools::graph::surface::dead_pub_report(params).await
     }
 
+    #[tool(
+        description = "List `pub` fns and methods of library crates that no test reaches: walks fn-body references forward from every test fn up to `depth` hops (default 6, capped at 12) and returns the public callables left unreached. Inherent methods use their own modifier, capped by their host type's; trait methods take the trait's; items under `::tests::` are skipped. Optional `krate` limits which items are listed. Errors when the snapshot has no tests — set `test_targets = true` under `[rust_analyzer]` in `.rmc.toml` and rebuild. Calls through trait objects and generic bounds reach the trait declaration, not its impls. Requires build_hypergraph to have created a snapshot first."
+    )]
+    async fn untested_pub_items(
+        &self,
+        Parameters(params): Parameters<crate::tools::params::UntestedPubItemsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        crate::tools::graph::surface::untested_pub_items(params).await
+    }
+
     #[tool(
         description = "All cross-crate consumer→producer edges in the workspace, with the symbols carrying each edge (sorted by total ref count desc). Local inherent method calls and local trait-declaration dispatch are captured in usage counts as Method items; unresolved indirect calls such as external dyn-trait dispatch or generic Fn calls remain blind spots."
     )]
@@ -488,6 +563,26 @@ impl SearchToolRouter {
         crate::tools::graph::surface::enum_variants(params).await
     }
 
+    #[tool(
+        description = "Every `impl Trait for Type` block in the workspace's local crates that implements the trait at `target` (qualified name — a local trait, or the stub name of an imported external trait such as `serde::Serialize`). One row per impl block with trait_name, self_type (HirDisplay, e.g. `Wrapper<T>`), self_type_qualified_name when the self type is a workspace ADT (absent for blanket impls / refs / foreign types), declaring crate, impl generics with declaration-site bounds, is_negative, and (file, byte span). Derive-generated impls are included and point at the deriving type. Sorted by (file, span). Supports `limit` / `offset` / `summary` (summary drops generics and locations)."
+    )]
+    async fn find_implementations(
+        &self,
+        Parameters(params): Parameters<crate::tools::params::FindImplementationsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        crate::tools::graph::surface::find_implementations(params).await
+    }
+
+    #[tool(
+        description = "Every trait implemented by the struct / enum / union at `target` (qualified name) through an `impl Trait for Type` block in a local crate — local traits and external ones (`Default`, `Display`, derive output) alike. Inherent impls are not listed. Same row shape as find_implementations; trait_qualified_name is absent for external traits no local module imports. Blanket impls (`impl<T: Foo> Bar for T`) are not indexed by type and do not appear. Sorted by (file, span). Supports `limit` / `offset` / `summary`."
+    )]
+    async fn impls_of_type(
+        &self,
+        Parameters(params): Parameters<crate::tools::params::ImplsOfTypeParams>,
+    ) -> Result<CallToolResult, McpError> {
+        crate::tools::graph::surface::impls_of_type(params).await
+    }
+
     #[tool(
         description = "Outer attributes and doc-comment lines recorded for the Item at `target`. Returns the trimmed source text of each `#[...]` attribute (e.g. `#[derive(Debug, Clone)]`, `#[must_use]`, `#[non_exhaustive]`, `#[inline]`) and each doc-comment line as `/// ...` (one entry per line). Source order preserved. Empty list when the item has no attributes or its AST source can't be resolved."
     )]
@@ -559,7 +654,7 @@ impl SearchToolRouter {
     }
 
     #[tool(
-        description = "List crate-owned type items from the hypergraph snapshot. Defaults to Struct, Enum, Union, Trait, and TypeAlias; optional filters support pure `pub` only, associated types, test-module exclusion, and pagination. Requires build_hypergraph to have created a snapshot first."
+        description = "List crate-owned type items from the hypergraph snapshot. Defaults to Struct, Enum, Union, Trait, and TypeAlias; optional filters support pure `pub` only, associated types, test-module exclusion, and pagination. Structs and unions list their fields with declared type and visibility. Requires build_hypergraph to have created a snapshot first."
     )]
     async fn crate_types(
         &self,
@@ -568,6 +663,26 @@ impl SearchToolRouter {
         crate::tools::graph::surface::crate_types(params).await
     }
 
+    #[tool(
+        description = "Rank local macros (`macro_rules!`, `macro` 2.0, and proc macros) by fan-in: invocation count, distinct consumer modules and functions, and invocations from other crates. Each row carries the macro kind (Declarative / FunctionLike / Derive / Attribute), `#[macro_export]` flag, visibility and declaration site. Optional `krate` limits which macros are listed. Use who_uses on a macro's qualified name for the individual invocation sites. Requires build_hypergraph to have created a snapshot first."
+    )]
+    async fn macros_by_fan_in(
+        &self,
+        Parameters(params): Parameters<crate::tools::params::MacrosByFanInParams>,
+    ) -> Result<CallToolResult, McpError> {
+        crate::tools::graph::surface::macros_by_fan_in(params).await
+    }
+
+    #[tool(
+        description = "Rank local functions by complexity weighted by reach: score = complexity × (transitive callers + 1), so a tangled fn on a hot path outranks an equally tangled leaf. Complexity is measured per function from the syntax tree at build time — `metric` picks `cyclomatic` (default) or `cognitive`; each row also carries max nesting depth, parameter count and LOC. `depth` (default 3, capped at 8) bounds the caller walk; `min_complexity` (default 5) drops simple fns; optional `krate` limits which fns are ranked. Requires build_hypergraph to have created a snapshot first."
+    )]
+    async fn complexity_hotspots(
+        &self,
+        Parameters(params): Parameters<crate::tools::params::ComplexityHotspotsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        crate::tools::graph::surface::complexity_hotspots(params).await
+    }
+
     #[tool(
         description = "Write stripped Rust skeleton files under `<directory>/.skeleton/`, mirroring the workspace source layout and adding aggregate `<package>.rs` files that concatenate each package's skeleton sources. Uses the persisted hypergraph snapshot to select local crate items, slices current source files, strips function bodies and value initializers, and writes parseable facade files for codebase context. Requires build_hypergraph to have created a snapshot first."
     )]
@@ -658,6 +773,56 @@ impl SearchToolRouter {
         crate::tools::graph::audits::recursion_check(params).await
     }
 
+    #[tool(
+        description = "Semantic diff of two hypergraph snapshots — two worktrees (`base_directory` vs `head_directory`) or two retained `graph_id`s of one workspace (same directory, different `base_graph_id` / `head_graph_id`; omitted ids open the CURRENT snapshot). Build both sides with `build_hypergraph` first. Items are matched by `(qualified_name, item kind)` because node ids differ per worktree. Reports `added_items` / `removed_items` (with file and declared visibility), `moved_items` (same name in a new file, or a unique `(name, kind, signature)` pair that changed module), `visibility_changes` (declared visibility before/after), `signature_changes` (rendered `signatures_by_target` headers before/after), `added_crate_edges` / `removed_crate_edges` (consumer → producer), and `added_call_edges` / `removed_call_edges` (local caller fn → local callee fn). Every section is sorted and capped by `limit` (default 50); `counts` carries the full totals. Use this to review what a branch changed structurally."
+    )]
+    async fn snapshot_diff(
+        &self,
+        Parameters(params): Parameters<crate::tools::params::SnapshotDiffParams>,
+    ) -> Result<CallToolResult, McpError> {
+        crate::tools::graph::audits::snapshot_diff(params).await
+    }
+
+    #[tool(
+        description = "Semver check of one crate's pure-`pub` surface between two hypergraph snapshots (two worktrees, or two retained `graph_id`s of one workspace — same selectors as `snapshot_diff`). Items are matched by `(qualified_name, item kind)`; inherent methods and associated items use their own modifier, capped by their host type's; trait members and enum variants inherit their host's declared visibility; `::tests::` items are ignored. Each change is classified as `item_removed`, `visibility_narrowed`, `param_added`, `param_removed`, `param_type_changed`, `return_type_changed` (including async-ness), `receiver_changed`, `generic_bounds_tightened`, `enum_variant_added` (enum lacks `#[non_exhaustive]`), `trait_method_added` (no default body), `trait_method_default_removed`, or `non_exhaustive_added`. Signature classes carry the rendered `before` / `after` headers from `signatures_by_target`. `crate_name` must exist in the base snapshot; a crate missing from head reports every public item as removed. Sorted by `(qualified_name, change)`; `counts_by_change` tallies the full list. Use this as a pre-release semver check that speaks the graph's qualified names."
+    )]
+    async fn api_breaking_changes(
+        &self,
+        Parameters(params): Parameters<crate::tools::params::ApiBreakingChangesParams>,
+    ) -> Result<CallToolResult, McpError> {
+        crate::tools::graph::audits::api_breaking_changes(params).await
+    }
+
+    #[tool(
+        description = "Per-item churn from the local git history (THEORY P11/P12). Walks `git log` of the workspace (no network, merges skipped, newest `max_commits` first, optional `since`), maps each commit's changed line ranges onto hypergraph Items via their file + span, and counts the commits that changed each Item. Edits are charged to the innermost Item, so a method change is not also charged to its trait. Commits touching more than `max_items_per_commit` Items (formatting sweeps) are skipped and counted in `history.commits_skipped_large`. Sorted by `commit_count` desc; each row carries the last commit sha and time. Requires a hypergraph snapshot (`build_hypergraph`) and a git checkout."
+    )]
+    async fn item_churn(
+        &self,
+        Parameters(params): Parameters<crate::tools::params::ItemChurnParams>,
+    ) -> Result<CallToolResult, McpError> {
+        crate::tools::graph::history::item_churn(params).await
+    }
+
+    #[tool(
+        description = "Items that change together in the local git history (THEORY P11 co-change locality). Same git walk as `item_churn`; reports Item pairs changed by at least `min_co_changes` (default 2) shared commits, with `jaccard = co / (a_commits + b_commits - co)`, `cross_module` and `cross_crate` flags. Pass `target` to list one Item's change partners, `cross_module_only` to hide same-module pairs, `crate_name` to scope. High co-change across a module or crate boundary with no static edge between the pair points at a missing abstraction or a misplaced boundary. Sorted by `co_change_count`, then `jaccard`, desc."
+    )]
+    async fn item_co_change(
+        &self,
+        Parameters(params): Parameters<crate::tools::params::ItemCoChangeParams>,
+    ) -> Result<CallToolResult, McpError> {
+        crate::tools::graph::history::item_co_change(params).await
+    }
+
+    #[tool(
+        description = "Module pairs whose Items change in the same commits (THEORY P11), aggregated from the same git walk as `item_co_change`. A commit counts once per module pair regardless of how many Items it touched. Each row has `co_change_count`, per-module commit counts, `jaccard`, and `cross_crate`. Compare against `module_dependencies` / `crate_edges` to find coupling the static graph does not show. Sorted by `co_change_count`, then `jaccard`, desc."
+    )]
+    async fn module_co_change(
+        &self,
+        Parameters(params): Parameters<crate::tools::params::ModuleCoChangeParams>,
+    ) -> Result<CallToolResult, McpError> {
+        crate::tools::graph::history::module_co_change(params).await
+    }
+
     #[tool(
         description = "Phase 8: query-time AST-walk audit of every channel-construction call site in the workspace's local crates. Loads the workspace through rust-analyzer (~2-3s, dominates per-call cost), iterates every local module's source file, walks the syntax tree for `CallExpr` nodes, and resolves each call's path through `Semantics::resolve_path` so aliased imports such as `use tokio::sync::mpsc; mpsc::channel(N)` still match the canonical entry. Matches the hardcoded v1 path table: `tokio::sync::mpsc::channel` (bounded), `tokio::sync::mpsc::unbounded_channel`, `std::sync::mpsc::channel` (legacy unbounded — flag), `std::sync::mpsc::sync_channel` (bounded), `crossbeam_channel::bounded`, `crossbeam_channel::unbounded`, `flume::bounded`, `flume::unbounded`. Per finding: workspace-relative crate name, `kind` (one of the 8 labels above), `bounded` flag, `capacity` (Some(N) for a literal int arg with `_` separators allowed, None for a const / variable / arithmetic expression / unbounded constructor), file, byte span of the call expression, and enclosing fn (NodeId rendered as 64-char hex + qualified name when resolvable; null for calls in const initializers / closures-without-fn-parent). Filters: optional `crate_name` (qualified name; accepts a Crate or its root Module), `skip_test_fns` (default true — drops findings whose enclosing fn / module carries `#[cfg(test)]`). Sorted by (file, span). Use this to inventory channel construction across the workspace, enforce §12 'use bounded channels', and surface unbounded-channel call sites for review."
     )]
@@ -787,14 +952,73 @@ impl ServerHandler for SearchToolRouter {
             capabilities: ServerCapabilities::builder()
                 .enable_prompts()
                 .enable_resources()
+                .enable_resources_subscribe()
                 .enable_tools()
                 .build(),
             server_info: Implementation::from_build_env(),
-            instructions: Some("Rust code intelligence server for searching code, reading files, resolving symbols and references, previewing renames, inspecting dependencies and call graphs, semantic similarity, persisted hypergraph queries, workspace audits, and cache/index maintenance. List-shaped graph and audit tools accept `limit` (default 50), `offset`, and `summary`; responses include `total_match_count` plus returned-count metadata. See each tool's description for parameters and limitations; run `build_hypergraph` before graph-backed tools that require it."
+            instructions: Some("Rust code intelligence server for searching code, reading files, resolving symbols and references, previewing renames, inspecting dependencies and call graphs, semantic similarity, persisted hypergraph queries, workspace audits, and cache/index maintenance. List-shaped graph and audit tools accept `limit` (default 50), `offset`, and `summary`; responses include `total_match_count` plus returned-count metadata. See each tool's description for parameters and limitations; run `build_hypergraph` before graph-backed tools that require it. Workspaces with a published snapshot are also exposed as `rmc://<workspace>/...` resources (snapshot manifest, files, module trees, audit reports); subscribe to be notified when `build_hypergraph` republishes. The `rmc-*` prompts package guided audit workflows (workspace overview, crate audit, refactor plan, ...) parameterized by `directory`, `crate_name` and `target`."
                 .into(),
             ),
         }
     }
+
+    async fn list_prompts(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListPromptsResult, McpError> {
+        Ok(crate::tools::prompts::list_prompts())
+    }
+
+    async fn get_prompt(
+        &self,
+        request: GetPromptRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<GetPromptResult, McpError> {
+        crate::tools::prompts::get_prompt(&request.name, request.arguments.as_ref())
+    }
+
+    async fn list_resources(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourcesResult, McpError> {
+        crate::tools::resources::list_resources()
+    }
+
+    async fn list_resource_templates(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourceTemplatesResult, McpError> {
+        Ok(crate::tools::resources::list_resource_templates())
+    }
+
+    async fn read_resource(
+        &self,
+        request: ReadResourceRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ReadResourceResult, McpError> {
+        crate::tools::resources::read_resource(self.runtime.resources(), &request.uri).await
+    }
+
+    async fn subscribe(
+        &self,
+        request: SubscribeRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<(), McpError> {
+        self.resource_subscriptions
+            .subscribe(&request.uri, context.peer, self.runtime.resources())
+    }
+
+    async fn unsubscribe(
+        &self,
+        request: UnsubscribeRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<(), McpError> {
+        self.resource_subscriptions.unsubscribe(&request.uri);
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -133,6 +133,7 @@ async fn main() -> Result<()> {
         "embed_duration_secs={:.6}",
         metrics.embed_duration.as_secs_f64()
     );
+    println!("reused_embeddings={}", metrics.reused_embeddings);
     println!(
         "index_duration_secs={:.6}",
         metrics.index_duration.as_secs_f64()
@@ -107,6 +107,7 @@ fn main() {
                 force_rebuild: true,
                 data_dir_override: Some(tempdir.path().to_path_buf()),
                 env: GraphEnvOptions::default(),
+                incremental: false,
             };
             let t = Instant::now();
             let result = build_and_persist(workspace_path, opts).expect("build_and_persist");
@@ -0,0 +1,91 @@
+//! Command-line arguments for the server binary.
+//!
+//! - No arguments: serve MCP over stdio (one server per editor session)
+//! - `--listen <ADDR>`: serve the MCP streamable-HTTP transport at
+//!   `http://<ADDR>/mcp`, so several clients share one long-lived daemon
+
+use std::net::SocketAddr;
+
+/// Path the streamable-HTTP service is mounted under.
+pub(crate) const HTTP_MCP_PATH: &str = "/mcp";
+
+pub(crate) const USAGE: &str = "\
+Usage: rust-code-mcp [--listen <ADDR>]
+
+Options:
+  --listen <ADDR>  Serve MCP over streamable HTTP at http://<ADDR>/mcp
+                   (e.g. 127.0.0.1:8765) instead of stdio. Clients share
+                   one runtime: warm rust-analyzer, search caches, and
+                   workspace locks.
+  -h, --help       Print this help";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Transport {
+    Stdio,
+    Http(SocketAddr),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Command {
+    Serve(Transport),
+    Help,
+}
+
+/// Parse arguments after the program name.
+pub(crate) fn parse_args<I>(args: I) -> Result<Command, String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut transport = Transport::Stdio;
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let listen = match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--listen" => args
+                .next()
+                .ok_or_else(|| "--listen requires an address, e.g. 127.0.0.1:8765".to_string())?,
+            other => match other.strip_prefix("--listen=") {
+                Some(value) => value.to_string(),
+                None => return Err(format!("unexpected argument '{other}'")),
+            },
+        };
+        let addr = listen
+            .parse::<SocketAddr>()
+            .map_err(|e| format!("invalid --listen address '{listen}': {e}"))?;
+        transport = Transport::Http(addr);
+    }
+    Ok(Command::Serve(transport))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Command, String> {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn defaults_to_stdio() {
+        assert_eq!(parse(&[]), Ok(Command::Serve(Transport::Stdio)));
+    }
+
+    #[test]
+    fn listen_accepts_separate_and_inline_values() {
+        let expected = Command::Serve(Transport::Http("127.0.0.1:8765".parse().unwrap()));
+        assert_eq!(parse(&["--listen", "127.0.0.1:8765"]), Ok(expected.clone()));
+        assert_eq!(parse(&["--listen=127.0.0.1:8765"]), Ok(expected));
+        assert_eq!(
+            parse(&["--listen", "[::1]:0"]),
+            Ok(Command::Serve(Transport::Http("[::1]:0".parse().unwrap())))
+        );
+    }
+
+    #[test]
+    fn rejects_bad_arguments() {
+        assert!(parse(&["--listen"]).is_err());
+        assert!(parse(&["--listen", "localhost"]).is_err());
+        assert!(parse(&["--stdio"]).is_err());
+        assert_eq!(parse(&["--help"]), Ok(Command::Help));
+    }
+}
@@ -4,17 +4,39 @@
 // compile-time inference budget, not a runtime cost.
 #![recursion_limit = "512"]
 
+mod cli;
+
+use cli::{Command, HTTP_MCP_PATH, Transport, USAGE};
 use rmc_server::mcp::{
     automatic_embedding_profile_name, cuda_capable_features_compiled,
-    parse_background_sync_env, ServerRuntime, BACKGROUND_SYNC_ENABLED_VALUES, BACKGROUND_SYNC_ENV,
+    parse_background_sync_env, parse_watch_debounce_env, parse_watch_env, ServerRuntime,
+    SyncManager, BACKGROUND_SYNC_ENABLED_VALUES, BACKGROUND_SYNC_ENV, WATCH_DEBOUNCE_MS_ENV,
+    WATCH_ENV,
 };
 use rmc_server::tools::SearchTool;
+use rmcp::transport::streamable_http_server::{
+    StreamableHttpServerConfig, StreamableHttpService, session::local::LocalSessionManager,
+};
 use rmcp::{ServiceExt, transport::stdio};
+use std::net::SocketAddr;
+use std::sync::Arc;
 use std::time::Duration;
 use tracing_subscriber::{self, EnvFilter};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let transport = match cli::parse_args(std::env::args().skip(1)) {
+        Ok(Command::Serve(transport)) => transport,
+        Ok(Command::Help) => {
+            println!("{USAGE}");
+            return Ok(());
+        }
+        Err(message) => {
+            eprintln!("error: {message}\n\n{USAGE}");
+            std::process::exit(2);
+        }
+    };
+
     // Default to WARN for everything, INFO for our own crate. Users who want
     // RA's internal debug logs can set `RUST_LOG=ra_ap_hir=debug,...`.
     //
@@ -33,9 +55,28 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     tracing::info!("Starting MCP Server...");
 
+    // Watch mode reacts to file events for tracked directories within the
+    // debounce window instead of waiting for the next periodic sync.
+    let watch_enabled = parse_watch_env(std::env::var(WATCH_ENV).ok().as_deref());
+    let watch_debounce =
+        parse_watch_debounce_env(std::env::var(WATCH_DEBOUNCE_MS_ENV).ok().as_deref());
+
     // Syncs every 5 minutes (300 seconds).
-    let runtime = ServerRuntime::new(300);
-    tracing::info!("Created MCP server runtime (5-minute sync interval)");
+    let sync_manager = SyncManager::with_defaults(300);
+    let sync_manager = if watch_enabled {
+        sync_manager.with_watch(watch_debounce)
+    } else {
+        sync_manager
+    };
+    let runtime = ServerRuntime::with_sync_manager(Arc::new(sync_manager));
+    tracing::info!(
+        "Created MCP server runtime (5-minute sync interval; watch mode {})",
+        if watch_enabled {
+            format!("enabled, {}ms debounce", watch_debounce.as_millis())
+        } else {
+            format!("disabled; set {WATCH_ENV}=1 to enable")
+        },
+    );
 
     let background_sync_env = std::env::var(BACKGROUND_SYNC_ENV).ok();
     let background_sync_enabled = parse_background_sync_env(background_sync_env.as_deref());
@@ -49,7 +90,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         cuda_capable_features_compiled(),
     );
 
-    if background_sync_enabled {
+    // Watch mode runs inside the background sync task, so enabling it
+    // starts that task even without RMC_BACKGROUND_SYNC.
+    if background_sync_enabled || watch_enabled {
         runtime.start_background_sync();
         tracing::info!("Started background sync task");
     } else {
@@ -59,6 +102,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         );
     }
 
+    if let Transport::Http(addr) = transport {
+        let served = serve_http(&runtime, addr).await;
+        if let Err(e) = &served {
+            tracing::error!("HTTP serving error: {:?}", e);
+        }
+        let shutdown = runtime.shutdown_gracefully(Duration::from_secs(10)).await;
+        tracing::info!("Runtime shutdown complete: {:?}", shutdown);
+        return served;
+    }
+
     let service = match SearchTool::with_server_runtime(&runtime).serve(stdio()).await {
         Ok(service) => service,
         Err(e) => {
@@ -80,3 +133,65 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     service_result?;
     Ok(())
 }
+
+/// Serve the streamable-HTTP transport until Ctrl-C / SIGTERM.
+///
+/// Every MCP session gets its own `SearchTool`, but all of them share the
+/// runtime state: the warm `SemanticService`, the `SearchRuntimeCache`, and
+/// the `WorkspaceLockRegistry` that serializes indexing against queries.
+async fn serve_http(
+    runtime: &ServerRuntime,
+    addr: SocketAddr,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let state = runtime.state();
+    let service = StreamableHttpService::new(
+        move || Ok(SearchTool::with_runtime_state(state.clone())),
+        LocalSessionManager::default().into(),
+        StreamableHttpServerConfig::default(),
+    );
+    let router = axum::Router::new().nest_service(HTTP_MCP_PATH, service);
+
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    let local_addr = listener.local_addr()?;
+    if !local_addr.ip().is_loopback() {
+        tracing::warn!(
+            "Listening on non-loopback address {}; the MCP endpoint has no authentication",
+            local_addr
+        );
+    }
+    tracing::info!("Serving MCP streamable HTTP at http://{}{}", local_addr, HTTP_MCP_PATH);
+
+    axum::serve(listener, router)
+        .with_graceful_shutdown(shutdown_signal())
+        .await?;
+    Ok(())
+}
+
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            tracing::warn!("Failed to listen for Ctrl-C: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                tracing::warn!("Failed to listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+    tracing::info!("Shutdown signal received; stopping HTTP server");
+}
@@ -0,0 +1,122 @@
+//! Smoke tests for the `--listen` streamable-HTTP transport.
+//!
+//! One daemon must accept independent MCP sessions from several clients.
+
+use anyhow::{Context, Result, anyhow};
+use serde_json::{Value, json};
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+struct ChildGuard {
+    child: Child,
+}
+
+impl Drop for ChildGuard {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+const LISTEN_MARKER: &str = "Serving MCP streamable HTTP at ";
+
+/// Spawn the server on an ephemeral port and return the endpoint URL it logs.
+fn spawn_http_server() -> Result<(ChildGuard, String)> {
+    let mut child = ChildGuard {
+        child: Command::new(env!("CARGO_BIN_EXE_rust-code-mcp"))
+            .args(["--listen", "127.0.0.1:0"])
+            .env("RUST_LOG", "rust_code_mcp=info")
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .context("failed to spawn MCP server")?,
+    };
+    let stderr = child
+        .child
+        .stderr
+        .take()
+        .context("child stderr was not piped")?;
+
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+            if let Some((_, url)) = line.split_once(LISTEN_MARKER) {
+                let _ = tx.send(url.trim().to_string());
+            }
+        }
+    });
+    let url = rx
+        .recv_timeout(Duration::from_secs(30))
+        .map_err(|_| anyhow!("server did not report its listen address"))?;
+    Ok((child, url))
+}
+
+/// POST `initialize` and return (session id, JSON-RPC response).
+async fn initialize(client: &reqwest::Client, url: &str, name: &str) -> Result<(String, Value)> {
+    let mut response = client
+        .post(url)
+        .header("Accept", "application/json, text/event-stream")
+        .json(&json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "initialize",
+            "params": {
+                "protocolVersion": "2025-06-18",
+                "capabilities": {},
+                "clientInfo": { "name": name, "version": "0.0.0" }
+            }
+        }))
+        .send()
+        .await?
+        .error_for_status()?;
+    let session_id = response
+        .headers()
+        .get("mcp-session-id")
+        .context("initialize response has no mcp-session-id header")?
+        .to_str()?
+        .to_string();
+
+    // The reply arrives as an SSE `data:` line; read until it shows up.
+    let mut body = String::new();
+    loop {
+        let chunk = tokio::time::timeout(Duration::from_secs(30), response.chunk())
+            .await
+            .map_err(|_| anyhow!("timed out waiting for initialize response"))??;
+        let Some(chunk) = chunk else {
+            break;
+        };
+        body.push_str(&String::from_utf8_lossy(&chunk));
+        if let Some(message) = body
+            .lines()
+            .filter_map(|line| line.strip_prefix("data:"))
+            .filter_map(|data| serde_json::from_str::<Value>(data.trim()).ok())
+            .find(|message| message.get("id") == Some(&json!(1)))
+        {
+            return Ok((session_id, message));
+        }
+    }
+    Err(anyhow!("initialize stream ended without a response: {body}"))
+}
+
+#[tokio::test]
+async fn http_transport_serves_independent_sessions() -> Result<()> {
+    let (_server, url) = spawn_http_server()?;
+    let client = reqwest::Client::new();
+
+    let (first_session, first) = initialize(&client, &url, "http-client-a").await?;
+    let (second_session, second) = initialize(&client, &url, "http-client-b").await?;
+
+    for response in [&first, &second] {
+        assert!(response.get("error").is_none(), "initialize failed: {response}");
+        assert!(
+            response.pointer("/result/serverInfo").is_some(),
+            "missing serverInfo: {response}"
+        );
+    }
+    assert_ne!(first_session, second_session, "each client gets its own session");
+    Ok(())
+}